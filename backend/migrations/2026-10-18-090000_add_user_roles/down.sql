@@ -0,0 +1,3 @@
+ALTER TABLE users DROP CONSTRAINT chk_users_access;
+ALTER TABLE users ALTER COLUMN access SET DEFAULT '1';
+UPDATE users SET access = '1';
@@ -0,0 +1,7 @@
+-- Access used to be unchecked, so every existing account could already do everything.
+-- Keep that behaviour by making them administrators; they can be downgraded afterwards.
+UPDATE users SET access = 'admin' WHERE access NOT IN ('admin', 'agent', 'readonly', 'requester');
+
+ALTER TABLE users ALTER COLUMN access SET DEFAULT 'agent';
+ALTER TABLE users ADD CONSTRAINT chk_users_access
+    CHECK (access IN ('admin', 'agent', 'readonly', 'requester'));
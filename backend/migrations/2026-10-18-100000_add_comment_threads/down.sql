@@ -0,0 +1,3 @@
+DROP INDEX idx_comments_document_id;
+ALTER TABLE comments DROP CONSTRAINT fk_comments_parent;
+ALTER TABLE comments DROP COLUMN parent_id;
@@ -0,0 +1,7 @@
+ALTER TABLE comments ADD COLUMN parent_id UUID;
+ALTER TABLE comments ADD CONSTRAINT fk_comments_parent
+    FOREIGN KEY (parent_id)
+    REFERENCES comments (comment_id)
+    ON DELETE CASCADE;
+
+CREATE INDEX idx_comments_document_id ON comments (document_id);
@@ -0,0 +1,3 @@
+DROP INDEX idx_documents_search;
+DROP INDEX idx_notes_search;
+DROP INDEX idx_tickets_search;
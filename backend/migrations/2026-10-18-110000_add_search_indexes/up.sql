@@ -0,0 +1,8 @@
+-- Expression indexes for full-text search, the expressions must match handlers::search exactly
+CREATE INDEX idx_tickets_search ON tickets USING GIN (
+    (setweight(to_tsvector('english', title), 'A') || setweight(to_tsvector('english', description), 'B'))
+);
+CREATE INDEX idx_notes_search ON notes USING GIN (to_tsvector('english', text));
+CREATE INDEX idx_documents_search ON documents USING GIN (
+    (setweight(to_tsvector('english', title), 'A') || setweight(to_tsvector('english', content), 'B'))
+);
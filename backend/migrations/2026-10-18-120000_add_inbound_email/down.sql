@@ -0,0 +1,3 @@
+ALTER TABLE notes DROP CONSTRAINT fk_note_contact;
+ALTER TABLE notes DROP COLUMN contact;
+ALTER TABLE contacts ALTER COLUMN contact_id DROP DEFAULT;
@@ -0,0 +1,9 @@
+-- Contacts are created automatically from inbound e-mail
+ALTER TABLE contacts ALTER COLUMN contact_id SET DEFAULT uuid_generate_v4();
+
+-- Notes appended from e-mail replies are written by a contact instead of a user
+ALTER TABLE notes ADD COLUMN contact UUID;
+ALTER TABLE notes ADD CONSTRAINT fk_note_contact
+    FOREIGN KEY (contact)
+    REFERENCES contacts (contact_id)
+    ON DELETE SET NULL;
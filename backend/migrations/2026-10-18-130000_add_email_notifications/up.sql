@@ -0,0 +1,2 @@
+-- Ticket e-mail notifications are opt-in per user
+ALTER TABLE user_preferences ADD COLUMN email_notifications BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,15 @@
+CREATE TABLE ticket_watchers (
+    ticket_id INTEGER NOT NULL REFERENCES tickets(ticket_id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(user_id) ON DELETE CASCADE,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (ticket_id, user_id)
+);
+
+CREATE INDEX ticket_watchers_user_id_idx ON ticket_watchers (user_id);
+
+-- Existing creators and note authors follow their tickets, same as new ones will
+INSERT INTO ticket_watchers (ticket_id, user_id)
+SELECT ticket_id, created_by FROM tickets WHERE created_by IS NOT NULL
+UNION
+SELECT ticket, owner FROM notes WHERE owner IS NOT NULL
+ON CONFLICT DO NOTHING;
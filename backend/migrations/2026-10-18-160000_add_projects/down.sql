@@ -0,0 +1,7 @@
+DROP INDEX tickets_project_number_idx;
+
+ALTER TABLE tickets
+    DROP COLUMN project_number,
+    DROP COLUMN project;
+
+DROP TABLE projects;
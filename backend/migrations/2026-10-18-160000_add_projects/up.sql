@@ -0,0 +1,16 @@
+CREATE TABLE projects (
+    project_id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    name TEXT NOT NULL,
+    -- Prefix of the project's ticket numbers, e.g. NET for NET-42
+    key TEXT NOT NULL UNIQUE,
+    default_assignee UUID REFERENCES users(user_id) ON DELETE SET NULL,
+    -- Number the next ticket in the project gets
+    next_number INTEGER NOT NULL DEFAULT 1,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+ALTER TABLE tickets
+    ADD COLUMN project UUID REFERENCES projects(project_id) ON DELETE SET NULL,
+    ADD COLUMN project_number INTEGER;
+
+CREATE UNIQUE INDEX tickets_project_number_idx ON tickets (project, project_number);
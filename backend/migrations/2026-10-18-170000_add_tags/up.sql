@@ -0,0 +1,16 @@
+CREATE TABLE tags (
+    tag_id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    name TEXT NOT NULL UNIQUE,
+    -- CSS color of the label, e.g. #d73a4a
+    color TEXT NOT NULL DEFAULT '#808080',
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE ticket_tags (
+    ticket_id INTEGER NOT NULL REFERENCES tickets(ticket_id) ON DELETE CASCADE,
+    tag_id UUID NOT NULL REFERENCES tags(tag_id) ON DELETE CASCADE,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (ticket_id, tag_id)
+);
+
+CREATE INDEX ticket_tags_tag_id_idx ON ticket_tags (tag_id);
@@ -0,0 +1,22 @@
+CREATE TABLE custom_fields (
+    field_id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    name TEXT NOT NULL UNIQUE,
+    -- text, number, date, select or user, see CustomFieldType
+    field_type TEXT NOT NULL,
+    -- Choices of select fields
+    options TEXT[] NOT NULL DEFAULT '{}',
+    required BOOLEAN NOT NULL DEFAULT FALSE,
+    -- Order in forms and on the ticket page
+    position INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Values are stored as text in a canonical form, e.g. 2026-10-18 for dates
+CREATE TABLE ticket_field_values (
+    ticket_id INTEGER NOT NULL REFERENCES tickets(ticket_id) ON DELETE CASCADE,
+    field_id UUID NOT NULL REFERENCES custom_fields(field_id) ON DELETE CASCADE,
+    value TEXT NOT NULL,
+    PRIMARY KEY (ticket_id, field_id)
+);
+
+CREATE INDEX ticket_field_values_field_id_idx ON ticket_field_values (field_id, value);
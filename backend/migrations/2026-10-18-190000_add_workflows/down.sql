@@ -0,0 +1,3 @@
+DROP TABLE ticket_priorities;
+DROP TABLE status_transitions;
+DROP TABLE ticket_statuses;
@@ -0,0 +1,53 @@
+-- Tickets keep the status and priority names as text, these tables define which names exist
+CREATE TABLE ticket_statuses (
+    status_id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    name TEXT NOT NULL UNIQUE,
+    -- Closed statuses count as done for the open/closed filters
+    is_closed BOOLEAN NOT NULL DEFAULT FALSE,
+    -- Status of new tickets, exactly one status is the default
+    is_default BOOLEAN NOT NULL DEFAULT FALSE,
+    -- Order in menus and when sorting by status
+    position INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX ticket_statuses_default_idx ON ticket_statuses (is_default) WHERE is_default;
+
+-- A status without rows here can change to any status
+CREATE TABLE status_transitions (
+    from_status UUID NOT NULL REFERENCES ticket_statuses(status_id) ON DELETE CASCADE,
+    to_status UUID NOT NULL REFERENCES ticket_statuses(status_id) ON DELETE CASCADE,
+    PRIMARY KEY (from_status, to_status)
+);
+
+CREATE TABLE ticket_priorities (
+    priority_id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    name TEXT NOT NULL UNIQUE,
+    -- Lower ranks are more urgent
+    rank INTEGER NOT NULL DEFAULT 0,
+    -- Priority of tickets opened by mail
+    is_default BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX ticket_priorities_default_idx ON ticket_priorities (is_default) WHERE is_default;
+
+INSERT INTO ticket_statuses (name, is_closed, is_default, position) VALUES
+    ('Open', FALSE, TRUE, 0),
+    ('In Progress', FALSE, FALSE, 1),
+    ('On Hold', FALSE, FALSE, 2),
+    ('Closed', TRUE, FALSE, 3);
+
+-- Keep any other status already in use
+INSERT INTO ticket_statuses (name, position)
+SELECT DISTINCT status, 10 FROM tickets WHERE status <> ''
+ON CONFLICT (name) DO NOTHING;
+
+INSERT INTO ticket_priorities (name, rank, is_default) VALUES
+    ('High', 0, FALSE),
+    ('Medium', 1, TRUE),
+    ('Low', 2, FALSE);
+
+INSERT INTO ticket_priorities (name, rank)
+SELECT DISTINCT priority, 10 FROM tickets WHERE priority <> ''
+ON CONFLICT (name) DO NOTHING;
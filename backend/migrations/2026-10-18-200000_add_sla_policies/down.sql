@@ -0,0 +1,3 @@
+DROP TABLE ticket_slas;
+DROP TABLE sla_policies;
+ALTER TABLE ticket_statuses DROP COLUMN pauses_sla;
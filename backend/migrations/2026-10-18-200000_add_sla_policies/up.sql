@@ -0,0 +1,35 @@
+-- Tickets in these statuses do not use up their SLA time, e.g. while waiting on the customer
+ALTER TABLE ticket_statuses ADD COLUMN pauses_sla BOOLEAN NOT NULL DEFAULT FALSE;
+UPDATE ticket_statuses SET pauses_sla = TRUE WHERE name = 'On Hold';
+
+-- The most specific policy matching a ticket applies, empty criteria match any ticket
+CREATE TABLE sla_policies (
+    policy_id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    name TEXT NOT NULL UNIQUE,
+    priority TEXT,
+    project UUID REFERENCES projects(project_id) ON DELETE CASCADE,
+    contact UUID REFERENCES contacts(contact_id) ON DELETE CASCADE,
+    -- Targets from the start of the SLA clock, no target when null
+    response_minutes INTEGER,
+    resolution_minutes INTEGER,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- SLA clock of tickets with a policy
+CREATE TABLE ticket_slas (
+    ticket_id INTEGER PRIMARY KEY REFERENCES tickets(ticket_id) ON DELETE CASCADE,
+    policy_id UUID NOT NULL REFERENCES sla_policies(policy_id) ON DELETE CASCADE,
+    -- When the first policy applied, usually the creation of the ticket
+    started_at TIMESTAMP NOT NULL,
+    -- Deadlines are pushed back by the time spent paused
+    response_due TIMESTAMP,
+    resolution_due TIMESTAMP,
+    first_response_at TIMESTAMP,
+    resolved_at TIMESTAMP,
+    paused_at TIMESTAMP,
+    paused_seconds INTEGER NOT NULL DEFAULT 0,
+    response_breached BOOLEAN NOT NULL DEFAULT FALSE,
+    resolution_breached BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+CREATE INDEX ticket_slas_policy_id_idx ON ticket_slas (policy_id);
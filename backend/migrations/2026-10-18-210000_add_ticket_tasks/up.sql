@@ -0,0 +1,15 @@
+-- Checklist items of a ticket, in the order they are shown
+CREATE TABLE ticket_tasks (
+    task_id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    ticket_id INTEGER NOT NULL REFERENCES tickets(ticket_id) ON DELETE CASCADE,
+    text TEXT NOT NULL,
+    position INTEGER NOT NULL DEFAULT 0,
+    done BOOLEAN NOT NULL DEFAULT FALSE,
+    -- Tickets cannot be closed while a required task is open
+    required BOOLEAN NOT NULL DEFAULT FALSE,
+    completed_at TIMESTAMP,
+    completed_by UUID REFERENCES users(user_id) ON DELETE SET NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX ticket_tasks_ticket_id_idx ON ticket_tasks (ticket_id, position);
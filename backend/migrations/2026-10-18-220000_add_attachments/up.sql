@@ -0,0 +1,19 @@
+-- Files uploaded to tickets, notes and wiki documents, the contents live in blob storage
+CREATE TABLE attachments (
+    attachment_id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    -- Note attachments also belong to the ticket of the note
+    ticket_id INTEGER REFERENCES tickets(ticket_id) ON DELETE CASCADE,
+    note_id UUID REFERENCES notes(note_id) ON DELETE CASCADE,
+    document_id UUID REFERENCES documents(document_id) ON DELETE CASCADE,
+    filename TEXT NOT NULL,
+    mime_type TEXT NOT NULL,
+    size BIGINT NOT NULL,
+    storage_key TEXT NOT NULL,
+    uploaded_by UUID REFERENCES users(user_id) ON DELETE SET NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK ((ticket_id IS NULL) <> (document_id IS NULL)),
+    CHECK (note_id IS NULL OR ticket_id IS NOT NULL)
+);
+
+CREATE INDEX attachments_ticket_id_idx ON attachments (ticket_id);
+CREATE INDEX attachments_document_id_idx ON attachments (document_id);
@@ -0,0 +1,14 @@
+-- Typed links between tickets, stored in one direction:
+-- the source duplicates, blocks, relates to or is the parent of the target
+CREATE TABLE ticket_links (
+    link_id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    source_id INTEGER NOT NULL REFERENCES tickets(ticket_id) ON DELETE CASCADE,
+    target_id INTEGER NOT NULL REFERENCES tickets(ticket_id) ON DELETE CASCADE,
+    link_type VARCHAR(16) NOT NULL CHECK (link_type IN ('duplicates', 'blocks', 'relates_to', 'parent_of')),
+    created_by UUID REFERENCES users(user_id) ON DELETE SET NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK (source_id <> target_id),
+    UNIQUE (source_id, target_id, link_type)
+);
+
+CREATE INDEX ticket_links_target_id_idx ON ticket_links (target_id);
@@ -0,0 +1,2 @@
+-- Set on tickets that were merged into another one, mail replies to them go there instead
+ALTER TABLE tickets ADD COLUMN merged_into INTEGER REFERENCES tickets(ticket_id) ON DELETE SET NULL;
@@ -0,0 +1,13 @@
+CREATE TABLE saved_views (
+    view_id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    owner UUID NOT NULL REFERENCES users(user_id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    -- Ticket list filter as JSON, see TicketFilterPayload
+    filter TEXT NOT NULL,
+    -- Shown to every user, only the owner can change it
+    shared BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (owner, name)
+);
+
+CREATE INDEX saved_views_shared_idx ON saved_views (shared) WHERE shared;
@@ -0,0 +1,3 @@
+DROP TABLE portal_tokens;
+
+DROP TABLE portal_passwords;
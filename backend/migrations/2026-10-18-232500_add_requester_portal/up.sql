@@ -0,0 +1,16 @@
+-- Kept apart from contacts so the hash is never returned with a contact
+CREATE TABLE portal_passwords (
+    contact_id UUID PRIMARY KEY REFERENCES contacts(contact_id) ON DELETE CASCADE,
+    password_hash TEXT NOT NULL,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Single-use sign-in links sent by e-mail, only the SHA-256 of the token is stored
+CREATE TABLE portal_tokens (
+    token_hash TEXT PRIMARY KEY,
+    contact_id UUID NOT NULL REFERENCES contacts(contact_id) ON DELETE CASCADE,
+    expires_at TIMESTAMP NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX portal_tokens_contact_idx ON portal_tokens (contact_id);
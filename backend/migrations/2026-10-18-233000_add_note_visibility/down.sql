@@ -0,0 +1,2 @@
+ALTER TABLE user_preferences DROP COLUMN notes_public;
+ALTER TABLE notes DROP COLUMN is_public;
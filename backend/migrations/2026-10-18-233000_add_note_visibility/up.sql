@@ -0,0 +1,4 @@
+-- Internal notes are kept from requesters, existing notes stay public as they were
+ALTER TABLE notes ADD COLUMN is_public BOOLEAN NOT NULL DEFAULT TRUE;
+-- Visibility of new notes when the author does not choose one
+ALTER TABLE user_preferences ADD COLUMN notes_public BOOLEAN NOT NULL DEFAULT TRUE;
@@ -0,0 +1,2 @@
+DROP TABLE recurring_ticket_runs;
+DROP TABLE recurring_tickets;
@@ -0,0 +1,35 @@
+CREATE TABLE recurring_tickets (
+    recurring_id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    name TEXT NOT NULL UNIQUE,
+    -- Preset of the created tickets
+    title TEXT NOT NULL,
+    description TEXT NOT NULL DEFAULT '',
+    assignee UUID REFERENCES users(user_id) ON DELETE SET NULL,
+    priority TEXT,
+    -- Checklist as JSON, see TicketTaskPayload
+    tasks TEXT NOT NULL DEFAULT '[]',
+    -- Schedule as JSON, see Recurrence
+    recurrence TEXT NOT NULL,
+    -- First possible run, runs happen at its time of day
+    start_at TIMESTAMP NOT NULL,
+    -- Seconds east of UTC, days and weekdays of the schedule are counted in this time zone
+    utc_offset INTEGER NOT NULL DEFAULT 0,
+    -- NULL while disabled
+    next_run TIMESTAMP,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    created_by UUID REFERENCES users(user_id) ON DELETE SET NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX recurring_tickets_next_run_idx ON recurring_tickets (next_run) WHERE enabled;
+
+-- One row per scheduled occurrence, so a run is never repeated after a restart
+CREATE TABLE recurring_ticket_runs (
+    run_id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    recurring_id UUID NOT NULL REFERENCES recurring_tickets(recurring_id) ON DELETE CASCADE,
+    scheduled_for TIMESTAMP NOT NULL,
+    ticket_id INTEGER REFERENCES tickets(ticket_id) ON DELETE SET NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (recurring_id, scheduled_for)
+);
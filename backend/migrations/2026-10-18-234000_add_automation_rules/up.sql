@@ -0,0 +1,30 @@
+CREATE TABLE automation_rules (
+    rule_id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    name TEXT NOT NULL UNIQUE,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    -- AutomationTrigger as JSON
+    trigger TEXT NOT NULL,
+    -- AutomationCondition list as JSON, all of them have to match
+    conditions TEXT NOT NULL DEFAULT '[]',
+    -- AutomationAction list as JSON, run in order
+    actions TEXT NOT NULL,
+    created_by UUID REFERENCES users(user_id) ON DELETE SET NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Execution log, one row each time a rule matched a ticket
+CREATE TABLE automation_runs (
+    run_id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    rule_id UUID NOT NULL REFERENCES automation_rules(rule_id) ON DELETE CASCADE,
+    ticket_id INTEGER REFERENCES tickets(ticket_id) ON DELETE SET NULL,
+    -- ticket_created, idle or the event type that triggered the rule
+    trigger TEXT NOT NULL,
+    success BOOLEAN NOT NULL,
+    -- What the actions did, or why they failed
+    message TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX automation_runs_rule_idx ON automation_runs (rule_id, created_at);
+CREATE INDEX automation_runs_ticket_idx ON automation_runs (ticket_id, rule_id);
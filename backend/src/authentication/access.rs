@@ -0,0 +1,157 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::str::FromStr;
+
+use actix_web::dev::Payload;
+use actix_web::error::InternalError;
+use actix_web::{web, Error, FromRequest, HttpMessage, HttpRequest, HttpResponse};
+use diesel::prelude::*;
+use shared::models::users::Role;
+use uuid::Uuid;
+
+use super::{ContactId, UserId};
+use super::super::DbPool;
+
+type DbError = Box<dyn std::error::Error + Send + Sync>;
+
+/// The logged in user and their role.
+/// Looked up on every request so access changes apply without logging out.
+#[derive(Copy, Clone, Debug)]
+pub struct CurrentUser {
+    pub user_id: Uuid,
+    pub role: Role,
+}
+
+impl CurrentUser {
+    /// Returns 403 unless the role passes the given check, e.g. `caller.ensure(Role::can_edit)?`
+    pub fn ensure(&self, permitted: fn(&Role) -> bool) -> Result<(), Error> {
+        if permitted(&self.role) {
+            Ok(())
+        } else {
+            Err(forbidden())
+        }
+    }
+}
+
+pub fn forbidden() -> Error {
+    InternalError::from_response("Forbidden", HttpResponse::Forbidden().finish()).into()
+}
+
+impl FromRequest for CurrentUser {
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        //UserId is inserted by reject_anonymous_users
+        let user_id = req.extensions().get::<UserId>().copied();
+        let pool = req.app_data::<web::Data<DbPool>>().cloned();
+
+        Box::pin(async move {
+            let user_id = match user_id {
+                Some(id) => *id,
+                None => {
+                    return Err(InternalError::from_response(
+                        "Unauthorized",
+                        HttpResponse::Unauthorized().finish(),
+                    )
+                    .into())
+                }
+            };
+            let pool = pool.ok_or_else(|| {
+                actix_web::error::ErrorInternalServerError("Database pool not configured")
+            })?;
+
+            let access = web::block(move || {
+                let mut conn = pool.get()?;
+                get_access(user_id, &mut conn)
+            })
+            .await?
+            .map_err(actix_web::error::ErrorInternalServerError)?;
+
+            //Session outlived the user
+            let access = match access {
+                Some(access) => access,
+                None => {
+                    return Err(InternalError::from_response(
+                        "Unauthorized",
+                        HttpResponse::Unauthorized().finish(),
+                    )
+                    .into())
+                }
+            };
+
+            let role = Role::from_str(&access).map_err(|e| {
+                log::error!("User {} has an invalid access level: {}", user_id, e);
+                forbidden()
+            })?;
+
+            Ok(CurrentUser { user_id, role })
+        })
+    }
+}
+
+fn get_access(id: Uuid, conn: &mut PgConnection) -> Result<Option<String>, DbError> {
+    use crate::schema::users::dsl::*;
+
+    let result = users
+        .filter(user_id.eq(id))
+        .select(access)
+        .first::<String>(conn)
+        .optional()?;
+
+    Ok(result)
+}
+
+/// The requester signed in to the portal.
+/// Only handlers under /api/portal can extract it, see `reject_anonymous_contacts`.
+#[derive(Copy, Clone, Debug)]
+pub struct CurrentContact {
+    pub contact_id: Uuid,
+}
+
+impl FromRequest for CurrentContact {
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let contact_id = req.extensions().get::<ContactId>().copied();
+        let pool = req.app_data::<web::Data<DbPool>>().cloned();
+
+        Box::pin(async move {
+            let unauthorized = || -> Error {
+                InternalError::from_response("Unauthorized", HttpResponse::Unauthorized().finish())
+                    .into()
+            };
+            let contact_id = match contact_id {
+                Some(id) => *id,
+                None => return Err(unauthorized()),
+            };
+            let pool = pool.ok_or_else(|| {
+                actix_web::error::ErrorInternalServerError("Database pool not configured")
+            })?;
+
+            let exists = web::block(move || {
+                let mut conn = pool.get()?;
+                contact_exists(contact_id, &mut conn)
+            })
+            .await?
+            .map_err(actix_web::error::ErrorInternalServerError)?;
+
+            //Session outlived the contact
+            if !exists {
+                return Err(unauthorized());
+            }
+
+            Ok(CurrentContact { contact_id })
+        })
+    }
+}
+
+fn contact_exists(id: Uuid, conn: &mut PgConnection) -> Result<bool, DbError> {
+    use crate::schema::contacts::dsl::*;
+
+    let exists =
+        diesel::select(diesel::dsl::exists(contacts.filter(contact_id.eq(id)))).get_result(conn)?;
+
+    Ok(exists)
+}
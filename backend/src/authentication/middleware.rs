@@ -25,6 +25,18 @@ impl Deref for UserId {
     }
 }
 
+/// Requester signed in to the portal
+#[derive(Copy, Clone, Debug)]
+pub struct ContactId(Uuid);
+
+impl Deref for ContactId {
+    type Target = Uuid;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
 pub async fn reject_anonymous_users(
     mut req: ServiceRequest,
     next: Next<impl MessageBody>,
@@ -54,3 +66,34 @@ pub async fn reject_anonymous_users(
         }
     }
 }
+
+/// Guards the requester portal, internal users have to sign in to it like everyone else
+pub async fn reject_anonymous_contacts(
+    mut req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    // Allow requests to sign in
+    if req.path() == "/api/portal/login" || req.path().starts_with("/api/portal/magic-link") {
+        return next.call(req).await;
+    }
+
+    let session = {
+        let (http_request, payload) = req.parts_mut();
+        TypedSession::from_request(http_request, payload).await
+    }?;
+
+    match session
+        .get_contact_id()
+        .map_err(actix_web::error::ErrorInternalServerError)?
+    {
+        Some(contact_id) => {
+            req.extensions_mut().insert(ContactId(contact_id));
+            next.call(req).await
+        }
+        None => {
+            let response = HttpResponse::Unauthorized().finish();
+            let e = anyhow::anyhow!("Not signed in to the portal");
+            Err(InternalError::from_response(e, response).into())
+        }
+    }
+}
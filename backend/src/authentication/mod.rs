@@ -1,7 +1,10 @@
+mod access;
 mod middleware;
 mod password;
-pub use middleware::reject_anonymous_users;
-pub use middleware::UserId;
+pub use access::{forbidden, CurrentContact, CurrentUser};
+pub use middleware::{reject_anonymous_contacts, reject_anonymous_users};
+pub use middleware::{ContactId, UserId};
 pub use password::{
-    check_password_reqs, compute_password_hash, validate_credentials, AuthError, Credentials,
+    check_password_reqs, compute_password_hash, validate_credentials, verify_password_hash,
+    AuthError, Credentials,
 };
@@ -71,7 +71,7 @@ pub async fn validate_credentials(
         .map_err(AuthError::InvalidCredentials)
 }
 
-fn verify_password_hash(
+pub fn verify_password_hash(
     password_candidate: Secret<String>,
     rec_expected_password_hash: Secret<String>,
 ) -> Result<(), AuthError> {
@@ -0,0 +1,41 @@
+//! Background sweep for automation rules triggered by idle tickets.
+//!
+//! Rules triggered by ticket changes run right after the change, tickets nobody touches
+//! are picked up here.
+
+use std::time::Duration;
+
+use actix_web::web;
+
+use crate::handlers::automation::{run_idle, send_outcome};
+use crate::live::Broadcaster;
+use crate::mail::Mailer;
+use crate::DbPool;
+
+const SWEEP_INTERVAL_SECONDS: u64 = 300;
+
+/// Starts looking for idle tickets in the background
+pub fn spawn_sweeper(pool: DbPool, mailer: Mailer, broadcaster: web::Data<Broadcaster>) {
+    actix_rt::spawn(async move {
+        let mut interval = actix_rt::time::interval(Duration::from_secs(SWEEP_INTERVAL_SECONDS));
+        loop {
+            interval.tick().await;
+
+            let sweep_pool = pool.clone();
+            let result = web::block(move || {
+                let mut conn = sweep_pool.get()?;
+                run_idle(&mut conn)
+            })
+            .await;
+            match result {
+                Ok(Ok(outcomes)) => {
+                    for (ticket_id, outcome) in outcomes {
+                        send_outcome(&pool, &mailer, &broadcaster, ticket_id, outcome);
+                    }
+                }
+                Ok(Err(e)) => log::error!("Automation sweep failed: {}", e),
+                Err(e) => log::error!("Automation sweep failed: {}", e),
+            }
+        }
+    });
+}
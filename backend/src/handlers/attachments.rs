@@ -0,0 +1,545 @@
+use super::super::DbPool;
+
+use actix_multipart::{
+    form::{MultipartForm, MultipartFormConfig},
+    MultipartError,
+};
+use actix_web::{
+    delete,
+    error::PayloadError,
+    get,
+    http::header::{self, ContentDisposition, DispositionParam, DispositionType},
+    post, web, Error, HttpResponse,
+};
+use diesel::prelude::*;
+use shared::models::{
+    attachments::{is_allowed_mime_type, is_image, MAX_ATTACHMENT_SIZE},
+    live::LiveEvent,
+    response::Response,
+    users::Role,
+};
+use uuid::Uuid;
+
+use super::notes::fetch_note;
+use super::tickets::{check_ticket_access, ticket_exists};
+use crate::authentication::CurrentUser;
+use crate::live::Broadcaster;
+use crate::models::{
+    attachments::{Attachment, AttachmentUpload, NewAttachment},
+    SuccessResponse,
+};
+use crate::storage::Storage;
+use crate::utils::sanitize_filename;
+
+type DbError = Box<dyn std::error::Error + Send + Sync>;
+
+/// What an attachment belongs to
+#[derive(Clone, Copy, Debug)]
+pub enum AttachmentOwner {
+    Ticket(i32),
+    /// Notes are always on a ticket, the attachment is listed with the ticket as well
+    Note(i32, Uuid),
+    Document(Uuid),
+}
+
+/// Uploads are read into memory, the limits keep that to one file of the maximum size
+pub fn form_config() -> MultipartFormConfig {
+    MultipartFormConfig::default()
+        .total_limit(MAX_ATTACHMENT_SIZE)
+        .memory_limit(MAX_ATTACHMENT_SIZE)
+        .error_handler(|err, _| match err {
+            MultipartError::Payload(PayloadError::Overflow) => {
+                actix_web::error::ErrorPayloadTooLarge(format!(
+                    "File is too large, max size is {} MB",
+                    MAX_ATTACHMENT_SIZE / (1024 * 1024)
+                ))
+            }
+            err => actix_web::error::ErrorBadRequest(err.to_string()),
+        })
+}
+
+/// Attachments of a ticket, including the ones added to its notes
+#[get("/tickets/{id}/attachments")]
+async fn ticket_attachments(
+    id: web::Path<i32>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    check_ticket_access(&pool, *id, &caller).await?;
+    let public_only = !caller.role.can_view();
+
+    let attachments = web::block(move || {
+        let mut conn = pool.get()?;
+        let id = id.into_inner();
+        let mut found = find_by_owner(AttachmentOwner::Ticket(id), &mut conn)?;
+        //Files on internal notes are hidden from requesters along with the notes
+        if public_only {
+            let internal = internal_note_ids(id, &mut conn)?;
+            found.retain(|attachment| {
+                !attachment
+                    .note_id
+                    .is_some_and(|note| internal.contains(&note))
+            });
+        }
+        Ok::<_, DbError>(found)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(attachments))
+}
+
+#[post("/tickets/{id}/attachments")]
+async fn add_ticket_attachment(
+    id: web::Path<i32>,
+    form: MultipartForm<AttachmentUpload>,
+    pool: web::Data<DbPool>,
+    storage: web::Data<dyn Storage>,
+    broadcaster: web::Data<Broadcaster>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::can_create_tickets)?;
+    let id = id.into_inner();
+    check_ticket_access(&pool, id, &caller).await?;
+
+    let exists = {
+        let pool = pool.clone();
+        web::block(move || {
+            let mut conn = pool.get()?;
+            ticket_exists(id, &mut conn)
+        })
+        .await?
+        .map_err(actix_web::error::ErrorInternalServerError)?
+    };
+    if !exists {
+        return Err(actix_web::error::ErrorNotFound("Ticket not found"));
+    }
+
+    let result = upload(
+        AttachmentOwner::Ticket(id),
+        form.into_inner(),
+        pool,
+        storage,
+        &caller,
+    )
+    .await?;
+    if result.success {
+        broadcaster.broadcast(LiveEvent::TicketChanged {
+            ticket_id: id,
+            user_id: Some(caller.user_id),
+        });
+    }
+
+    Ok(HttpResponse::Ok().json(result))
+}
+
+/// Anyone who can edit the note can attach files to it
+#[post("/notes/{id}/attachments")]
+async fn add_note_attachment(
+    id: web::Path<Uuid>,
+    form: MultipartForm<AttachmentUpload>,
+    pool: web::Data<DbPool>,
+    storage: web::Data<dyn Storage>,
+    broadcaster: web::Data<Broadcaster>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    let note = fetch_note(&pool, *id).await?;
+    check_ticket_access(&pool, note.ticket, &caller).await?;
+    if note.owner != Some(caller.user_id) {
+        caller.ensure(Role::can_edit)?;
+    }
+
+    let result = upload(
+        AttachmentOwner::Note(note.ticket, note.note_id),
+        form.into_inner(),
+        pool,
+        storage,
+        &caller,
+    )
+    .await?;
+    if result.success {
+        broadcaster.broadcast(LiveEvent::NotesChanged {
+            ticket_id: note.ticket,
+            user_id: Some(caller.user_id),
+        });
+    }
+
+    Ok(HttpResponse::Ok().json(result))
+}
+
+#[get("/documents/{id}/attachments")]
+async fn document_attachments(
+    id: web::Path<Uuid>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::can_view)?;
+
+    let attachments = web::block(move || {
+        let mut conn = pool.get()?;
+        find_by_owner(AttachmentOwner::Document(id.into_inner()), &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(attachments))
+}
+
+#[post("/documents/{id}/attachments")]
+async fn add_document_attachment(
+    id: web::Path<Uuid>,
+    form: MultipartForm<AttachmentUpload>,
+    pool: web::Data<DbPool>,
+    storage: web::Data<dyn Storage>,
+    broadcaster: web::Data<Broadcaster>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::can_edit)?;
+    let id = id.into_inner();
+
+    let exists = {
+        let pool = pool.clone();
+        web::block(move || {
+            let mut conn = pool.get()?;
+            document_exists(id, &mut conn)
+        })
+        .await?
+        .map_err(actix_web::error::ErrorInternalServerError)?
+    };
+    if !exists {
+        return Err(actix_web::error::ErrorNotFound("Document not found"));
+    }
+
+    let result = upload(
+        AttachmentOwner::Document(id),
+        form.into_inner(),
+        pool,
+        storage,
+        &caller,
+    )
+    .await?;
+    if result.success {
+        broadcaster.broadcast(LiveEvent::DocumentChanged {
+            document_id: id,
+            user_id: Some(caller.user_id),
+        });
+    }
+
+    Ok(HttpResponse::Ok().json(result))
+}
+
+/// Images are shown inline, everything else is downloaded.
+/// Nothing served here is allowed to run scripts, whatever the browser thinks the file is.
+#[get("/attachments/{id}")]
+async fn download(
+    id: web::Path<Uuid>,
+    pool: web::Data<DbPool>,
+    storage: web::Data<dyn Storage>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    let attachment = fetch_attachment(&pool, *id).await?;
+    check_attachment_access(&pool, &attachment, &caller).await?;
+
+    let key = attachment.storage_key.clone();
+    let contents = web::block(move || storage.get(&key)).await?.map_err(|e| {
+        log::error!(
+            "Unable to read attachment {}: {}",
+            attachment.attachment_id,
+            e
+        );
+        actix_web::error::ErrorNotFound("File not found")
+    })?;
+
+    let disposition = ContentDisposition {
+        disposition: if is_image(&attachment.mime_type) {
+            DispositionType::Inline
+        } else {
+            DispositionType::Attachment
+        },
+        parameters: vec![DispositionParam::Filename(attachment.filename)],
+    };
+
+    Ok(HttpResponse::Ok()
+        .content_type(attachment.mime_type)
+        .insert_header(disposition)
+        .insert_header((header::X_CONTENT_TYPE_OPTIONS, "nosniff"))
+        .insert_header((
+            header::CONTENT_SECURITY_POLICY,
+            "default-src 'none'; sandbox",
+        ))
+        .insert_header((header::CACHE_CONTROL, "private, max-age=3600"))
+        .body(contents))
+}
+
+/// Uploaders can remove their own files, agents can remove any
+#[delete("/attachments/{id}")]
+async fn delete(
+    id: web::Path<Uuid>,
+    pool: web::Data<DbPool>,
+    storage: web::Data<dyn Storage>,
+    broadcaster: web::Data<Broadcaster>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    let attachment = fetch_attachment(&pool, *id).await?;
+    check_attachment_access(&pool, &attachment, &caller).await?;
+    if attachment.uploaded_by != Some(caller.user_id) {
+        caller.ensure(Role::can_edit)?;
+    }
+
+    let key = attachment.storage_key.clone();
+    web::block(move || {
+        let mut conn = pool.get()?;
+        delete_attachment(id.into_inner(), &mut conn)?;
+        delete_blobs(storage.as_ref(), &[key]);
+        Ok::<_, DbError>(())
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let user_id = Some(caller.user_id);
+    match (
+        attachment.ticket_id,
+        attachment.note_id,
+        attachment.document_id,
+    ) {
+        (Some(ticket_id), Some(_), _) => {
+            broadcaster.broadcast(LiveEvent::NotesChanged { ticket_id, user_id })
+        }
+        (Some(ticket_id), None, _) => {
+            broadcaster.broadcast(LiveEvent::TicketChanged { ticket_id, user_id })
+        }
+        (None, _, Some(document_id)) => broadcaster.broadcast(LiveEvent::DocumentChanged {
+            document_id,
+            user_id,
+        }),
+        _ => (),
+    }
+
+    let response = SuccessResponse {
+        success: true,
+        message: "Attachment deleted".to_string(),
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Stores the file and records it, the type is guessed from the file name
+async fn upload(
+    owner: AttachmentOwner,
+    form: AttachmentUpload,
+    pool: web::Data<DbPool>,
+    storage: web::Data<dyn Storage>,
+    caller: &CurrentUser,
+) -> Result<Response<Attachment>, Error> {
+    let filename = sanitize_filename(form.file.file_name.as_deref().unwrap_or("attachment"));
+    let contents = form.file.data;
+
+    let mime_type = match check_file(&filename, contents.len()) {
+        Ok(mime_type) => mime_type,
+        Err(message) => {
+            return Ok(Response {
+                success: false,
+                message: Some(message),
+                data: None,
+            })
+        }
+    };
+    let new_attachment = new_attachment(
+        Uuid::new_v4(),
+        owner,
+        filename,
+        mime_type,
+        contents.len(),
+        Some(caller.user_id),
+    );
+
+    let attachment = web::block(move || {
+        let mut conn = pool.get()?;
+        save_attachment(storage.as_ref(), new_attachment, &contents, &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(Response {
+        success: true,
+        message: None,
+        data: Some(attachment),
+    })
+}
+
+/// Checks the size and type of a file before it is stored, returns the type guessed
+/// from the file name
+pub fn check_file(filename: &str, size: usize) -> Result<String, String> {
+    if size == 0 {
+        return Err("File is empty".to_string());
+    }
+    if size > MAX_ATTACHMENT_SIZE {
+        return Err(format!(
+            "File is too large, max size is {} MB",
+            MAX_ATTACHMENT_SIZE / (1024 * 1024)
+        ));
+    }
+    let mime_type = mime_guess::from_path(filename)
+        .first_raw()
+        .unwrap_or("application/octet-stream");
+    if !is_allowed_mime_type(mime_type) {
+        return Err(format!("Files of type {} cannot be uploaded", mime_type));
+    }
+
+    Ok(mime_type.to_string())
+}
+
+/// The blob is stored under the id of the attachment
+pub fn new_attachment(
+    attachment_id: Uuid,
+    owner: AttachmentOwner,
+    filename: String,
+    mime_type: String,
+    size: usize,
+    uploaded_by: Option<Uuid>,
+) -> NewAttachment {
+    let (ticket_id, note_id, document_id) = match owner {
+        AttachmentOwner::Ticket(id) => (Some(id), None, None),
+        AttachmentOwner::Note(id, note) => (Some(id), Some(note), None),
+        AttachmentOwner::Document(document) => (None, None, Some(document)),
+    };
+
+    NewAttachment {
+        attachment_id,
+        ticket_id,
+        note_id,
+        document_id,
+        filename,
+        mime_type,
+        size: size as i64,
+        storage_key: attachment_id.to_string(),
+        uploaded_by,
+    }
+}
+
+/// Writes the blob first, it is removed again when the row cannot be added
+pub fn save_attachment(
+    storage: &dyn Storage,
+    new_attachment: NewAttachment,
+    contents: &[u8],
+    conn: &mut PgConnection,
+) -> Result<Attachment, DbError> {
+    use crate::schema::attachments::dsl::*;
+
+    storage.put(&new_attachment.storage_key, contents)?;
+    let result = diesel::insert_into(attachments)
+        .values(&new_attachment)
+        .get_result::<Attachment>(conn);
+    if result.is_err() {
+        delete_blobs(storage, &[new_attachment.storage_key]);
+    }
+
+    Ok(result?)
+}
+
+/// Storage keys of everything attached to a ticket (with its notes), a note or a document.
+/// Read before deleting the owner, the rows go away with it but the blobs have to be removed.
+pub fn find_storage_keys(
+    owner: AttachmentOwner,
+    conn: &mut PgConnection,
+) -> Result<Vec<String>, DbError> {
+    let keys = find_by_owner(owner, conn)?
+        .into_iter()
+        .map(|attachment| attachment.storage_key)
+        .collect();
+
+    Ok(keys)
+}
+
+/// Failures are only logged, a left over blob does no harm
+pub fn delete_blobs(storage: &dyn Storage, keys: &[String]) {
+    for key in keys {
+        if let Err(e) = storage.delete(key) {
+            log::error!("Unable to delete attachment blob {}: {}", key, e);
+        }
+    }
+}
+
+async fn fetch_attachment(pool: &web::Data<DbPool>, id: Uuid) -> Result<Attachment, Error> {
+    let pool = pool.clone();
+    web::block(move || {
+        let mut conn = pool.get()?;
+        get_attachment_by_id(id, &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorNotFound)
+}
+
+/// Ticket attachments follow the ticket, document attachments need access to the wiki
+async fn check_attachment_access(
+    pool: &web::Data<DbPool>,
+    attachment: &Attachment,
+    caller: &CurrentUser,
+) -> Result<(), Error> {
+    match attachment.ticket_id {
+        Some(id) => check_ticket_access(pool, id, caller).await?,
+        None => caller.ensure(Role::can_view)?,
+    }
+    if let (Some(note_id), false) = (attachment.note_id, caller.role.can_view()) {
+        if !fetch_note(pool, note_id).await?.is_public {
+            return Err(actix_web::error::ErrorNotFound("Attachment not found"));
+        }
+    }
+
+    Ok(())
+}
+
+fn internal_note_ids(ticket_id: i32, conn: &mut PgConnection) -> Result<Vec<Uuid>, DbError> {
+    use crate::schema::notes;
+
+    let ids = notes::table
+        .filter(notes::ticket.eq(ticket_id))
+        .filter(notes::is_public.eq(false))
+        .select(notes::note_id)
+        .load::<Uuid>(conn)?;
+
+    Ok(ids)
+}
+
+fn get_attachment_by_id(id: Uuid, conn: &mut PgConnection) -> Result<Attachment, DbError> {
+    use crate::schema::attachments::dsl::*;
+
+    let result = attachments.find(id).first::<Attachment>(conn)?;
+
+    Ok(result)
+}
+
+fn find_by_owner(
+    owner: AttachmentOwner,
+    conn: &mut PgConnection,
+) -> Result<Vec<Attachment>, DbError> {
+    use crate::schema::attachments::dsl::*;
+
+    let query = attachments.into_boxed();
+    let query = match owner {
+        AttachmentOwner::Ticket(id) => query.filter(ticket_id.eq(id)),
+        AttachmentOwner::Note(_, note) => query.filter(note_id.eq(note)),
+        AttachmentOwner::Document(document) => query.filter(document_id.eq(document)),
+    };
+    let items = query
+        .order((created_at.asc(), filename.asc()))
+        .load::<Attachment>(conn)?;
+
+    Ok(items)
+}
+
+fn document_exists(id: Uuid, conn: &mut PgConnection) -> Result<bool, DbError> {
+    use crate::schema::documents::dsl::*;
+
+    let exists = diesel::select(diesel::dsl::exists(documents.find(id))).get_result(conn)?;
+
+    Ok(exists)
+}
+
+fn delete_attachment(id: Uuid, conn: &mut PgConnection) -> Result<usize, DbError> {
+    use crate::schema::attachments::dsl::*;
+
+    let count = diesel::delete(attachments.find(id)).execute(conn)?;
+
+    Ok(count)
+}
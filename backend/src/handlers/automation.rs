@@ -0,0 +1,812 @@
+use super::super::DbPool;
+
+use actix_web::{delete, get, post, put, web, Error, HttpResponse};
+use diesel::prelude::*;
+use shared::models::{
+    automation::{
+        AutomationAction, AutomationCondition, AutomationTrigger, ConditionField, MAX_IDLE_HOURS,
+        TRIGGER_EVENT_TYPES,
+    },
+    live::LiveEvent,
+    notifications::AUTOMATION_NOTICE,
+    response::Response,
+    tickets::TicketEventType,
+    users::Role,
+    webhooks::WebhookEvent,
+    MAX_TITLE_LENGTH,
+};
+use uuid::Uuid;
+
+use super::bulk::{rejectable, Rejected};
+use super::links::closed_as_duplicate;
+use super::notifications::{notify_followers, notify_note_followers};
+use super::sla::update_sla;
+use super::tasks::check_required_tasks;
+use super::webhooks::{publish, WebhookSubject};
+use super::workflows::check_ticket_workflow;
+use crate::authentication::CurrentUser;
+use crate::live::Broadcaster;
+use crate::mail::{notify, Mailer};
+use crate::models::{
+    automation::{
+        AutomationRule, AutomationRulePayload, AutomationRuleRepresentation, AutomationRun,
+        NewAutomationRule, NewAutomationRun,
+    },
+    notes::NewNote,
+    notifications::NewNotification,
+    tickets::{NewTicketEvent, Ticket, UpdateTicket},
+    SuccessResponse,
+};
+
+type DbError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Runs shown in the execution log of a rule
+const LOG_LENGTH: i64 = 100;
+
+/// Trigger of runs started by the periodic sweep
+const IDLE_TRIGGER: &str = "idle";
+
+/// What the rules did to a ticket, sent out once they are done
+#[derive(Default)]
+pub struct AutomationOutcome {
+    pub events: Vec<NewTicketEvent>,
+    pub notes: Vec<Uuid>,
+}
+
+impl AutomationOutcome {
+    fn is_empty(&self) -> bool {
+        self.events.is_empty() && self.notes.is_empty()
+    }
+}
+
+#[get("/automation_rules")]
+async fn index(pool: web::Data<DbPool>, caller: CurrentUser) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::is_admin)?;
+
+    let rules = web::block(move || {
+        let mut conn = pool.get()?;
+        find_all(&mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(rules))
+}
+
+#[post("/automation_rules")]
+async fn create(
+    pool: web::Data<DbPool>,
+    payload: web::Json<AutomationRulePayload>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::is_admin)?;
+
+    let mut payload = payload.into_inner();
+    if let Some(message) = validate(&mut payload) {
+        return Ok(HttpResponse::Ok().json(failure(message)));
+    }
+
+    let result = web::block(move || {
+        let mut conn = pool.get()?;
+        save_rule(None, payload, Some(caller.user_id), &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(respond(result)))
+}
+
+#[put("/automation_rules/{id}")]
+async fn update(
+    id: web::Path<Uuid>,
+    payload: web::Json<AutomationRulePayload>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::is_admin)?;
+
+    let mut payload = payload.into_inner();
+    if let Some(message) = validate(&mut payload) {
+        return Ok(HttpResponse::Ok().json(failure(message)));
+    }
+
+    let result = web::block(move || {
+        let mut conn = pool.get()?;
+        save_rule(Some(id.into_inner()), payload, None, &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(respond(result)))
+}
+
+/// The execution log goes with the rule
+#[delete("/automation_rules/{id}")]
+async fn delete(
+    id: web::Path<Uuid>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::is_admin)?;
+
+    let result = web::block(move || {
+        let mut conn = pool.get()?;
+        delete_rule(id.into_inner(), &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let response = if result > 0 {
+        SuccessResponse {
+            success: true,
+            message: "Automation rule deleted".to_string(),
+        }
+    } else {
+        SuccessResponse {
+            success: false,
+            message: "Automation rule not found".to_string(),
+        }
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Execution log of a rule, latest first
+#[get("/automation_rules/{id}/runs")]
+async fn runs(
+    id: web::Path<Uuid>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::is_admin)?;
+
+    let log = web::block(move || {
+        let mut conn = pool.get()?;
+        find_runs(id.into_inner(), &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(log))
+}
+
+fn failure(message: String) -> Response<AutomationRuleRepresentation> {
+    Response {
+        success: false,
+        message: Some(message),
+        data: None,
+    }
+}
+
+fn respond(
+    result: Result<AutomationRuleRepresentation, String>,
+) -> Response<AutomationRuleRepresentation> {
+    match result {
+        Ok(rule) => Response {
+            success: true,
+            message: None,
+            data: Some(rule),
+        },
+        Err(message) => failure(message),
+    }
+}
+
+/// Checks what can be checked without the database, also tidies up the payload
+fn validate(payload: &mut AutomationRulePayload) -> Option<String> {
+    payload.name = payload.name.trim().to_string();
+    if payload.name.is_empty() {
+        return Some("Rule name is required".to_string());
+    }
+    if payload.name.len() > MAX_TITLE_LENGTH {
+        return Some(format!(
+            "Rule name is too long, max length is {}",
+            MAX_TITLE_LENGTH
+        ));
+    }
+
+    match &payload.trigger {
+        AutomationTrigger::TicketEvent { event_type }
+            if !TRIGGER_EVENT_TYPES
+                .iter()
+                .any(|allowed| allowed.to_string() == *event_type) =>
+        {
+            return Some(format!("Rules cannot be triggered by {}", event_type));
+        }
+        AutomationTrigger::Idle { hours } if *hours == 0 => {
+            return Some("Tickets have to be idle for at least an hour".to_string());
+        }
+        AutomationTrigger::Idle { hours } if *hours > MAX_IDLE_HOURS => {
+            return Some(format!(
+                "Tickets can be idle for at most {} hours",
+                MAX_IDLE_HOURS
+            ));
+        }
+        _ => (),
+    }
+
+    for condition in &mut payload.conditions {
+        condition.value = condition.value.trim().to_string();
+    }
+
+    if payload.actions.is_empty() {
+        return Some("A rule needs at least one action".to_string());
+    }
+    for action in &payload.actions {
+        let missing = match action {
+            AutomationAction::SetStatus { status } => status.trim().is_empty(),
+            AutomationAction::SetPriority { priority } => priority.trim().is_empty(),
+            AutomationAction::AddNote { text, .. } => text.trim().is_empty(),
+            AutomationAction::Notify { message, .. } => message.trim().is_empty(),
+            AutomationAction::Assign { .. } => false,
+        };
+        if missing {
+            return Some("Every action needs a value".to_string());
+        }
+    }
+
+    None
+}
+
+/// Statuses, priorities and users named by the actions have to exist
+fn check_actions(
+    actions: &[AutomationAction],
+    conn: &mut PgConnection,
+) -> Result<Result<(), String>, DbError> {
+    use crate::schema::users;
+
+    for action in actions {
+        let checked = match action {
+            AutomationAction::SetStatus { status } => {
+                check_ticket_workflow(None, Some(status), None, conn)?
+            }
+            AutomationAction::SetPriority { priority } => {
+                check_ticket_workflow(None, None, Some(priority), conn)?
+            }
+            AutomationAction::Assign { user_id: Some(user) }
+            | AutomationAction::Notify {
+                user_id: Some(user),
+                ..
+            } => {
+                let exists = diesel::select(diesel::dsl::exists(users::table.find(user)))
+                    .get_result::<bool>(conn)?;
+                if exists {
+                    Ok(())
+                } else {
+                    Err("Unknown user".to_string())
+                }
+            }
+            _ => Ok(()),
+        };
+        if checked.is_err() {
+            return Ok(checked);
+        }
+    }
+
+    Ok(Ok(()))
+}
+
+fn save_rule(
+    id: Option<Uuid>,
+    payload: AutomationRulePayload,
+    creator: Option<Uuid>,
+    conn: &mut PgConnection,
+) -> Result<Result<AutomationRuleRepresentation, String>, DbError> {
+    use crate::schema::automation_rules;
+
+    if let Err(message) = check_actions(&payload.actions, conn)? {
+        return Ok(Err(message));
+    }
+
+    let mut query = automation_rules::table
+        .filter(automation_rules::name.eq(&payload.name))
+        .into_boxed();
+    if let Some(id) = id {
+        query = query.filter(automation_rules::rule_id.ne(id));
+    }
+    if query.count().get_result::<i64>(conn)? > 0 {
+        return Ok(Err(format!("A rule named {} already exists", payload.name)));
+    }
+
+    let values = NewAutomationRule {
+        name: payload.name,
+        enabled: payload.enabled,
+        trigger: serde_json::to_string(&payload.trigger)?,
+        conditions: serde_json::to_string(&payload.conditions)?,
+        actions: serde_json::to_string(&payload.actions)?,
+        updated_at: chrono::Utc::now().naive_utc(),
+    };
+
+    let saved = match id {
+        Some(id) => diesel::update(automation_rules::table.find(id))
+            .set(&values)
+            .get_result::<AutomationRule>(conn)
+            .optional()?,
+        None => diesel::insert_into(automation_rules::table)
+            .values((&values, automation_rules::created_by.eq(creator)))
+            .get_result::<AutomationRule>(conn)
+            .optional()?,
+    };
+
+    match saved {
+        Some(saved) => Ok(Ok(represent(saved)?)),
+        None => Ok(Err("Automation rule not found".to_string())),
+    }
+}
+
+fn delete_rule(id: Uuid, conn: &mut PgConnection) -> Result<usize, DbError> {
+    use crate::schema::automation_rules::dsl::*;
+
+    let result = diesel::delete(automation_rules.find(id)).execute(conn)?;
+
+    Ok(result)
+}
+
+/// Follows the rename of a status or priority in the conditions and actions of every rule
+pub fn rename_in_rules(
+    field: ConditionField,
+    old: &str,
+    new: &str,
+    conn: &mut PgConnection,
+) -> Result<(), DbError> {
+    use crate::schema::automation_rules::dsl::*;
+
+    for rule in automation_rules.load::<AutomationRule>(conn)? {
+        let mut rule_conditions: Vec<AutomationCondition> = serde_json::from_str(&rule.conditions)?;
+        let mut rule_actions: Vec<AutomationAction> = serde_json::from_str(&rule.actions)?;
+        let mut renamed = false;
+        for condition in rule_conditions.iter_mut() {
+            if condition.field == field && condition.value == old {
+                condition.value = new.to_string();
+                renamed = true;
+            }
+        }
+        for action in rule_actions.iter_mut() {
+            let value = match (field, action) {
+                (ConditionField::Status, AutomationAction::SetStatus { status }) => status,
+                (ConditionField::Priority, AutomationAction::SetPriority { priority }) => priority,
+                _ => continue,
+            };
+            if value == old {
+                *value = new.to_string();
+                renamed = true;
+            }
+        }
+
+        if renamed {
+            diesel::update(automation_rules.find(rule.rule_id))
+                .set((
+                    conditions.eq(serde_json::to_string(&rule_conditions)?),
+                    actions.eq(serde_json::to_string(&rule_actions)?),
+                ))
+                .execute(conn)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn represent(rule: AutomationRule) -> Result<AutomationRuleRepresentation, DbError> {
+    Ok(AutomationRuleRepresentation {
+        rule_id: rule.rule_id,
+        name: rule.name,
+        enabled: rule.enabled,
+        trigger: serde_json::from_str(&rule.trigger)?,
+        conditions: serde_json::from_str(&rule.conditions)?,
+        actions: serde_json::from_str(&rule.actions)?,
+        created_at: rule.created_at,
+    })
+}
+
+/// Rules in the order they run
+fn find_all(conn: &mut PgConnection) -> Result<Vec<AutomationRuleRepresentation>, DbError> {
+    use crate::schema::automation_rules::dsl::*;
+
+    automation_rules
+        .order(created_at.asc())
+        .load::<AutomationRule>(conn)?
+        .into_iter()
+        .map(represent)
+        .collect()
+}
+
+fn find_enabled(conn: &mut PgConnection) -> Result<Vec<AutomationRuleRepresentation>, DbError> {
+    find_all(conn).map(|rules| rules.into_iter().filter(|rule| rule.enabled).collect())
+}
+
+fn find_runs(id: Uuid, conn: &mut PgConnection) -> Result<Vec<AutomationRun>, DbError> {
+    use crate::schema::automation_runs::dsl::*;
+
+    let log = automation_runs
+        .filter(rule_id.eq(id))
+        .order(created_at.desc())
+        .limit(LOG_LENGTH)
+        .load::<AutomationRun>(conn)?;
+
+    Ok(log)
+}
+
+/// Runs the rules triggered by a ticket being created or updated, then sends out what they
+/// changed. Failing rules are logged and do not affect the change that triggered them.
+/// Returns whether the rules changed anything.
+pub async fn automate(
+    pool: &DbPool,
+    mailer: &Mailer,
+    broadcaster: &Broadcaster,
+    ticket_id: i32,
+    triggers: Vec<AutomationTrigger>,
+) -> bool {
+    if triggers.is_empty() {
+        return false;
+    }
+
+    let run_pool = pool.clone();
+    let result = web::block(move || {
+        let mut conn = run_pool.get()?;
+        run_triggered(ticket_id, &triggers, &mut conn)
+    })
+    .await;
+    let outcome = match result {
+        Ok(Ok(outcome)) => outcome,
+        Ok(Err(e)) => {
+            log::error!("Automation rules failed on ticket {}: {}", ticket_id, e);
+            return false;
+        }
+        Err(e) => {
+            log::error!("Automation rules failed on ticket {}: {}", ticket_id, e);
+            return false;
+        }
+    };
+
+    let changed = !outcome.is_empty();
+    send_outcome(pool, mailer, broadcaster, ticket_id, outcome);
+
+    changed
+}
+
+/// Triggers of the events recorded by a ticket update
+pub fn event_triggers(events: &[NewTicketEvent]) -> Vec<AutomationTrigger> {
+    events
+        .iter()
+        .map(|event| AutomationTrigger::TicketEvent {
+            event_type: event.event_type.clone(),
+        })
+        .collect()
+}
+
+/// Broadcasts and mails what the rules did to a ticket, rules act as nobody in particular
+pub fn send_outcome(
+    pool: &DbPool,
+    mailer: &Mailer,
+    broadcaster: &Broadcaster,
+    ticket_id: i32,
+    outcome: AutomationOutcome,
+) {
+    if outcome.is_empty() {
+        return;
+    }
+
+    broadcaster.broadcast(LiveEvent::TicketChanged {
+        ticket_id,
+        user_id: None,
+    });
+    if !outcome.notes.is_empty() {
+        broadcaster.broadcast(LiveEvent::NotesChanged {
+            ticket_id,
+            user_id: None,
+        });
+    }
+    for note_id in outcome.notes {
+        publish(
+            pool.clone(),
+            WebhookEvent::NoteCreated,
+            WebhookSubject::Note(note_id),
+        );
+        notify::new_note(pool.clone(), mailer.clone(), note_id);
+    }
+    if !outcome.events.is_empty() {
+        publish(
+            pool.clone(),
+            WebhookEvent::TicketUpdated,
+            WebhookSubject::Ticket(ticket_id, outcome.events.clone()),
+        );
+    }
+    notify::ticket_events(
+        pool.clone(),
+        mailer.clone(),
+        ticket_id,
+        outcome.events,
+        notify::Actor::Automation,
+    );
+}
+
+fn run_triggered(
+    ticket_id: i32,
+    triggers: &[AutomationTrigger],
+    conn: &mut PgConnection,
+) -> Result<AutomationOutcome, DbError> {
+    let mut outcome = AutomationOutcome::default();
+    for rule in find_enabled(conn)? {
+        if !triggers.contains(&rule.trigger) {
+            continue;
+        }
+        let trigger = match &rule.trigger {
+            AutomationTrigger::TicketEvent { event_type } => event_type.clone(),
+            trigger => trigger.kind().to_string(),
+        };
+        if let Some(done) = run_rule(&rule, ticket_id, &trigger, conn)? {
+            outcome.events.extend(done.events);
+            outcome.notes.extend(done.notes);
+        }
+    }
+
+    Ok(outcome)
+}
+
+/// Runs the rules for tickets that were left alone for too long, each rule runs once
+/// until the ticket is updated again. Returns what the rules did to each ticket.
+pub fn run_idle(conn: &mut PgConnection) -> Result<Vec<(i32, AutomationOutcome)>, DbError> {
+    use crate::schema::{automation_runs, ticket_statuses, tickets};
+
+    let closed = ticket_statuses::table
+        .filter(ticket_statuses::is_closed.eq(true))
+        .select(ticket_statuses::name)
+        .load::<String>(conn)?;
+
+    let now = chrono::Utc::now().naive_utc();
+    let mut outcomes: Vec<(i32, AutomationOutcome)> = vec![];
+    for rule in find_enabled(conn)? {
+        let AutomationTrigger::Idle { hours } = rule.trigger else {
+            continue;
+        };
+        let Some(idle_since) = now.checked_sub_signed(chrono::Duration::hours(hours as i64)) else {
+            continue;
+        };
+        let already_run = automation_runs::table
+            .filter(automation_runs::rule_id.eq(rule.rule_id))
+            .filter(automation_runs::ticket_id.eq(tickets::ticket_id.nullable()))
+            .filter(automation_runs::created_at.ge(tickets::updated_at));
+        let idle = tickets::table
+            .filter(tickets::updated_at.lt(idle_since))
+            .filter(tickets::status.ne_all(&closed))
+            .filter(diesel::dsl::not(diesel::dsl::exists(already_run)))
+            .select(tickets::ticket_id)
+            .order(tickets::ticket_id.asc())
+            .load::<i32>(conn)?;
+
+        for ticket_id in idle {
+            let Some(done) = run_rule(&rule, ticket_id, IDLE_TRIGGER, conn)? else {
+                continue;
+            };
+            match outcomes.iter_mut().find(|(id, _)| *id == ticket_id) {
+                Some((_, outcome)) => {
+                    outcome.events.extend(done.events);
+                    outcome.notes.extend(done.notes);
+                }
+                None => outcomes.push((ticket_id, done)),
+            }
+        }
+    }
+
+    Ok(outcomes)
+}
+
+fn matches(condition: &AutomationCondition, ticket: &Ticket) -> bool {
+    let id = |id: Option<Uuid>| id.map(|id| id.to_string()).unwrap_or_default();
+    let actual = match condition.field {
+        ConditionField::Title => ticket.title.clone(),
+        ConditionField::Description => ticket.description.clone(),
+        ConditionField::Status => ticket.status.clone(),
+        ConditionField::Priority => ticket.priority.clone(),
+        ConditionField::Assignee => id(ticket.assignee),
+        ConditionField::Project => id(ticket.project),
+    };
+    condition.operator.matches(&actual, &condition.value)
+}
+
+/// Runs the actions of a rule when its conditions match the ticket as it is now, and logs
+/// the run. A failing action rolls back the whole rule, which is logged as failed, so the
+/// other rules still run.
+fn run_rule(
+    rule: &AutomationRuleRepresentation,
+    ticket_id: i32,
+    trigger: &str,
+    conn: &mut PgConnection,
+) -> Result<Option<AutomationOutcome>, DbError> {
+    use crate::schema::{automation_runs, tickets};
+
+    let Some(ticket) = tickets::table
+        .find(ticket_id)
+        .first::<Ticket>(conn)
+        .optional()?
+    else {
+        return Ok(None);
+    };
+    if !rule
+        .conditions
+        .iter()
+        .all(|condition| matches(condition, &ticket))
+    {
+        return Ok(None);
+    }
+
+    let time = chrono::Utc::now().naive_utc();
+    let result = match rejectable(conn, |conn| run_actions(&rule.actions, ticket, time, conn)) {
+        Ok(result) => result,
+        Err(e) => {
+            log::error!(
+                "Automation rule {} failed on ticket {}: {}",
+                rule.name,
+                ticket_id,
+                e
+            );
+            Err(e.to_string())
+        }
+    };
+    let (success, message, outcome) = match result {
+        Ok((done, outcome)) => (true, done.join(", "), Some(outcome)),
+        Err(message) => (false, message, None),
+    };
+
+    diesel::insert_into(automation_runs::table)
+        .values(NewAutomationRun {
+            rule_id: rule.rule_id,
+            ticket_id: Some(ticket_id),
+            trigger: trigger.to_string(),
+            success,
+            message,
+            created_at: time,
+        })
+        .execute(conn)?;
+
+    Ok(Some(outcome.unwrap_or_default()))
+}
+
+/// Applies the actions in order, returns a description of each one that did something
+fn run_actions(
+    actions: &[AutomationAction],
+    mut ticket: Ticket,
+    time: chrono::NaiveDateTime,
+    conn: &mut PgConnection,
+) -> Result<(Vec<String>, AutomationOutcome), DbError> {
+    use crate::schema::{notes, notifications, ticket_events, tickets, users};
+
+    let id = ticket.ticket_id;
+    let event = |event_type: TicketEventType, event_data: String| NewTicketEvent {
+        event_id: Uuid::new_v4(),
+        ticket_id: id,
+        event_type: event_type.to_string(),
+        event_data,
+        user_id: None,
+        created_at: time,
+    };
+    let mut done = vec![];
+    let mut outcome = AutomationOutcome::default();
+    let mut changes = UpdateTicket::default();
+
+    for action in actions {
+        match action {
+            AutomationAction::SetStatus { status } if *status != ticket.status => {
+                let mut checked =
+                    check_ticket_workflow(Some(&ticket.status), Some(status), None, conn)?;
+                if checked.is_ok() {
+                    checked = check_required_tasks(id, status, conn)?;
+                }
+                if let Err(message) = checked {
+                    return Err(Rejected(message).into());
+                }
+                outcome
+                    .events
+                    .push(event(TicketEventType::StatusUpdated, status.clone()));
+                if let Some(original) = closed_as_duplicate(id, status, conn)? {
+                    outcome.events.push(event(
+                        TicketEventType::ClosedAsDuplicate,
+                        original.to_string(),
+                    ));
+                }
+                done.push(format!("Status changed to {}", status));
+                ticket.status = status.clone();
+                changes.status = Some(status.clone());
+            }
+            AutomationAction::SetPriority { priority } if *priority != ticket.priority => {
+                if let Err(message) = check_ticket_workflow(None, None, Some(priority), conn)? {
+                    return Err(Rejected(message).into());
+                }
+                outcome
+                    .events
+                    .push(event(TicketEventType::PriorityUpdated, priority.clone()));
+                done.push(format!("Priority changed to {}", priority));
+                ticket.priority = priority.clone();
+                changes.priority = Some(priority.clone());
+            }
+            AutomationAction::Assign { user_id } if *user_id != ticket.assignee => {
+                let name = match user_id {
+                    Some(user) => users::table
+                        .find(user)
+                        .select(users::display_name)
+                        .first::<String>(conn)
+                        .optional()?
+                        .ok_or_else(|| Rejected("The assignee no longer exists".to_string()))?,
+                    None => String::new(),
+                };
+                let data = user_id.map(|user| user.to_string()).unwrap_or_default();
+                outcome.events.push(event(TicketEventType::Assigned, data));
+                done.push(match user_id {
+                    Some(_) => format!("Assigned to {}", name),
+                    None => "Unassigned".to_string(),
+                });
+                ticket.assignee = *user_id;
+                changes.assignee = Some(*user_id);
+            }
+            AutomationAction::AddNote { text, is_public } => {
+                let new_note = NewNote {
+                    note_id: Uuid::new_v4(),
+                    ticket: id,
+                    owner: None,
+                    text,
+                    time: 0,
+                    created_at: time,
+                    contact: None,
+                    is_public: *is_public,
+                };
+                diesel::insert_into(notes::table)
+                    .values(&new_note)
+                    .execute(conn)?;
+                notify_note_followers(id, None, new_note.note_id, *is_public, conn)?;
+                outcome.notes.push(new_note.note_id);
+                done.push("Note added".to_string());
+                changes.updated_at = Some(time);
+            }
+            AutomationAction::Notify { user_id, message } => {
+                //Without a user the assignee is notified, if there is one
+                let Some(recipient) = user_id.or(ticket.assignee) else {
+                    continue;
+                };
+                let exists = diesel::select(diesel::dsl::exists(users::table.find(recipient)))
+                    .get_result::<bool>(conn)?;
+                if !exists {
+                    return Err(Rejected("The user no longer exists".to_string()).into());
+                }
+                diesel::insert_into(notifications::table)
+                    .values(NewNotification {
+                        notification_id: Uuid::new_v4(),
+                        user_id: recipient,
+                        ticket_id: id,
+                        event_type: AUTOMATION_NOTICE.to_string(),
+                        event_data: message.clone(),
+                        actor: None,
+                        created_at: time,
+                    })
+                    .execute(conn)?;
+                done.push("Notification sent".to_string());
+            }
+            //Setting a field to its current value does nothing
+            _ => (),
+        }
+    }
+
+    if !outcome.events.is_empty() {
+        changes.updated_at = Some(time);
+    }
+    if changes.updated_at.is_some() {
+        diesel::update(tickets::table.find(id))
+            .set(&changes)
+            .execute(conn)?;
+    }
+    if !outcome.events.is_empty() {
+        diesel::insert_into(ticket_events::table)
+            .values(&outcome.events)
+            .execute(conn)?;
+        update_sla(id, conn)?;
+        let followed = outcome
+            .events
+            .iter()
+            .map(|event| (event.event_type.clone(), event.event_data.clone()))
+            .collect::<Vec<(String, String)>>();
+        notify_followers(id, None, &followed, conn)?;
+    }
+    if done.is_empty() {
+        done.push("Nothing to change".to_string());
+    }
+
+    Ok((done, outcome))
+}
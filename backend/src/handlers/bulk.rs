@@ -0,0 +1,346 @@
+use super::super::DbPool;
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use actix_web::{post, web, Error, HttpResponse};
+use diesel::prelude::*;
+use shared::models::{
+    live::LiveEvent,
+    response::Response,
+    tickets::{TicketEventType, MAX_BULK_TICKETS},
+    users::Role,
+    webhooks::WebhookEvent,
+};
+use uuid::Uuid;
+
+use super::attachments::{delete_blobs, find_storage_keys, AttachmentOwner};
+use super::automation::{automate, event_triggers};
+use super::links::closed_as_duplicate;
+use super::notifications::notify_followers;
+use super::sla::update_sla;
+use super::tags::set_ticket_tag;
+use super::tasks::check_required_tasks;
+use super::webhooks::{publish, WebhookSubject};
+use super::workflows::check_ticket_workflow;
+use crate::authentication::CurrentUser;
+use crate::live::Broadcaster;
+use crate::mail::{notify, Mailer};
+use crate::models::tickets::{NewTicketEvent, Ticket, TicketBulkPayload, UpdateTicket};
+use crate::storage::Storage;
+use crate::utils::parse_uuid;
+
+type DbError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Rolls back a bulk change, the message is shown to the user
+#[derive(Debug)]
+pub struct Rejected(pub String);
+
+impl fmt::Display for Rejected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Rejected {}
+
+/// Runs `change` in a transaction, a `Rejected` error becomes the message for the user
+pub fn rejectable<T, F>(conn: &mut PgConnection, change: F) -> Result<Result<T, String>, DbError>
+where
+    F: FnOnce(&mut PgConnection) -> Result<T, DbError>,
+{
+    match conn.transaction(change) {
+        Ok(result) => Ok(Ok(result)),
+        Err(e) => match e.downcast::<Rejected>() {
+            Ok(rejected) => Ok(Err(rejected.0)),
+            Err(e) => Err(e),
+        },
+    }
+}
+
+/// Applies the same change to many tickets at once, either every ticket is changed or none.
+/// Records the same events as single updates, responds with the changed ticket ids.
+#[post("/tickets/bulk")]
+async fn bulk(
+    payload: web::Json<TicketBulkPayload>,
+    pool: web::Data<DbPool>,
+    storage: web::Data<dyn Storage>,
+    broadcaster: web::Data<Broadcaster>,
+    mailer: web::Data<Mailer>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::can_edit)?;
+    if payload.delete {
+        caller.ensure(Role::is_admin)?;
+    }
+
+    let mut payload = payload.into_inner();
+    payload.ticket_ids.sort();
+    payload.ticket_ids.dedup();
+    if let Some(message) = validate(&payload) {
+        return Ok(HttpResponse::Ok().json(failure(message)));
+    }
+
+    let assignee = parse_uuid(&payload.assignee)?;
+    let ticket_ids = payload.ticket_ids.clone();
+    let deleted = payload.delete;
+    let user_id = Some(caller.user_id);
+    let notify_pool = pool.get_ref().clone();
+    let result = web::block(move || {
+        let mut conn = pool.get()?;
+        if payload.delete {
+            return delete_tickets(&payload.ticket_ids, storage.as_ref(), &mut conn)
+                .map(|result| result.map(|_| vec![]));
+        }
+
+        let events = match change_tickets(&payload, assignee, user_id, &mut conn)? {
+            Ok(events) => events,
+            Err(message) => return Ok(Err(message)),
+        };
+        for (id, events) in by_ticket(&events) {
+            let changes = events
+                .iter()
+                .map(|event| (event.event_type.clone(), event.event_data.clone()))
+                .collect::<Vec<(String, String)>>();
+            notify_followers(id, user_id, &changes, &mut conn)?;
+        }
+        Ok::<_, DbError>(Ok(events))
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let events = match result {
+        Ok(events) => events,
+        Err(message) => return Ok(HttpResponse::Ok().json(failure(message))),
+    };
+
+    for ticket_id in &ticket_ids {
+        broadcaster.broadcast(LiveEvent::TicketChanged {
+            ticket_id: *ticket_id,
+            user_id,
+        });
+        if deleted {
+            publish(
+                notify_pool.clone(),
+                WebhookEvent::TicketDeleted,
+                WebhookSubject::Deleted(serde_json::json!({ "ticket_id": ticket_id })),
+            );
+        }
+    }
+    for (ticket_id, events) in by_ticket(&events) {
+        let triggers = event_triggers(&events);
+        publish(
+            notify_pool.clone(),
+            WebhookEvent::TicketUpdated,
+            WebhookSubject::Ticket(ticket_id, events.clone()),
+        );
+        notify::ticket_events(
+            notify_pool.clone(),
+            mailer.get_ref().clone(),
+            ticket_id,
+            events,
+            notify::Actor::User(caller.user_id),
+        );
+        automate(&notify_pool, &mailer, &broadcaster, ticket_id, triggers).await;
+    }
+
+    let response = Response {
+        success: true,
+        message: None,
+        data: Some(ticket_ids),
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+fn failure(message: String) -> Response<Vec<i32>> {
+    Response {
+        success: false,
+        message: Some(message),
+        data: None,
+    }
+}
+
+fn validate(payload: &TicketBulkPayload) -> Option<String> {
+    if payload.ticket_ids.is_empty() {
+        return Some("No tickets selected".to_string());
+    }
+    if payload.ticket_ids.len() > MAX_BULK_TICKETS {
+        return Some(format!(
+            "Too many tickets selected, at most {} can be changed at once",
+            MAX_BULK_TICKETS
+        ));
+    }
+    let changes = payload.status.is_some()
+        || payload.priority.is_some()
+        || payload.assignee.is_some()
+        || payload.add_tag.is_some()
+        || payload.remove_tag.is_some();
+    if !changes && !payload.delete {
+        return Some("Nothing to change".to_string());
+    }
+
+    None
+}
+
+/// Events grouped by their ticket, in the order they were recorded
+fn by_ticket(events: &[NewTicketEvent]) -> BTreeMap<i32, Vec<NewTicketEvent>> {
+    let mut grouped: BTreeMap<i32, Vec<NewTicketEvent>> = BTreeMap::new();
+    for event in events {
+        grouped
+            .entry(event.ticket_id)
+            .or_default()
+            .push(event.clone());
+    }
+
+    grouped
+}
+
+/// Checks each ticket like a single update does, the first ticket that cannot be changed
+/// rolls back the whole operation
+fn change_tickets(
+    payload: &TicketBulkPayload,
+    assignee: Option<Option<Uuid>>,
+    actor: Option<Uuid>,
+    conn: &mut PgConnection,
+) -> Result<Result<Vec<NewTicketEvent>, String>, DbError> {
+    use crate::schema::{tags, ticket_events, tickets, users};
+
+    if let Err(message) = check_ticket_workflow(None, None, payload.priority.as_deref(), conn)? {
+        return Ok(Err(message));
+    }
+    if let Some(Some(user)) = assignee {
+        let exists = diesel::select(diesel::dsl::exists(users::table.find(user)))
+            .get_result::<bool>(conn)?;
+        if !exists {
+            return Ok(Err("Unknown assignee".to_string()));
+        }
+    }
+    for tag in [payload.add_tag, payload.remove_tag].into_iter().flatten() {
+        let exists =
+            diesel::select(diesel::dsl::exists(tags::table.find(tag))).get_result::<bool>(conn)?;
+        if !exists {
+            return Ok(Err("Unknown tag".to_string()));
+        }
+    }
+
+    rejectable(conn, |conn| {
+        let found = tickets::table
+            .filter(tickets::ticket_id.eq_any(&payload.ticket_ids))
+            .order(tickets::ticket_id.asc())
+            .load::<Ticket>(conn)?;
+        if found.len() != payload.ticket_ids.len() {
+            let missing = payload
+                .ticket_ids
+                .iter()
+                .find(|id| !found.iter().any(|ticket| ticket.ticket_id == **id));
+            return Err(Rejected(format!("Ticket #{} not found", missing.unwrap_or(&0))).into());
+        }
+
+        let time = chrono::Utc::now().naive_utc();
+        let mut events = Vec::new();
+        for ticket in found {
+            let id = ticket.ticket_id;
+            let event = |event_type: TicketEventType, event_data: String| NewTicketEvent {
+                event_id: Uuid::new_v4(),
+                ticket_id: id,
+                event_type: event_type.to_string(),
+                event_data,
+                user_id: actor,
+                created_at: time,
+            };
+            let mut recorded = Vec::new();
+
+            let status = payload
+                .status
+                .clone()
+                .filter(|status| *status != ticket.status);
+            if let Some(status) = &status {
+                let mut checked =
+                    check_ticket_workflow(Some(&ticket.status), Some(status), None, conn)?;
+                if checked.is_ok() {
+                    checked = check_required_tasks(id, status, conn)?;
+                }
+                if let Err(message) = checked {
+                    return Err(Rejected(format!("#{}: {}", id, message)).into());
+                }
+                recorded.push(event(TicketEventType::StatusUpdated, status.clone()));
+                if let Some(original) = closed_as_duplicate(id, status, conn)? {
+                    recorded.push(event(
+                        TicketEventType::ClosedAsDuplicate,
+                        original.to_string(),
+                    ));
+                }
+            }
+
+            let priority = payload
+                .priority
+                .clone()
+                .filter(|priority| *priority != ticket.priority);
+            if let Some(priority) = &priority {
+                recorded.push(event(TicketEventType::PriorityUpdated, priority.clone()));
+            }
+
+            let assignee = assignee.filter(|assignee| *assignee != ticket.assignee);
+            if let Some(assignee) = assignee {
+                let data = assignee.map(|user| user.to_string()).unwrap_or_default();
+                recorded.push(event(TicketEventType::Assigned, data));
+            }
+
+            if !recorded.is_empty() {
+                let changes = UpdateTicket {
+                    status,
+                    priority,
+                    assignee,
+                    updated_at: Some(time),
+                    ..Default::default()
+                };
+                diesel::update(tickets::table.find(id))
+                    .set(&changes)
+                    .execute(conn)?;
+                diesel::insert_into(ticket_events::table)
+                    .values(&recorded)
+                    .execute(conn)?;
+                update_sla(id, conn)?;
+            }
+            events.extend(recorded);
+
+            if let Some(tag) = payload.add_tag {
+                events.extend(set_ticket_tag(id, tag, true, actor, conn)?);
+            }
+            if let Some(tag) = payload.remove_tag {
+                events.extend(set_ticket_tag(id, tag, false, actor, conn)?);
+            }
+        }
+
+        Ok(events)
+    })
+}
+
+fn delete_tickets(
+    ids: &[i32],
+    storage: &dyn Storage,
+    conn: &mut PgConnection,
+) -> Result<Result<(), String>, DbError> {
+    use crate::schema::tickets;
+
+    let mut keys = Vec::new();
+    for id in ids {
+        keys.extend(find_storage_keys(AttachmentOwner::Ticket(*id), conn)?);
+    }
+
+    let deleted = rejectable(conn, |conn| {
+        let count =
+            diesel::delete(tickets::table.filter(tickets::ticket_id.eq_any(ids))).execute(conn)?;
+        if count != ids.len() {
+            return Err(Rejected("Some of the tickets were not found".to_string()).into());
+        }
+        Ok(())
+    })?;
+    //Files are only removed once the tickets are gone for good
+    if deleted.is_ok() {
+        delete_blobs(storage, &keys);
+    }
+
+    Ok(deleted)
+}
@@ -1,9 +1,283 @@
-// use super::super::DbPool;
+use super::super::DbPool;
 
-// use actix_web::{delete, get, post, put, web, Error, HttpResponse};
-// use diesel::prelude::*;
-// use uuid::Uuid;
+use actix_web::{delete, get, post, put, web, Error, HttpResponse};
+use diesel::prelude::*;
+use shared::models::{response::Response, users::Role};
+use uuid::Uuid;
 
-//use crate::models::
+use crate::authentication::CurrentUser;
+use crate::models::{
+    comments::{Comment, CommentPayload, CommentRepresentation, NewComment},
+    users::User,
+    SuccessResponse,
+};
 
-// type DbError = Box<dyn std::error::Error + Send + Sync>;
+type DbError = Box<dyn std::error::Error + Send + Sync>;
+
+/// All comments for a document, oldest first
+#[get("/documents/{id}/comments")]
+async fn index(
+    document_id: web::Path<Uuid>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::can_view)?;
+
+    let comments = web::block(move || {
+        let mut conn = pool.get()?;
+        find_by_document_id(document_id.into_inner(), &mut conn)
+    })
+    .await?
+    .map(|x| {
+        x.into_iter()
+            .map(CommentRepresentation::from)
+            .collect::<Vec<CommentRepresentation>>()
+    })
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(comments))
+}
+
+#[post("/documents/{id}/comments")]
+async fn create(
+    document_id: web::Path<Uuid>,
+    payload: web::Json<CommentPayload>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::can_edit)?;
+
+    let document_id = document_id.into_inner();
+    let payload = payload.into_inner();
+
+    if payload.text.trim().is_empty() {
+        return Ok(failure("Comment cannot be empty"));
+    }
+
+    let exists = {
+        let pool = pool.clone();
+        web::block(move || {
+            let mut conn = pool.get()?;
+            document_exists(document_id, &mut conn)
+        })
+        .await?
+        .map_err(actix_web::error::ErrorInternalServerError)?
+    };
+    if !exists {
+        return Err(actix_web::error::ErrorNotFound("Document not found"));
+    }
+
+    //Replies have to stay within the same document
+    if let Some(parent_id) = payload.parent_id {
+        let parent = fetch_comment(&pool, parent_id).await?;
+        if parent.document_id != document_id {
+            return Ok(failure("Parent comment belongs to another document"));
+        }
+    }
+
+    let comment = web::block(move || {
+        let mut conn = pool.get()?;
+        add_comment(document_id, caller.user_id, payload, &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let response = Response {
+        success: true,
+        message: None,
+        data: Some(CommentRepresentation::from(comment)),
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+#[put("/documents/{id}/comments/{comment_id}")]
+async fn update(
+    path: web::Path<(Uuid, Uuid)>,
+    payload: web::Json<CommentPayload>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    let (document_id, comment_id) = path.into_inner();
+
+    let existing = fetch_comment(&pool, comment_id).await?;
+    if existing.document_id != document_id {
+        return Err(actix_web::error::ErrorNotFound("Comment not found"));
+    }
+    if existing.author != Some(caller.user_id) {
+        caller.ensure(Role::can_edit)?;
+    }
+
+    if payload.text.trim().is_empty() {
+        return Ok(failure("Comment cannot be empty"));
+    }
+
+    let comment = web::block(move || {
+        let mut conn = pool.get()?;
+        update_comment(comment_id, payload.into_inner(), &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let response = Response {
+        success: true,
+        message: None,
+        data: Some(CommentRepresentation::from(comment)),
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Deleting a comment also removes its replies
+#[delete("/documents/{id}/comments/{comment_id}")]
+async fn delete(
+    path: web::Path<(Uuid, Uuid)>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    let (document_id, comment_id) = path.into_inner();
+
+    let existing = fetch_comment(&pool, comment_id).await?;
+    if existing.document_id != document_id {
+        return Err(actix_web::error::ErrorNotFound("Comment not found"));
+    }
+    if existing.author != Some(caller.user_id) {
+        caller.ensure(Role::is_admin)?;
+    }
+
+    let result = web::block(move || {
+        let mut conn = pool.get()?;
+        delete_comment(comment_id, &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let response = if result > 0 {
+        SuccessResponse {
+            success: true,
+            message: "Comment deleted".to_string(),
+        }
+    } else {
+        SuccessResponse {
+            success: false,
+            message: "Comment not found".to_string(),
+        }
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+fn failure(message: &str) -> HttpResponse {
+    let response: Response<CommentRepresentation> = Response {
+        success: false,
+        message: Some(message.to_string()),
+        data: None,
+    };
+    HttpResponse::Ok().json(response)
+}
+
+/// Loads a comment without joins, used for permission checks
+async fn fetch_comment(pool: &web::Data<DbPool>, id: Uuid) -> Result<Comment, Error> {
+    let pool = pool.clone();
+    web::block(move || {
+        let mut conn = pool.get()?;
+        get_comment_by_id(id, &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorNotFound)
+}
+
+fn document_exists(id: Uuid, conn: &mut PgConnection) -> Result<bool, DbError> {
+    use crate::schema::documents::dsl::*;
+
+    let exists = diesel::select(diesel::dsl::exists(documents.find(id))).get_result(conn)?;
+
+    Ok(exists)
+}
+
+fn add_comment(
+    doc_id: Uuid,
+    user_id: Uuid,
+    payload: CommentPayload,
+    conn: &mut PgConnection,
+) -> Result<(Comment, Option<User>), DbError> {
+    use crate::schema::comments::dsl::*;
+    use crate::schema::users::dsl::users;
+
+    let now = chrono::Utc::now().naive_utc();
+    let new_comment = NewComment {
+        comment_id: Uuid::new_v4(),
+        document_id: doc_id,
+        author: Some(user_id),
+        text: &payload.text,
+        parent_id: payload.parent_id,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let result: Comment = diesel::insert_into(comments)
+        .values(&new_comment)
+        .get_result(conn)?;
+
+    let comment = comments
+        .filter(comment_id.eq(result.comment_id))
+        .left_join(users)
+        .first::<(Comment, Option<User>)>(conn)?;
+
+    Ok(comment)
+}
+
+fn find_by_document_id(
+    id: Uuid,
+    conn: &mut PgConnection,
+) -> Result<Vec<(Comment, Option<User>)>, DbError> {
+    use crate::schema::comments::dsl::*;
+    use crate::schema::users::dsl::users;
+
+    let items = comments
+        .filter(document_id.eq(id))
+        .left_join(users)
+        .order(created_at.asc())
+        .load::<(Comment, Option<User>)>(conn)?;
+
+    Ok(items)
+}
+
+fn get_comment_by_id(id: Uuid, conn: &mut PgConnection) -> Result<Comment, DbError> {
+    use crate::schema::comments::dsl::*;
+
+    let comment = comments.find(id).first::<Comment>(conn)?;
+
+    Ok(comment)
+}
+
+fn update_comment(
+    id: Uuid,
+    payload: CommentPayload,
+    conn: &mut PgConnection,
+) -> Result<(Comment, Option<User>), DbError> {
+    use crate::schema::comments::dsl::*;
+    use crate::schema::users::dsl::users;
+
+    //Only the text can change, replies stay attached to their parent
+    diesel::update(comments.find(id))
+        .set((
+            text.eq(payload.text),
+            updated_at.eq(chrono::Utc::now().naive_utc()),
+        ))
+        .execute(conn)?;
+
+    let comment = comments
+        .filter(comment_id.eq(id))
+        .left_join(users)
+        .first::<(Comment, Option<User>)>(conn)?;
+
+    Ok(comment)
+}
+
+fn delete_comment(id: Uuid, conn: &mut PgConnection) -> Result<usize, DbError> {
+    use crate::schema::comments::dsl::*;
+
+    let count = diesel::delete(comments.find(id)).execute(conn)?;
+    Ok(count)
+}
@@ -2,8 +2,10 @@ use super::super::DbPool;
 
 use actix_web::{delete, get, post, put, web, Error, HttpResponse};
 use diesel::prelude::*;
+use shared::models::users::Role;
 use uuid::Uuid;
 
+use crate::authentication::CurrentUser;
 use crate::models::contacts::{Contact, ContactPayload, NewContact};
 
 type DbError = Box<dyn std::error::Error + Send + Sync>;
@@ -12,7 +14,10 @@ type DbError = Box<dyn std::error::Error + Send + Sync>;
 async fn create(
     pool: web::Data<DbPool>,
     payload: web::Json<ContactPayload>,
+    caller: CurrentUser,
 ) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::can_edit)?;
+
     let contact = web::block(move || {
         let mut conn = pool.get()?;
         add_a_contact(payload.into_inner(), &mut conn)
@@ -24,7 +29,9 @@ async fn create(
 }
 
 #[get("/contacts")]
-async fn index(pool: web::Data<DbPool>) -> Result<HttpResponse, Error> {
+async fn index(pool: web::Data<DbPool>, caller: CurrentUser) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::can_view)?;
+
     let contacts = web::block(move || {
         let mut conn = pool.get()?;
         find_all(&mut conn)
@@ -36,7 +43,13 @@ async fn index(pool: web::Data<DbPool>) -> Result<HttpResponse, Error> {
 }
 
 #[get("/contacts/{id}")]
-async fn show(contact_id: web::Path<Uuid>, pool: web::Data<DbPool>) -> Result<HttpResponse, Error> {
+async fn show(
+    contact_id: web::Path<Uuid>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::can_view)?;
+
     let contact = web::block(move || {
         let mut conn = pool.get()?;
         find_by_id(contact_id.into_inner(), &mut conn)
@@ -52,7 +65,10 @@ async fn update(
     contact_id: web::Path<Uuid>,
     payload: web::Json<ContactPayload>,
     pool: web::Data<DbPool>,
+    caller: CurrentUser,
 ) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::can_edit)?;
+
     let contact = web::block(move || {
         let mut conn = pool.get()?;
         update_contact(contact_id.into_inner(), payload.into_inner(), &mut conn)
@@ -64,7 +80,13 @@ async fn update(
 }
 
 #[delete("/contacts/{id}")]
-async fn delete(id: web::Path<Uuid>, pool: web::Data<DbPool>) -> Result<HttpResponse, Error> {
+async fn delete(
+    id: web::Path<Uuid>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::is_admin)?;
+
     let contact = web::block(move || {
         let mut conn = pool.get()?;
         delete_contact(id.into_inner(), &mut conn)
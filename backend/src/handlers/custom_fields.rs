@@ -0,0 +1,422 @@
+use super::super::DbPool;
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use actix_web::{delete, get, post, put, web, Error, HttpResponse};
+use diesel::prelude::*;
+use shared::models::{
+    custom_fields::CustomFieldType, response::Response, users::Role, MAX_TITLE_LENGTH,
+};
+use uuid::Uuid;
+
+use crate::authentication::CurrentUser;
+use crate::models::{
+    custom_fields::{
+        CustomField, CustomFieldPayload, NewTicketFieldValue, TicketFieldRepresentation,
+    },
+    SuccessResponse,
+};
+
+type DbError = Box<dyn std::error::Error + Send + Sync>;
+
+const MAX_VALUE_LENGTH: usize = 1000;
+
+/// Value changes of a ticket, None clears the value
+pub type FieldChanges = Vec<(Uuid, Option<String>)>;
+
+//Everyone who can open tickets needs the definitions to fill them in
+#[get("/custom_fields")]
+async fn index(pool: web::Data<DbPool>, _caller: CurrentUser) -> Result<HttpResponse, Error> {
+    let fields = web::block(move || {
+        let mut conn = pool.get()?;
+        find_all(&mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(fields))
+}
+
+#[post("/custom_fields")]
+async fn create(
+    pool: web::Data<DbPool>,
+    payload: web::Json<CustomFieldPayload>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::is_admin)?;
+
+    let mut payload = payload.into_inner();
+    if let Some(message) = validate(&mut payload) {
+        return Ok(HttpResponse::Ok().json(failure(message)));
+    }
+
+    let field = web::block(move || {
+        let mut conn = pool.get()?;
+        if name_in_use(&payload.name, None, &mut conn)? {
+            return Ok(None);
+        }
+        add_a_field(payload, &mut conn).map(Some)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let response = match field {
+        Some(field) => Response {
+            success: true,
+            message: None,
+            data: Some(field),
+        },
+        None => failure("Field name is already in use".to_string()),
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// The type of a field is fixed, existing values would not fit another one
+#[put("/custom_fields/{id}")]
+async fn update(
+    id: web::Path<Uuid>,
+    payload: web::Json<CustomFieldPayload>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::is_admin)?;
+
+    let mut payload = payload.into_inner();
+    if let Some(message) = validate(&mut payload) {
+        return Ok(HttpResponse::Ok().json(failure(message)));
+    }
+
+    let id = id.into_inner();
+    let field = web::block(move || {
+        let mut conn = pool.get()?;
+        let current = find_by_id(id, &mut conn)?;
+        if current.field_type != payload.field_type {
+            return Ok(Err("Field type cannot be changed"));
+        }
+        if name_in_use(&payload.name, Some(id), &mut conn)? {
+            return Ok(Err("Field name is already in use"));
+        }
+        update_field(id, payload, &mut conn).map(Ok)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let response = match field {
+        Ok(field) => Response {
+            success: true,
+            message: None,
+            data: Some(field),
+        },
+        Err(message) => failure(message.to_string()),
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Removes the values of the field from every ticket as well
+#[delete("/custom_fields/{id}")]
+async fn delete(
+    id: web::Path<Uuid>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::is_admin)?;
+
+    let result = web::block(move || {
+        let mut conn = pool.get()?;
+        delete_field(id.into_inner(), &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let response = if result > 0 {
+        SuccessResponse {
+            success: true,
+            message: "Field deleted".to_string(),
+        }
+    } else {
+        SuccessResponse {
+            success: false,
+            message: "Field not found".to_string(),
+        }
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+fn failure(message: String) -> Response<CustomField> {
+    Response {
+        success: false,
+        message: Some(message),
+        data: None,
+    }
+}
+
+/// Also tidies up the payload, only select fields keep their options
+fn validate(payload: &mut CustomFieldPayload) -> Option<String> {
+    payload.name = payload.name.trim().to_string();
+    if payload.name.is_empty() {
+        return Some("Field name is required".to_string());
+    }
+    if payload.name.len() > MAX_TITLE_LENGTH {
+        return Some(format!(
+            "Field name is too long, max length is {}",
+            MAX_TITLE_LENGTH
+        ));
+    }
+
+    let field_type = match CustomFieldType::from_str(&payload.field_type) {
+        Ok(field_type) => field_type,
+        Err(e) => return Some(e),
+    };
+
+    if field_type != CustomFieldType::Select {
+        payload.options.clear();
+        return None;
+    }
+    let mut options: Vec<Option<String>> = vec![];
+    for option in payload.options.iter().flatten() {
+        let option = option.trim().to_string();
+        if !option.is_empty() && !options.contains(&Some(option.clone())) {
+            options.push(Some(option));
+        }
+    }
+    if options.is_empty() {
+        return Some("Select fields need at least one option".to_string());
+    }
+    payload.options = options;
+
+    None
+}
+
+/// Checks the values given for a ticket and brings them to their stored form.
+/// When `creating`, every required field needs a value.
+/// The inner error is a message for the user.
+pub fn check_values(
+    values: &HashMap<Uuid, String>,
+    creating: bool,
+    conn: &mut PgConnection,
+) -> Result<Result<FieldChanges, String>, DbError> {
+    let fields = find_all(conn)?;
+
+    if let Some(unknown) = values
+        .keys()
+        .find(|id| !fields.iter().any(|field| field.field_id == **id))
+    {
+        return Ok(Err(format!("Unknown custom field {}", unknown)));
+    }
+
+    let mut changes = vec![];
+    for field in &fields {
+        let value = match values.get(&field.field_id) {
+            Some(value) => match normalize_value(field, value, conn)? {
+                Ok(value) => value,
+                Err(message) => return Ok(Err(format!("{}: {}", field.name, message))),
+            },
+            None if creating => None,
+            None => continue,
+        };
+        if field.required && value.is_none() {
+            return Ok(Err(format!("{} is required", field.name)));
+        }
+        changes.push((field.field_id, value));
+    }
+
+    Ok(Ok(changes))
+}
+
+/// Stored form of a value, None for an empty one
+fn normalize_value(
+    field: &CustomField,
+    value: &str,
+    conn: &mut PgConnection,
+) -> Result<Result<Option<String>, &'static str>, DbError> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(Ok(None));
+    }
+
+    let normalized = match CustomFieldType::from_str(&field.field_type) {
+        Ok(CustomFieldType::Text) if value.len() > MAX_VALUE_LENGTH => Err("Value is too long"),
+        Ok(CustomFieldType::Text) => Ok(value.to_string()),
+        Ok(CustomFieldType::Number) => match value.parse::<f64>() {
+            Ok(number) if number.is_finite() => Ok(number.to_string()),
+            _ => Err("Value must be a number"),
+        },
+        Ok(CustomFieldType::Date) => match chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+            Ok(date) => Ok(date.format("%Y-%m-%d").to_string()),
+            Err(_) => Err("Value must be a date in the form YYYY-MM-DD"),
+        },
+        Ok(CustomFieldType::Select) => {
+            if field.options.iter().flatten().any(|option| option == value) {
+                Ok(value.to_string())
+            } else {
+                Err("Value is not one of the options")
+            }
+        }
+        Ok(CustomFieldType::User) => match Uuid::parse_str(value) {
+            Ok(id) if user_exists(id, conn)? => Ok(id.to_string()),
+            _ => Err("Value must be an existing user"),
+        },
+        Err(_) => Err("Field has an unknown type"),
+    };
+
+    Ok(normalized.map(Some))
+}
+
+/// Value to filter by, values that do not fit the field are compared as they are
+pub fn filter_value(
+    field: &CustomField,
+    value: &str,
+    conn: &mut PgConnection,
+) -> Result<String, DbError> {
+    let normalized = normalize_value(field, value, conn)?
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| value.trim().to_string());
+
+    Ok(normalized)
+}
+
+pub fn set_values(id: i32, changes: FieldChanges, conn: &mut PgConnection) -> Result<(), DbError> {
+    use crate::schema::ticket_field_values::dsl::*;
+
+    for (field, new_value) in changes {
+        match new_value {
+            Some(new_value) => {
+                let row = NewTicketFieldValue {
+                    ticket_id: id,
+                    field_id: field,
+                    value: new_value,
+                };
+                diesel::insert_into(ticket_field_values)
+                    .values(&row)
+                    .on_conflict((ticket_id, field_id))
+                    .do_update()
+                    .set(&row)
+                    .execute(conn)?;
+            }
+            None => {
+                diesel::delete(ticket_field_values.find((id, field))).execute(conn)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Custom field values of each of the given tickets, for showing them in ticket lists
+pub fn find_values_by_ticket_ids(
+    ids: &[i32],
+    conn: &mut PgConnection,
+) -> Result<HashMap<i32, Vec<TicketFieldRepresentation>>, DbError> {
+    use crate::schema::custom_fields;
+    use crate::schema::ticket_field_values::dsl::*;
+
+    let items = ticket_field_values
+        .inner_join(custom_fields::table)
+        .filter(ticket_id.eq_any(ids))
+        .order((custom_fields::position.asc(), custom_fields::name.asc()))
+        .select((
+            ticket_id,
+            field_id,
+            custom_fields::name,
+            custom_fields::field_type,
+            value,
+        ))
+        .load::<(i32, Uuid, String, String, String)>(conn)?;
+
+    let mut by_ticket: HashMap<i32, Vec<TicketFieldRepresentation>> = HashMap::new();
+    for (id, field, name, field_type, field_value) in items {
+        by_ticket
+            .entry(id)
+            .or_default()
+            .push(TicketFieldRepresentation {
+                field_id: field,
+                name,
+                field_type,
+                value: field_value,
+            });
+    }
+
+    Ok(by_ticket)
+}
+
+fn user_exists(id: Uuid, conn: &mut PgConnection) -> Result<bool, DbError> {
+    use crate::schema::users::dsl::*;
+
+    let exists = diesel::select(diesel::dsl::exists(users.find(id))).get_result(conn)?;
+
+    Ok(exists)
+}
+
+fn name_in_use(
+    field_name: &str,
+    except: Option<Uuid>,
+    conn: &mut PgConnection,
+) -> Result<bool, DbError> {
+    use crate::schema::custom_fields::dsl::*;
+
+    let mut query = custom_fields.filter(name.eq(field_name)).into_boxed();
+    if let Some(except) = except {
+        query = query.filter(field_id.ne(except));
+    }
+    let count = query.count().get_result::<i64>(conn)?;
+
+    Ok(count > 0)
+}
+
+fn add_a_field(
+    payload: CustomFieldPayload,
+    conn: &mut PgConnection,
+) -> Result<CustomField, DbError> {
+    use crate::schema::custom_fields::dsl::*;
+
+    let field = diesel::insert_into(custom_fields)
+        .values(&payload)
+        .get_result::<CustomField>(conn)?;
+
+    Ok(field)
+}
+
+pub fn find_all(conn: &mut PgConnection) -> Result<Vec<CustomField>, DbError> {
+    use crate::schema::custom_fields::dsl::*;
+
+    let items = custom_fields
+        .order((position.asc(), name.asc()))
+        .load::<CustomField>(conn)?;
+
+    Ok(items)
+}
+
+pub fn find_by_id(id: Uuid, conn: &mut PgConnection) -> Result<CustomField, DbError> {
+    use crate::schema::custom_fields::dsl::*;
+
+    let field = custom_fields.find(id).first::<CustomField>(conn)?;
+
+    Ok(field)
+}
+
+fn update_field(
+    id: Uuid,
+    payload: CustomFieldPayload,
+    conn: &mut PgConnection,
+) -> Result<CustomField, DbError> {
+    use crate::schema::custom_fields::dsl::*;
+
+    let field = diesel::update(custom_fields.find(id))
+        .set(&payload)
+        .get_result::<CustomField>(conn)?;
+
+    Ok(field)
+}
+
+fn delete_field(id: Uuid, conn: &mut PgConnection) -> Result<usize, DbError> {
+    use crate::schema::custom_fields::dsl::*;
+
+    let count = diesel::delete(custom_fields.find(id)).execute(conn)?;
+
+    Ok(count)
+}
@@ -2,11 +2,18 @@ use super::super::DbPool;
 
 use actix_web::{delete, error::InternalError, get, post, put, web, Error, HttpResponse};
 use diesel::prelude::*;
-use shared::models::{response::Response, MAX_TITLE_LENGTH};
+use shared::models::{
+    live::LiveEvent, response::Response, users::Role, webhooks::WebhookEvent, MAX_TITLE_LENGTH,
+};
 use uuid::Uuid;
 
+use super::attachments::{delete_blobs, find_storage_keys, AttachmentOwner};
+use super::webhooks::{publish, WebhookSubject};
 use crate::{
+    authentication::CurrentUser,
+    live::Broadcaster,
     models::{documents::*, session::TypedSession, SuccessResponse},
+    storage::Storage,
     utils::parse_uuid,
 };
 
@@ -15,8 +22,11 @@ type DbError = Box<dyn std::error::Error + Send + Sync>;
 #[post("/documents")]
 async fn create(
     pool: web::Data<DbPool>,
+    broadcaster: web::Data<Broadcaster>,
     payload: web::Json<DocumentCreatePayload>,
+    caller: CurrentUser,
 ) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::can_edit)?;
 
     if payload.title.len() > MAX_TITLE_LENGTH {
         let response: Response<Document> = Response {
@@ -27,6 +37,7 @@ async fn create(
         return Ok(HttpResponse::Ok().json(response));
     }
 
+    let webhook_pool = pool.get_ref().clone();
     let document = web::block(move || {
         let mut conn = pool.get()?;
         create_document(payload.into_inner(), &mut conn)
@@ -34,6 +45,16 @@ async fn create(
     .await?
     .map_err(actix_web::error::ErrorInternalServerError)?;
 
+    broadcaster.broadcast(LiveEvent::DocumentChanged {
+        document_id: document.document_id,
+        user_id: Some(caller.user_id),
+    });
+    publish(
+        webhook_pool,
+        WebhookEvent::DocumentCreated,
+        WebhookSubject::Document(document.document_id),
+    );
+
     let response = Response {
         success: true,
         message: None,
@@ -45,7 +66,9 @@ async fn create(
 
 /// Handler for GET /documents, returns documents for generating tree
 #[get("/documents")]
-async fn index(pool: web::Data<DbPool>) -> Result<HttpResponse, Error> {
+async fn index(pool: web::Data<DbPool>, caller: CurrentUser) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::can_view)?;
+
     let documents = web::block(move || {
         let mut conn = pool.get()?;
         get_document_list(&mut conn)
@@ -60,7 +83,10 @@ async fn index(pool: web::Data<DbPool>) -> Result<HttpResponse, Error> {
 async fn show(
     document_id: web::Path<Uuid>,
     pool: web::Data<DbPool>,
+    caller: CurrentUser,
 ) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::can_view)?;
+
     let document = web::block(move || {
         let mut conn = pool.get()?;
         get_document_by_id(document_id.into_inner(), &mut conn)
@@ -76,8 +102,12 @@ async fn update(
     document_id: web::Path<Uuid>,
     mut payload: web::Json<DocumentUpdatePayload>,
     pool: web::Data<DbPool>,
+    broadcaster: web::Data<Broadcaster>,
     session: TypedSession,
+    caller: CurrentUser,
 ) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::can_edit)?;
+
     let user_id: Option<Uuid> = match session.get_user_id() {
         Ok(id) => id,
         Err(_) => {
@@ -177,6 +207,7 @@ async fn update(
         }
     }
 
+    let webhook_pool = pool.get_ref().clone();
     let document = web::block(move || {
         let mut conn = pool.get()?;
         update_document(document_id.into_inner(), doc, &mut conn)
@@ -184,6 +215,16 @@ async fn update(
     .await?
     .map_err(actix_web::error::ErrorInternalServerError)?;
 
+    broadcaster.broadcast(LiveEvent::DocumentChanged {
+        document_id: document.document_id,
+        user_id: Some(caller.user_id),
+    });
+    publish(
+        webhook_pool,
+        WebhookEvent::DocumentUpdated,
+        WebhookSubject::Document(document.document_id),
+    );
+
     let response = Response {
         success: true,
         message: None,
@@ -197,14 +238,37 @@ async fn update(
 async fn delete(
     document_id: web::Path<Uuid>,
     pool: web::Data<DbPool>,
+    storage: web::Data<dyn Storage>,
+    broadcaster: web::Data<Broadcaster>,
+    caller: CurrentUser,
 ) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::is_admin)?;
+
+    let deleted_id = *document_id;
+    let webhook_pool = pool.get_ref().clone();
     let result = web::block(move || {
         let mut conn = pool.get()?;
-        delete_document(document_id.into_inner(), &mut conn)
+        //Attachments go with the document, their files have to be removed separately
+        let keys = find_storage_keys(AttachmentOwner::Document(deleted_id), &mut conn)?;
+        let result = delete_document(document_id.into_inner(), &mut conn)?;
+        delete_blobs(storage.as_ref(), &keys);
+        Ok::<_, DbError>(result)
     })
     .await?
     .map_err(actix_web::error::ErrorInternalServerError)?;
 
+    broadcaster.broadcast(LiveEvent::DocumentChanged {
+        document_id: deleted_id,
+        user_id: Some(caller.user_id),
+    });
+    if result > 0 {
+        publish(
+            webhook_pool,
+            WebhookEvent::DocumentDeleted,
+            WebhookSubject::Deleted(serde_json::json!({ "document_id": deleted_id })),
+        );
+    }
+
     if result > 1 {
         let response = SuccessResponse {
             success: true,
@@ -224,7 +288,10 @@ async fn delete(
 async fn revisions(
     document_id: web::Path<Uuid>,
     pool: web::Data<DbPool>,
+    caller: CurrentUser,
 ) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::can_view)?;
+
     let revisions = web::block(move || {
         let mut conn = pool.get()?;
         get_document_revisions(document_id.into_inner(), &mut conn)
@@ -0,0 +1,493 @@
+use super::super::DbPool;
+
+use std::collections::{BTreeMap, HashSet};
+
+use actix_web::{delete, get, post, web, Error, HttpResponse};
+use diesel::prelude::*;
+use shared::models::{
+    links::{link_event_data, TicketLinkType},
+    live::LiveEvent,
+    response::Response,
+    tickets::TicketEventType,
+    users::Role,
+    webhooks::WebhookEvent,
+};
+use uuid::Uuid;
+
+use super::notifications::notify_followers;
+use super::tickets::{check_ticket_access, ticket_exists};
+use super::webhooks::{publish, WebhookSubject};
+use crate::authentication::CurrentUser;
+use crate::live::Broadcaster;
+use crate::mail::{notify, Mailer};
+use crate::models::{
+    links::{NewTicketLink, TicketLink, TicketLinkPayload, TicketLinkRepresentation},
+    tickets::NewTicketEvent,
+};
+
+type DbError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Requesters only see links to tickets they opened themselves
+#[get("/tickets/{id}/links")]
+async fn index(
+    id: web::Path<i32>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    check_ticket_access(&pool, *id, &caller).await?;
+
+    let visible_to = (!caller.role.can_view()).then_some(caller.user_id);
+    let links = web::block(move || {
+        let mut conn = pool.get()?;
+        find_by_ticket_id(id.into_inner(), visible_to, &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(links))
+}
+
+/// Links the ticket to another one, the event is recorded on both tickets
+#[post("/tickets/{id}/links")]
+async fn create(
+    id: web::Path<i32>,
+    payload: web::Json<TicketLinkPayload>,
+    pool: web::Data<DbPool>,
+    broadcaster: web::Data<Broadcaster>,
+    mailer: web::Data<Mailer>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::can_edit)?;
+
+    let id = id.into_inner();
+    let payload = payload.into_inner();
+    let user_id = caller.user_id;
+    change_links(id, pool, broadcaster, mailer, caller, move |conn| {
+        add_link(id, payload.link_type, payload.ticket_id, user_id, conn)
+    })
+    .await
+}
+
+/// Removes a link from either of its tickets
+#[delete("/tickets/{id}/links/{link_id}")]
+async fn delete(
+    path: web::Path<(i32, Uuid)>,
+    pool: web::Data<DbPool>,
+    broadcaster: web::Data<Broadcaster>,
+    mailer: web::Data<Mailer>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::can_edit)?;
+
+    let (id, link) = path.into_inner();
+    let user_id = caller.user_id;
+    change_links(id, pool, broadcaster, mailer, caller, move |conn| {
+        remove_link(id, link, user_id, conn)
+    })
+    .await
+}
+
+/// Runs a change to the links of a ticket, then lets the followers of both tickets know
+/// and open pages refresh. Responds with the links of the ticket afterwards.
+async fn change_links<F>(
+    id: i32,
+    pool: web::Data<DbPool>,
+    broadcaster: web::Data<Broadcaster>,
+    mailer: web::Data<Mailer>,
+    caller: CurrentUser,
+    change: F,
+) -> Result<HttpResponse, Error>
+where
+    F: FnOnce(&mut PgConnection) -> Result<Result<Vec<NewTicketEvent>, String>, DbError>
+        + Send
+        + 'static,
+{
+    let exists = {
+        let pool = pool.clone();
+        web::block(move || {
+            let mut conn = pool.get()?;
+            ticket_exists(id, &mut conn)
+        })
+        .await?
+        .map_err(actix_web::error::ErrorInternalServerError)?
+    };
+    if !exists {
+        return Err(actix_web::error::ErrorNotFound("Ticket not found"));
+    }
+
+    let user_id = Some(caller.user_id);
+    let notify_pool = pool.get_ref().clone();
+    let result = web::block(move || {
+        let mut conn = pool.get()?;
+        let events = match change(&mut conn)? {
+            Ok(events) => events,
+            Err(message) => return Ok(Err(message)),
+        };
+        for event in &events {
+            let changes = [(event.event_type.clone(), event.event_data.clone())];
+            notify_followers(event.ticket_id, user_id, &changes, &mut conn)?;
+        }
+        let links = find_by_ticket_id(id, None, &mut conn)?;
+        Ok::<_, DbError>(Ok((links, events)))
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let (links, events) = match result {
+        Ok(result) => result,
+        Err(message) => {
+            let response: Response<Vec<TicketLinkRepresentation>> = Response {
+                success: false,
+                message: Some(message),
+                data: None,
+            };
+            return Ok(HttpResponse::Ok().json(response));
+        }
+    };
+
+    //One event for each ticket of the link
+    let mut by_ticket: BTreeMap<i32, Vec<NewTicketEvent>> = BTreeMap::new();
+    for event in events {
+        by_ticket.entry(event.ticket_id).or_default().push(event);
+    }
+    for (ticket_id, events) in by_ticket {
+        broadcaster.broadcast(LiveEvent::TicketChanged { ticket_id, user_id });
+        publish(
+            notify_pool.clone(),
+            WebhookEvent::TicketUpdated,
+            WebhookSubject::Ticket(ticket_id, events.clone()),
+        );
+        notify::ticket_events(
+            notify_pool.clone(),
+            mailer.get_ref().clone(),
+            ticket_id,
+            events,
+            notify::Actor::User(caller.user_id),
+        );
+    }
+
+    let response = Response {
+        success: true,
+        message: None,
+        data: Some(links),
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// The ticket that a ticket duplicates, when it is being closed with `new_status`
+pub fn closed_as_duplicate(
+    id: i32,
+    new_status: &str,
+    conn: &mut PgConnection,
+) -> Result<Option<i32>, DbError> {
+    use crate::schema::ticket_links::dsl::*;
+    use crate::schema::ticket_statuses;
+
+    let closing = ticket_statuses::table
+        .filter(ticket_statuses::name.eq(new_status))
+        .select(ticket_statuses::is_closed)
+        .first::<bool>(conn)
+        .optional()?
+        .unwrap_or_default();
+    if !closing {
+        return Ok(None);
+    }
+
+    let original = ticket_links
+        .filter(source_id.eq(id))
+        .filter(link_type.eq(TicketLinkType::Duplicates.as_str()))
+        .order(created_at.asc())
+        .select(target_id)
+        .first::<i32>(conn)
+        .optional()?;
+
+    Ok(original)
+}
+
+/// Links of a ticket in both directions, oldest first.
+/// With `visible_to` only links to tickets opened by that user are listed.
+fn find_by_ticket_id(
+    id: i32,
+    visible_to: Option<Uuid>,
+    conn: &mut PgConnection,
+) -> Result<Vec<TicketLinkRepresentation>, DbError> {
+    use crate::schema::ticket_links::dsl::*;
+    use crate::schema::ticket_statuses;
+    use crate::schema::tickets;
+
+    let links = ticket_links
+        .filter(source_id.eq(id).or(target_id.eq(id)))
+        .order(created_at.asc())
+        .load::<TicketLink>(conn)?;
+
+    let others = links
+        .iter()
+        .map(|link| other_ticket(link, id))
+        .collect::<Vec<i32>>();
+    let mut query = tickets::table
+        .filter(tickets::ticket_id.eq_any(&others))
+        .select((tickets::ticket_id, tickets::title, tickets::status))
+        .into_boxed();
+    if let Some(user) = visible_to {
+        query = query.filter(tickets::created_by.eq(user));
+    }
+    let details = query.load::<(i32, String, String)>(conn)?;
+
+    let closed = ticket_statuses::table
+        .filter(ticket_statuses::is_closed.eq(true))
+        .select(ticket_statuses::name)
+        .load::<String>(conn)?
+        .into_iter()
+        .collect::<HashSet<String>>();
+
+    let results = links
+        .into_iter()
+        .filter_map(|link| {
+            let other = other_ticket(&link, id);
+            let (_, title, status) = details.iter().find(|(ticket, _, _)| *ticket == other)?;
+            let stored = link.link_type.parse::<TicketLinkType>().ok()?;
+            Some(TicketLinkRepresentation {
+                link_id: link.link_id,
+                link_type: if link.source_id == id {
+                    stored
+                } else {
+                    stored.inverse()
+                },
+                ticket_id: other,
+                title: title.clone(),
+                is_closed: closed.contains(status),
+                status: status.clone(),
+                created_at: link.created_at,
+            })
+        })
+        .collect();
+
+    Ok(results)
+}
+
+fn other_ticket(link: &TicketLink, id: i32) -> i32 {
+    if link.source_id == id {
+        link.target_id
+    } else {
+        link.source_id
+    }
+}
+
+/// `Linked` or `Unlinked` events for both tickets of a link, each seen from its own ticket
+fn link_events(
+    event_type: TicketEventType,
+    id: i32,
+    kind: TicketLinkType,
+    other: i32,
+    actor: Uuid,
+    conn: &mut PgConnection,
+) -> Result<Vec<NewTicketEvent>, DbError> {
+    use crate::schema::tickets;
+
+    let now = chrono::Utc::now().naive_utc();
+    let events = vec![
+        NewTicketEvent {
+            event_id: Uuid::new_v4(),
+            ticket_id: id,
+            event_type: event_type.to_string(),
+            event_data: link_event_data(kind, other),
+            user_id: Some(actor),
+            created_at: now,
+        },
+        NewTicketEvent {
+            event_id: Uuid::new_v4(),
+            ticket_id: other,
+            event_type: event_type.to_string(),
+            event_data: link_event_data(kind.inverse(), id),
+            user_id: Some(actor),
+            created_at: now,
+        },
+    ];
+    diesel::insert_into(crate::schema::ticket_events::table)
+        .values(&events)
+        .execute(conn)?;
+    diesel::update(tickets::table.filter(tickets::ticket_id.eq_any([id, other])))
+        .set(tickets::updated_at.eq(now))
+        .execute(conn)?;
+
+    Ok(events)
+}
+
+/// A ticket can only duplicate one original and have one parent,
+/// two tickets are only linked once and parents and duplicates never go in a circle
+fn add_link(
+    id: i32,
+    kind: TicketLinkType,
+    other: i32,
+    actor: Uuid,
+    conn: &mut PgConnection,
+) -> Result<Result<Vec<NewTicketEvent>, String>, DbError> {
+    use crate::schema::ticket_links::dsl::*;
+
+    if other == id {
+        return Ok(Err("A ticket cannot be linked to itself".to_string()));
+    }
+    if !ticket_exists(other, conn)? {
+        return Ok(Err(format!("Ticket #{} not found", other)));
+    }
+
+    let (source, target, stored) = if kind.stored() {
+        (id, other, kind)
+    } else {
+        (other, id, kind.inverse())
+    };
+
+    conn.transaction(|conn| {
+        let linked = ticket_links
+            .filter(
+                source_id
+                    .eq(source)
+                    .and(target_id.eq(target))
+                    .or(source_id.eq(target).and(target_id.eq(source))),
+            )
+            .count()
+            .get_result::<i64>(conn)?;
+        if linked > 0 {
+            return Ok(Err(format!("The ticket is already linked to #{}", other)));
+        }
+
+        let single = match stored {
+            //The source duplicates one original
+            TicketLinkType::Duplicates => ticket_links
+                .filter(source_id.eq(source))
+                .filter(link_type.eq(stored.as_str()))
+                .count()
+                .get_result::<i64>(conn)?,
+            //The target has one parent
+            TicketLinkType::ParentOf => ticket_links
+                .filter(target_id.eq(target))
+                .filter(link_type.eq(stored.as_str()))
+                .count()
+                .get_result::<i64>(conn)?,
+            _ => 0,
+        };
+        if single > 0 {
+            let message = match stored {
+                TicketLinkType::Duplicates => "already duplicates another ticket",
+                _ => "already has a parent",
+            };
+            let ticket = if stored == TicketLinkType::Duplicates {
+                source
+            } else {
+                target
+            };
+            return Ok(Err(format!("Ticket #{} {}", ticket, message)));
+        }
+
+        let circular = match stored {
+            //The target would become its own original
+            TicketLinkType::Duplicates => leads_to(target, source, stored, conn)?,
+            //The target would become its own ancestor
+            TicketLinkType::ParentOf => leads_to(source, target, stored, conn)?,
+            _ => false,
+        };
+        if circular {
+            return Ok(Err(format!(
+                "Linking #{} would make the tickets go in a circle",
+                other
+            )));
+        }
+
+        let new_link = NewTicketLink {
+            source_id: source,
+            target_id: target,
+            link_type: stored.to_string(),
+            created_by: Some(actor),
+        };
+        diesel::insert_into(ticket_links)
+            .values(&new_link)
+            .execute(conn)?;
+
+        Ok(Ok(link_events(
+            TicketEventType::Linked,
+            id,
+            kind,
+            other,
+            actor,
+            conn,
+        )?))
+    })
+}
+
+/// Whether following originals (duplicates) or parents (parent of) from `start` reaches `goal`
+fn leads_to(
+    start: i32,
+    goal: i32,
+    kind: TicketLinkType,
+    conn: &mut PgConnection,
+) -> Result<bool, DbError> {
+    use crate::schema::ticket_links::dsl::*;
+
+    let mut visited = vec![start];
+    let mut current = start;
+    loop {
+        let next = match kind {
+            TicketLinkType::Duplicates => ticket_links
+                .filter(source_id.eq(current))
+                .filter(link_type.eq(kind.as_str()))
+                .select(target_id)
+                .first::<i32>(conn)
+                .optional()?,
+            TicketLinkType::ParentOf => ticket_links
+                .filter(target_id.eq(current))
+                .filter(link_type.eq(kind.as_str()))
+                .select(source_id)
+                .first::<i32>(conn)
+                .optional()?,
+            _ => None,
+        };
+        match next {
+            Some(next) if next == goal => return Ok(true),
+            Some(next) if !visited.contains(&next) => {
+                visited.push(next);
+                current = next;
+            }
+            _ => return Ok(false),
+        }
+    }
+}
+
+fn remove_link(
+    id: i32,
+    link: Uuid,
+    actor: Uuid,
+    conn: &mut PgConnection,
+) -> Result<Result<Vec<NewTicketEvent>, String>, DbError> {
+    use crate::schema::ticket_links::dsl::*;
+
+    conn.transaction(|conn| {
+        let Some(old) = ticket_links
+            .find(link)
+            .filter(source_id.eq(id).or(target_id.eq(id)))
+            .first::<TicketLink>(conn)
+            .optional()?
+        else {
+            return Ok(Err("Link not found".to_string()));
+        };
+        let Ok(stored) = old.link_type.parse::<TicketLinkType>() else {
+            return Ok(Err("Link not found".to_string()));
+        };
+
+        diesel::delete(ticket_links.find(link)).execute(conn)?;
+
+        let (kind, other) = if old.source_id == id {
+            (stored, old.target_id)
+        } else {
+            (stored.inverse(), old.source_id)
+        };
+        Ok(Ok(link_events(
+            TicketEventType::Unlinked,
+            id,
+            kind,
+            other,
+            actor,
+            conn,
+        )?))
+    })
+}
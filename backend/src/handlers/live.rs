@@ -0,0 +1,66 @@
+use super::super::DbPool;
+
+use actix_web::{get, post, web, Error, HttpResponse, Responder};
+use diesel::prelude::*;
+use shared::models::{
+    live::{LiveEvent, PresencePayload},
+    users::Role,
+};
+use uuid::Uuid;
+
+use crate::authentication::CurrentUser;
+use crate::live::Broadcaster;
+use crate::models::SuccessResponse;
+
+type DbError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Event stream of changes made by anyone, see `LiveEvent`
+#[get("/live")]
+async fn stream(
+    broadcaster: web::Data<Broadcaster>,
+    caller: CurrentUser,
+) -> Result<impl Responder, Error> {
+    caller.ensure(Role::can_view)?;
+
+    Ok(broadcaster.subscribe())
+}
+
+/// Tells everyone else that the caller opened or left an editor
+#[post("/live/presence")]
+async fn presence(
+    payload: web::Json<PresencePayload>,
+    pool: web::Data<DbPool>,
+    broadcaster: web::Data<Broadcaster>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::can_edit)?;
+
+    let display_name = web::block(move || {
+        let mut conn = pool.get()?;
+        get_display_name(caller.user_id, &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let payload = payload.into_inner();
+    broadcaster.broadcast(LiveEvent::Presence {
+        target: payload.target,
+        user_id: caller.user_id,
+        display_name,
+        editing: payload.editing,
+    });
+
+    let response = SuccessResponse {
+        success: true,
+        message: "Presence sent".to_string(),
+    };
+    Ok(HttpResponse::Ok().json(response))
+}
+
+fn get_display_name(id: Uuid, conn: &mut PgConnection) -> Result<String, DbError> {
+    use crate::schema::users::dsl::*;
+
+    let name = users.find(id).select(display_name).first::<String>(conn)?;
+
+    Ok(name)
+}
@@ -0,0 +1,234 @@
+use super::super::DbPool;
+
+use actix_web::{post, web, Error, HttpResponse};
+use diesel::prelude::*;
+use shared::models::{
+    live::LiveEvent, response::Response, tickets::TicketEventType, users::Role,
+    webhooks::WebhookEvent,
+};
+use uuid::Uuid;
+
+use super::notifications::notify_followers;
+use super::sla::update_sla;
+use super::tasks::check_required_tasks;
+use super::tickets::find_by_id;
+use super::webhooks::{publish, WebhookSubject};
+use super::workflows::closed_status;
+use crate::authentication::CurrentUser;
+use crate::live::Broadcaster;
+use crate::mail::{notify, Mailer};
+use crate::models::tickets::{NewTicketEvent, Ticket, TicketMergePayload, TicketRepresentation};
+
+type DbError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Moves the history and tasks of the ticket into `target` and closes it, pointing at the target.
+/// Responds with the target ticket.
+#[post("/tickets/{id}/merge")]
+async fn merge(
+    id: web::Path<i32>,
+    payload: web::Json<TicketMergePayload>,
+    pool: web::Data<DbPool>,
+    broadcaster: web::Data<Broadcaster>,
+    mailer: web::Data<Mailer>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::can_edit)?;
+
+    let source = id.into_inner();
+    let target = payload.target;
+    let user_id = Some(caller.user_id);
+    let notify_pool = pool.get_ref().clone();
+    let result = web::block(move || {
+        let mut conn = pool.get()?;
+        let events = match merge_tickets(source, target, user_id, &mut conn)? {
+            Ok(events) => events,
+            Err(message) => return Ok(Err(message)),
+        };
+        for event in &events {
+            let changes = [(event.event_type.clone(), event.event_data.clone())];
+            notify_followers(event.ticket_id, user_id, &changes, &mut conn)?;
+        }
+        let ticket = find_by_id(target, &mut conn)?;
+        Ok::<_, DbError>(Ok((ticket, events)))
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let (ticket, events) = match result {
+        Ok(result) => result,
+        Err(message) => {
+            let response: Response<TicketRepresentation> = Response {
+                success: false,
+                message: Some(message),
+                data: None,
+            };
+            return Ok(HttpResponse::Ok().json(response));
+        }
+    };
+
+    for event in events {
+        let ticket_id = event.ticket_id;
+        broadcaster.broadcast(LiveEvent::TicketChanged { ticket_id, user_id });
+        broadcaster.broadcast(LiveEvent::NotesChanged { ticket_id, user_id });
+        publish(
+            notify_pool.clone(),
+            WebhookEvent::TicketUpdated,
+            WebhookSubject::Ticket(ticket_id, vec![event.clone()]),
+        );
+        notify::ticket_events(
+            notify_pool.clone(),
+            mailer.get_ref().clone(),
+            ticket_id,
+            vec![event],
+            notify::Actor::User(caller.user_id),
+        );
+    }
+
+    let response = Response {
+        success: true,
+        message: None,
+        data: ticket.into_iter().next(),
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Moves notes, revisions, events, watchers and attachments of `source` to `target`,
+/// then closes `source` and records a merge event on both.
+/// Everything happens in one transaction, a failure leaves both tickets as they were.
+fn merge_tickets(
+    source: i32,
+    target: i32,
+    actor: Option<Uuid>,
+    conn: &mut PgConnection,
+) -> Result<Result<Vec<NewTicketEvent>, String>, DbError> {
+    use crate::schema::{
+        attachments, notes, ticket_events, ticket_revisions, ticket_tasks, ticket_watchers, tickets,
+    };
+
+    if source == target {
+        return Ok(Err("A ticket cannot be merged into itself".to_string()));
+    }
+
+    conn.transaction(|conn| {
+        let found = tickets::table
+            .filter(tickets::ticket_id.eq_any([source, target]))
+            .load::<Ticket>(conn)?;
+        let Some(from) = found.iter().find(|ticket| ticket.ticket_id == source) else {
+            return Ok(Err("Ticket not found".to_string()));
+        };
+        let Some(into) = found.iter().find(|ticket| ticket.ticket_id == target) else {
+            return Ok(Err(format!("Ticket #{} not found", target)));
+        };
+        if let Some(merged) = from.merged_into {
+            return Ok(Err(format!(
+                "The ticket was already merged into #{}",
+                merged
+            )));
+        }
+        if let Some(merged) = into.merged_into {
+            return Ok(Err(format!(
+                "Ticket #{} was merged into #{}, merge into that ticket instead",
+                target, merged
+            )));
+        }
+        let Some(closed) = closed_status(conn)? else {
+            return Ok(Err(
+                "There is no closed status to close the ticket with".to_string()
+            ));
+        };
+        //The tasks move along, a closed target cannot take open required tasks
+        if check_required_tasks(source, &into.status, conn)?.is_err() {
+            return Ok(Err(format!(
+                "Ticket #{} is closed and cannot take the open required tasks of the ticket",
+                target
+            )));
+        }
+
+        diesel::update(notes::table.filter(notes::ticket.eq(source)))
+            .set(notes::ticket.eq(target))
+            .execute(conn)?;
+        diesel::update(ticket_revisions::table.filter(ticket_revisions::ticket_id.eq(source)))
+            .set(ticket_revisions::ticket_id.eq(target))
+            .execute(conn)?;
+        diesel::update(ticket_events::table.filter(ticket_events::ticket_id.eq(source)))
+            .set(ticket_events::ticket_id.eq(target))
+            .execute(conn)?;
+        //Files of notes moved with them already have the ticket id of the note
+        diesel::update(attachments::table.filter(attachments::ticket_id.eq(source)))
+            .set(attachments::ticket_id.eq(target))
+            .execute(conn)?;
+
+        //Tasks of the ticket come after the tasks of the target
+        let last = ticket_tasks::table
+            .filter(ticket_tasks::ticket_id.eq(target))
+            .select(diesel::dsl::max(ticket_tasks::position))
+            .first::<Option<i32>>(conn)?;
+        let offset = last.map_or(0, |last| last + 1);
+        diesel::update(ticket_tasks::table.filter(ticket_tasks::ticket_id.eq(source)))
+            .set((
+                ticket_tasks::ticket_id.eq(target),
+                ticket_tasks::position.eq(ticket_tasks::position + offset),
+            ))
+            .execute(conn)?;
+
+        //Watchers of both tickets keep watching the target
+        let watchers = ticket_watchers::table
+            .filter(ticket_watchers::ticket_id.eq(source))
+            .select((ticket_watchers::user_id, ticket_watchers::created_at))
+            .load::<(Uuid, chrono::NaiveDateTime)>(conn)?;
+        let moved = watchers
+            .into_iter()
+            .map(|(user, created)| {
+                (
+                    ticket_watchers::ticket_id.eq(target),
+                    ticket_watchers::user_id.eq(user),
+                    ticket_watchers::created_at.eq(created),
+                )
+            })
+            .collect::<Vec<_>>();
+        diesel::insert_into(ticket_watchers::table)
+            .values(&moved)
+            .on_conflict_do_nothing()
+            .execute(conn)?;
+        diesel::delete(ticket_watchers::table.filter(ticket_watchers::ticket_id.eq(source)))
+            .execute(conn)?;
+
+        let now = chrono::Utc::now().naive_utc();
+        diesel::update(tickets::table.find(source))
+            .set((
+                tickets::status.eq(closed),
+                tickets::merged_into.eq(target),
+                tickets::updated_at.eq(now),
+            ))
+            .execute(conn)?;
+        diesel::update(tickets::table.find(target))
+            .set(tickets::updated_at.eq(now))
+            .execute(conn)?;
+        update_sla(source, conn)?;
+
+        let events = vec![
+            NewTicketEvent {
+                event_id: Uuid::new_v4(),
+                ticket_id: source,
+                event_type: TicketEventType::MergedInto.to_string(),
+                event_data: target.to_string(),
+                user_id: actor,
+                created_at: now,
+            },
+            NewTicketEvent {
+                event_id: Uuid::new_v4(),
+                ticket_id: target,
+                event_type: TicketEventType::Merged.to_string(),
+                event_data: source.to_string(),
+                user_id: actor,
+                created_at: now,
+            },
+        ];
+        diesel::insert_into(ticket_events::table)
+            .values(&events)
+            .execute(conn)?;
+
+        Ok(Ok(events))
+    })
+}
@@ -1,8 +1,28 @@
+pub mod attachments;
 pub mod auth;
+pub mod automation;
+pub mod bulk;
 pub mod comments;
 pub mod contacts;
+pub mod custom_fields;
 pub mod documents;
+pub mod links;
+pub mod live;
+pub mod merge;
 pub mod notes;
+pub mod notifications;
+pub mod portal;
+pub mod projects;
+pub mod recurring;
+pub mod reports;
+pub mod search;
+pub mod sla;
+pub mod tags;
+pub mod tasks;
 pub mod tickets;
 pub mod user_preferences;
 pub mod users;
+pub mod views;
+pub mod watchers;
+pub mod webhooks;
+pub mod workflows;
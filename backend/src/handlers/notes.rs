@@ -2,24 +2,59 @@ use super::super::DbPool;
 
 use actix_web::{delete, get, post, put, web, Error, HttpResponse};
 use diesel::prelude::*;
+use shared::models::{live::LiveEvent, users::Role, webhooks::WebhookEvent};
 use uuid::Uuid;
 
+use super::attachments::{delete_blobs, find_storage_keys, AttachmentOwner};
+use super::notifications::notify_note_followers;
+use super::sla::record_response;
+use super::tickets::check_ticket_access;
+use super::watchers::add_watcher;
+use super::webhooks::{publish, WebhookSubject};
+use crate::authentication::CurrentUser;
+use crate::live::Broadcaster;
+use crate::mail::{notify, Mailer};
 use crate::models::{
-    notes::{NewNote, Note, NotePayload, NoteRepresentation},
-    users::User,
+    notes::{NewNote, Note, NotePayload, NoteRepresentation, NoteWithAuthor},
     SuccessResponse,
 };
+use crate::storage::Storage;
 
 type DbError = Box<dyn std::error::Error + Send + Sync>;
 
 #[post("/notes")]
 async fn create(
     pool: web::Data<DbPool>,
+    broadcaster: web::Data<Broadcaster>,
+    mailer: web::Data<Mailer>,
     payload: web::Json<NotePayload>,
+    caller: CurrentUser,
 ) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::can_create_tickets)?;
+    check_ticket_access(&pool, payload.ticket, &caller).await?;
+
+    //Notes are always posted as the logged in user
+    let mut payload = payload.into_inner();
+    payload.owner = Some(caller.user_id);
+    //Notes from agents answer the ticket for its SLA
+    let responds = caller.role.can_edit();
+    //Requesters cannot hide their notes from themselves
+    if !caller.role.can_edit() {
+        payload.is_public = Some(true);
+    }
+
+    let notify_pool = pool.get_ref().clone();
     let note = web::block(move || {
         let mut conn = pool.get()?;
-        add_a_note(payload.into_inner(), &mut conn)
+        let ticket = payload.ticket;
+        if payload.is_public.is_none() {
+            payload.is_public = Some(notes_public_by_default(caller.user_id, &mut conn)?);
+        }
+        let note = add_a_note(payload, &mut conn)?;
+        if responds {
+            record_response(ticket, &mut conn)?;
+        }
+        Ok::<_, DbError>(note)
     })
     .await?
     .map(|x| {
@@ -30,12 +65,24 @@ async fn create(
     .map_err(actix_web::error::ErrorInternalServerError)?;
 
     let note = note.first().unwrap();
+    broadcaster.broadcast(LiveEvent::NotesChanged {
+        ticket_id: note.ticket,
+        user_id: Some(caller.user_id),
+    });
+    publish(
+        notify_pool.clone(),
+        WebhookEvent::NoteCreated,
+        WebhookSubject::Note(note.note_id),
+    );
+    notify::new_note(notify_pool, mailer.get_ref().clone(), note.note_id);
 
     Ok(HttpResponse::Ok().json(note))
 }
 
 #[get("/notes")]
-async fn index(pool: web::Data<DbPool>) -> Result<HttpResponse, Error> {
+async fn index(pool: web::Data<DbPool>, caller: CurrentUser) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::can_view)?;
+
     let notes = web::block(move || {
         let mut conn = pool.get()?;
         find_all(&mut conn)
@@ -52,7 +99,17 @@ async fn index(pool: web::Data<DbPool>) -> Result<HttpResponse, Error> {
 }
 
 #[get("/notes/{id}")]
-async fn show(id: web::Path<Uuid>, pool: web::Data<DbPool>) -> Result<HttpResponse, Error> {
+async fn show(
+    id: web::Path<Uuid>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    let existing = fetch_note(&pool, *id).await?;
+    check_ticket_access(&pool, existing.ticket, &caller).await?;
+    if !existing.is_public && !caller.role.can_view() {
+        return Ok(HttpResponse::NotFound().finish());
+    }
+
     let note = web::block(move || {
         let mut conn = pool.get()?;
         find_by_id(id.into_inner(), &mut conn)
@@ -72,10 +129,18 @@ async fn show(id: web::Path<Uuid>, pool: web::Data<DbPool>) -> Result<HttpRespon
 
 //all notes for a ticket
 #[get("/tickets/{id}/notes")]
-async fn ticket_notes(id: web::Path<i32>, pool: web::Data<DbPool>) -> Result<HttpResponse, Error> {
+async fn ticket_notes(
+    id: web::Path<i32>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    check_ticket_access(&pool, *id, &caller).await?;
+    //Requesters only get the public side of the conversation
+    let public_only = !caller.role.can_view();
+
     let notes = web::block(move || {
         let mut conn = pool.get()?;
-        find_by_ticket_id(id.into_inner(), &mut conn)
+        find_by_ticket_id(id.into_inner(), public_only, &mut conn)
     })
     .await?
     .map(|x| {
@@ -93,26 +158,80 @@ async fn update(
     id: web::Path<Uuid>,
     payload: web::Json<NotePayload>,
     pool: web::Data<DbPool>,
+    broadcaster: web::Data<Broadcaster>,
+    caller: CurrentUser,
 ) -> Result<HttpResponse, Error> {
+    let existing = fetch_note(&pool, *id).await?;
+    if existing.owner != Some(caller.user_id) {
+        caller.ensure(Role::can_edit)?;
+    }
+    let mut payload = payload.into_inner();
+    if !caller.role.can_edit() {
+        payload.is_public = None;
+    }
+
+    let webhook_pool = pool.get_ref().clone();
     let note = web::block(move || {
         let mut conn = pool.get()?;
-        update_note(id.into_inner(), payload.into_inner(), &mut conn)
+        update_note(id.into_inner(), payload, &mut conn)
     })
     .await?
     .map_err(actix_web::error::ErrorInternalServerError)?;
 
+    broadcaster.broadcast(LiveEvent::NotesChanged {
+        ticket_id: existing.ticket,
+        user_id: Some(caller.user_id),
+    });
+    publish(
+        webhook_pool,
+        WebhookEvent::NoteUpdated,
+        WebhookSubject::Note(existing.note_id),
+    );
+
     Ok(HttpResponse::Ok().json(note))
 }
 
 #[delete("/notes/{id}")]
-async fn delete(id: web::Path<Uuid>, pool: web::Data<DbPool>) -> Result<HttpResponse, Error> {
+async fn delete(
+    id: web::Path<Uuid>,
+    pool: web::Data<DbPool>,
+    storage: web::Data<dyn Storage>,
+    broadcaster: web::Data<Broadcaster>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    let existing = fetch_note(&pool, *id).await?;
+    if existing.owner != Some(caller.user_id) {
+        caller.ensure(Role::is_admin)?;
+    }
+
+    let owner = AttachmentOwner::Note(existing.ticket, existing.note_id);
+    let webhook_pool = pool.get_ref().clone();
     let result = web::block(move || {
         let mut conn = pool.get()?;
-        delete_note(id.into_inner(), &mut conn)
+        //Attachments go with the note, their files have to be removed separately
+        let keys = find_storage_keys(owner, &mut conn)?;
+        let result = delete_note(id.into_inner(), &mut conn)?;
+        delete_blobs(storage.as_ref(), &keys);
+        Ok::<_, DbError>(result)
     })
     .await?
     .map_err(actix_web::error::ErrorInternalServerError)?;
 
+    broadcaster.broadcast(LiveEvent::NotesChanged {
+        ticket_id: existing.ticket,
+        user_id: Some(caller.user_id),
+    });
+    if result > 0 {
+        publish(
+            webhook_pool,
+            WebhookEvent::NoteDeleted,
+            WebhookSubject::Deleted(serde_json::json!({
+                "note_id": existing.note_id,
+                "ticket_id": existing.ticket,
+            })),
+        );
+    }
+
     if result > 1 {
         let response = SuccessResponse {
             success: true,
@@ -128,10 +247,22 @@ async fn delete(id: web::Path<Uuid>, pool: web::Data<DbPool>) -> Result<HttpResp
     }
 }
 
+/// Loads a note without joins, used for permission checks
+pub async fn fetch_note(pool: &web::Data<DbPool>, id: Uuid) -> Result<Note, Error> {
+    let pool = pool.clone();
+    web::block(move || {
+        let mut conn = pool.get()?;
+        get_note_by_id(id, &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorNotFound)
+}
+
 fn add_a_note(
     payload: NotePayload,
     conn: &mut PgConnection,
-) -> Result<Vec<(Note, Option<User>)>, DbError> {
+) -> Result<Vec<NoteWithAuthor>, DbError> {
+    use crate::schema::contacts::dsl::contacts;
     use crate::schema::notes::dsl::*;
     use crate::schema::users::dsl::users;
 
@@ -142,6 +273,8 @@ fn add_a_note(
         text: &payload.text,
         time: payload.time,
         created_at: chrono::Utc::now().naive_utc(),
+        contact: None,
+        is_public: payload.is_public.unwrap_or(true),
     };
 
     let result: Note = diesel::insert_into(notes)
@@ -154,33 +287,58 @@ fn add_a_note(
         .set(crate::schema::tickets::updated_at.eq(result.created_at))
         .execute(conn)?;
 
-    let note: Vec<(Note, Option<User>)> = notes
+    //Note authors follow the ticket from then on
+    if let Some(author) = result.owner {
+        add_watcher(result.ticket, author, conn)?;
+    }
+    notify_note_followers(
+        result.ticket,
+        result.owner,
+        result.note_id,
+        result.is_public,
+        conn,
+    )?;
+
+    let note: Vec<NoteWithAuthor> = notes
         .filter(note_id.eq(result.note_id))
         .left_join(users)
-        .load::<(Note, Option<User>)>(conn)?;
+        .left_join(contacts)
+        .load::<NoteWithAuthor>(conn)?;
 
     Ok(note)
 }
 
-fn find_all(conn: &mut PgConnection) -> Result<Vec<(Note, Option<User>)>, DbError> {
+fn find_all(conn: &mut PgConnection) -> Result<Vec<NoteWithAuthor>, DbError> {
+    use crate::schema::contacts::dsl::contacts;
     use crate::schema::notes::dsl::*;
     use crate::schema::users::dsl::users;
 
-    let items: Vec<(Note, Option<User>)> =
-        notes.left_join(users).load::<(Note, Option<User>)>(conn)?;
+    let items: Vec<NoteWithAuthor> = notes
+        .left_join(users)
+        .left_join(contacts)
+        .load::<NoteWithAuthor>(conn)?;
 
     Ok(items)
 }
 
-fn find_by_id(id: Uuid, conn: &mut PgConnection) -> Result<Vec<(Note, Option<User>)>, DbError> {
+fn find_by_id(id: Uuid, conn: &mut PgConnection) -> Result<Vec<NoteWithAuthor>, DbError> {
+    use crate::schema::contacts::dsl::contacts;
     use crate::schema::notes::dsl::*;
     use crate::schema::users::dsl::users;
 
-    let note: Vec<(Note, Option<User>)> =
-        notes
-            .filter(note_id.eq(&id))
-            .left_join(users)
-            .load::<(Note, Option<User>)>(conn)?;
+    let note: Vec<NoteWithAuthor> = notes
+        .filter(note_id.eq(&id))
+        .left_join(users)
+        .left_join(contacts)
+        .load::<NoteWithAuthor>(conn)?;
+
+    Ok(note)
+}
+
+fn get_note_by_id(id: Uuid, conn: &mut PgConnection) -> Result<Note, DbError> {
+    use crate::schema::notes::dsl::*;
+
+    let note = notes.find(id).first::<Note>(conn)?;
 
     Ok(note)
 }
@@ -190,17 +348,22 @@ fn update_note(
     payload: NotePayload,
     conn: &mut PgConnection,
 ) -> Result<NoteRepresentation, DbError> {
+    use crate::schema::contacts::dsl::contacts;
     use crate::schema::notes::dsl::*;
     use crate::schema::users::dsl::users;
 
     let result: Note = diesel::update(notes.find(id))
-        .set(text.eq(payload.text))
+        .set((
+            text.eq(payload.text),
+            payload.is_public.map(|public| is_public.eq(public)),
+        ))
         .get_result(conn)?;
 
-    let note: (Note, Option<User>) = notes
+    let note: NoteWithAuthor = notes
         .filter(note_id.eq(result.note_id))
         .left_join(users)
-        .first::<(Note, Option<User>)>(conn)?;
+        .left_join(contacts)
+        .first::<NoteWithAuthor>(conn)?;
 
     let note = NoteRepresentation::from(note);
 
@@ -216,16 +379,35 @@ fn delete_note(id: Uuid, conn: &mut PgConnection) -> Result<usize, DbError> {
 
 fn find_by_ticket_id(
     id: i32,
+    public_only: bool,
     conn: &mut PgConnection,
-) -> Result<Vec<(Note, Option<User>)>, DbError> {
+) -> Result<Vec<NoteWithAuthor>, DbError> {
+    use crate::schema::contacts::dsl::contacts;
     use crate::schema::notes::dsl::*;
     use crate::schema::users::dsl::users;
 
-    let items: Vec<(Note, Option<User>)> =
-        notes
-            .filter(ticket.eq(&id))
-            .left_join(users)
-            .load::<(Note, Option<User>)>(conn)?;
+    let mut query = notes
+        .filter(ticket.eq(&id))
+        .left_join(users)
+        .left_join(contacts)
+        .into_boxed();
+    if public_only {
+        query = query.filter(is_public.eq(true));
+    }
+    let items: Vec<NoteWithAuthor> = query.load::<NoteWithAuthor>(conn)?;
 
     Ok(items)
 }
+
+/// The author's choice for notes that do not say whether they are public
+fn notes_public_by_default(user: Uuid, conn: &mut PgConnection) -> Result<bool, DbError> {
+    use crate::schema::user_preferences::dsl::*;
+
+    let preference = user_preferences
+        .find(user)
+        .select(notes_public)
+        .first::<bool>(conn)
+        .optional()?;
+
+    Ok(preference.unwrap_or(true))
+}
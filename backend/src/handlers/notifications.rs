@@ -0,0 +1,251 @@
+use super::super::DbPool;
+
+use actix_web::{get, put, web, Error, HttpResponse};
+use diesel::prelude::*;
+use shared::models::{
+    notifications::{NotificationQuery, UnreadCount, NOTE_ADDED},
+    users::Role,
+};
+use uuid::Uuid;
+
+use crate::authentication::CurrentUser;
+use crate::models::{
+    notifications::{NewNotification, Notification, NotificationRepresentation},
+    SuccessResponse,
+};
+
+type DbError = Box<dyn std::error::Error + Send + Sync>;
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 200;
+
+/// The caller's notifications, newest first
+#[get("/notifications")]
+async fn index(
+    query: web::Query<NotificationQuery>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    let query = query.into_inner();
+
+    let notifications = web::block(move || {
+        let mut conn = pool.get()?;
+        find_by_user_id(caller.user_id, query, &mut conn)
+    })
+    .await?
+    .map(|x| {
+        x.into_iter()
+            .map(NotificationRepresentation::from)
+            .collect::<Vec<NotificationRepresentation>>()
+    })
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(notifications))
+}
+
+#[get("/notifications/count")]
+async fn unread_count(pool: web::Data<DbPool>, caller: CurrentUser) -> Result<HttpResponse, Error> {
+    let unread = web::block(move || {
+        let mut conn = pool.get()?;
+        count_unread(caller.user_id, &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(UnreadCount { unread }))
+}
+
+#[put("/notifications/{id}/read")]
+async fn mark_read(
+    id: web::Path<Uuid>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    let result = web::block(move || {
+        let mut conn = pool.get()?;
+        set_read(caller.user_id, Some(id.into_inner()), &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let response = if result > 0 {
+        SuccessResponse {
+            success: true,
+            message: "Notification marked as read".to_string(),
+        }
+    } else {
+        SuccessResponse {
+            success: false,
+            message: "Notification not found".to_string(),
+        }
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+#[put("/notifications/read")]
+async fn mark_all_read(
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    let result = web::block(move || {
+        let mut conn = pool.get()?;
+        set_read(caller.user_id, None, &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let response = SuccessResponse {
+        success: true,
+        message: format!("{} notifications marked as read", result),
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Adds a notification for each change to everyone following the ticket (the assignee
+/// and watchers), except whoever made the change.
+/// `changes` are `(event_type, event_data)` pairs, see `TicketEventType` and `NOTE_ADDED`.
+pub fn notify_followers(
+    id: i32,
+    actor_id: Option<Uuid>,
+    changes: &[(String, String)],
+    conn: &mut PgConnection,
+) -> Result<(), DbError> {
+    add_notifications(id, actor_id, changes, false, conn)
+}
+
+/// Notifies followers of a new note, requesters only hear of public notes
+pub fn notify_note_followers(
+    id: i32,
+    actor_id: Option<Uuid>,
+    note_id: Uuid,
+    is_public: bool,
+    conn: &mut PgConnection,
+) -> Result<(), DbError> {
+    let changes = [(NOTE_ADDED.to_string(), note_id.to_string())];
+    add_notifications(id, actor_id, &changes, !is_public, conn)
+}
+
+/// Requesters are left out of `internal` changes
+fn add_notifications(
+    id: i32,
+    actor_id: Option<Uuid>,
+    changes: &[(String, String)],
+    internal: bool,
+    conn: &mut PgConnection,
+) -> Result<(), DbError> {
+    use crate::schema::notifications::dsl::*;
+    use crate::schema::ticket_watchers::dsl as watchers;
+    use crate::schema::tickets::dsl as tickets;
+    use crate::schema::users;
+
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    let mut followers: Vec<Uuid> = watchers::ticket_watchers
+        .filter(watchers::ticket_id.eq(id))
+        .select(watchers::user_id)
+        .load(conn)?;
+    let assignee: Option<Uuid> = tickets::tickets
+        .find(id)
+        .select(tickets::assignee)
+        .first(conn)?;
+    followers.extend(assignee);
+    followers.sort();
+    followers.dedup();
+    followers.retain(|follower| Some(*follower) != actor_id);
+    if internal {
+        followers = users::table
+            .filter(users::user_id.eq_any(&followers))
+            .filter(users::access.ne(Role::Requester.as_str()))
+            .select(users::user_id)
+            .load(conn)?;
+    }
+
+    let now = chrono::Utc::now().naive_utc();
+    let new_notifications: Vec<NewNotification> = followers
+        .iter()
+        .flat_map(|follower| {
+            changes.iter().map(move |(kind, data)| NewNotification {
+                notification_id: Uuid::new_v4(),
+                user_id: *follower,
+                ticket_id: id,
+                event_type: kind.clone(),
+                event_data: data.clone(),
+                actor: actor_id,
+                created_at: now,
+            })
+        })
+        .collect();
+
+    diesel::insert_into(notifications)
+        .values(&new_notifications)
+        .execute(conn)?;
+
+    Ok(())
+}
+
+fn find_by_user_id(
+    id: Uuid,
+    query: NotificationQuery,
+    conn: &mut PgConnection,
+) -> Result<Vec<(Notification, String, Option<String>)>, DbError> {
+    use crate::schema::notifications::dsl::*;
+    use crate::schema::tickets::dsl::{tickets, title};
+    use crate::schema::users::dsl::{display_name, users};
+
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    let mut items = notifications
+        .inner_join(tickets)
+        .left_join(users.on(crate::schema::users::user_id.nullable().eq(actor)))
+        .filter(user_id.eq(id))
+        .select((notifications::all_columns(), title, display_name.nullable()))
+        .order(created_at.desc())
+        .limit(limit)
+        .into_boxed();
+
+    if query.unread == Some(true) {
+        items = items.filter(read_at.is_null());
+    }
+
+    let items = items.load::<(Notification, String, Option<String>)>(conn)?;
+
+    Ok(items)
+}
+
+fn count_unread(id: Uuid, conn: &mut PgConnection) -> Result<i64, DbError> {
+    use crate::schema::notifications::dsl::*;
+
+    let count = notifications
+        .filter(user_id.eq(id))
+        .filter(read_at.is_null())
+        .count()
+        .get_result(conn)?;
+
+    Ok(count)
+}
+
+/// Marks one notification, or all of them when `notification` is None
+fn set_read(
+    id: Uuid,
+    notification: Option<Uuid>,
+    conn: &mut PgConnection,
+) -> Result<usize, DbError> {
+    use crate::schema::notifications::dsl::*;
+
+    let now = chrono::Utc::now().naive_utc();
+    let mut target = diesel::update(notifications)
+        .filter(user_id.eq(id))
+        .filter(read_at.is_null())
+        .into_boxed();
+    if let Some(notification) = notification {
+        target = target.filter(notification_id.eq(notification));
+    }
+
+    let count = target.set(read_at.eq(now)).execute(conn)?;
+
+    Ok(count)
+}
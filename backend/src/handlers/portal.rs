@@ -0,0 +1,734 @@
+//! Requester portal.
+//!
+//! Contacts sign in with a link sent by e-mail or a password they set after signing in once,
+//! submit tickets and follow their own tickets. The session only carries a contact id,
+//! so none of the internal endpoints under /api accept it.
+
+use super::super::DbPool;
+
+use actix_web::{get, post, put, web, Error, HttpResponse};
+use diesel::prelude::*;
+use lettre::message::Mailbox;
+use secrecy::ExposeSecret;
+use sha2::{Digest, Sha256};
+use shared::models::{
+    automation::AutomationTrigger, live::LiveEvent, notifications::NOTE_ADDED, response::Response,
+    webhooks::WebhookEvent, MAX_TITLE_LENGTH,
+};
+use uuid::Uuid;
+
+use super::automation::automate;
+use super::notifications::notify_followers;
+use super::sla::update_sla;
+use super::webhooks::{publish, WebhookSubject};
+use super::workflows::{default_priority, default_status};
+use crate::authentication::{
+    check_password_reqs, compute_password_hash, verify_password_hash, AuthError, CurrentContact,
+};
+use crate::live::Broadcaster;
+use crate::mail::{notify, Mailer};
+use crate::models::{
+    contacts::Contact,
+    notes::{NewNote, Note},
+    portal::{
+        MagicLinkPayload, MagicLinkVerifyPayload, NewPortalToken, PortalContactRepresentation,
+        PortalLoginPayload, PortalNoteRepresentation, PortalPassword, PortalPasswordPayload,
+        PortalReplyPayload, PortalTicketDetail, PortalTicketPayload, PortalTicketRepresentation,
+    },
+    session::TypedSession,
+    tickets::{NewTicket, Ticket},
+    SuccessResponse,
+};
+
+type DbError = Box<dyn std::error::Error + Send + Sync>;
+
+define_sql_function!(fn lower(x: diesel::sql_types::Text) -> diesel::sql_types::Text);
+
+/// How long a sign-in link stays valid
+const TOKEN_MINUTES: i64 = 30;
+const TOKEN_LENGTH: usize = 48;
+
+const MAGIC_LINK_SUBJECT: &str = "Sign in to the support portal";
+
+const MAGIC_LINK_TEMPLATE: &str = "Hello {name},
+
+Use this link to sign in to the support portal, it can be used once within {minutes} minutes:
+
+{url}
+
+If you did not ask to sign in, you can ignore this e-mail.
+";
+
+#[post("/login")]
+async fn login(
+    pool: web::Data<DbPool>,
+    payload: web::Json<PortalLoginPayload>,
+    session: TypedSession,
+) -> Result<HttpResponse, Error> {
+    let payload = payload.into_inner();
+    let email = payload.email.trim().to_lowercase();
+    let stored = web::block(move || {
+        let mut conn = pool.get()?;
+        find_password_hash(&email, &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    //Unknown addresses and contacts without a password fail the same way
+    let (contact_id, hash) = stored.unwrap_or_default();
+    let verified =
+        web::block(move || verify_password_hash(payload.password, secrecy::Secret::new(hash)))
+            .await?;
+    if verified.is_err() || contact_id.is_nil() {
+        return Ok(HttpResponse::Ok().json(SuccessResponse {
+            success: false,
+            message: "Invalid e-mail address or password".to_string(),
+        }));
+    }
+
+    sign_in(&session, contact_id)
+}
+
+/// Always responds the same way, so it cannot be used to find out which addresses are known
+#[post("/magic-link")]
+async fn magic_link(
+    pool: web::Data<DbPool>,
+    mailer: web::Data<Mailer>,
+    payload: web::Json<MagicLinkPayload>,
+) -> Result<HttpResponse, Error> {
+    let email = payload.email.trim().to_lowercase();
+    let token = generate_token();
+    let token_hash = hash_token(&token);
+    let contact = web::block(move || {
+        let mut conn = pool.get()?;
+        let Some(contact) = find_by_email(&email, &mut conn)? else {
+            return Ok::<_, DbError>(None);
+        };
+        add_token(contact.contact_id, token_hash, &mut conn)?;
+        Ok(Some(contact))
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    if let Some(contact) = contact {
+        send_magic_link(mailer.get_ref().clone(), contact, token);
+    }
+
+    let response = SuccessResponse {
+        success: true,
+        message: "If the address is known, a sign-in link is on its way".to_string(),
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+#[post("/magic-link/verify")]
+async fn verify_magic_link(
+    pool: web::Data<DbPool>,
+    payload: web::Json<MagicLinkVerifyPayload>,
+    session: TypedSession,
+) -> Result<HttpResponse, Error> {
+    let token_hash = hash_token(payload.token.trim());
+    let contact_id = web::block(move || {
+        let mut conn = pool.get()?;
+        consume_token(&token_hash, &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    match contact_id {
+        Some(contact_id) => sign_in(&session, contact_id),
+        None => Ok(HttpResponse::Ok().json(SuccessResponse {
+            success: false,
+            message: "The sign-in link is invalid or has expired".to_string(),
+        })),
+    }
+}
+
+#[post("/logout")]
+async fn logout(session: TypedSession) -> Result<HttpResponse, Error> {
+    session.log_out();
+
+    let response = SuccessResponse {
+        success: true,
+        message: "Logout successful".to_string(),
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+#[get("/me")]
+async fn me(pool: web::Data<DbPool>, caller: CurrentContact) -> Result<HttpResponse, Error> {
+    let contact = web::block(move || {
+        let mut conn = pool.get()?;
+        find_contact(caller.contact_id, &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(contact))
+}
+
+/// Sets or replaces the portal password of the signed in requester
+#[put("/password")]
+async fn set_password(
+    pool: web::Data<DbPool>,
+    payload: web::Json<PortalPasswordPayload>,
+    caller: CurrentContact,
+) -> Result<HttpResponse, Error> {
+    let password = payload.into_inner().password;
+    //The requirement itself is the source, the error only says the credentials are invalid
+    if let Err(AuthError::InvalidCredentials(e) | AuthError::UnexpectedError(e)) =
+        check_password_reqs(&password)
+    {
+        return Ok(HttpResponse::Ok().json(SuccessResponse {
+            success: false,
+            message: e.to_string(),
+        }));
+    }
+
+    let hash = web::block(move || compute_password_hash(password))
+        .await?
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    web::block(move || {
+        let mut conn = pool.get()?;
+        save_password(caller.contact_id, hash.expose_secret(), &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let response = SuccessResponse {
+        success: true,
+        message: "Password saved".to_string(),
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Tickets the requester opened, newest first
+#[get("/tickets")]
+async fn own_tickets(
+    pool: web::Data<DbPool>,
+    caller: CurrentContact,
+) -> Result<HttpResponse, Error> {
+    let found = web::block(move || {
+        let mut conn = pool.get()?;
+        find_own_tickets(caller.contact_id, &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(found))
+}
+
+#[post("/tickets")]
+async fn create_ticket(
+    pool: web::Data<DbPool>,
+    payload: web::Json<PortalTicketPayload>,
+    broadcaster: web::Data<Broadcaster>,
+    mailer: web::Data<Mailer>,
+    caller: CurrentContact,
+) -> Result<HttpResponse, Error> {
+    let mut payload = payload.into_inner();
+    payload.title = payload.title.trim().to_string();
+    if let Some(message) = validate(&payload) {
+        return Ok(HttpResponse::Ok().json(failure::<PortalTicketRepresentation>(message)));
+    }
+
+    let notify_pool = pool.get_ref().clone();
+    let contact_id = caller.contact_id;
+    let mut ticket = {
+        let pool = pool.clone();
+        web::block(move || {
+            let mut conn = pool.get()?;
+            add_ticket(contact_id, payload, &mut conn)
+        })
+        .await?
+        .map_err(actix_web::error::ErrorInternalServerError)?
+    };
+    let ticket_id = ticket.ticket_id;
+
+    broadcaster.broadcast(LiveEvent::TicketChanged {
+        ticket_id,
+        user_id: None,
+    });
+    publish(
+        notify_pool.clone(),
+        WebhookEvent::TicketCreated,
+        WebhookSubject::Ticket(ticket_id, vec![]),
+    );
+    //Rules may have changed the ticket right away
+    let triggers = vec![AutomationTrigger::TicketCreated];
+    if automate(&notify_pool, &mailer, &broadcaster, ticket_id, triggers).await {
+        ticket = web::block(move || {
+            let mut conn = pool.get()?;
+            let found =
+                find_own_ticket(ticket_id, contact_id, &mut conn)?.ok_or("Ticket not found")?;
+            represent(found, &mut conn)
+        })
+        .await?
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    }
+
+    let response = Response {
+        success: true,
+        message: None,
+        data: Some(ticket),
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Other requesters' tickets are reported as missing
+#[get("/tickets/{id}")]
+async fn own_ticket(
+    id: web::Path<i32>,
+    pool: web::Data<DbPool>,
+    caller: CurrentContact,
+) -> Result<HttpResponse, Error> {
+    let id = id.into_inner();
+    let detail = web::block(move || {
+        let mut conn = pool.get()?;
+        let Some(ticket) = find_own_ticket(id, caller.contact_id, &mut conn)? else {
+            return Ok(None);
+        };
+        let notes = find_notes(id, &mut conn)?;
+        let ticket = represent(ticket, &mut conn)?;
+        Ok::<_, DbError>(Some(PortalTicketDetail { ticket, notes }))
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    match detail {
+        Some(detail) => Ok(HttpResponse::Ok().json(detail)),
+        None => Ok(HttpResponse::NotFound().finish()),
+    }
+}
+
+#[post("/tickets/{id}/notes")]
+async fn reply(
+    id: web::Path<i32>,
+    pool: web::Data<DbPool>,
+    payload: web::Json<PortalReplyPayload>,
+    broadcaster: web::Data<Broadcaster>,
+    mailer: web::Data<Mailer>,
+    caller: CurrentContact,
+) -> Result<HttpResponse, Error> {
+    let id = id.into_inner();
+    let text = payload.into_inner().text;
+    if text.trim().is_empty() {
+        return Ok(HttpResponse::Ok().json(failure::<PortalNoteRepresentation>(
+            "The reply is empty".to_string(),
+        )));
+    }
+
+    let notify_pool = pool.get_ref().clone();
+    let result = web::block(move || {
+        let mut conn = pool.get()?;
+        let Some(ticket) = find_own_ticket(id, caller.contact_id, &mut conn)? else {
+            return Ok(None);
+        };
+        if let Some(merged) = ticket.merged_into {
+            return Ok(Some(Err(format!(
+                "This ticket was merged into #{}, reply there instead",
+                merged
+            ))));
+        }
+        add_reply(id, caller.contact_id, &text, &mut conn).map(|note| Some(Ok(note)))
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let note = match result {
+        Some(Ok(note)) => note,
+        Some(Err(message)) => return Ok(HttpResponse::Ok().json(failure::<()>(message))),
+        None => return Ok(HttpResponse::NotFound().finish()),
+    };
+
+    broadcaster.broadcast(LiveEvent::NotesChanged {
+        ticket_id: id,
+        user_id: None,
+    });
+    publish(
+        notify_pool.clone(),
+        WebhookEvent::NoteCreated,
+        WebhookSubject::Note(note.note_id),
+    );
+    notify::new_note(notify_pool, mailer.get_ref().clone(), note.note_id);
+
+    let response = Response {
+        success: true,
+        message: None,
+        data: Some(note),
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+fn failure<T>(message: String) -> Response<T> {
+    Response {
+        success: false,
+        message: Some(message),
+        data: None,
+    }
+}
+
+fn sign_in(session: &TypedSession, contact_id: Uuid) -> Result<HttpResponse, Error> {
+    session.renew();
+    session
+        .insert_contact_id(contact_id)
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let response = SuccessResponse {
+        success: true,
+        message: "Login successful".to_string(),
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+fn validate(payload: &PortalTicketPayload) -> Option<String> {
+    if payload.title.is_empty() {
+        return Some("Title is required".to_string());
+    }
+    if payload.title.len() > MAX_TITLE_LENGTH {
+        return Some(format!(
+            "Title is too long, max length is {}",
+            MAX_TITLE_LENGTH
+        ));
+    }
+    if payload.description.trim().is_empty() {
+        return Some("Please describe the issue".to_string());
+    }
+
+    None
+}
+
+fn generate_token() -> String {
+    rand::Rng::sample_iter(rand::thread_rng(), &rand::distributions::Alphanumeric)
+        .take(TOKEN_LENGTH)
+        .map(char::from)
+        .collect()
+}
+
+fn hash_token(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Sends the sign-in link in the background, the response does not wait for the mail server
+fn send_magic_link(mailer: Mailer, contact: Contact, token: String) {
+    if !mailer.is_enabled() {
+        log::warn!(
+            "Mail is disabled, unable to send a sign-in link to {}",
+            contact.email
+        );
+        return;
+    }
+
+    actix_rt::spawn(async move {
+        let address = match contact.email.parse() {
+            Ok(address) => address,
+            Err(e) => {
+                return log::warn!("Unable to send a sign-in link to {}: {}", contact.email, e)
+            }
+        };
+        let url = format!("{}/portal/login/{}", mailer.base_url, token);
+        let minutes = TOKEN_MINUTES.to_string();
+        let body = MAGIC_LINK_TEMPLATE
+            .replace("{name}", &contact.display_name)
+            .replace("{minutes}", &minutes)
+            .replace("{url}", &url);
+        let recipient = Mailbox::new(Some(contact.display_name), address);
+        if let Err(e) = mailer.send(recipient, MAGIC_LINK_SUBJECT, body).await {
+            log::error!("Unable to send a sign-in link to {}: {}", contact.email, e);
+        }
+    });
+}
+
+fn find_by_email(address: &str, conn: &mut PgConnection) -> Result<Option<Contact>, DbError> {
+    use crate::schema::contacts::dsl::*;
+
+    let contact = contacts
+        .filter(lower(email).eq(address))
+        .first::<Contact>(conn)
+        .optional()?;
+
+    Ok(contact)
+}
+
+fn find_password_hash(
+    address: &str,
+    conn: &mut PgConnection,
+) -> Result<Option<(Uuid, String)>, DbError> {
+    use crate::schema::{contacts, portal_passwords};
+
+    let stored = contacts::table
+        .inner_join(portal_passwords::table)
+        .filter(lower(contacts::email).eq(address))
+        .select((contacts::contact_id, portal_passwords::password_hash))
+        .first::<(Uuid, String)>(conn)
+        .optional()?;
+
+    Ok(stored)
+}
+
+fn find_contact(id: Uuid, conn: &mut PgConnection) -> Result<PortalContactRepresentation, DbError> {
+    use crate::schema::{contacts, portal_passwords};
+
+    let contact = contacts::table.find(id).first::<Contact>(conn)?;
+    let has_password =
+        diesel::select(diesel::dsl::exists(portal_passwords::table.find(id))).get_result(conn)?;
+
+    Ok(PortalContactRepresentation {
+        contact_id: contact.contact_id,
+        display_name: contact.display_name,
+        email: contact.email,
+        has_password,
+    })
+}
+
+/// Stores a new sign-in link, expired links of every contact are cleaned up on the way
+fn add_token(contact: Uuid, hash: String, conn: &mut PgConnection) -> Result<(), DbError> {
+    use crate::schema::portal_tokens;
+
+    let now = chrono::Utc::now().naive_utc();
+    diesel::delete(portal_tokens::table.filter(portal_tokens::expires_at.lt(now))).execute(conn)?;
+    let new_token = NewPortalToken {
+        token_hash: hash,
+        contact_id: contact,
+        expires_at: now + chrono::Duration::minutes(TOKEN_MINUTES),
+    };
+    diesel::insert_into(portal_tokens::table)
+        .values(&new_token)
+        .execute(conn)?;
+
+    Ok(())
+}
+
+/// Links work once, the token is removed whether or not it expired
+fn consume_token(hash: &str, conn: &mut PgConnection) -> Result<Option<Uuid>, DbError> {
+    use crate::schema::portal_tokens;
+
+    let token = diesel::delete(portal_tokens::table.find(hash))
+        .returning((portal_tokens::contact_id, portal_tokens::expires_at))
+        .get_result::<(Uuid, chrono::NaiveDateTime)>(conn)
+        .optional()?;
+
+    let now = chrono::Utc::now().naive_utc();
+    Ok(token
+        .filter(|(_, expires_at)| *expires_at > now)
+        .map(|(contact, _)| contact))
+}
+
+fn save_password(contact: Uuid, hash: &str, conn: &mut PgConnection) -> Result<(), DbError> {
+    use crate::schema::portal_passwords;
+
+    let password = PortalPassword {
+        contact_id: contact,
+        password_hash: hash.to_string(),
+        updated_at: chrono::Utc::now().naive_utc(),
+    };
+    diesel::insert_into(portal_passwords::table)
+        .values(&password)
+        .on_conflict(portal_passwords::contact_id)
+        .do_update()
+        .set(&password)
+        .execute(conn)?;
+
+    Ok(())
+}
+
+fn is_closed(status: &str, conn: &mut PgConnection) -> Result<bool, DbError> {
+    use crate::schema::ticket_statuses;
+
+    let closed = ticket_statuses::table
+        .filter(ticket_statuses::name.eq(status))
+        .select(ticket_statuses::is_closed)
+        .first::<bool>(conn)
+        .optional()?;
+
+    Ok(closed.unwrap_or(false))
+}
+
+fn represent(
+    ticket: Ticket,
+    conn: &mut PgConnection,
+) -> Result<PortalTicketRepresentation, DbError> {
+    use crate::schema::tickets;
+
+    let merged_into_own = match ticket.merged_into {
+        Some(target) => {
+            tickets::table
+                .find(target)
+                .select(tickets::contact)
+                .first::<Option<Uuid>>(conn)?
+                == ticket.contact
+        }
+        None => false,
+    };
+
+    Ok(PortalTicketRepresentation {
+        is_closed: is_closed(&ticket.status, conn)?,
+        merged_into_own,
+        ticket_id: ticket.ticket_id,
+        title: ticket.title,
+        description: ticket.description,
+        status: ticket.status,
+        merged_into: ticket.merged_into,
+        created_at: ticket.created_at,
+        updated_at: ticket.updated_at,
+    })
+}
+
+fn find_own_tickets(
+    contact: Uuid,
+    conn: &mut PgConnection,
+) -> Result<Vec<PortalTicketRepresentation>, DbError> {
+    use crate::schema::tickets;
+
+    let found = tickets::table
+        .filter(tickets::contact.eq(contact))
+        .order(tickets::created_at.desc())
+        .load::<Ticket>(conn)?;
+
+    found
+        .into_iter()
+        .map(|ticket| represent(ticket, conn))
+        .collect()
+}
+
+/// The requester's ticket. A ticket merged into someone else's only shows the number of
+/// that ticket, its description and notes stay hidden.
+fn find_own_ticket(
+    id: i32,
+    contact: Uuid,
+    conn: &mut PgConnection,
+) -> Result<Option<Ticket>, DbError> {
+    use crate::schema::tickets;
+
+    let ticket = tickets::table
+        .find(id)
+        .filter(tickets::contact.eq(contact))
+        .first::<Ticket>(conn)
+        .optional()?;
+
+    Ok(ticket)
+}
+
+/// Public notes of the ticket, oldest first
+fn find_notes(id: i32, conn: &mut PgConnection) -> Result<Vec<PortalNoteRepresentation>, DbError> {
+    use crate::schema::{contacts, notes, users};
+
+    let found = notes::table
+        .left_join(users::table)
+        .left_join(contacts::table)
+        .filter(notes::ticket.eq(id))
+        .filter(notes::is_public.eq(true))
+        .order(notes::created_at.asc())
+        .select((
+            notes::all_columns,
+            users::display_name.nullable(),
+            contacts::display_name.nullable(),
+        ))
+        .load::<(Note, Option<String>, Option<String>)>(conn)?;
+
+    Ok(found
+        .into_iter()
+        .map(|(note, user, contact)| PortalNoteRepresentation {
+            note_id: note.note_id,
+            from_requester: note.contact.is_some(),
+            author: contact.or(user).unwrap_or_else(|| "Support".to_string()),
+            text: note.text,
+            created_at: note.created_at,
+        })
+        .collect())
+}
+
+/// Files the ticket like an e-mail from the requester would be
+fn add_ticket(
+    contact: Uuid,
+    payload: PortalTicketPayload,
+    conn: &mut PgConnection,
+) -> Result<PortalTicketRepresentation, DbError> {
+    use crate::schema::tickets;
+
+    let ticket = conn.transaction::<_, DbError, _>(|conn| {
+        let time = chrono::Utc::now().naive_utc();
+        let new_ticket = NewTicket {
+            title: payload.title,
+            assignee: None,
+            contact: Some(contact),
+            description: payload.description,
+            created_at: time,
+            updated_at: time,
+            due_date: None,
+            priority: default_priority(conn)?,
+            status: default_status(conn)?,
+            created_by: None,
+            updated_by: None,
+            revision: time,
+            revision_by: None,
+            project: None,
+            project_number: None,
+        };
+
+        let ticket: Ticket = diesel::insert_into(tickets::table)
+            .values(&new_ticket)
+            .get_result(conn)?;
+        update_sla(ticket.ticket_id, conn)?;
+
+        Ok(ticket)
+    })?;
+
+    represent(ticket, conn)
+}
+
+fn add_reply(
+    id: i32,
+    contact: Uuid,
+    text: &str,
+    conn: &mut PgConnection,
+) -> Result<PortalNoteRepresentation, DbError> {
+    use crate::schema::{contacts, notes, tickets};
+
+    let time = chrono::Utc::now().naive_utc();
+    let new_note = NewNote {
+        note_id: Uuid::new_v4(),
+        ticket: id,
+        owner: None,
+        text,
+        time: 0,
+        created_at: time,
+        contact: Some(contact),
+        is_public: true,
+    };
+
+    diesel::insert_into(notes::table)
+        .values(&new_note)
+        .execute(conn)?;
+    diesel::update(tickets::table.find(id))
+        .set(tickets::updated_at.eq(time))
+        .execute(conn)?;
+    notify_followers(
+        id,
+        None,
+        &[(NOTE_ADDED.to_string(), new_note.note_id.to_string())],
+        conn,
+    )?;
+
+    let author = contacts::table
+        .find(contact)
+        .select(contacts::display_name)
+        .first::<String>(conn)?;
+
+    Ok(PortalNoteRepresentation {
+        note_id: new_note.note_id,
+        author,
+        from_requester: true,
+        text: text.to_string(),
+        created_at: time,
+    })
+}
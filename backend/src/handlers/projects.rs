@@ -0,0 +1,291 @@
+use super::super::DbPool;
+
+use actix_web::{delete, get, post, put, web, Error, HttpResponse};
+use diesel::prelude::*;
+use shared::models::{response::Response, users::Role, MAX_TITLE_LENGTH};
+use uuid::Uuid;
+
+use crate::authentication::CurrentUser;
+use crate::models::{
+    projects::{Project, ProjectPayload},
+    SuccessResponse,
+};
+
+type DbError = Box<dyn std::error::Error + Send + Sync>;
+
+const MAX_KEY_LENGTH: usize = 10;
+
+#[post("/projects")]
+async fn create(
+    pool: web::Data<DbPool>,
+    payload: web::Json<ProjectPayload>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::is_admin)?;
+
+    let mut payload = payload.into_inner();
+    payload.key = payload.key.trim().to_uppercase();
+    if let Some(message) = validate(&payload) {
+        return Ok(HttpResponse::Ok().json(failure(message)));
+    }
+
+    let project = web::block(move || {
+        let mut conn = pool.get()?;
+        if key_in_use(&payload.key, None, &mut conn)? {
+            return Ok(None);
+        }
+        add_a_project(payload, &mut conn).map(Some)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let response = match project {
+        Some(project) => Response {
+            success: true,
+            message: None,
+            data: Some(project),
+        },
+        None => failure("Project key is already in use".to_string()),
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+//Everyone who can open tickets needs the list for the project picker
+#[get("/projects")]
+async fn index(pool: web::Data<DbPool>, _caller: CurrentUser) -> Result<HttpResponse, Error> {
+    let projects = web::block(move || {
+        let mut conn = pool.get()?;
+        find_all(&mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(projects))
+}
+
+#[get("/projects/{id}")]
+async fn show(
+    id: web::Path<Uuid>,
+    pool: web::Data<DbPool>,
+    _caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    let project = web::block(move || {
+        let mut conn = pool.get()?;
+        find_by_id(id.into_inner(), &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorNotFound)?;
+
+    Ok(HttpResponse::Ok().json(project))
+}
+
+#[put("/projects/{id}")]
+async fn update(
+    id: web::Path<Uuid>,
+    payload: web::Json<ProjectPayload>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::is_admin)?;
+
+    let mut payload = payload.into_inner();
+    payload.key = payload.key.trim().to_uppercase();
+    if let Some(message) = validate(&payload) {
+        return Ok(HttpResponse::Ok().json(failure(message)));
+    }
+
+    let id = id.into_inner();
+    let project = web::block(move || {
+        let mut conn = pool.get()?;
+        if key_in_use(&payload.key, Some(id), &mut conn)? {
+            return Ok(None);
+        }
+        update_project(id, payload, &mut conn).map(Some)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let response = match project {
+        Some(project) => Response {
+            success: true,
+            message: None,
+            data: Some(project),
+        },
+        None => failure("Project key is already in use".to_string()),
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Tickets in the project are kept, they just lose their project and number
+#[delete("/projects/{id}")]
+async fn delete(
+    id: web::Path<Uuid>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::is_admin)?;
+
+    let result = web::block(move || {
+        let mut conn = pool.get()?;
+        delete_project(id.into_inner(), &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let response = if result > 0 {
+        SuccessResponse {
+            success: true,
+            message: "Project deleted".to_string(),
+        }
+    } else {
+        SuccessResponse {
+            success: false,
+            message: "Project not found".to_string(),
+        }
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+fn failure(message: String) -> Response<Project> {
+    Response {
+        success: false,
+        message: Some(message),
+        data: None,
+    }
+}
+
+fn validate(payload: &ProjectPayload) -> Option<String> {
+    if payload.name.trim().is_empty() {
+        return Some("Project name is required".to_string());
+    }
+    if payload.name.len() > MAX_TITLE_LENGTH {
+        return Some(format!(
+            "Project name is too long, max length is {}",
+            MAX_TITLE_LENGTH
+        ));
+    }
+    //Keys are used in ticket numbers like NET-42, so a hyphen or space would be ambiguous
+    let valid_key = payload
+        .key
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic())
+        && payload.key.chars().all(|c| c.is_ascii_alphanumeric())
+        && payload.key.len() <= MAX_KEY_LENGTH;
+    if !valid_key {
+        return Some(format!(
+            "Project key must start with a letter and contain up to {} letters or digits",
+            MAX_KEY_LENGTH
+        ));
+    }
+
+    None
+}
+
+/// Takes the next ticket number of the project, the row lock keeps numbers unique
+pub fn claim_ticket_number(id: Uuid, conn: &mut PgConnection) -> Result<(i32, Project), DbError> {
+    use crate::schema::projects::dsl::*;
+
+    let project: Project = diesel::update(projects.find(id))
+        .set(next_number.eq(next_number + 1))
+        .get_result(conn)?;
+
+    Ok((project.next_number - 1, project))
+}
+
+/// Resolves a reference like NET-42 to the ticket id
+pub fn find_by_reference(reference: &str, conn: &mut PgConnection) -> Result<Option<i32>, DbError> {
+    use crate::schema::projects::dsl::{key, project_id, projects};
+    use crate::schema::tickets::dsl::{project, project_number, ticket_id, tickets};
+
+    let Some((prefix, number)) = reference.trim().rsplit_once('-') else {
+        return Ok(None);
+    };
+    let Ok(number) = number.parse::<i32>() else {
+        return Ok(None);
+    };
+
+    let found = tickets
+        .inner_join(projects.on(project.eq(project_id.nullable())))
+        .filter(key.eq(prefix.to_uppercase()))
+        .filter(project_number.eq(number))
+        .select(ticket_id)
+        .first::<i32>(conn)
+        .optional()?;
+
+    Ok(found)
+}
+
+fn key_in_use(
+    project_key: &str,
+    except: Option<Uuid>,
+    conn: &mut PgConnection,
+) -> Result<bool, DbError> {
+    use crate::schema::projects::dsl::*;
+
+    let mut query = projects.filter(key.eq(project_key)).into_boxed();
+    if let Some(except) = except {
+        query = query.filter(project_id.ne(except));
+    }
+    let count = query.count().get_result::<i64>(conn)?;
+
+    Ok(count > 0)
+}
+
+fn add_a_project(payload: ProjectPayload, conn: &mut PgConnection) -> Result<Project, DbError> {
+    use crate::schema::projects::dsl::*;
+
+    let project = diesel::insert_into(projects)
+        .values(&payload)
+        .get_result::<Project>(conn)?;
+
+    Ok(project)
+}
+
+fn find_all(conn: &mut PgConnection) -> Result<Vec<Project>, DbError> {
+    use crate::schema::projects::dsl::*;
+
+    let items = projects.order(name.asc()).load::<Project>(conn)?;
+
+    Ok(items)
+}
+
+fn find_by_id(id: Uuid, conn: &mut PgConnection) -> Result<Project, DbError> {
+    use crate::schema::projects::dsl::*;
+
+    let project = projects.find(id).first::<Project>(conn)?;
+
+    Ok(project)
+}
+
+fn update_project(
+    id: Uuid,
+    payload: ProjectPayload,
+    conn: &mut PgConnection,
+) -> Result<Project, DbError> {
+    use crate::schema::projects::dsl::*;
+
+    let project = diesel::update(projects.find(id))
+        .set(&payload)
+        .get_result::<Project>(conn)?;
+
+    Ok(project)
+}
+
+fn delete_project(id: Uuid, conn: &mut PgConnection) -> Result<usize, DbError> {
+    use crate::schema::projects::dsl::*;
+
+    conn.transaction(|conn| {
+        //Numbers belong to the project, so they go with it
+        diesel::update(crate::schema::tickets::table)
+            .filter(crate::schema::tickets::project.eq(id))
+            .set(crate::schema::tickets::project_number.eq(None::<i32>))
+            .execute(conn)?;
+        let count = diesel::delete(projects.find(id)).execute(conn)?;
+
+        Ok(count)
+    })
+}
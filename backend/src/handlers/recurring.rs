@@ -0,0 +1,795 @@
+use super::super::DbPool;
+
+use actix_web::{delete, get, post, put, web, Error, HttpResponse};
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime};
+use diesel::prelude::*;
+use shared::models::{recurring::Recurrence, response::Response, users::Role, MAX_TITLE_LENGTH};
+use uuid::Uuid;
+
+use super::sla::update_sla;
+use super::workflows::{check_ticket_workflow, default_priority, default_status};
+use crate::authentication::CurrentUser;
+use crate::models::{
+    recurring::{
+        NewRecurringTicket, NewRecurringTicketRun, RecurringTicket, RecurringTicketPayload,
+        RecurringTicketRepresentation, RecurringTicketRun,
+    },
+    tasks::{NewTicketTask, TicketTaskPayload},
+    tickets::{NewTicket, Ticket},
+    SuccessResponse,
+};
+
+type DbError = Box<dyn std::error::Error + Send + Sync>;
+
+const MAX_TASK_LENGTH: usize = 256;
+
+/// Missed runs beyond this many are skipped after a long downtime, the latest ones are kept
+const MAX_CATCH_UP: usize = 31;
+
+/// Every schedule `Recurrence::check` allows matches at least once in this many days,
+/// longer searches give up
+const SEARCH_DAYS: i64 = 800;
+
+/// Runs shown in the history of a recurring ticket
+const HISTORY_LENGTH: i64 = 100;
+
+#[get("/recurring_tickets")]
+async fn index(pool: web::Data<DbPool>, caller: CurrentUser) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::can_view)?;
+
+    let recurring = web::block(move || {
+        let mut conn = pool.get()?;
+        find_all(&mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(recurring))
+}
+
+/// The first ticket is created at the first run after both the start and now
+#[post("/recurring_tickets")]
+async fn create(
+    pool: web::Data<DbPool>,
+    payload: web::Json<RecurringTicketPayload>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::can_edit)?;
+
+    let mut payload = payload.into_inner();
+    if let Some(message) = validate(&mut payload) {
+        return Ok(HttpResponse::Ok().json(failure(message)));
+    }
+
+    let result = web::block(move || {
+        let mut conn = pool.get()?;
+        save_recurring(None, payload, Some(caller.user_id), &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(respond(result)))
+}
+
+/// Changing the schedule moves the next run, runs that already happened are kept
+#[put("/recurring_tickets/{id}")]
+async fn update(
+    id: web::Path<Uuid>,
+    payload: web::Json<RecurringTicketPayload>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::can_edit)?;
+
+    let mut payload = payload.into_inner();
+    if let Some(message) = validate(&mut payload) {
+        return Ok(HttpResponse::Ok().json(failure(message)));
+    }
+
+    let result = web::block(move || {
+        let mut conn = pool.get()?;
+        save_recurring(Some(id.into_inner()), payload, None, &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(respond(result)))
+}
+
+/// Tickets that were already created are kept
+#[delete("/recurring_tickets/{id}")]
+async fn delete(
+    id: web::Path<Uuid>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::can_edit)?;
+
+    let result = web::block(move || {
+        let mut conn = pool.get()?;
+        delete_recurring(id.into_inner(), &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let response = if result > 0 {
+        SuccessResponse {
+            success: true,
+            message: "Recurring ticket deleted".to_string(),
+        }
+    } else {
+        SuccessResponse {
+            success: false,
+            message: "Recurring ticket not found".to_string(),
+        }
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Latest runs first
+#[get("/recurring_tickets/{id}/runs")]
+async fn runs(
+    id: web::Path<Uuid>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::can_view)?;
+
+    let history = web::block(move || {
+        let mut conn = pool.get()?;
+        find_runs(id.into_inner(), &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(history))
+}
+
+fn failure(message: String) -> Response<RecurringTicketRepresentation> {
+    Response {
+        success: false,
+        message: Some(message),
+        data: None,
+    }
+}
+
+fn respond(
+    result: Result<RecurringTicketRepresentation, String>,
+) -> Response<RecurringTicketRepresentation> {
+    match result {
+        Ok(recurring) => Response {
+            success: true,
+            message: None,
+            data: Some(recurring),
+        },
+        Err(message) => failure(message),
+    }
+}
+
+/// Also tidies up the payload, empty checklist items are dropped
+fn validate(payload: &mut RecurringTicketPayload) -> Option<String> {
+    payload.name = payload.name.trim().to_string();
+    payload.title = payload.title.trim().to_string();
+    if payload.name.is_empty() {
+        return Some("Name is required".to_string());
+    }
+    if payload.title.is_empty() {
+        return Some("Title is required".to_string());
+    }
+    if payload.name.len() > MAX_TITLE_LENGTH || payload.title.len() > MAX_TITLE_LENGTH {
+        return Some(format!(
+            "Name and title can be at most {} characters long",
+            MAX_TITLE_LENGTH
+        ));
+    }
+    payload.priority = payload
+        .priority
+        .take()
+        .map(|priority| priority.trim().to_string())
+        .filter(|priority| !priority.is_empty());
+
+    payload.tasks = payload
+        .tasks
+        .drain(..)
+        .map(|task| TicketTaskPayload {
+            text: task.text.trim().to_string(),
+            required: task.required,
+        })
+        .filter(|task| !task.text.is_empty())
+        .collect();
+    if payload
+        .tasks
+        .iter()
+        .any(|task| task.text.len() > MAX_TASK_LENGTH)
+    {
+        return Some(format!(
+            "Task text is too long, max length is {}",
+            MAX_TASK_LENGTH
+        ));
+    }
+
+    //Time zones are less than a day away from UTC
+    if payload.utc_offset.abs() >= 24 * 60 * 60 {
+        return Some("Unknown time zone".to_string());
+    }
+
+    if let Recurrence::Weekly { weekdays, .. } = &mut payload.recurrence {
+        weekdays.sort_unstable();
+        weekdays.dedup();
+    }
+
+    payload.recurrence.check()
+}
+
+/// Whether the schedule has a run on `date`, schedules counting days or weeks count from `start`
+fn occurs_on(recurrence: &Recurrence, start: NaiveDate, date: NaiveDate) -> bool {
+    match recurrence {
+        Recurrence::Daily { interval } => (date - start).num_days() % *interval as i64 == 0,
+        Recurrence::Weekly { interval, weekdays } => {
+            let week = |date: NaiveDate| {
+                (date - Duration::days(date.weekday().num_days_from_monday() as i64))
+                    .num_days_from_ce()
+                    / 7
+            };
+            weekdays.contains(&date.weekday().num_days_from_monday())
+                && (week(date) - week(start)) % *interval as i32 == 0
+        }
+        Recurrence::Monthly { day } => date.day() == (*day).min(last_day_of_month(date)),
+        Recurrence::FirstBusinessDay => {
+            let weekday = date.weekday().num_days_from_monday();
+            //The 2nd or 3rd is the first business day only when the month starts on a weekend
+            weekday < 5 && (date.day() == 1 || (date.day() <= 3 && weekday == 0))
+        }
+    }
+}
+
+fn last_day_of_month(date: NaiveDate) -> u32 {
+    let (year, month) = match date.month() {
+        12 => (date.year() + 1, 1),
+        month => (date.year(), month + 1),
+    };
+    NaiveDate::from_ymd_opt(year, month, 1)
+        .and_then(|first| first.pred_opt())
+        .map_or(28, |last| last.day())
+}
+
+/// First run of the schedule at or after `from`, runs happen at the time of day of `start_at`.
+/// Dates are counted in the time zone `utc_offset` seconds east of UTC, times are in UTC.
+fn next_occurrence(
+    recurrence: &Recurrence,
+    start_at: NaiveDateTime,
+    utc_offset: i32,
+    from: NaiveDateTime,
+) -> Option<NaiveDateTime> {
+    let offset = Duration::seconds(utc_offset as i64);
+    let local_start = start_at + offset;
+    let local_from = from.max(start_at) + offset;
+    (0..SEARCH_DAYS)
+        .map(|days| local_from.date() + Duration::days(days))
+        .filter(|date| occurs_on(recurrence, local_start.date(), *date))
+        .map(|date| date.and_time(local_start.time()))
+        .find(|run| *run >= local_from)
+        .map(|run| run - offset)
+}
+
+/// Runs from `next` up to `now`, how many older ones were skipped past `MAX_CATCH_UP`,
+/// and the first run after `now`
+fn due_runs(
+    recurrence: &Recurrence,
+    start_at: NaiveDateTime,
+    utc_offset: i32,
+    mut next: Option<NaiveDateTime>,
+    now: NaiveDateTime,
+) -> (Vec<NaiveDateTime>, usize, Option<NaiveDateTime>) {
+    let mut due = vec![];
+    while let Some(run) = next.filter(|run| *run <= now) {
+        due.push(run);
+        next = next_occurrence(recurrence, start_at, utc_offset, run + Duration::seconds(1));
+    }
+    let skipped = due.len().saturating_sub(MAX_CATCH_UP);
+    due.drain(..skipped);
+
+    (due, skipped, next)
+}
+
+fn save_recurring(
+    id: Option<Uuid>,
+    payload: RecurringTicketPayload,
+    creator: Option<Uuid>,
+    conn: &mut PgConnection,
+) -> Result<Result<RecurringTicketRepresentation, String>, DbError> {
+    use crate::schema::recurring_tickets;
+
+    if let Err(message) = check_ticket_workflow(None, None, payload.priority.as_deref(), conn)? {
+        return Ok(Err(message));
+    }
+
+    let mut query = recurring_tickets::table
+        .filter(recurring_tickets::name.eq(&payload.name))
+        .into_boxed();
+    if let Some(id) = id {
+        query = query.filter(recurring_tickets::recurring_id.ne(id));
+    }
+    if query.count().get_result::<i64>(conn)? > 0 {
+        return Ok(Err(format!(
+            "A recurring ticket named {} already exists",
+            payload.name
+        )));
+    }
+
+    let now = chrono::Utc::now().naive_utc();
+    let next_run = if payload.enabled {
+        next_occurrence(
+            &payload.recurrence,
+            payload.start_at,
+            payload.utc_offset,
+            now,
+        )
+    } else {
+        None
+    };
+    if payload.enabled && next_run.is_none() {
+        return Ok(Err("The schedule never runs".to_string()));
+    }
+    let values = NewRecurringTicket {
+        name: payload.name,
+        title: payload.title,
+        description: payload.description,
+        assignee: payload.assignee,
+        priority: payload.priority,
+        tasks: serde_json::to_string(&payload.tasks)?,
+        recurrence: serde_json::to_string(&payload.recurrence)?,
+        start_at: payload.start_at,
+        utc_offset: payload.utc_offset,
+        next_run,
+        enabled: payload.enabled,
+        updated_at: now,
+    };
+
+    let saved = match id {
+        Some(id) => diesel::update(recurring_tickets::table.find(id))
+            .set(&values)
+            .get_result::<RecurringTicket>(conn)
+            .optional()?,
+        None => diesel::insert_into(recurring_tickets::table)
+            .values((&values, recurring_tickets::created_by.eq(creator)))
+            .get_result::<RecurringTicket>(conn)
+            .optional()?,
+    };
+
+    match saved {
+        Some(saved) => Ok(Ok(represent(saved)?)),
+        None => Ok(Err("Recurring ticket not found".to_string())),
+    }
+}
+
+fn delete_recurring(id: Uuid, conn: &mut PgConnection) -> Result<usize, DbError> {
+    use crate::schema::recurring_tickets::dsl::*;
+
+    let result = diesel::delete(recurring_tickets.find(id)).execute(conn)?;
+
+    Ok(result)
+}
+
+fn represent(recurring: RecurringTicket) -> Result<RecurringTicketRepresentation, DbError> {
+    Ok(RecurringTicketRepresentation {
+        recurring_id: recurring.recurring_id,
+        name: recurring.name,
+        title: recurring.title,
+        description: recurring.description,
+        assignee: recurring.assignee,
+        priority: recurring.priority,
+        tasks: serde_json::from_str(&recurring.tasks)?,
+        recurrence: serde_json::from_str(&recurring.recurrence)?,
+        start_at: recurring.start_at,
+        utc_offset: recurring.utc_offset,
+        next_run: recurring.next_run,
+        enabled: recurring.enabled,
+        created_at: recurring.created_at,
+    })
+}
+
+fn find_all(conn: &mut PgConnection) -> Result<Vec<RecurringTicketRepresentation>, DbError> {
+    use crate::schema::recurring_tickets::dsl::*;
+
+    recurring_tickets
+        .order(name.asc())
+        .load::<RecurringTicket>(conn)?
+        .into_iter()
+        .map(represent)
+        .collect()
+}
+
+fn find_runs(id: Uuid, conn: &mut PgConnection) -> Result<Vec<RecurringTicketRun>, DbError> {
+    use crate::schema::recurring_ticket_runs::dsl::*;
+
+    let history = recurring_ticket_runs
+        .filter(recurring_id.eq(id))
+        .order(scheduled_for.desc())
+        .limit(HISTORY_LENGTH)
+        .load::<RecurringTicketRun>(conn)?;
+
+    Ok(history)
+}
+
+/// Creates the tickets of every run that is due, including runs missed while the
+/// backend was down. Returns the created tickets.
+pub fn run_due(conn: &mut PgConnection) -> Result<Vec<i32>, DbError> {
+    use crate::schema::recurring_tickets::dsl::*;
+
+    let now = chrono::Utc::now().naive_utc();
+    let due = recurring_tickets
+        .filter(enabled.eq(true))
+        .filter(next_run.le(now))
+        .select(recurring_id)
+        .load::<Uuid>(conn)?;
+
+    let mut created = vec![];
+    for id in due {
+        //Locking the row keeps two backends from creating the same run twice
+        let result = conn.transaction::<_, DbError, _>(|conn| {
+            let Some(recurring) = recurring_tickets
+                .find(id)
+                .for_update()
+                .first::<RecurringTicket>(conn)
+                .optional()?
+            else {
+                return Ok(vec![]);
+            };
+            run_recurring(recurring, now, conn)
+        });
+        match result {
+            Ok(ticket_ids) => created.extend(ticket_ids),
+            Err(e) => log::error!("Recurring ticket {} failed to run: {}", id, e),
+        }
+    }
+
+    Ok(created)
+}
+
+fn run_recurring(
+    recurring: RecurringTicket,
+    now: NaiveDateTime,
+    conn: &mut PgConnection,
+) -> Result<Vec<i32>, DbError> {
+    use crate::schema::recurring_tickets::dsl::*;
+
+    let schedule = serde_json::from_str::<Recurrence>(&recurring.recurrence)?;
+    let next = recurring.next_run.filter(|_| recurring.enabled);
+    let (due, skipped, next) = due_runs(
+        &schedule,
+        recurring.start_at,
+        recurring.utc_offset,
+        next,
+        now,
+    );
+    if skipped > 0 {
+        log::warn!(
+            "Recurring ticket {} missed {} runs, only the last {} are created",
+            recurring.name,
+            due.len() + skipped,
+            MAX_CATCH_UP
+        );
+    }
+
+    let mut created = vec![];
+    for run in due {
+        if let Some(ticket_id) = create_run(&recurring, run, conn)? {
+            created.push(ticket_id);
+        }
+    }
+
+    diesel::update(recurring_tickets.find(recurring.recurring_id))
+        .set(next_run.eq(next))
+        .execute(conn)?;
+
+    Ok(created)
+}
+
+/// Creates the ticket of one run, unless that run already happened
+fn create_run(
+    recurring: &RecurringTicket,
+    scheduled: NaiveDateTime,
+    conn: &mut PgConnection,
+) -> Result<Option<i32>, DbError> {
+    use crate::schema::{recurring_ticket_runs, ticket_tasks, tickets};
+
+    let run = diesel::insert_into(recurring_ticket_runs::table)
+        .values(NewRecurringTicketRun {
+            recurring_id: recurring.recurring_id,
+            scheduled_for: scheduled,
+        })
+        .on_conflict_do_nothing()
+        .get_result::<RecurringTicketRun>(conn)
+        .optional()?;
+    let Some(run) = run else {
+        return Ok(None);
+    };
+
+    //Priorities may have been removed since the recurring ticket was saved
+    let ticket_priority = match &recurring.priority {
+        Some(name) if check_ticket_workflow(None, None, Some(name), conn)?.is_ok() => name.clone(),
+        _ => default_priority(conn)?,
+    };
+    let time = chrono::Utc::now().naive_utc();
+    let new_ticket = NewTicket {
+        title: recurring.title.clone(),
+        assignee: recurring.assignee,
+        contact: None,
+        description: recurring.description.clone(),
+        created_at: time,
+        updated_at: time,
+        due_date: None,
+        priority: ticket_priority,
+        status: default_status(conn)?,
+        created_by: None,
+        updated_by: None,
+        revision: time,
+        revision_by: None,
+        project: None,
+        project_number: None,
+    };
+    let ticket: Ticket = diesel::insert_into(tickets::table)
+        .values(&new_ticket)
+        .get_result(conn)?;
+
+    let tasks = serde_json::from_str::<Vec<TicketTaskPayload>>(&recurring.tasks)?
+        .into_iter()
+        .enumerate()
+        .map(|(position, task)| NewTicketTask {
+            ticket_id: ticket.ticket_id,
+            text: task.text,
+            position: position as i32,
+            required: task.required,
+        })
+        .collect::<Vec<NewTicketTask>>();
+    diesel::insert_into(ticket_tasks::table)
+        .values(&tasks)
+        .execute(conn)?;
+    update_sla(ticket.ticket_id, conn)?;
+
+    diesel::update(recurring_ticket_runs::table.find(run.run_id))
+        .set(recurring_ticket_runs::ticket_id.eq(ticket.ticket_id))
+        .execute(conn)?;
+
+    Ok(Some(ticket.ticket_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shared::models::recurring::{MAX_DAILY_INTERVAL, MAX_WEEKLY_INTERVAL};
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    fn time(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    /// Runs of the schedule on or after `from`, as local dates
+    fn runs(recurrence: &Recurrence, start: &str, from: &str, count: usize) -> Vec<NaiveDate> {
+        let start = date(start);
+        let mut dates = vec![];
+        let mut day = date(from);
+        while dates.len() < count {
+            if occurs_on(recurrence, start, day) {
+                dates.push(day);
+            }
+            day += Duration::days(1);
+        }
+        dates
+    }
+
+    #[test]
+    fn first_business_day_skips_weekends() {
+        let dates = runs(&Recurrence::FirstBusinessDay, "2026-01-01", "2026-01-01", 4);
+
+        //Feb 1st and Mar 1st 2026 are Sundays
+        assert_eq!(
+            dates,
+            [
+                date("2026-01-01"),
+                date("2026-02-02"),
+                date("2026-03-02"),
+                date("2026-04-01"),
+            ]
+        );
+        //Aug 1st 2026 is a Saturday
+        assert_eq!(
+            runs(&Recurrence::FirstBusinessDay, "2026-01-01", "2026-07-15", 1),
+            [date("2026-08-03")]
+        );
+    }
+
+    #[test]
+    fn monthly_uses_the_last_day_of_short_months() {
+        let dates = runs(&Recurrence::Monthly { day: 31 }, "2026-01-01", "2026-01-01", 4);
+
+        assert_eq!(
+            dates,
+            [
+                date("2026-01-31"),
+                date("2026-02-28"),
+                date("2026-03-31"),
+                date("2026-04-30"),
+            ]
+        );
+        assert_eq!(
+            runs(&Recurrence::Monthly { day: 31 }, "2028-01-01", "2028-02-01", 1),
+            [date("2028-02-29")]
+        );
+    }
+
+    #[test]
+    fn daily_counts_from_the_start() {
+        let dates = runs(&Recurrence::Daily { interval: 3 }, "2026-12-30", "2026-12-31", 3);
+
+        assert_eq!(
+            dates,
+            [date("2027-01-02"), date("2027-01-05"), date("2027-01-08")]
+        );
+    }
+
+    #[test]
+    fn weekly_intervals_carry_across_years() {
+        let every_other_monday = Recurrence::Weekly {
+            interval: 2,
+            weekdays: vec![0],
+        };
+        let dates = runs(&every_other_monday, "2026-12-14", "2026-12-14", 4);
+
+        assert_eq!(
+            dates,
+            [
+                date("2026-12-14"),
+                date("2026-12-28"),
+                date("2027-01-11"),
+                date("2027-01-25"),
+            ]
+        );
+    }
+
+    #[test]
+    fn weekly_weeks_start_on_monday() {
+        let schedule = Recurrence::Weekly {
+            interval: 2,
+            weekdays: vec![0, 6],
+        };
+        //A Sunday start counts its own week, the following Monday is in the next one
+        let dates = runs(&schedule, "2027-01-03", "2027-01-03", 4);
+
+        assert_eq!(
+            dates,
+            [
+                date("2027-01-03"),
+                date("2027-01-11"),
+                date("2027-01-17"),
+                date("2027-01-25"),
+            ]
+        );
+    }
+
+    #[test]
+    fn utc_offset_moves_runs_to_another_utc_day() {
+        let every_monday = Recurrence::Weekly {
+            interval: 1,
+            weekdays: vec![0],
+        };
+
+        //Monday 00:30 in UTC+9 is Sunday 15:30 in UTC
+        let start_at = time("2026-01-04 15:30");
+        assert_eq!(
+            next_occurrence(&every_monday, start_at, 9 * 3600, time("2026-01-05 00:00")),
+            Some(time("2026-01-11 15:30"))
+        );
+
+        //Monday 22:00 in UTC-5 is Tuesday 03:00 in UTC
+        let start_at = time("2026-01-06 03:00");
+        assert_eq!(
+            next_occurrence(&every_monday, start_at, -5 * 3600, time("2026-01-06 03:00")),
+            Some(time("2026-01-06 03:00"))
+        );
+        assert_eq!(
+            next_occurrence(&every_monday, start_at, -5 * 3600, time("2026-01-06 03:01")),
+            Some(time("2026-01-13 03:00"))
+        );
+    }
+
+    #[test]
+    fn next_occurrence_waits_for_the_start() {
+        let daily = Recurrence::Daily { interval: 1 };
+        let start_at = time("2026-03-10 09:00");
+
+        assert_eq!(
+            next_occurrence(&daily, start_at, 0, time("2026-01-01 00:00")),
+            Some(start_at)
+        );
+        assert_eq!(
+            next_occurrence(&daily, start_at, 0, time("2026-03-12 09:01")),
+            Some(time("2026-03-13 09:00"))
+        );
+    }
+
+    #[test]
+    fn next_occurrence_finds_the_longest_intervals() {
+        let start_at = time("2026-01-05 09:00");
+        let daily = Recurrence::Daily {
+            interval: MAX_DAILY_INTERVAL,
+        };
+        let weekly = Recurrence::Weekly {
+            interval: MAX_WEEKLY_INTERVAL,
+            weekdays: vec![0],
+        };
+
+        assert_eq!(
+            next_occurrence(&daily, start_at, 0, time("2026-01-05 09:01")),
+            Some(time("2027-01-06 09:00"))
+        );
+        assert_eq!(
+            next_occurrence(&weekly, start_at, 0, time("2026-01-05 09:01")),
+            Some(time("2028-01-03 09:00"))
+        );
+    }
+
+    #[test]
+    fn due_runs_catch_up_on_missed_runs() {
+        let daily = Recurrence::Daily { interval: 1 };
+        let start_at = time("2026-01-01 09:00");
+
+        let (due, skipped, next) = due_runs(
+            &daily,
+            start_at,
+            0,
+            Some(time("2026-01-03 09:00")),
+            time("2026-01-05 12:00"),
+        );
+
+        assert_eq!(
+            due,
+            [
+                time("2026-01-03 09:00"),
+                time("2026-01-04 09:00"),
+                time("2026-01-05 09:00"),
+            ]
+        );
+        assert_eq!(skipped, 0);
+        assert_eq!(next, Some(time("2026-01-06 09:00")));
+    }
+
+    #[test]
+    fn due_runs_keep_only_the_latest_runs() {
+        let daily = Recurrence::Daily { interval: 1 };
+        let start_at = time("2026-01-01 09:00");
+
+        //40 runs were missed, from Jan 1st to Feb 9th
+        let (due, skipped, next) =
+            due_runs(&daily, start_at, 0, Some(start_at), time("2026-02-09 12:00"));
+
+        assert_eq!(due.len(), MAX_CATCH_UP);
+        assert_eq!(skipped, 40 - MAX_CATCH_UP);
+        assert_eq!(due.first(), Some(&time("2026-01-10 09:00")));
+        assert_eq!(due.last(), Some(&time("2026-02-09 09:00")));
+        assert_eq!(next, Some(time("2026-02-10 09:00")));
+    }
+
+    #[test]
+    fn due_runs_wait_for_the_next_run() {
+        let daily = Recurrence::Daily { interval: 1 };
+        let next_run = time("2026-01-06 09:00");
+
+        let (due, skipped, next) = due_runs(
+            &daily,
+            time("2026-01-01 09:00"),
+            0,
+            Some(next_run),
+            time("2026-01-06 08:59"),
+        );
+
+        assert!(due.is_empty());
+        assert_eq!(skipped, 0);
+        assert_eq!(next, Some(next_run));
+    }
+}
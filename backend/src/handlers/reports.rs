@@ -0,0 +1,247 @@
+use super::super::DbPool;
+
+use std::collections::HashMap;
+
+use actix_web::{get, http::header, web, Error, HttpResponse};
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use diesel::prelude::*;
+use shared::models::{
+    reports::{TimeGrouping, TimeReportQuery},
+    users::Role,
+};
+
+use crate::authentication::CurrentUser;
+use crate::models::reports::{TimeEntry, TimeReport, TimeTotal};
+
+type DbError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Time logged on notes, summed up by user, ticket, contact or day
+#[get("/reports/time")]
+async fn time_report(
+    pool: web::Data<DbPool>,
+    query: web::Query<TimeReportQuery>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::can_view)?;
+
+    let query = query.into_inner();
+    let range = parse_range(&query)?;
+    let grouping = match query.group_by.as_deref() {
+        Some(value) => value
+            .parse::<TimeGrouping>()
+            .map_err(actix_web::error::ErrorBadRequest)?,
+        None => TimeGrouping::default(),
+    };
+
+    let entries = web::block(move || {
+        let mut conn = pool.get()?;
+        find_entries(&query, range, &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let report = TimeReport {
+        totals: totals(&entries, grouping),
+        total_minutes: entries.iter().map(|entry| entry.minutes as i64).sum(),
+        entries,
+    };
+
+    Ok(HttpResponse::Ok().json(report))
+}
+
+/// The entries of the time report as CSV for spreadsheets and billing
+#[get("/reports/time.csv")]
+async fn time_report_csv(
+    pool: web::Data<DbPool>,
+    query: web::Query<TimeReportQuery>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::can_view)?;
+
+    let query = query.into_inner();
+    let range = parse_range(&query)?;
+
+    let entries = web::block(move || {
+        let mut conn = pool.get()?;
+        find_entries(&query, range, &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/csv; charset=utf-8")
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            "attachment; filename=\"time-report.csv\"",
+        ))
+        .body(to_csv(&entries)))
+}
+
+/// Start and end (exclusive) of the report, the `to` date is included
+fn parse_range(
+    query: &TimeReportQuery,
+) -> Result<(Option<NaiveDateTime>, Option<NaiveDateTime>), Error> {
+    let parse = |value: &Option<String>| -> Result<Option<NaiveDate>, Error> {
+        match value.as_deref().map(str::trim) {
+            None | Some("") => Ok(None),
+            Some(value) => NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .map(Some)
+                .map_err(|_| actix_web::error::ErrorBadRequest("Dates must be YYYY-MM-DD")),
+        }
+    };
+
+    let from = parse(&query.from)?.map(|date| date.and_hms_opt(0, 0, 0).unwrap());
+    let to = parse(&query.to)?.map(|date| date.and_hms_opt(0, 0, 0).unwrap() + Duration::days(1));
+
+    Ok((from, to))
+}
+
+fn find_entries(
+    query: &TimeReportQuery,
+    (from, to): (Option<NaiveDateTime>, Option<NaiveDateTime>),
+    conn: &mut PgConnection,
+) -> Result<Vec<TimeEntry>, DbError> {
+    use crate::schema::contacts;
+    use crate::schema::notes::dsl::*;
+    use crate::schema::tickets;
+    use crate::schema::users;
+
+    let mut items = notes
+        .inner_join(tickets::table.on(tickets::ticket_id.eq(ticket)))
+        .left_join(users::table.on(users::user_id.nullable().eq(owner)))
+        .left_join(contacts::table.on(contacts::contact_id.nullable().eq(tickets::contact)))
+        .filter(time.gt(0))
+        .select((
+            note_id,
+            ticket,
+            tickets::title,
+            owner,
+            users::display_name.nullable(),
+            tickets::contact,
+            contacts::display_name.nullable(),
+            time,
+            created_at,
+        ))
+        .into_boxed();
+
+    if let Some(from) = from {
+        items = items.filter(created_at.ge(from));
+    }
+    if let Some(to) = to {
+        items = items.filter(created_at.lt(to));
+    }
+    if let Some(user) = query.user {
+        items = items.filter(owner.eq(user));
+    }
+    if let Some(id) = query.ticket {
+        items = items.filter(ticket.eq(id));
+    }
+    if let Some(contact_id) = query.contact {
+        items = items.filter(tickets::contact.eq(contact_id));
+    }
+
+    let entries = items
+        .order((created_at.asc(), note_id.asc()))
+        .load::<TimeEntry>(conn)?;
+
+    Ok(entries)
+}
+
+fn totals(entries: &[TimeEntry], grouping: TimeGrouping) -> Vec<TimeTotal> {
+    let mut totals: HashMap<String, TimeTotal> = HashMap::new();
+    for entry in entries {
+        let (key, label) = match grouping {
+            TimeGrouping::User => (
+                entry.user_id.map(|id| id.to_string()),
+                entry.user_name.clone(),
+            ),
+            TimeGrouping::Ticket => (
+                Some(entry.ticket_id.to_string()),
+                Some(format!("#{} {}", entry.ticket_id, entry.title)),
+            ),
+            TimeGrouping::Contact => (
+                entry.contact_id.map(|id| id.to_string()),
+                entry.contact_name.clone(),
+            ),
+            TimeGrouping::Date => {
+                let date = entry.created_at.date().to_string();
+                (Some(date.clone()), Some(date))
+            }
+        };
+        totals
+            .entry(key.clone().unwrap_or_default())
+            .or_insert_with(|| TimeTotal {
+                key: key.unwrap_or_default(),
+                label: label.unwrap_or_default(),
+                minutes: 0,
+            })
+            .minutes += entry.minutes as i64;
+    }
+
+    let mut totals = totals.into_values().collect::<Vec<TimeTotal>>();
+    //Days stay in order, everything else goes by the time spent
+    if grouping == TimeGrouping::Date {
+        totals.sort_by(|a, b| a.key.cmp(&b.key));
+    } else {
+        totals.sort_by(|a, b| b.minutes.cmp(&a.minutes).then(a.label.cmp(&b.label)));
+    }
+
+    totals
+}
+
+fn to_csv(entries: &[TimeEntry]) -> String {
+    let mut csv = String::from("date,ticket,title,user,contact,minutes\r\n");
+    for entry in entries {
+        let row = [
+            entry.created_at.format("%Y-%m-%d %H:%M").to_string(),
+            entry.ticket_id.to_string(),
+            entry.title.clone(),
+            entry.user_name.clone().unwrap_or_default(),
+            entry.contact_name.clone().unwrap_or_default(),
+            entry.minutes.to_string(),
+        ];
+        let row = row
+            .iter()
+            .map(|field| csv_field(field))
+            .collect::<Vec<String>>();
+        csv.push_str(&row.join(","));
+        csv.push_str("\r\n");
+    }
+
+    csv
+}
+
+/// Quotes a field when it would break the row, formulas are defused for spreadsheets
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+/// Minutes logged on each ticket that has any, by ticket id
+pub fn find_time_by_ticket_ids(
+    ids: &[i32],
+    conn: &mut PgConnection,
+) -> Result<HashMap<i32, i64>, DbError> {
+    use crate::schema::notes::dsl::*;
+
+    let items = notes
+        .filter(ticket.eq_any(ids))
+        .group_by(ticket)
+        .select((ticket, diesel::dsl::sum(time)))
+        .load::<(i32, Option<i64>)>(conn)?;
+
+    let result = items
+        .into_iter()
+        .map(|(id, minutes)| (id, minutes.unwrap_or_default()))
+        .collect();
+
+    Ok(result)
+}
@@ -0,0 +1,103 @@
+use super::super::DbPool;
+
+use actix_web::{get, web, Error, HttpResponse};
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Bool, Nullable, Text};
+use shared::models::search::{SearchQuery, SearchResult};
+use shared::models::users::Role;
+use uuid::Uuid;
+
+use crate::authentication::CurrentUser;
+use crate::models::search::SearchHit;
+
+type DbError = Box<dyn std::error::Error + Send + Sync>;
+
+const DEFAULT_LIMIT: i64 = 25;
+const MAX_LIMIT: i64 = 100;
+
+//The to_tsvector expressions have to match the indexes in the add_search_indexes migration,
+//otherwise postgres falls back to sequential scans
+const SEARCH_QUERY: &str = r#"
+WITH query AS (SELECT websearch_to_tsquery('english', $1) AS q)
+SELECT * FROM (
+    SELECT 'ticket' AS entity_type, t.ticket_id, NULL::uuid AS document_id, NULL::uuid AS note_id,
+        t.title,
+        ts_headline('english', t.description, query.q, 'StartSel=<mark>, StopSel=</mark>, MaxFragments=2') AS snippet,
+        ts_rank(setweight(to_tsvector('english', t.title), 'A') || setweight(to_tsvector('english', t.description), 'B'), query.q) AS rank
+    FROM tickets t, query
+    WHERE (setweight(to_tsvector('english', t.title), 'A') || setweight(to_tsvector('english', t.description), 'B')) @@ query.q
+        AND ($2::uuid IS NULL OR t.created_by = $2)
+    UNION ALL
+    SELECT 'note', n.ticket, NULL::uuid, n.note_id,
+        t.title,
+        ts_headline('english', n.text, query.q, 'StartSel=<mark>, StopSel=</mark>, MaxFragments=2'),
+        ts_rank(to_tsvector('english', n.text), query.q)
+    FROM notes n JOIN tickets t ON t.ticket_id = n.ticket, query
+    WHERE to_tsvector('english', n.text) @@ query.q
+        AND ($2::uuid IS NULL OR (t.created_by = $2 AND n.is_public))
+    UNION ALL
+    SELECT 'document', NULL::int4, d.document_id, NULL::uuid,
+        d.title,
+        ts_headline('english', d.content, query.q, 'StartSel=<mark>, StopSel=</mark>, MaxFragments=2'),
+        ts_rank(setweight(to_tsvector('english', d.title), 'A') || setweight(to_tsvector('english', d.content), 'B'), query.q)
+    FROM documents d, query
+    WHERE $3 AND NOT d.archived
+        AND (setweight(to_tsvector('english', d.title), 'A') || setweight(to_tsvector('english', d.content), 'B')) @@ query.q
+) hits
+ORDER BY rank DESC
+LIMIT $4
+"#;
+
+/// Handler for GET /search?q=, searches tickets, notes and wiki documents
+#[get("/search")]
+async fn search(
+    query: web::Query<SearchQuery>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    let query = query.into_inner();
+    if query.q.trim().is_empty() {
+        return Ok(HttpResponse::Ok().json(Vec::<SearchResult>::new()));
+    }
+
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    //Requesters only see their own tickets and never the wiki
+    let (created_by, include_documents) = if caller.role.can_view() {
+        (None, true)
+    } else {
+        caller.ensure(Role::can_create_tickets)?;
+        (Some(caller.user_id), false)
+    };
+
+    let results = web::block(move || {
+        let mut conn = pool.get()?;
+        search_all(&query.q, created_by, include_documents, limit, &mut conn)
+    })
+    .await?
+    .map(|x| {
+        x.into_iter()
+            .map(SearchResult::from)
+            .collect::<Vec<SearchResult>>()
+    })
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(results))
+}
+
+fn search_all(
+    terms: &str,
+    created_by: Option<Uuid>,
+    include_documents: bool,
+    limit: i64,
+    conn: &mut PgConnection,
+) -> Result<Vec<SearchHit>, DbError> {
+    let hits = diesel::sql_query(SEARCH_QUERY)
+        .bind::<Text, _>(terms)
+        .bind::<Nullable<diesel::sql_types::Uuid>, _>(created_by)
+        .bind::<Bool, _>(include_documents)
+        .bind::<BigInt, _>(limit)
+        .load::<SearchHit>(conn)?;
+
+    Ok(hits)
+}
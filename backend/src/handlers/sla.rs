@@ -0,0 +1,598 @@
+use super::super::DbPool;
+
+use std::collections::HashMap;
+
+use actix_web::{delete, get, post, put, web, Error, HttpResponse};
+use chrono::{Duration, NaiveDateTime};
+use diesel::prelude::*;
+use shared::models::{
+    response::Response,
+    sla::{SlaStatus, AT_RISK_MINUTES, RESOLUTION_TARGET, RESPONSE_TARGET},
+    tickets::TicketEventType,
+    users::Role,
+    MAX_TITLE_LENGTH,
+};
+use uuid::Uuid;
+
+use super::notifications::notify_followers;
+use crate::authentication::CurrentUser;
+use crate::models::{
+    sla::{SlaPolicy, SlaPolicyPayload, TicketSla, TicketSlaRepresentation},
+    tickets::{NewTicketEvent, Ticket},
+    workflows::TicketStatus,
+    SuccessResponse,
+};
+
+type DbError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Current time in SQL, timestamps are stored as UTC
+const SQL_NOW: &str = "(NOW() AT TIME ZONE 'UTC')";
+
+#[get("/sla_policies")]
+async fn index(pool: web::Data<DbPool>, caller: CurrentUser) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::can_view)?;
+
+    let policies = web::block(move || {
+        let mut conn = pool.get()?;
+        find_all(&mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(policies))
+}
+
+/// Open tickets are matched against the policies again after every change
+#[post("/sla_policies")]
+async fn create(
+    pool: web::Data<DbPool>,
+    payload: web::Json<SlaPolicyPayload>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::is_admin)?;
+
+    let mut payload = payload.into_inner();
+    if let Some(message) = validate(&mut payload) {
+        return Ok(HttpResponse::Ok().json(failure(message)));
+    }
+
+    let policy = web::block(move || {
+        let mut conn = pool.get()?;
+        save_policy(None, payload, &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(respond(policy)))
+}
+
+#[put("/sla_policies/{id}")]
+async fn update(
+    id: web::Path<Uuid>,
+    payload: web::Json<SlaPolicyPayload>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::is_admin)?;
+
+    let mut payload = payload.into_inner();
+    if let Some(message) = validate(&mut payload) {
+        return Ok(HttpResponse::Ok().json(failure(message)));
+    }
+
+    let policy = web::block(move || {
+        let mut conn = pool.get()?;
+        save_policy(Some(id.into_inner()), payload, &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(respond(policy)))
+}
+
+/// Open tickets under the policy move to the next matching one, if any
+#[delete("/sla_policies/{id}")]
+async fn delete(
+    id: web::Path<Uuid>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::is_admin)?;
+
+    let result = web::block(move || {
+        let mut conn = pool.get()?;
+        delete_policy(id.into_inner(), &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let response = if result > 0 {
+        SuccessResponse {
+            success: true,
+            message: "SLA policy deleted".to_string(),
+        }
+    } else {
+        SuccessResponse {
+            success: false,
+            message: "SLA policy not found".to_string(),
+        }
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+fn failure(message: String) -> Response<SlaPolicy> {
+    Response {
+        success: false,
+        message: Some(message),
+        data: None,
+    }
+}
+
+fn respond(result: Result<SlaPolicy, String>) -> Response<SlaPolicy> {
+    match result {
+        Ok(policy) => Response {
+            success: true,
+            message: None,
+            data: Some(policy),
+        },
+        Err(message) => failure(message),
+    }
+}
+
+/// Also tidies up the payload, an empty priority matches any priority
+fn validate(payload: &mut SlaPolicyPayload) -> Option<String> {
+    payload.name = payload.name.trim().to_string();
+    if payload.name.is_empty() {
+        return Some("Policy name is required".to_string());
+    }
+    if payload.name.len() > MAX_TITLE_LENGTH {
+        return Some(format!(
+            "Policy name is too long, max length is {}",
+            MAX_TITLE_LENGTH
+        ));
+    }
+    payload.priority = payload
+        .priority
+        .take()
+        .map(|priority| priority.trim().to_string())
+        .filter(|priority| !priority.is_empty());
+
+    if payload.response_minutes.is_none() && payload.resolution_minutes.is_none() {
+        return Some("A policy needs a response or resolution target".to_string());
+    }
+    let positive = |minutes: Option<i32>| minutes.is_none_or(|minutes| minutes > 0);
+    if !positive(payload.response_minutes) || !positive(payload.resolution_minutes) {
+        return Some("Targets must be at least one minute".to_string());
+    }
+
+    None
+}
+
+/// Starts, moves or stops the SLA clock of a ticket after it was added or changed.
+/// The most specific matching policy applies, statuses that pause the SLA stop the clock
+/// and closed statuses resolve the ticket. Deadlines follow the targets of the policy,
+/// so changing a policy or switching to another one moves them.
+pub fn update_sla(id: i32, conn: &mut PgConnection) -> Result<(), DbError> {
+    use crate::schema::ticket_slas::dsl::*;
+
+    let existing = ticket_slas.find(id).first::<TicketSla>(conn).optional()?;
+    apply_sla(id, existing, conn)
+}
+
+/// Matches a ticket against the policies, carrying on with its `existing` clock
+fn apply_sla(id: i32, existing: Option<TicketSla>, conn: &mut PgConnection) -> Result<(), DbError> {
+    use crate::schema::ticket_slas::dsl::*;
+
+    let ticket = crate::schema::tickets::table
+        .find(id)
+        .first::<Ticket>(conn)?;
+    let policy = matching_policy(&ticket, conn)?;
+
+    let Some(policy) = policy else {
+        diesel::delete(ticket_slas.find(id)).execute(conn)?;
+        return Ok(());
+    };
+
+    let now = chrono::Utc::now().naive_utc();
+    //A ticket that only gets a policy later, e.g. when one is added, starts from that moment.
+    //Missed targets stay missed on another policy so each breach is reported once.
+    let mut sla = existing.unwrap_or(TicketSla {
+        ticket_id: id,
+        policy_id: policy.policy_id,
+        started_at: now,
+        response_due: None,
+        resolution_due: None,
+        first_response_at: None,
+        resolved_at: None,
+        paused_at: None,
+        paused_seconds: 0,
+        response_breached: false,
+        resolution_breached: false,
+    });
+    //Time spent paused does not count, the current pause is added when it ends
+    let due = |minutes: Option<i32>| {
+        minutes.map(|minutes| {
+            sla.started_at
+                + Duration::minutes(minutes as i64)
+                + Duration::seconds(sla.paused_seconds as i64)
+        })
+    };
+    let (response, resolution) = (due(policy.response_minutes), due(policy.resolution_minutes));
+    sla.policy_id = policy.policy_id;
+    sla.response_due = response;
+    sla.resolution_due = resolution;
+
+    let status = crate::schema::ticket_statuses::table
+        .filter(crate::schema::ticket_statuses::name.eq(&ticket.status))
+        .first::<TicketStatus>(conn)
+        .optional()?;
+    let pauses = status.as_ref().is_some_and(|status| status.pauses_sla);
+    let closed = status.as_ref().is_some_and(|status| status.is_closed);
+
+    match (sla.paused_at, pauses) {
+        (None, true) => sla.paused_at = Some(now),
+        (Some(since), false) => {
+            let elapsed = now - since;
+            sla.response_due = sla.response_due.map(|due| due + elapsed);
+            sla.resolution_due = sla.resolution_due.map(|due| due + elapsed);
+            sla.paused_seconds += elapsed.num_seconds() as i32;
+            sla.paused_at = None;
+        }
+        _ => (),
+    }
+    match (sla.resolved_at, closed) {
+        (None, true) => sla.resolved_at = Some(now),
+        (Some(_), false) => sla.resolved_at = None,
+        _ => (),
+    }
+
+    record_breaches(&mut sla, now, conn)?;
+
+    diesel::insert_into(ticket_slas)
+        .values(&sla)
+        .on_conflict(ticket_id)
+        .do_update()
+        .set(&sla)
+        .execute(conn)?;
+
+    Ok(())
+}
+
+/// Stops the response clock, called when an agent first adds a note
+pub fn record_response(id: i32, conn: &mut PgConnection) -> Result<(), DbError> {
+    use crate::schema::ticket_slas::dsl::*;
+
+    let Some(mut sla) = ticket_slas.find(id).first::<TicketSla>(conn).optional()? else {
+        return Ok(());
+    };
+    if sla.first_response_at.is_some() {
+        return Ok(());
+    }
+
+    let now = chrono::Utc::now().naive_utc();
+    sla.first_response_at = Some(now);
+    record_breaches(&mut sla, now, conn)?;
+    diesel::update(ticket_slas.find(id))
+        .set(&sla)
+        .execute(conn)?;
+
+    Ok(())
+}
+
+/// Records breaches of running SLA clocks, returns the tickets that breached
+pub fn check_breaches(conn: &mut PgConnection) -> Result<Vec<i32>, DbError> {
+    use crate::schema::ticket_slas::dsl::*;
+
+    let now = chrono::Utc::now().naive_utc();
+    let due = ticket_slas
+        .filter(paused_at.is_null())
+        .filter(
+            response_breached
+                .eq(false)
+                .and(first_response_at.is_null())
+                .and(resolved_at.is_null())
+                .and(response_due.lt(now))
+                .or(resolution_breached
+                    .eq(false)
+                    .and(resolved_at.is_null())
+                    .and(resolution_due.lt(now))),
+        )
+        .load::<TicketSla>(conn)?;
+
+    let mut breached = vec![];
+    for mut sla in due {
+        conn.transaction::<_, DbError, _>(|conn| {
+            if record_breaches(&mut sla, now, conn)? {
+                diesel::update(ticket_slas.find(sla.ticket_id))
+                    .set(&sla)
+                    .execute(conn)?;
+                breached.push(sla.ticket_id);
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(breached)
+}
+
+/// Flags targets that were missed by `now` and records an event for each,
+/// returns whether any was missed. The clock does not run while paused.
+fn record_breaches(
+    sla: &mut TicketSla,
+    now: NaiveDateTime,
+    conn: &mut PgConnection,
+) -> Result<bool, DbError> {
+    let running = sla.paused_at.is_none();
+    let missed = |done: Option<NaiveDateTime>, due: Option<NaiveDateTime>| match (done, due) {
+        (Some(done), Some(due)) => done > due,
+        (None, Some(due)) => running && now > due,
+        _ => false,
+    };
+
+    let mut targets = vec![];
+    //Resolving a ticket also answers it
+    if !sla.response_breached && missed(sla.first_response_at.or(sla.resolved_at), sla.response_due)
+    {
+        sla.response_breached = true;
+        targets.push(RESPONSE_TARGET);
+    }
+    if !sla.resolution_breached && missed(sla.resolved_at, sla.resolution_due) {
+        sla.resolution_breached = true;
+        targets.push(RESOLUTION_TARGET);
+    }
+    if targets.is_empty() {
+        return Ok(false);
+    }
+
+    use crate::schema::ticket_events::dsl::*;
+
+    let events = targets
+        .iter()
+        .map(|target| NewTicketEvent {
+            event_id: Uuid::new_v4(),
+            ticket_id: sla.ticket_id,
+            event_type: TicketEventType::SlaBreached.to_string(),
+            event_data: target.to_string(),
+            user_id: None,
+            created_at: now,
+        })
+        .collect::<Vec<NewTicketEvent>>();
+    diesel::insert_into(ticket_events)
+        .values(&events)
+        .execute(conn)?;
+
+    let changes = events
+        .iter()
+        .map(|event| (event.event_type.clone(), event.event_data.clone()))
+        .collect::<Vec<(String, String)>>();
+    notify_followers(sla.ticket_id, None, &changes, conn)?;
+
+    Ok(true)
+}
+
+fn sla_status(sla: &TicketSla, now: NaiveDateTime) -> SlaStatus {
+    if sla.response_breached || sla.resolution_breached {
+        return SlaStatus::Breached;
+    }
+    if sla.resolved_at.is_some() {
+        return SlaStatus::Met;
+    }
+    if sla.paused_at.is_some() {
+        return SlaStatus::Paused;
+    }
+
+    let soon = now + Duration::minutes(AT_RISK_MINUTES);
+    let response_soon =
+        sla.first_response_at.is_none() && sla.response_due.is_some_and(|due| due < soon);
+    if response_soon || sla.resolution_due.is_some_and(|due| due < soon) {
+        SlaStatus::AtRisk
+    } else {
+        SlaStatus::OnTrack
+    }
+}
+
+/// SQL condition on `ticket_slas` rows matching the same rules as `sla_status`
+pub fn sla_status_condition(status: SlaStatus) -> String {
+    let breached = "(response_breached OR resolution_breached)";
+    let soon = format!("{} + INTERVAL '{} minutes'", SQL_NOW, AT_RISK_MINUTES);
+    let at_risk = format!(
+        "COALESCE((first_response_at IS NULL AND response_due < {soon}) OR resolution_due < {soon}, FALSE)",
+        soon = soon
+    );
+    let running = format!(
+        "NOT {} AND resolved_at IS NULL AND paused_at IS NULL",
+        breached
+    );
+    match status {
+        SlaStatus::Breached => breached.to_string(),
+        SlaStatus::Met => format!("NOT {} AND resolved_at IS NOT NULL", breached),
+        SlaStatus::Paused => format!(
+            "NOT {} AND resolved_at IS NULL AND paused_at IS NOT NULL",
+            breached
+        ),
+        SlaStatus::AtRisk => format!("{} AND {}", running, at_risk),
+        SlaStatus::OnTrack => format!("{} AND NOT {}", running, at_risk),
+    }
+}
+
+/// SLA of each ticket that has one, by ticket id
+pub fn find_by_ticket_ids(
+    ids: &[i32],
+    conn: &mut PgConnection,
+) -> Result<HashMap<i32, TicketSlaRepresentation>, DbError> {
+    use crate::schema::sla_policies::dsl::{name, sla_policies};
+    use crate::schema::ticket_slas::dsl::*;
+
+    let items = ticket_slas
+        .inner_join(sla_policies)
+        .filter(ticket_id.eq_any(ids))
+        .select((ticket_slas::all_columns(), name))
+        .load::<(TicketSla, String)>(conn)?;
+
+    let now = chrono::Utc::now().naive_utc();
+    let result = items
+        .into_iter()
+        .map(|(sla, policy_name)| {
+            let representation = TicketSlaRepresentation {
+                policy_id: sla.policy_id,
+                policy_name,
+                response_due: sla.response_due,
+                resolution_due: sla.resolution_due,
+                first_response_at: sla.first_response_at,
+                resolved_at: sla.resolved_at,
+                paused: sla.paused_at.is_some(),
+                response_breached: sla.response_breached,
+                resolution_breached: sla.resolution_breached,
+                sla_status: sla_status(&sla, now).to_string(),
+            };
+            (sla.ticket_id, representation)
+        })
+        .collect();
+
+    Ok(result)
+}
+
+fn matching_policy(ticket: &Ticket, conn: &mut PgConnection) -> Result<Option<SlaPolicy>, DbError> {
+    use crate::schema::sla_policies::dsl::*;
+
+    let mut query = sla_policies
+        .filter(priority.is_null().or(priority.eq(&ticket.priority)))
+        .into_boxed();
+    query = match ticket.project {
+        Some(id) => query.filter(project.is_null().or(project.eq(id))),
+        None => query.filter(project.is_null()),
+    };
+    query = match ticket.contact {
+        Some(id) => query.filter(contact.is_null().or(contact.eq(id))),
+        None => query.filter(contact.is_null()),
+    };
+    let candidates = query.order(name.asc()).load::<SlaPolicy>(conn)?;
+
+    //Ties go to the first policy by name
+    let best = candidates
+        .into_iter()
+        .rev()
+        .max_by_key(|policy| policy.specificity());
+
+    Ok(best)
+}
+
+/// Matches every ticket that is not resolved yet against the policies again
+fn reapply_open_tickets(conn: &mut PgConnection) -> Result<(), DbError> {
+    use crate::schema::ticket_statuses;
+    use crate::schema::tickets::dsl::*;
+
+    let closed = ticket_statuses::table
+        .filter(ticket_statuses::is_closed.eq(true))
+        .select(ticket_statuses::name);
+    let ids = tickets
+        .filter(status.ne_all(closed))
+        .select(ticket_id)
+        .load::<i32>(conn)?;
+    for id in ids {
+        update_sla(id, conn)?;
+    }
+
+    Ok(())
+}
+
+/// Adds a policy (no `id`) or updates one
+fn save_policy(
+    id: Option<Uuid>,
+    payload: SlaPolicyPayload,
+    conn: &mut PgConnection,
+) -> Result<Result<SlaPolicy, String>, DbError> {
+    use crate::schema::sla_policies::dsl::*;
+
+    conn.transaction::<_, DbError, _>(|conn| {
+        let mut query = sla_policies.filter(name.eq(&payload.name)).into_boxed();
+        if let Some(id) = id {
+            query = query.filter(policy_id.ne(id));
+        }
+        if query.count().get_result::<i64>(conn)? > 0 {
+            return Ok(Err("Policy name is already in use".to_string()));
+        }
+        if let Some(message) = check_criteria(&payload, conn)? {
+            return Ok(Err(message));
+        }
+
+        let policy = match id {
+            Some(id) => match diesel::update(sla_policies.find(id))
+                .set(&payload)
+                .get_result::<SlaPolicy>(conn)
+                .optional()?
+            {
+                Some(policy) => policy,
+                None => return Ok(Err("SLA policy not found".to_string())),
+            },
+            None => diesel::insert_into(sla_policies)
+                .values(&payload)
+                .get_result::<SlaPolicy>(conn)?,
+        };
+        reapply_open_tickets(conn)?;
+
+        Ok(Ok(policy))
+    })
+}
+
+/// The priority, project and contact of a policy have to exist
+fn check_criteria(
+    payload: &SlaPolicyPayload,
+    conn: &mut PgConnection,
+) -> Result<Option<String>, DbError> {
+    use crate::schema::{contacts, projects, ticket_priorities};
+    use diesel::dsl::exists;
+
+    if let Some(priority) = &payload.priority {
+        let found = diesel::select(exists(
+            ticket_priorities::table.filter(ticket_priorities::name.eq(priority)),
+        ))
+        .get_result::<bool>(conn)?;
+        if !found {
+            return Ok(Some(format!("Unknown priority: {}", priority)));
+        }
+    }
+    if let Some(project) = payload.project {
+        let found =
+            diesel::select(exists(projects::table.find(project))).get_result::<bool>(conn)?;
+        if !found {
+            return Ok(Some("Project not found".to_string()));
+        }
+    }
+    if let Some(contact) = payload.contact {
+        let found =
+            diesel::select(exists(contacts::table.find(contact))).get_result::<bool>(conn)?;
+        if !found {
+            return Ok(Some("Contact not found".to_string()));
+        }
+    }
+
+    Ok(None)
+}
+
+fn find_all(conn: &mut PgConnection) -> Result<Vec<SlaPolicy>, DbError> {
+    use crate::schema::sla_policies::dsl::*;
+
+    let items = sla_policies.order(name.asc()).load::<SlaPolicy>(conn)?;
+
+    Ok(items)
+}
+
+/// Tickets on the policy keep their clocks under the next matching policy
+fn delete_policy(id: Uuid, conn: &mut PgConnection) -> Result<usize, DbError> {
+    use crate::schema::sla_policies::dsl::*;
+    use crate::schema::ticket_slas;
+
+    conn.transaction(|conn| {
+        let clocks = ticket_slas::table
+            .filter(ticket_slas::policy_id.eq(id))
+            .load::<TicketSla>(conn)?;
+        let count = diesel::delete(sla_policies.find(id)).execute(conn)?;
+        for clock in clocks {
+            apply_sla(clock.ticket_id, Some(clock), conn)?;
+        }
+
+        Ok(count)
+    })
+}
@@ -0,0 +1,450 @@
+use super::super::DbPool;
+
+use std::collections::HashMap;
+
+use actix_web::{delete, get, post, put, web, Error, HttpResponse};
+use diesel::prelude::*;
+use shared::models::{
+    live::LiveEvent,
+    response::Response,
+    tickets::{tagged_event_data, TicketEventType},
+    users::Role,
+    webhooks::WebhookEvent,
+};
+use uuid::Uuid;
+
+use super::notifications::notify_followers;
+use super::tickets::{check_ticket_access, ticket_exists};
+use super::webhooks::{publish, WebhookSubject};
+use crate::authentication::CurrentUser;
+use crate::live::Broadcaster;
+use crate::mail::{notify, Mailer};
+use crate::models::{
+    tags::{NewTicketTag, Tag, TagPayload, TicketTagPayload},
+    tickets::NewTicketEvent,
+    SuccessResponse,
+};
+
+type DbError = Box<dyn std::error::Error + Send + Sync>;
+
+const MAX_NAME_LENGTH: usize = 50;
+
+//Everyone who can see tickets needs the list to show and filter by tags
+#[get("/tags")]
+async fn index(pool: web::Data<DbPool>, _caller: CurrentUser) -> Result<HttpResponse, Error> {
+    let tags = web::block(move || {
+        let mut conn = pool.get()?;
+        find_all(&mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(tags))
+}
+
+/// Agents can create tags while tagging, renaming and deleting is left to admins
+#[post("/tags")]
+async fn create(
+    pool: web::Data<DbPool>,
+    payload: web::Json<TagPayload>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::can_edit)?;
+
+    let mut payload = payload.into_inner();
+    payload.name = payload.name.trim().to_string();
+    if let Some(message) = validate(&payload) {
+        return Ok(HttpResponse::Ok().json(failure(message)));
+    }
+
+    let tag = web::block(move || {
+        let mut conn = pool.get()?;
+        if name_in_use(&payload.name, None, &mut conn)? {
+            return Ok(None);
+        }
+        add_a_tag(payload, &mut conn).map(Some)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let response = match tag {
+        Some(tag) => Response {
+            success: true,
+            message: None,
+            data: Some(tag),
+        },
+        None => failure("Tag name is already in use".to_string()),
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+#[put("/tags/{id}")]
+async fn update(
+    id: web::Path<Uuid>,
+    payload: web::Json<TagPayload>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::is_admin)?;
+
+    let mut payload = payload.into_inner();
+    payload.name = payload.name.trim().to_string();
+    if let Some(message) = validate(&payload) {
+        return Ok(HttpResponse::Ok().json(failure(message)));
+    }
+
+    let id = id.into_inner();
+    let tag = web::block(move || {
+        let mut conn = pool.get()?;
+        if name_in_use(&payload.name, Some(id), &mut conn)? {
+            return Ok(None);
+        }
+        update_tag(id, payload, &mut conn).map(Some)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let response = match tag {
+        Some(tag) => Response {
+            success: true,
+            message: None,
+            data: Some(tag),
+        },
+        None => failure("Tag name is already in use".to_string()),
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Removes the tag from every ticket as well
+#[delete("/tags/{id}")]
+async fn delete(
+    id: web::Path<Uuid>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::is_admin)?;
+
+    let result = web::block(move || {
+        let mut conn = pool.get()?;
+        delete_tag(id.into_inner(), &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let response = if result > 0 {
+        SuccessResponse {
+            success: true,
+            message: "Tag deleted".to_string(),
+        }
+    } else {
+        SuccessResponse {
+            success: false,
+            message: "Tag not found".to_string(),
+        }
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+#[get("/tickets/{id}/tags")]
+async fn ticket_tags(
+    id: web::Path<i32>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    check_ticket_access(&pool, *id, &caller).await?;
+
+    let tags = web::block(move || {
+        let mut conn = pool.get()?;
+        find_by_ticket_id(id.into_inner(), &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(tags))
+}
+
+#[post("/tickets/{id}/tags")]
+async fn add_ticket_tag(
+    id: web::Path<i32>,
+    payload: web::Json<TicketTagPayload>,
+    pool: web::Data<DbPool>,
+    broadcaster: web::Data<Broadcaster>,
+    mailer: web::Data<Mailer>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::can_edit)?;
+
+    change_ticket_tag(
+        id.into_inner(),
+        payload.tag_id,
+        true,
+        pool,
+        broadcaster,
+        mailer,
+        caller,
+    )
+    .await
+}
+
+#[delete("/tickets/{id}/tags/{tag_id}")]
+async fn remove_ticket_tag(
+    path: web::Path<(i32, Uuid)>,
+    pool: web::Data<DbPool>,
+    broadcaster: web::Data<Broadcaster>,
+    mailer: web::Data<Mailer>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::can_edit)?;
+
+    let (id, tag) = path.into_inner();
+    change_ticket_tag(id, tag, false, pool, broadcaster, mailer, caller).await
+}
+
+/// Adds or removes a tag, records the event and lets followers know.
+/// Responds with the tags of the ticket afterwards.
+async fn change_ticket_tag(
+    id: i32,
+    tag: Uuid,
+    added: bool,
+    pool: web::Data<DbPool>,
+    broadcaster: web::Data<Broadcaster>,
+    mailer: web::Data<Mailer>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    let (ticket_found, tag_found) = {
+        let pool = pool.clone();
+        web::block(move || {
+            let mut conn = pool.get()?;
+            Ok::<_, DbError>((ticket_exists(id, &mut conn)?, tag_exists(tag, &mut conn)?))
+        })
+        .await?
+        .map_err(actix_web::error::ErrorInternalServerError)?
+    };
+    if !ticket_found {
+        return Err(actix_web::error::ErrorNotFound("Ticket not found"));
+    }
+    if !tag_found {
+        return Err(actix_web::error::ErrorNotFound("Tag not found"));
+    }
+
+    let user_id = Some(caller.user_id);
+    let notify_pool = pool.get_ref().clone();
+    let (event, tags) = web::block(move || {
+        let mut conn = pool.get()?;
+        let event = set_ticket_tag(id, tag, added, user_id, &mut conn)?;
+        if let Some(event) = &event {
+            let changes = [(event.event_type.clone(), event.event_data.clone())];
+            notify_followers(id, user_id, &changes, &mut conn)?;
+        }
+        let tags = find_by_ticket_id(id, &mut conn)?;
+        Ok::<_, DbError>((event, tags))
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    //Adding a tag twice or removing one that is not there changes nothing
+    if let Some(event) = event {
+        broadcaster.broadcast(LiveEvent::TicketChanged {
+            ticket_id: id,
+            user_id,
+        });
+        publish(
+            notify_pool.clone(),
+            WebhookEvent::TicketUpdated,
+            WebhookSubject::Ticket(id, vec![event.clone()]),
+        );
+        notify::ticket_events(
+            notify_pool,
+            mailer.get_ref().clone(),
+            id,
+            vec![event],
+            notify::Actor::User(caller.user_id),
+        );
+    }
+
+    let response = Response {
+        success: true,
+        message: None,
+        data: Some(tags),
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+fn failure(message: String) -> Response<Tag> {
+    Response {
+        success: false,
+        message: Some(message),
+        data: None,
+    }
+}
+
+fn validate(payload: &TagPayload) -> Option<String> {
+    if payload.name.is_empty() {
+        return Some("Tag name is required".to_string());
+    }
+    if payload.name.len() > MAX_NAME_LENGTH {
+        return Some(format!(
+            "Tag name is too long, max length is {}",
+            MAX_NAME_LENGTH
+        ));
+    }
+    //Tag filters are passed as a comma-separated list of names
+    if payload.name.contains(',') {
+        return Some("Tag name cannot contain a comma".to_string());
+    }
+    if let Some(color) = &payload.color {
+        let valid_color = color.len() == 7
+            && color.starts_with('#')
+            && color[1..].chars().all(|c| c.is_ascii_hexdigit());
+        if !valid_color {
+            return Some("Tag color must be in the form #rrggbb".to_string());
+        }
+    }
+
+    None
+}
+
+/// Tags of each of the given tickets, for showing them in ticket lists
+pub fn find_by_ticket_ids(
+    ids: &[i32],
+    conn: &mut PgConnection,
+) -> Result<HashMap<i32, Vec<Tag>>, DbError> {
+    use crate::schema::tags;
+    use crate::schema::ticket_tags::dsl::*;
+
+    let items = ticket_tags
+        .inner_join(tags::table)
+        .filter(ticket_id.eq_any(ids))
+        .order(tags::name.asc())
+        .select((ticket_id, tags::all_columns))
+        .load::<(i32, Tag)>(conn)?;
+
+    let mut by_ticket: HashMap<i32, Vec<Tag>> = HashMap::new();
+    for (id, tag) in items {
+        by_ticket.entry(id).or_default().push(tag);
+    }
+
+    Ok(by_ticket)
+}
+
+fn find_by_ticket_id(id: i32, conn: &mut PgConnection) -> Result<Vec<Tag>, DbError> {
+    let mut by_ticket = find_by_ticket_ids(&[id], conn)?;
+
+    Ok(by_ticket.remove(&id).unwrap_or_default())
+}
+
+fn tag_exists(id: Uuid, conn: &mut PgConnection) -> Result<bool, DbError> {
+    use crate::schema::tags::dsl::*;
+
+    let exists = diesel::select(diesel::dsl::exists(tags.find(id))).get_result(conn)?;
+
+    Ok(exists)
+}
+
+/// Returns the recorded event, or None when the ticket already was in the requested state
+pub fn set_ticket_tag(
+    id: i32,
+    tag: Uuid,
+    added: bool,
+    actor: Option<Uuid>,
+    conn: &mut PgConnection,
+) -> Result<Option<NewTicketEvent>, DbError> {
+    use crate::schema::ticket_tags::dsl::*;
+
+    conn.transaction::<_, DbError, _>(|conn| {
+        let tag_name: String = crate::schema::tags::table
+            .find(tag)
+            .select(crate::schema::tags::name)
+            .first(conn)?;
+        let time = chrono::Utc::now().naive_utc();
+
+        let count = if added {
+            diesel::insert_into(ticket_tags)
+                .values(&NewTicketTag {
+                    ticket_id: id,
+                    tag_id: tag,
+                    created_at: time,
+                })
+                .on_conflict_do_nothing()
+                .execute(conn)?
+        } else {
+            diesel::delete(ticket_tags.find((id, tag))).execute(conn)?
+        };
+        if count == 0 {
+            return Ok(None);
+        }
+
+        let event = NewTicketEvent {
+            event_id: Uuid::new_v4(),
+            ticket_id: id,
+            event_type: TicketEventType::Tagged.to_string(),
+            event_data: tagged_event_data(&tag_name, added),
+            user_id: actor,
+            created_at: time,
+        };
+        diesel::insert_into(crate::schema::ticket_events::table)
+            .values(&event)
+            .execute(conn)?;
+        diesel::update(crate::schema::tickets::table.find(id))
+            .set(crate::schema::tickets::updated_at.eq(time))
+            .execute(conn)?;
+
+        Ok(Some(event))
+    })
+}
+
+fn name_in_use(
+    tag_name: &str,
+    except: Option<Uuid>,
+    conn: &mut PgConnection,
+) -> Result<bool, DbError> {
+    use crate::schema::tags::dsl::*;
+
+    let mut query = tags.filter(name.eq(tag_name)).into_boxed();
+    if let Some(except) = except {
+        query = query.filter(tag_id.ne(except));
+    }
+    let count = query.count().get_result::<i64>(conn)?;
+
+    Ok(count > 0)
+}
+
+fn add_a_tag(payload: TagPayload, conn: &mut PgConnection) -> Result<Tag, DbError> {
+    use crate::schema::tags::dsl::*;
+
+    let tag = diesel::insert_into(tags)
+        .values(&payload)
+        .get_result::<Tag>(conn)?;
+
+    Ok(tag)
+}
+
+fn find_all(conn: &mut PgConnection) -> Result<Vec<Tag>, DbError> {
+    use crate::schema::tags::dsl::*;
+
+    let items = tags.order(name.asc()).load::<Tag>(conn)?;
+
+    Ok(items)
+}
+
+fn update_tag(id: Uuid, payload: TagPayload, conn: &mut PgConnection) -> Result<Tag, DbError> {
+    use crate::schema::tags::dsl::*;
+
+    let tag = diesel::update(tags.find(id))
+        .set(&payload)
+        .get_result::<Tag>(conn)?;
+
+    Ok(tag)
+}
+
+fn delete_tag(id: Uuid, conn: &mut PgConnection) -> Result<usize, DbError> {
+    use crate::schema::tags::dsl::*;
+
+    let count = diesel::delete(tags.find(id)).execute(conn)?;
+
+    Ok(count)
+}
@@ -0,0 +1,445 @@
+use super::super::DbPool;
+
+use std::collections::HashMap;
+
+use actix_web::{delete, get, post, put, web, Error, HttpResponse};
+use diesel::prelude::*;
+use shared::models::{
+    live::LiveEvent, response::Response, tickets::TicketEventType, users::Role,
+    webhooks::WebhookEvent,
+};
+use uuid::Uuid;
+
+use super::notifications::notify_followers;
+use super::tickets::{check_ticket_access, ticket_exists};
+use super::webhooks::{publish, WebhookSubject};
+use crate::authentication::CurrentUser;
+use crate::live::Broadcaster;
+use crate::mail::{notify, Mailer};
+use crate::models::{
+    tasks::{
+        NewTicketTask, TicketTask, TicketTaskOrderPayload, TicketTaskPayload,
+        TicketTaskUpdatePayload, UpdateTicketTask,
+    },
+    tickets::NewTicketEvent,
+};
+
+type DbError = Box<dyn std::error::Error + Send + Sync>;
+
+const MAX_TEXT_LENGTH: usize = 256;
+
+/// Result of a change, the tasks of the ticket afterwards and the recorded event
+type TaskChange = (Vec<TicketTask>, Option<NewTicketEvent>);
+
+#[get("/tickets/{id}/tasks")]
+async fn index(
+    id: web::Path<i32>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    check_ticket_access(&pool, *id, &caller).await?;
+
+    let tasks = web::block(move || {
+        let mut conn = pool.get()?;
+        find_by_ticket_id(id.into_inner(), &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(tasks))
+}
+
+/// New tasks go to the end of the list
+#[post("/tickets/{id}/tasks")]
+async fn create(
+    id: web::Path<i32>,
+    payload: web::Json<TicketTaskPayload>,
+    pool: web::Data<DbPool>,
+    broadcaster: web::Data<Broadcaster>,
+    mailer: web::Data<Mailer>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::can_edit)?;
+
+    let id = id.into_inner();
+    let payload = payload.into_inner();
+    let text = payload.text.trim().to_string();
+    if let Some(message) = validate(&text) {
+        return Ok(HttpResponse::Ok().json(failure(message)));
+    }
+
+    let new_task = NewTicketTask {
+        ticket_id: id,
+        text,
+        position: 0,
+        required: payload.required,
+    };
+    change_tasks(id, pool, broadcaster, mailer, caller, move |conn| {
+        add_task(new_task, conn).map(|tasks| Ok((tasks, None)))
+    })
+    .await
+}
+
+/// Changes the order of the tasks, every task of the ticket has to be listed once
+#[put("/tickets/{id}/tasks/order")]
+async fn reorder(
+    id: web::Path<i32>,
+    payload: web::Json<TicketTaskOrderPayload>,
+    pool: web::Data<DbPool>,
+    broadcaster: web::Data<Broadcaster>,
+    mailer: web::Data<Mailer>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::can_edit)?;
+
+    let id = id.into_inner();
+    let task_ids = payload.into_inner().task_ids;
+    change_tasks(id, pool, broadcaster, mailer, caller, move |conn| {
+        reorder_tasks(id, &task_ids, conn).map(|tasks| tasks.map(|tasks| (tasks, None)))
+    })
+    .await
+}
+
+/// Checks, unchecks, renames or marks a task as required
+#[put("/tickets/{id}/tasks/{task_id}")]
+async fn update(
+    path: web::Path<(i32, Uuid)>,
+    payload: web::Json<TicketTaskUpdatePayload>,
+    pool: web::Data<DbPool>,
+    broadcaster: web::Data<Broadcaster>,
+    mailer: web::Data<Mailer>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::can_edit)?;
+
+    let (id, task) = path.into_inner();
+    let mut payload = payload.into_inner();
+    if let Some(text) = &payload.text {
+        let text = text.trim().to_string();
+        if let Some(message) = validate(&text) {
+            return Ok(HttpResponse::Ok().json(failure(message)));
+        }
+        payload.text = Some(text);
+    }
+
+    let user_id = caller.user_id;
+    change_tasks(id, pool, broadcaster, mailer, caller, move |conn| {
+        update_task(id, task, payload, user_id, conn)
+    })
+    .await
+}
+
+#[delete("/tickets/{id}/tasks/{task_id}")]
+async fn delete(
+    path: web::Path<(i32, Uuid)>,
+    pool: web::Data<DbPool>,
+    broadcaster: web::Data<Broadcaster>,
+    mailer: web::Data<Mailer>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::can_edit)?;
+
+    let (id, task) = path.into_inner();
+    change_tasks(id, pool, broadcaster, mailer, caller, move |conn| {
+        delete_task(id, task, conn).map(|tasks| tasks.map(|tasks| (tasks, None)))
+    })
+    .await
+}
+
+/// Runs a change to the tasks of a ticket, then lets followers know about a recorded event
+/// and open pages refresh. Responds with the tasks of the ticket afterwards.
+async fn change_tasks<F>(
+    id: i32,
+    pool: web::Data<DbPool>,
+    broadcaster: web::Data<Broadcaster>,
+    mailer: web::Data<Mailer>,
+    caller: CurrentUser,
+    change: F,
+) -> Result<HttpResponse, Error>
+where
+    F: FnOnce(&mut PgConnection) -> Result<Result<TaskChange, String>, DbError> + Send + 'static,
+{
+    let exists = {
+        let pool = pool.clone();
+        web::block(move || {
+            let mut conn = pool.get()?;
+            ticket_exists(id, &mut conn)
+        })
+        .await?
+        .map_err(actix_web::error::ErrorInternalServerError)?
+    };
+    if !exists {
+        return Err(actix_web::error::ErrorNotFound("Ticket not found"));
+    }
+
+    let user_id = Some(caller.user_id);
+    let notify_pool = pool.get_ref().clone();
+    let result = web::block(move || {
+        let mut conn = pool.get()?;
+        let result = change(&mut conn)?;
+        if let Ok((_, Some(event))) = &result {
+            let changes = [(event.event_type.clone(), event.event_data.clone())];
+            notify_followers(id, user_id, &changes, &mut conn)?;
+        }
+        Ok::<_, DbError>(result)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let (tasks, event) = match result {
+        Ok(result) => result,
+        Err(message) => return Ok(HttpResponse::Ok().json(failure(message))),
+    };
+
+    broadcaster.broadcast(LiveEvent::TicketChanged {
+        ticket_id: id,
+        user_id,
+    });
+    if let Some(event) = event {
+        publish(
+            notify_pool.clone(),
+            WebhookEvent::TicketUpdated,
+            WebhookSubject::Ticket(id, vec![event.clone()]),
+        );
+        notify::ticket_events(
+            notify_pool,
+            mailer.get_ref().clone(),
+            id,
+            vec![event],
+            notify::Actor::User(caller.user_id),
+        );
+    }
+
+    let response = Response {
+        success: true,
+        message: None,
+        data: Some(tasks),
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+fn failure(message: String) -> Response<Vec<TicketTask>> {
+    Response {
+        success: false,
+        message: Some(message),
+        data: None,
+    }
+}
+
+fn validate(text: &str) -> Option<String> {
+    if text.is_empty() {
+        return Some("Task text is required".to_string());
+    }
+    if text.len() > MAX_TEXT_LENGTH {
+        return Some(format!(
+            "Task text is too long, max length is {}",
+            MAX_TEXT_LENGTH
+        ));
+    }
+
+    None
+}
+
+/// Closing a ticket needs its required tasks done, other statuses are always fine
+pub fn check_required_tasks(
+    id: i32,
+    new_status: &str,
+    conn: &mut PgConnection,
+) -> Result<Result<(), String>, DbError> {
+    use crate::schema::ticket_statuses;
+    use crate::schema::ticket_tasks::dsl::*;
+
+    let closing = ticket_statuses::table
+        .filter(ticket_statuses::name.eq(new_status))
+        .select(ticket_statuses::is_closed)
+        .first::<bool>(conn)
+        .optional()?
+        .unwrap_or_default();
+    if !closing {
+        return Ok(Ok(()));
+    }
+
+    let open = ticket_tasks
+        .filter(ticket_id.eq(id))
+        .filter(required.eq(true))
+        .filter(done.eq(false))
+        .count()
+        .get_result::<i64>(conn)?;
+    if open > 0 {
+        return Ok(Err(format!(
+            "The ticket has {} required tasks left to do",
+            open
+        )));
+    }
+
+    Ok(Ok(()))
+}
+
+/// Done and total tasks of each ticket that has any, by ticket id
+pub fn count_by_ticket_ids(
+    ids: &[i32],
+    conn: &mut PgConnection,
+) -> Result<HashMap<i32, (i64, i64)>, DbError> {
+    use crate::schema::ticket_tasks::dsl::*;
+
+    let items = ticket_tasks
+        .filter(ticket_id.eq_any(ids))
+        .select((ticket_id, done))
+        .load::<(i32, bool)>(conn)?;
+
+    let mut counts: HashMap<i32, (i64, i64)> = HashMap::new();
+    for (id, is_done) in items {
+        let count = counts.entry(id).or_default();
+        if is_done {
+            count.0 += 1;
+        }
+        count.1 += 1;
+    }
+
+    Ok(counts)
+}
+
+fn find_by_ticket_id(id: i32, conn: &mut PgConnection) -> Result<Vec<TicketTask>, DbError> {
+    use crate::schema::ticket_tasks::dsl::*;
+
+    let items = ticket_tasks
+        .filter(ticket_id.eq(id))
+        .order((position.asc(), created_at.asc()))
+        .load::<TicketTask>(conn)?;
+
+    Ok(items)
+}
+
+fn add_task(
+    mut new_task: NewTicketTask,
+    conn: &mut PgConnection,
+) -> Result<Vec<TicketTask>, DbError> {
+    use crate::schema::ticket_tasks::dsl::*;
+
+    conn.transaction(|conn| {
+        let last = ticket_tasks
+            .filter(ticket_id.eq(new_task.ticket_id))
+            .select(diesel::dsl::max(position))
+            .first::<Option<i32>>(conn)?;
+        new_task.position = last.map_or(0, |last| last + 1);
+
+        diesel::insert_into(ticket_tasks)
+            .values(&new_task)
+            .execute(conn)?;
+
+        find_by_ticket_id(new_task.ticket_id, conn)
+    })
+}
+
+/// Records an event when the task was checked or unchecked
+fn update_task(
+    id: i32,
+    task: Uuid,
+    payload: TicketTaskUpdatePayload,
+    actor: Uuid,
+    conn: &mut PgConnection,
+) -> Result<Result<TaskChange, String>, DbError> {
+    use crate::schema::ticket_tasks::dsl::*;
+
+    conn.transaction(|conn| {
+        let Some(old) = ticket_tasks
+            .find(task)
+            .filter(ticket_id.eq(id))
+            .first::<TicketTask>(conn)
+            .optional()?
+        else {
+            return Ok(Err("Task not found".to_string()));
+        };
+
+        let now = chrono::Utc::now().naive_utc();
+        let checked = payload.done.filter(|is_done| *is_done != old.done);
+        let changes = UpdateTicketTask {
+            text: payload.text,
+            done: payload.done,
+            required: payload.required,
+            completed_at: checked.map(|is_done| is_done.then_some(now)),
+            completed_by: checked.map(|is_done| is_done.then_some(actor)),
+        };
+        //Nothing to update is an error in diesel
+        if changes.text.is_some() || changes.done.is_some() || changes.required.is_some() {
+            diesel::update(ticket_tasks.find(task))
+                .set(&changes)
+                .execute(conn)?;
+        }
+
+        let event = match checked {
+            Some(is_done) => {
+                let event_type = if is_done {
+                    TicketEventType::TaskCompleted
+                } else {
+                    TicketEventType::TaskReopened
+                };
+                let event = NewTicketEvent {
+                    event_id: Uuid::new_v4(),
+                    ticket_id: id,
+                    event_type: event_type.to_string(),
+                    event_data: changes.text.unwrap_or(old.text),
+                    user_id: Some(actor),
+                    created_at: now,
+                };
+                diesel::insert_into(crate::schema::ticket_events::table)
+                    .values(&event)
+                    .execute(conn)?;
+                diesel::update(crate::schema::tickets::table.find(id))
+                    .set(crate::schema::tickets::updated_at.eq(now))
+                    .execute(conn)?;
+                Some(event)
+            }
+            None => None,
+        };
+
+        Ok(Ok((find_by_ticket_id(id, conn)?, event)))
+    })
+}
+
+fn reorder_tasks(
+    id: i32,
+    order: &[Uuid],
+    conn: &mut PgConnection,
+) -> Result<Result<Vec<TicketTask>, String>, DbError> {
+    use crate::schema::ticket_tasks::dsl::*;
+
+    conn.transaction(|conn| {
+        let mut current = ticket_tasks
+            .filter(ticket_id.eq(id))
+            .select(task_id)
+            .load::<Uuid>(conn)?;
+        let mut requested = order.to_vec();
+        current.sort();
+        requested.sort();
+        if current != requested {
+            return Ok(Err(
+                "The order has to list every task of the ticket once".to_string()
+            ));
+        }
+
+        for (new_position, item) in order.iter().enumerate() {
+            diesel::update(ticket_tasks.find(item))
+                .set(position.eq(new_position as i32))
+                .execute(conn)?;
+        }
+
+        Ok(Ok(find_by_ticket_id(id, conn)?))
+    })
+}
+
+fn delete_task(
+    id: i32,
+    task: Uuid,
+    conn: &mut PgConnection,
+) -> Result<Result<Vec<TicketTask>, String>, DbError> {
+    use crate::schema::ticket_tasks::dsl::*;
+
+    let count = diesel::delete(ticket_tasks.find(task).filter(ticket_id.eq(id))).execute(conn)?;
+    if count == 0 {
+        return Ok(Err("Task not found".to_string()));
+    }
+
+    Ok(Ok(find_by_ticket_id(id, conn)?))
+}
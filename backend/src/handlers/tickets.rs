@@ -1,23 +1,45 @@
 use super::super::DbPool;
 
-use actix_web::{delete, error::InternalError, get, options, post, put, web, Error, HttpResponse};
-use diesel::{prelude::*, dsl::sql, sql_types::{Integer, Text}};
-use shared::models::{response::Response, tickets::{TicketEventType, TicketFilterPayload}, MAX_TITLE_LENGTH};
+use actix_web::{delete, get, options, post, put, web, Error, HttpResponse};
+use diesel::{prelude::*, dsl::sql, sql_types::{Bool, Integer, Nullable, Text, Timestamp}};
+use shared::models::{automation::AutomationTrigger, custom_fields::{CustomFieldType, FIELD_SORT_PREFIX}, live::LiveEvent, response::Response, sla::SlaStatus, tickets::{TicketEventType, TicketFilterPayload}, users::Role, webhooks::WebhookEvent, MAX_TITLE_LENGTH};
 use uuid::Uuid;
 
+use super::attachments::{delete_blobs, find_storage_keys, AttachmentOwner};
+use super::automation::{automate, event_triggers};
+use super::custom_fields::{
+    check_values, filter_value, find_by_id as find_custom_field, find_values_by_ticket_ids, set_values,
+    FieldChanges,
+};
+use super::links::closed_as_duplicate;
+use super::notifications::notify_followers;
+use super::projects::{claim_ticket_number, find_by_reference};
+use super::reports::find_time_by_ticket_ids;
+use super::sla::{find_by_ticket_ids as find_slas_by_ticket_ids, sla_status_condition, update_sla};
+use super::tags::find_by_ticket_ids;
+use super::tasks::{check_required_tasks, count_by_ticket_ids};
+use super::watchers::add_watcher;
+use super::webhooks::{publish, WebhookSubject};
+use super::workflows::{check_ticket_workflow, default_status};
 use crate::{
+    authentication::{forbidden, CurrentUser},
+    live::Broadcaster,
+    mail::{notify, Mailer},
     models::{
-        session::TypedSession,
         tickets::{
             NewTicket, NewTicketEvent, NewTicketRevision, Ticket, TicketEvent,
             TicketPayload, TicketRepresentation, TicketRevision, TicketUpdatePayload, UpdateTicket, TicketWrapper},
+        projects::Project,
         users::User,
         SuccessResponse,
     },
+    storage::Storage,
     utils::parse_uuid,
 };
 
 type DbError = Box<dyn std::error::Error + Send + Sync>;
+/// A ticket joined with its assignee and project
+type TicketDetails = (Ticket, Option<User>, Option<Project>);
 
 //options
 #[options("/tickets")]
@@ -28,9 +50,12 @@ async fn options() -> Result<HttpResponse, Error> {
 #[post("/tickets")]
 async fn create(
     pool: web::Data<DbPool>,
+    broadcaster: web::Data<Broadcaster>,
+    mailer: web::Data<Mailer>,
     payload: web::Json<TicketPayload>,
-    session: TypedSession,
+    caller: CurrentUser,
 ) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::can_create_tickets)?;
 
     if payload.title.len() > MAX_TITLE_LENGTH {
         let response: Response<TicketRepresentation> = Response {
@@ -41,18 +66,60 @@ async fn create(
         return Ok(HttpResponse::Ok().json(response));
     }
 
-    let time = chrono::Utc::now().naive_utc();
-    let user_id: Option<Uuid> = match session.get_user_id() {
-        Ok(id) => id,
-        Err(_) => {
-            return Err(InternalError::from_response(
-                "Unauthorized",
-                HttpResponse::Unauthorized().finish(),
-            )
-            .into())
+    //An empty status means the default one
+    let workflow = {
+        let pool = pool.clone();
+        let ticket_status = payload.status.clone();
+        let ticket_priority = payload.priority.clone();
+        web::block(move || {
+            let mut conn = pool.get()?;
+            let ticket_status = match ticket_status.trim() {
+                "" => default_status(&mut conn)?,
+                name => name.to_string(),
+            };
+            check_ticket_workflow(None, Some(&ticket_status), Some(&ticket_priority), &mut conn)
+                .map(|checked| checked.map(|_| ticket_status))
+        })
+        .await?
+        .map_err(actix_web::error::ErrorInternalServerError)?
+    };
+    let ticket_status = match workflow {
+        Ok(ticket_status) => ticket_status,
+        Err(message) => {
+            let response: Response<TicketRepresentation> = Response {
+                success: false,
+                message: Some(message),
+                data: None,
+            };
+            return Ok(HttpResponse::Ok().json(response));
+        }
+    };
+
+    let field_changes = {
+        let pool = pool.clone();
+        let values = payload.custom_fields.clone();
+        web::block(move || {
+            let mut conn = pool.get()?;
+            check_values(&values, true, &mut conn)
+        })
+        .await?
+        .map_err(actix_web::error::ErrorInternalServerError)?
+    };
+    let field_changes = match field_changes {
+        Ok(changes) => changes,
+        Err(message) => {
+            let response: Response<TicketRepresentation> = Response {
+                success: false,
+                message: Some(message),
+                data: None,
+            };
+            return Ok(HttpResponse::Ok().json(response));
         }
     };
 
+    let time = chrono::Utc::now().naive_utc();
+    let user_id = Some(caller.user_id);
+
     let new_ticket = NewTicket {
         title: payload.title.clone(),
         assignee: payload.assignee,
@@ -62,24 +129,43 @@ async fn create(
         updated_at: time,
         due_date: payload.due_date,
         priority: payload.priority.clone(),
-        status: payload.status.clone(),
+        status: ticket_status,
         created_by: user_id,
         updated_by: user_id,
         revision: time,
         revision_by: user_id,
+        project: payload.project,
+        project_number: None,
     };
 
-    let ticket = web::block(move || {
-        let mut conn = pool.get()?;
-        add_a_ticket(new_ticket, &mut conn)
-    })
-    .await?
-    .map(|x| {
-        x.into_iter()
-            .map(TicketRepresentation::from)
-            .collect::<Vec<TicketRepresentation>>()
-    })
-    .map_err(actix_web::error::ErrorInternalServerError)?;
+    let mut ticket = {
+        let pool = pool.clone();
+        web::block(move || {
+            let mut conn = pool.get()?;
+            add_a_ticket(new_ticket, field_changes, &mut conn)
+        })
+        .await?
+        .map_err(actix_web::error::ErrorInternalServerError)?
+    };
+
+    let ticket_id = ticket.first().unwrap().ticket_id;
+    broadcaster.broadcast(LiveEvent::TicketChanged { ticket_id, user_id });
+
+    //Rules may have changed the ticket right away
+    publish(
+        pool.get_ref().clone(),
+        WebhookEvent::TicketCreated,
+        WebhookSubject::Ticket(ticket_id, vec![]),
+    );
+    let triggers = vec![AutomationTrigger::TicketCreated];
+    if automate(&pool, &mailer, &broadcaster, ticket_id, triggers).await {
+        ticket = web::block(move || {
+            let mut conn = pool.get()?;
+            find_by_id(ticket_id, &mut conn)
+        })
+        .await?
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    }
 
     let ticket = ticket.first().unwrap();
 
@@ -92,15 +178,16 @@ async fn create(
     Ok(HttpResponse::Ok().json(response))
 }
 
-// All tickets with optional status filter (open, closed)
+// All tickets with optional status filter (open, closed or a status name)
 #[get("/tickets")]
 async fn index(
     pool: web::Data<DbPool>,
     query: web::Query<TicketFilterPayload>,
+    caller: CurrentUser,
 ) -> Result<HttpResponse, Error> {
     let tickets = web::block(move || {
         let mut conn = pool.get()?;
-        find(&mut conn, Some(query.into_inner()))
+        find(&mut conn, Some(query.into_inner()), caller)
     })
     .await?
     .map_err(actix_web::error::ErrorInternalServerError)?;
@@ -133,34 +220,19 @@ async fn index(
 //     Ok(HttpResponse::Ok().json(ticket_list))
 // }
 
-//get tickets by user_id
-#[get("/tickets?user_id={user_id}")]
-async fn by_user_id(pool: web::Data<DbPool>, id: web::Path<i32>) -> Result<HttpResponse, Error> {
-    //convert to Uuid
-    let id = Uuid::from_u128(id.into_inner() as u128);
-
-    let tickets = web::block(move || {
-        let mut conn = pool.get()?;
-        find_by_user_id(id, &mut conn)
-    })
-    .await?
-    .map_err(actix_web::error::ErrorInternalServerError)?;
-
-    Ok(HttpResponse::Ok().json(tickets))
-}
-
 #[get("/tickets/{id}")]
-async fn show(id: web::Path<i32>, pool: web::Data<DbPool>) -> Result<HttpResponse, Error> {
+async fn show(
+    id: web::Path<i32>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    check_ticket_access(&pool, *id, &caller).await?;
+
     let ticket = web::block(move || {
         let mut conn = pool.get()?;
         find_by_id(id.into_inner(), &mut conn)
     })
     .await?
-    .map(|x| {
-        x.into_iter()
-            .map(TicketRepresentation::from)
-            .collect::<Vec<TicketRepresentation>>()
-    })
     .map_err(actix_web::error::ErrorInternalServerError)?;
 
     let ticket = ticket.first().unwrap();
@@ -173,8 +245,11 @@ async fn update(
     id: web::Path<i32>,
     payload: web::Json<TicketUpdatePayload>,
     pool: web::Data<DbPool>,
-    session: TypedSession,
+    broadcaster: web::Data<Broadcaster>,
+    mailer: web::Data<Mailer>,
+    caller: CurrentUser,
 ) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::can_edit)?;
 
     if let Some(title) = payload.title.clone() {
         if title.len() > MAX_TITLE_LENGTH {
@@ -187,18 +262,33 @@ async fn update(
         }
     }
 
-    let time = chrono::Utc::now().naive_utc();
-    let user_id: Option<Uuid> = match session.get_user_id() {
-        Ok(id) => id,
-        Err(_) => {
-            return Err(InternalError::from_response(
-                "Unauthorized",
-                HttpResponse::Unauthorized().finish(),
-            )
-            .into())
+    let field_changes = match payload.custom_fields.clone() {
+        Some(values) => {
+            let pool = pool.clone();
+            let checked = web::block(move || {
+                let mut conn = pool.get()?;
+                check_values(&values, false, &mut conn)
+            })
+            .await?
+            .map_err(actix_web::error::ErrorInternalServerError)?;
+            match checked {
+                Ok(changes) => changes,
+                Err(message) => {
+                    let response: Response<TicketRepresentation> = Response {
+                        success: false,
+                        message: Some(message),
+                        data: None,
+                    };
+                    return Ok(HttpResponse::Ok().json(response));
+                }
+            }
         }
+        None => vec![],
     };
 
+    let time = chrono::Utc::now().naive_utc();
+    let user_id = Some(caller.user_id);
+
     let mut updated_ticket = UpdateTicket {
         title: payload.title.clone(),
         assignee: None,
@@ -213,6 +303,8 @@ async fn update(
         } else {
             None
         },
+        project: None,
+        project_number: None,
     };
 
     //If assignee is None (either not in payload or null), set updated_ticket.assignee to None,
@@ -220,6 +312,7 @@ async fn update(
     //otherwise set it to Some(Some(assignee)) parsed as uuid
     updated_ticket.assignee = parse_uuid(&payload.assignee)?;
     updated_ticket.contact = parse_uuid(&payload.contact)?;
+    updated_ticket.project = parse_uuid(&payload.project)?;
     updated_ticket.due_date = {
         //If it is Some(None), means it was set to null (no due date, will be processed in db update).
         match payload.due_date {
@@ -239,6 +332,40 @@ async fn update(
         .map_err(actix_web::error::ErrorInternalServerError)?
     };
 
+    //Status changes have to follow the allowed transitions, and closing needs the required tasks done
+    let workflow = {
+        let pool = pool.clone();
+        let id = *id;
+        let old_status = old_ticket.status.clone();
+        let ticket_status = payload.status.clone();
+        let ticket_priority = payload.priority.clone();
+        web::block(move || {
+            let mut conn = pool.get()?;
+            let checked = check_ticket_workflow(
+                Some(&old_status),
+                ticket_status.as_deref(),
+                ticket_priority.as_deref(),
+                &mut conn,
+            )?;
+            match ticket_status {
+                Some(new_status) if checked.is_ok() && new_status != old_status => {
+                    check_required_tasks(id, &new_status, &mut conn)
+                }
+                _ => Ok(checked),
+            }
+        })
+        .await?
+        .map_err(actix_web::error::ErrorInternalServerError)?
+    };
+    if let Err(message) = workflow {
+        let response: Response<TicketRepresentation> = Response {
+            success: false,
+            message: Some(message),
+            data: None,
+        };
+        return Ok(HttpResponse::Ok().json(response));
+    }
+
     if updated_ticket.description.is_some() {
         //Set payload to none if content is the same (to prevent revision and timestamp update) otherwise proceed
         if updated_ticket.description.clone().unwrap() == old_ticket.description {
@@ -276,6 +403,9 @@ async fn update(
         }
     }
 
+    //Events are kept to send notifications once the update is done
+    let mut recorded_events = Vec::new();
+
     //For each status, priority, assignee, and title change, check if it is the same as old ticket and if not create an event for each
     if payload.status.is_some() && payload.status.clone().unwrap() != old_ticket.status {
         let event = NewTicketEvent {
@@ -287,13 +417,42 @@ async fn update(
             created_at: time,
         };
 
+        recorded_events.push(event.clone());
+        {
+            let pool = pool.clone();
+            web::block(move || {
+                let mut conn = pool.get()?;
+                create_ticket_event(event, &mut conn)
+            })
+            .await?
+            .map_err(actix_web::error::ErrorInternalServerError)?;
+        }
+
+        //Closing a duplicate points at the ticket it duplicates
         let pool = pool.clone();
-        web::block(move || {
+        let id = *id;
+        let new_status = payload.status.clone().unwrap();
+        let original = web::block(move || {
             let mut conn = pool.get()?;
-            create_ticket_event(event, &mut conn)
+            match closed_as_duplicate(id, &new_status, &mut conn)? {
+                Some(original) => {
+                    let event = NewTicketEvent {
+                        event_id: Uuid::new_v4(),
+                        ticket_id: id,
+                        event_type: TicketEventType::ClosedAsDuplicate.to_string(),
+                        event_data: original.to_string(),
+                        user_id,
+                        created_at: time,
+                    };
+                    create_ticket_event(event.clone(), &mut conn)?;
+                    Ok::<_, DbError>(Some(event))
+                }
+                None => Ok(None),
+            }
         })
         .await?
         .map_err(actix_web::error::ErrorInternalServerError)?;
+        recorded_events.extend(original);
     }
 
     if payload.priority.is_some() && payload.priority.clone().unwrap() != old_ticket.priority {
@@ -306,6 +465,7 @@ async fn update(
             created_at: time,
         };
 
+        recorded_events.push(event.clone());
         let pool = pool.clone();
         web::block(move || {
             let mut conn = pool.get()?;
@@ -328,6 +488,7 @@ async fn update(
                 created_at: time,
             };
 
+            recorded_events.push(event.clone());
             let pool = pool.clone();
             web::block(move || {
                 let mut conn = pool.get()?;
@@ -348,6 +509,7 @@ async fn update(
             created_at: time,
         };
 
+        recorded_events.push(event.clone());
         let pool = pool.clone();
         web::block(move || {
             let mut conn = pool.get()?;
@@ -372,6 +534,7 @@ async fn update(
             created_at: time,
         };
 
+        recorded_events.push(event.clone());
         let pool = pool.clone();
         web::block(move || {
             let mut conn = pool.get()?;
@@ -381,18 +544,56 @@ async fn update(
         .map_err(actix_web::error::ErrorInternalServerError)?;
     }
 
+    let ticket_id = *id;
+    let notify_pool = pool.get_ref().clone();
     let ticket = web::block(move || {
         let mut conn = pool.get()?;
-        update_ticket(id.into_inner(), updated_ticket, &mut conn)
+        update_ticket(id.into_inner(), updated_ticket, field_changes, &mut conn)
     })
     .await?
-    .map(|x| {
-        x.into_iter()
-            .map(TicketRepresentation::from)
-            .collect::<Vec<TicketRepresentation>>()
-    })
     .map_err(actix_web::error::ErrorInternalServerError)?;
 
+    if !recorded_events.is_empty() {
+        let changes = recorded_events
+            .iter()
+            .map(|event| (event.event_type.clone(), event.event_data.clone()))
+            .collect::<Vec<(String, String)>>();
+        let pool = notify_pool.clone();
+        web::block(move || {
+            let mut conn = pool.get()?;
+            notify_followers(ticket_id, user_id, &changes, &mut conn)
+        })
+        .await?
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    }
+
+    broadcaster.broadcast(LiveEvent::TicketChanged { ticket_id, user_id });
+
+    let triggers = event_triggers(&recorded_events);
+    publish(
+        notify_pool.clone(),
+        WebhookEvent::TicketUpdated,
+        WebhookSubject::Ticket(ticket_id, recorded_events.clone()),
+    );
+    notify::ticket_events(
+        notify_pool.clone(),
+        mailer.get_ref().clone(),
+        ticket_id,
+        recorded_events,
+        notify::Actor::User(caller.user_id),
+    );
+
+    //Rules see the ticket as the update left it
+    let mut ticket = ticket;
+    if automate(&notify_pool, &mailer, &broadcaster, ticket_id, triggers).await {
+        ticket = web::block(move || {
+            let mut conn = notify_pool.get()?;
+            find_by_id(ticket_id, &mut conn)
+        })
+        .await?
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    }
+
     let ticket = ticket.first().unwrap();
 
     let response = Response {
@@ -405,14 +606,40 @@ async fn update(
 }
 
 #[delete("/tickets/{id}")]
-async fn destroy(id: web::Path<i32>, pool: web::Data<DbPool>) -> Result<HttpResponse, Error> {
+async fn destroy(
+    id: web::Path<i32>,
+    pool: web::Data<DbPool>,
+    storage: web::Data<dyn Storage>,
+    broadcaster: web::Data<Broadcaster>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::is_admin)?;
+
+    let ticket_id = *id;
+    let webhook_pool = pool.get_ref().clone();
     let result = web::block(move || {
         let mut conn = pool.get()?;
-        delete_ticket(id.into_inner(), &mut conn)
+        //Attachments go with the ticket, their files have to be removed separately
+        let keys = find_storage_keys(AttachmentOwner::Ticket(ticket_id), &mut conn)?;
+        let result = delete_ticket(id.into_inner(), &mut conn)?;
+        delete_blobs(storage.as_ref(), &keys);
+        Ok::<_, DbError>(result)
     })
     .await?
     .map_err(actix_web::error::ErrorInternalServerError)?;
 
+    broadcaster.broadcast(LiveEvent::TicketChanged {
+        ticket_id,
+        user_id: Some(caller.user_id),
+    });
+    if result > 0 {
+        publish(
+            webhook_pool,
+            WebhookEvent::TicketDeleted,
+            WebhookSubject::Deleted(serde_json::json!({ "ticket_id": ticket_id })),
+        );
+    }
+
     if result > 1 {
         let response = SuccessResponse {
             success: true,
@@ -432,7 +659,10 @@ async fn destroy(id: web::Path<i32>, pool: web::Data<DbPool>) -> Result<HttpResp
 async fn revisions(
     ticket_id: web::Path<i32>,
     pool: web::Data<DbPool>,
+    caller: CurrentUser,
 ) -> Result<HttpResponse, Error> {
+    check_ticket_access(&pool, *ticket_id, &caller).await?;
+
     let revisions = web::block(move || {
         let mut conn = pool.get()?;
         get_ticket_revisions(ticket_id.into_inner(), &mut conn)
@@ -443,27 +673,72 @@ async fn revisions(
     Ok(HttpResponse::Ok().json(revisions))
 }
 
+/// Requesters can only see tickets they opened themselves, everyone else can see all tickets
+pub async fn check_ticket_access(
+    pool: &web::Data<DbPool>,
+    id: i32,
+    caller: &CurrentUser,
+) -> Result<(), Error> {
+    if caller.role.can_view() {
+        return Ok(());
+    }
+
+    let pool = pool.clone();
+    let ticket = web::block(move || {
+        let mut conn = pool.get()?;
+        get_ticket_by_id(id, &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorNotFound)?;
+
+    if ticket.created_by == Some(caller.user_id) {
+        Ok(())
+    } else {
+        Err(forbidden())
+    }
+}
+
 fn add_a_ticket(
-    payload: NewTicket,
+    mut payload: NewTicket,
+    field_changes: FieldChanges,
     conn: &mut PgConnection,
-) -> Result<Vec<(Ticket, Option<User>)>, DbError> {
+) -> Result<Vec<TicketRepresentation>, DbError> {
     use crate::schema::tickets::dsl::*;
-    use crate::schema::users::dsl::users;
 
-    let result: Ticket = diesel::insert_into(tickets)
-        .values(&payload)
-        .get_result(conn)?;
+    let result: Ticket = conn.transaction::<_, DbError, _>(|conn| {
+        //Tickets in a project get its next number, and its default assignee if none was picked
+        if let Some(project_id) = payload.project {
+            let (number, project_info) = claim_ticket_number(project_id, conn)?;
+            payload.project_number = Some(number);
+            if payload.assignee.is_none() {
+                payload.assignee = project_info.default_assignee;
+            }
+        }
 
-    let ticket: Vec<(Ticket, Option<User>)> = tickets
-        .filter(ticket_id.eq(result.ticket_id))
-        .left_join(users)
-        .load::<(Ticket, Option<User>)>(conn)?;
+        let result: Ticket = diesel::insert_into(tickets)
+            .values(&payload)
+            .get_result(conn)?;
+        set_values(result.ticket_id, field_changes, conn)?;
+        update_sla(result.ticket_id, conn)?;
+        Ok(result)
+    })?;
+
+    //Creators follow their own tickets
+    if let Some(creator) = result.created_by {
+        add_watcher(result.ticket_id, creator, conn)?;
+    }
 
-    Ok(ticket)
+    find_by_id(result.ticket_id, conn)
 }
 
 #[get("/tickets/{id}/events")]
-async fn events(ticket_id: web::Path<i32>, pool: web::Data<DbPool>) -> Result<HttpResponse, Error> {
+async fn events(
+    ticket_id: web::Path<i32>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    check_ticket_access(&pool, *ticket_id, &caller).await?;
+
     let events = web::block(move || {
         let mut conn = pool.get()?;
         get_ticket_events(ticket_id.into_inner(), &mut conn)
@@ -477,12 +752,22 @@ async fn events(ticket_id: web::Path<i32>, pool: web::Data<DbPool>) -> Result<Ht
 fn find(
     conn: &mut PgConnection,
     filters: Option<TicketFilterPayload>,
+    caller: CurrentUser,
 ) -> Result<TicketWrapper, DbError> {
+    use crate::schema::projects;
     use crate::schema::tickets::dsl::*;
     use crate::schema::users::dsl::users;
 
-    let mut query = tickets.left_join(users).into_boxed();
+    let mut query = tickets
+        .left_join(users)
+        .left_join(projects::table.on(project.eq(projects::project_id.nullable())))
+        .into_boxed();
     let mut count_query = tickets.into_boxed();
+
+    if !caller.role.can_view() {
+        query = query.filter(created_by.eq(caller.user_id));
+        count_query = count_query.filter(created_by.eq(caller.user_id));
+    }
     let mut page = 1;  
     let mut per_page = 50;
     let mut sort_by = "ticket_id".to_string();
@@ -499,14 +784,32 @@ fn find(
             }
         }
 
+        if let Some(tproject) = filters.project {
+            if tproject == Uuid::nil() {
+                query = query.filter(project.is_null());
+                count_query = count_query.filter(project.is_null());
+            } else {
+                query = query.filter(project.eq(tproject));
+                count_query = count_query.filter(project.eq(tproject));
+            }
+        }
+
         if let Some(tstatus) = filters.status {
-            if tstatus == "open" || tstatus == "Open" {
-                //anything but closed for now
-                query = query.filter(status.ne("Closed"));
-                count_query = count_query.filter(status.ne("Closed"));
-            } else if tstatus == "closed" || tstatus == "Closed" {
-                query = query.filter(status.eq("Closed"));
-                count_query = count_query.filter(status.eq("Closed"));
+            use crate::schema::ticket_statuses;
+
+            let closed = ticket_statuses::table
+                .filter(ticket_statuses::is_closed.eq(true))
+                .select(ticket_statuses::name);
+            //open and closed are categories, anything else is the name of a status
+            if tstatus == "open" {
+                query = query.filter(status.ne_all(closed));
+                count_query = count_query.filter(status.ne_all(closed));
+            } else if tstatus == "closed" {
+                query = query.filter(status.eq_any(closed));
+                count_query = count_query.filter(status.eq_any(closed));
+            } else if !tstatus.is_empty() {
+                query = query.filter(status.eq(tstatus.clone()));
+                count_query = count_query.filter(status.eq(tstatus));
             }
         }
         
@@ -529,17 +832,101 @@ fn find(
             sort_order = so;
         }
 
+        if filters.watching == Some(true) {
+            use crate::schema::ticket_watchers;
+
+            let watched = ticket_watchers::table
+                .filter(ticket_watchers::user_id.eq(caller.user_id))
+                .select(ticket_watchers::ticket_id);
+            query = query.filter(ticket_id.eq_any(watched));
+            count_query = count_query.filter(ticket_id.eq_any(watched));
+        }
+
+        //Tickets need every requested tag, and none of the excluded ones
+        {
+            use crate::schema::{tags, ticket_tags};
+
+            for name in tag_names(&filters.tags) {
+                let tagged = ticket_tags::table
+                    .inner_join(tags::table)
+                    .filter(tags::name.eq(name.to_string()))
+                    .select(ticket_tags::ticket_id);
+                query = query.filter(ticket_id.eq_any(tagged.clone()));
+                count_query = count_query.filter(ticket_id.eq_any(tagged));
+            }
+
+            for name in tag_names(&filters.exclude_tags) {
+                let tagged = ticket_tags::table
+                    .inner_join(tags::table)
+                    .filter(tags::name.eq(name.to_string()))
+                    .select(ticket_tags::ticket_id);
+                query = query.filter(ticket_id.ne_all(tagged.clone()));
+                count_query = count_query.filter(ticket_id.ne_all(tagged));
+            }
+        }
+
+        if let Some(tfield) = filters.field {
+            use crate::schema::ticket_field_values as values;
+
+            let with_value = values::table
+                .filter(values::field_id.eq(tfield))
+                .select(values::ticket_id);
+            match filters.field_value.as_deref().map(str::trim) {
+                None => (),
+                Some("") => {
+                    query = query.filter(ticket_id.ne_all(with_value));
+                    count_query = count_query.filter(ticket_id.ne_all(with_value));
+                }
+                Some(value) => {
+                    let field = find_custom_field(tfield, conn)?;
+                    let value = filter_value(&field, value, conn)?;
+                    let is_text = field.field_type == CustomFieldType::Text.as_str();
+                    let matching = || {
+                        let subquery = with_value.into_boxed();
+                        if is_text {
+                            subquery.filter(values::value.ilike(format!("%{}%", value)))
+                        } else {
+                            subquery.filter(values::value.eq(value.clone()))
+                        }
+                    };
+                    query = query.filter(ticket_id.eq_any(matching()));
+                    count_query = count_query.filter(ticket_id.eq_any(matching()));
+                }
+            }
+        }
+
+        //Unknown SLA statuses are ignored, tickets without an SLA never match
+        if let Some(tsla) = filters.sla_status.as_deref().and_then(|value| value.parse::<SlaStatus>().ok()) {
+            let condition = format!(
+                "tickets.ticket_id IN (SELECT ticket_id FROM ticket_slas WHERE {})",
+                sla_status_condition(tsla)
+            );
+            query = query.filter(sql::<Bool>(&condition));
+            count_query = count_query.filter(sql::<Bool>(&condition));
+        }
+
+        if let Some(tbreached) = filters.breached {
+            let breached = "tickets.ticket_id IN (SELECT ticket_id FROM ticket_slas WHERE response_breached OR resolution_breached)";
+            let condition = if tbreached { breached.to_string() } else { format!("NOT {}", breached) };
+            query = query.filter(sql::<Bool>(&condition));
+            count_query = count_query.filter(sql::<Bool>(&condition));
+        }
+
         if let Some(s) = filters.search {
             if !s.is_empty() {
+                //Searching for a reference like NET-42 also finds that ticket
+                let referenced = find_by_reference(&s, conn)?.unwrap_or_default();
                 query = query.filter(
                     title
                         .ilike(format!("%{}%", s))
-                        .or(description.ilike(format!("%{}%", s))),
+                        .or(description.ilike(format!("%{}%", s)))
+                        .or(ticket_id.eq(referenced)),
                 );
                 count_query = count_query.filter(
                     title
                         .ilike(format!("%{}%", s))
-                        .or(description.ilike(format!("%{}%", s))),
+                        .or(description.ilike(format!("%{}%", s)))
+                        .or(ticket_id.eq(referenced)),
                 );
             }
         }
@@ -591,10 +978,15 @@ fn find(
             query = query.order(due_date.desc());
         }
     } else if sort_by == "status" {
+        //In the configured order, unknown statuses last
         if sort_order == "asc" {
-            query = query.order(status.asc());
+            query = query.order_by(sql::<Integer>(
+                "(SELECT position FROM ticket_statuses WHERE ticket_statuses.name = tickets.status) ASC NULLS LAST",
+            ));
         } else {
-            query = query.order(status.desc());
+            query = query.order_by(sql::<Integer>(
+                "(SELECT position FROM ticket_statuses WHERE ticket_statuses.name = tickets.status) DESC NULLS LAST",
+            ));
         }
     } else if sort_by == "assignee" {
         if sort_order == "asc" {
@@ -614,27 +1006,51 @@ fn find(
         } else {
             query = query.order(title.desc());
         }
+    } else if let Some(field) = sort_by
+        .strip_prefix(FIELD_SORT_PREFIX)
+        .and_then(|id| Uuid::parse_str(id).ok())
+    {
+        //Tickets without a value come last either way
+        let field = find_custom_field(field, conn)?;
+        let sort_value = if field.field_type == CustomFieldType::Number.as_str() {
+            "CAST(v.value AS DOUBLE PRECISION)"
+        } else if field.field_type == CustomFieldType::User.as_str() {
+            "(SELECT LOWER(display_name) FROM users WHERE users.user_id::text = v.value)"
+        } else {
+            "LOWER(v.value)"
+        };
+        let direction = if sort_order == "asc" { "ASC" } else { "DESC" };
+        query = query.order_by(
+            sql::<Text>(&format!(
+                "(SELECT {} FROM ticket_field_values v WHERE v.ticket_id = tickets.ticket_id AND v.field_id = ",
+                sort_value
+            ))
+            .bind::<diesel::sql_types::Uuid, _>(field.field_id)
+            .sql(&format!(") {} NULLS LAST", direction)),
+        );
     } else if sort_by == "priority" {
-        if sort_order == "asc" { 
-            query = query.order_by(sql::<(Integer, Text)>(r#"
-                CASE priority
-                    WHEN 'High' THEN 0
-                    WHEN 'Medium' THEN 1
-                    WHEN 'Low' THEN 3
-                    ELSE 2
-                END
-                "#));
-    }
-        else {
-            query = query.order_by(sql::<(Integer, Text)>(r#"
-                CASE priority
-                    WHEN 'High' THEN 3
-                    WHEN 'Medium' THEN 2
-                    WHEN 'Low' THEN 0
-                    ELSE 1
-                END
-                "#));
-    }
+        //By rank, most urgent first when ascending, tickets without a known priority last
+        if sort_order == "asc" {
+            query = query.order_by(sql::<Integer>(
+                "(SELECT rank FROM ticket_priorities WHERE ticket_priorities.name = tickets.priority) ASC NULLS LAST",
+            ));
+        } else {
+            query = query.order_by(sql::<Integer>(
+                "(SELECT rank FROM ticket_priorities WHERE ticket_priorities.name = tickets.priority) DESC NULLS LAST",
+            ));
+        }
+    } else if sort_by == "response_due" || sort_by == "resolution_due" {
+        //Only targets still pending count, tickets without one come last either way
+        let due = if sort_by == "response_due" {
+            "s.response_due FROM ticket_slas s WHERE s.first_response_at IS NULL AND s.resolved_at IS NULL"
+        } else {
+            "s.resolution_due FROM ticket_slas s WHERE s.resolved_at IS NULL"
+        };
+        let direction = if sort_order == "asc" { "ASC" } else { "DESC" };
+        query = query.order_by(sql::<Nullable<Timestamp>>(&format!(
+            "(SELECT {} AND s.ticket_id = tickets.ticket_id) {} NULLS LAST",
+            due, direction
+        )));
     } else {
         query = query.order(ticket_id.asc());
     }
@@ -644,14 +1060,11 @@ fn find(
     }
 
 
-    let items = query.load::<(Ticket, Option<User>)>(conn)?;
+    let items = query.load::<TicketDetails>(conn)?;
     
 
-    //collect Vec<TicketRepresentation> from Vec<(Ticket, Option<User>)>
-    let results = items
-        .into_iter()
-        .map(|(ticket, user)| TicketRepresentation::from((ticket, user)))
-        .collect::<Vec<TicketRepresentation>>();
+    //collect Vec<TicketRepresentation> from Vec<TicketDetails>
+    let results = represent(items, conn)?;
 
     let wrapper = TicketWrapper {
         tickets: results,
@@ -663,17 +1076,63 @@ fn find(
     Ok(wrapper)
 }
 
-/// Find ticket by id and join with user
-fn find_by_id(id: i32, conn: &mut PgConnection) -> Result<Vec<(Ticket, Option<User>)>, DbError> {
+/// Splits a comma-separated list of tag names
+fn tag_names(names: &Option<String>) -> Vec<&str> {
+    names
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// Find ticket by id and join with user and project
+pub fn find_by_id(
+    id: i32,
+    conn: &mut PgConnection,
+) -> Result<Vec<TicketRepresentation>, DbError> {
+    use crate::schema::projects;
     use crate::schema::tickets::dsl::*;
     use crate::schema::users::dsl::users;
 
-    let ticket: Vec<(Ticket, Option<User>)> = tickets
+    let ticket: Vec<TicketDetails> = tickets
         .filter(ticket_id.eq(&id))
         .left_join(users)
-        .load::<(Ticket, Option<User>)>(conn)?;
+        .left_join(projects::table.on(project.eq(projects::project_id.nullable())))
+        .load::<TicketDetails>(conn)?;
 
-    Ok(ticket)
+    represent(ticket, conn)
+}
+
+/// Converts joined tickets to their representation with their tags, custom fields, SLA, time spent
+/// and task counts
+fn represent(
+    items: Vec<TicketDetails>,
+    conn: &mut PgConnection,
+) -> Result<Vec<TicketRepresentation>, DbError> {
+    let ids = items.iter().map(|item| item.0.ticket_id).collect::<Vec<i32>>();
+    let mut tags = find_by_ticket_ids(&ids, conn)?;
+    let mut fields = find_values_by_ticket_ids(&ids, conn)?;
+    let mut slas = find_slas_by_ticket_ids(&ids, conn)?;
+    let mut time_spent = find_time_by_ticket_ids(&ids, conn)?;
+    let mut tasks = count_by_ticket_ids(&ids, conn)?;
+
+    let results = items
+        .into_iter()
+        .map(|item| {
+            let mut ticket = TicketRepresentation::from(item);
+            ticket.tags = tags.remove(&ticket.ticket_id).unwrap_or_default();
+            ticket.custom_fields = fields.remove(&ticket.ticket_id).unwrap_or_default();
+            ticket.sla = slas.remove(&ticket.ticket_id);
+            ticket.time_spent = time_spent.remove(&ticket.ticket_id).unwrap_or_default();
+            (ticket.tasks_done, ticket.tasks_total) =
+                tasks.remove(&ticket.ticket_id).unwrap_or_default();
+            ticket
+        })
+        .collect();
+
+    Ok(results)
 }
 
 /// Find ticket by ID with no join
@@ -685,24 +1144,45 @@ fn get_ticket_by_id(id: i32, conn: &mut PgConnection) -> Result<Ticket, DbError>
     Ok(ticket)
 }
 
+pub fn ticket_exists(id: i32, conn: &mut PgConnection) -> Result<bool, DbError> {
+    use crate::schema::tickets::dsl::*;
+
+    let exists = diesel::select(diesel::dsl::exists(tickets.find(id))).get_result(conn)?;
+
+    Ok(exists)
+}
+
 fn update_ticket(
     id: i32,
-    payload: UpdateTicket,
+    mut payload: UpdateTicket,
+    field_changes: FieldChanges,
     conn: &mut PgConnection,
-) -> Result<Vec<(Ticket, Option<User>)>, DbError> {
+) -> Result<Vec<TicketRepresentation>, DbError> {
     use crate::schema::tickets::dsl::*;
-    use crate::schema::users::dsl::users;
 
-    let result: Ticket = diesel::update(tickets.find(id))
-        .set(&payload)
-        .get_result(conn)?;
+    let result: Ticket = conn.transaction::<_, DbError, _>(|conn| {
+        //Moving to another project takes a number there, numbers are never reused
+        if let Some(new_project) = payload.project {
+            let current: Option<Uuid> = tickets.find(id).select(project).first(conn)?;
+            if new_project == current {
+                payload.project = None;
+            } else {
+                payload.project_number = match new_project {
+                    Some(project_id) => Some(Some(claim_ticket_number(project_id, conn)?.0)),
+                    None => Some(None),
+                };
+            }
+        }
 
-    let ticket: Vec<(Ticket, Option<User>)> = tickets
-        .filter(ticket_id.eq(result.ticket_id))
-        .left_join(users)
-        .load::<(Ticket, Option<User>)>(conn)?;
+        let result = diesel::update(tickets.find(id))
+            .set(&payload)
+            .get_result(conn)?;
+        set_values(id, field_changes, conn)?;
+        update_sla(id, conn)?;
+        Ok(result)
+    })?;
 
-    Ok(ticket)
+    find_by_id(result.ticket_id, conn)
 }
 
 fn delete_ticket(id: i32, conn: &mut PgConnection) -> Result<usize, DbError> {
@@ -712,21 +1192,6 @@ fn delete_ticket(id: i32, conn: &mut PgConnection) -> Result<usize, DbError> {
     Ok(count)
 }
 
-fn find_by_user_id(
-    id: Uuid,
-    conn: &mut PgConnection,
-) -> Result<Vec<(Ticket, Option<User>)>, DbError> {
-    use crate::schema::tickets::dsl::*;
-    use crate::schema::users::dsl::users;
-
-    let items: Vec<(Ticket, Option<User>)> = tickets
-        .filter(assignee.eq(&id))
-        .left_join(users)
-        .load::<(Ticket, Option<User>)>(conn)?;
-
-    Ok(items)
-}
-
 fn create_ticket_revision(
     payload: NewTicketRevision,
     conn: &mut PgConnection,
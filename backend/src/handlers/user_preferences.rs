@@ -59,6 +59,8 @@ fn get_user_preferences(
         theme: preferences.theme,
         locale: preferences.locale,
         timezone: preferences.timezone,
+        email_notifications: preferences.email_notifications,
+        notes_public: preferences.notes_public,
     };
 
     Ok(preferences)
@@ -1,15 +1,16 @@
 use super::super::DbPool;
 
-use actix_session::Session;
+use std::str::FromStr;
+
 use actix_web::{delete, error::InternalError, get, post, put, web, Error, HttpResponse};
 use diesel::prelude::*;
 use secrecy::ExposeSecret;
-use shared::models::users::UserDisplay;
+use shared::models::users::{Role, UserDisplay};
 use uuid::Uuid;
 
 use crate::models::session::TypedSession;
 use crate::{
-    authentication::{check_password_reqs, compute_password_hash},
+    authentication::{check_password_reqs, compute_password_hash, forbidden, CurrentUser},
     models::users::{
         MyUser, NewUser, UpdateUser, User, UserPayload, UserRepresentation, UserUpdatePayload,
     },
@@ -21,11 +22,15 @@ type DbError = Box<dyn std::error::Error + Send + Sync>;
 async fn create(
     pool: web::Data<DbPool>,
     payload: web::Json<UserPayload>,
+    caller: CurrentUser,
 ) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::is_admin)?;
+
+    if let Err(e) = validate_username(&payload.username) {
+        return Err(InternalError::new(e, actix_web::http::StatusCode::BAD_REQUEST).into());
+    }
 
-    let username_ok = validate_username(&payload.username);
-    if username_ok.is_err() {
-        let e = username_ok.unwrap_err();
+    if let Err(e) = validate_access(&payload.access) {
         return Err(InternalError::new(e, actix_web::http::StatusCode::BAD_REQUEST).into());
     }
 
@@ -47,7 +52,9 @@ async fn create(
 }
 
 #[get("/users")]
-async fn index(pool: web::Data<DbPool>) -> Result<HttpResponse, Error> {
+async fn index(pool: web::Data<DbPool>, caller: CurrentUser) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::can_view)?;
+
     let users = web::block(move || {
         let mut conn = pool.get()?;
         find_all(&mut conn)
@@ -71,7 +78,15 @@ async fn display_names(pool: web::Data<DbPool>) -> Result<HttpResponse, Error> {
 }
 
 #[get("/users/{id}")]
-async fn show(user_id: web::Path<Uuid>, pool: web::Data<DbPool>) -> Result<HttpResponse, Error> {
+async fn show(
+    user_id: web::Path<Uuid>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    if *user_id != caller.user_id {
+        caller.ensure(Role::can_view)?;
+    }
+
     let user = web::block(move || {
         let mut conn = pool.get()?;
         find_by_id(user_id.into_inner(), &mut conn)
@@ -127,11 +142,25 @@ async fn update(
     user_id: web::Path<Uuid>,
     payload: web::Json<UserUpdatePayload>,
     pool: web::Data<DbPool>,
+    caller: CurrentUser,
 ) -> Result<HttpResponse, Error> {
+    //Users can edit their own profile, only admins can edit others
+    if *user_id != caller.user_id {
+        caller.ensure(Role::is_admin)?;
+    }
+
+    if let Some(access) = &payload.access {
+        if let Err(e) = validate_access(access) {
+            return Err(InternalError::new(e, actix_web::http::StatusCode::BAD_REQUEST).into());
+        }
+        //Prevent users from granting themselves a different role
+        if !caller.role.is_admin() && access != caller.role.as_str() {
+            return Err(forbidden());
+        }
+    }
+
     if let Some(username) = &payload.username {
-        let username_ok = validate_username(username);
-        if username_ok.is_err() {
-            let e = username_ok.unwrap_err();
+        if let Err(e) = validate_username(username) {
             return Err(InternalError::new(e, actix_web::http::StatusCode::BAD_REQUEST).into());
         }
     }
@@ -158,20 +187,17 @@ async fn update(
 async fn destroy(
     id: web::Path<Uuid>,
     pool: web::Data<DbPool>,
-    session: Session,
+    caller: CurrentUser,
 ) -> Result<HttpResponse, Error> {
-    let session_id: Option<Uuid> = session.get("user_id")?;
+    caller.ensure(Role::is_admin)?;
 
     //prevent user from deleting themselves
-    if let Some(session_id) = session_id {
-        let target_id = *id;
-        if session_id == target_id {
-            return Err(InternalError::from_response(
-                "You cannot delete yourself",
-                HttpResponse::Unauthorized().finish(),
-            )
-            .into());
-        }
+    if caller.user_id == *id {
+        return Err(InternalError::from_response(
+            "You cannot delete yourself",
+            HttpResponse::Unauthorized().finish(),
+        )
+        .into());
     }
 
     let user = web::block(move || {
@@ -382,6 +408,13 @@ fn validate_email(email: &str) -> Result<(), Error> {
     }
 }
 
+fn validate_access(access: &str) -> Result<(), Error> {
+    match Role::from_str(access) {
+        Ok(_) => Ok(()),
+        Err(e) => Err(actix_web::error::ErrorBadRequest(e)),
+    }
+}
+
 fn find_all_display_names(conn: &mut PgConnection) -> Result<Vec<UserDisplay>, DbError> {
     use crate::schema::users::dsl::*;
 
@@ -0,0 +1,309 @@
+use super::super::DbPool;
+
+use actix_web::{delete, get, post, put, web, Error, HttpResponse};
+use diesel::prelude::*;
+use shared::models::{
+    response::Response,
+    tickets::TicketFilterPayload,
+    users::{Role, UserDisplay},
+    MAX_TITLE_LENGTH,
+};
+use uuid::Uuid;
+
+use crate::authentication::{forbidden, CurrentUser};
+use crate::models::{
+    views::{NewSavedView, SavedView, SavedViewPayload, SavedViewRepresentation},
+    SuccessResponse,
+};
+
+type DbError = Box<dyn std::error::Error + Send + Sync>;
+
+/// The caller's own views followed by views other users shared
+#[get("/views")]
+async fn index(pool: web::Data<DbPool>, caller: CurrentUser) -> Result<HttpResponse, Error> {
+    //Requesters only see their own tickets, views of agents would not apply to them
+    let include_shared = caller.role.can_view();
+    let views = web::block(move || {
+        let mut conn = pool.get()?;
+        find_visible(caller.user_id, include_shared, &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(views))
+}
+
+#[post("/views")]
+async fn create(
+    pool: web::Data<DbPool>,
+    payload: web::Json<SavedViewPayload>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    let payload = payload.into_inner();
+    if payload.shared {
+        caller.ensure(Role::can_edit)?;
+    }
+    let new_view = match new_view(caller.user_id, payload) {
+        Ok(new_view) => new_view,
+        Err(message) => return Ok(HttpResponse::Ok().json(failure(message))),
+    };
+
+    let view = web::block(move || {
+        let mut conn = pool.get()?;
+        if name_in_use(caller.user_id, &new_view.name, None, &mut conn)? {
+            return Ok(None);
+        }
+        add_a_view(new_view, &mut conn).map(Some)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let response = match view {
+        Some(view) => Response {
+            success: true,
+            message: None,
+            data: Some(view),
+        },
+        None => failure("You already have a view with this name".to_string()),
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Only the owner can change a view, sharing it included
+#[put("/views/{id}")]
+async fn update(
+    id: web::Path<Uuid>,
+    payload: web::Json<SavedViewPayload>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    let payload = payload.into_inner();
+    if payload.shared {
+        caller.ensure(Role::can_edit)?;
+    }
+    let changes = match new_view(caller.user_id, payload) {
+        Ok(changes) => changes,
+        Err(message) => return Ok(HttpResponse::Ok().json(failure(message))),
+    };
+
+    let id = id.into_inner();
+    let view = web::block(move || {
+        let mut conn = pool.get()?;
+        let Some(existing) = find_by_id(id, &mut conn)? else {
+            return Ok(Err("View not found".to_string()));
+        };
+        if existing.owner != caller.user_id {
+            return Ok(Ok(None));
+        }
+        if name_in_use(caller.user_id, &changes.name, Some(id), &mut conn)? {
+            return Ok(Err("You already have a view with this name".to_string()));
+        }
+        update_view(id, changes, &mut conn).map(|view| Ok(Some(view)))
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let response = match view {
+        Ok(Some(view)) => Response {
+            success: true,
+            message: None,
+            data: Some(view),
+        },
+        Ok(None) => return Err(forbidden()),
+        Err(message) => failure(message),
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Owners delete their views, admins can also remove views others shared
+#[delete("/views/{id}")]
+async fn delete(
+    id: web::Path<Uuid>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    let id = id.into_inner();
+    let is_admin = caller.role.is_admin();
+    let result = web::block(move || {
+        let mut conn = pool.get()?;
+        let Some(existing) = find_by_id(id, &mut conn)? else {
+            return Ok(Some(0));
+        };
+        if existing.owner != caller.user_id && !(is_admin && existing.shared) {
+            return Ok(None);
+        }
+        delete_view(id, &mut conn).map(Some)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let response = match result {
+        None => return Err(forbidden()),
+        Some(count) if count > 0 => SuccessResponse {
+            success: true,
+            message: "View deleted".to_string(),
+        },
+        Some(_) => SuccessResponse {
+            success: false,
+            message: "View not found".to_string(),
+        },
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+fn failure(message: String) -> Response<SavedViewRepresentation> {
+    Response {
+        success: false,
+        message: Some(message),
+        data: None,
+    }
+}
+
+/// Validates the payload and stores the filter as JSON, views always open on the first page
+fn new_view(owner: Uuid, payload: SavedViewPayload) -> Result<NewSavedView, String> {
+    let name = payload.name.trim().to_string();
+    if name.is_empty() {
+        return Err("View name is required".to_string());
+    }
+    if name.len() > MAX_TITLE_LENGTH {
+        return Err(format!(
+            "View name is too long, max length is {}",
+            MAX_TITLE_LENGTH
+        ));
+    }
+    let filter = TicketFilterPayload {
+        page: Some(1),
+        ..payload.filter
+    };
+    let filter = serde_json::to_string(&filter).map_err(|e| e.to_string())?;
+
+    Ok(NewSavedView {
+        owner,
+        name,
+        filter,
+        shared: payload.shared,
+    })
+}
+
+fn represent(view: SavedView, display_name: String) -> Result<SavedViewRepresentation, DbError> {
+    Ok(SavedViewRepresentation {
+        view_id: view.view_id,
+        name: view.name,
+        filter: serde_json::from_str(&view.filter)?,
+        shared: view.shared,
+        owner: UserDisplay {
+            user_id: view.owner,
+            display_name,
+        },
+        created_at: view.created_at,
+    })
+}
+
+fn name_in_use(
+    owner: Uuid,
+    view_name: &str,
+    except: Option<Uuid>,
+    conn: &mut PgConnection,
+) -> Result<bool, DbError> {
+    use crate::schema::saved_views;
+
+    let mut query = saved_views::table
+        .filter(saved_views::owner.eq(owner))
+        .filter(saved_views::name.eq(view_name))
+        .into_boxed();
+    if let Some(except) = except {
+        query = query.filter(saved_views::view_id.ne(except));
+    }
+    let count = query.count().get_result::<i64>(conn)?;
+
+    Ok(count > 0)
+}
+
+fn find_visible(
+    user: Uuid,
+    include_shared: bool,
+    conn: &mut PgConnection,
+) -> Result<Vec<SavedViewRepresentation>, DbError> {
+    use crate::schema::{saved_views, users};
+
+    let mut query = saved_views::table
+        .inner_join(users::table)
+        .select((saved_views::all_columns, users::display_name))
+        .into_boxed();
+    query = if include_shared {
+        query.filter(saved_views::owner.eq(user).or(saved_views::shared.eq(true)))
+    } else {
+        query.filter(saved_views::owner.eq(user))
+    };
+    let views = query
+        .order((saved_views::owner.ne(user), saved_views::name.asc()))
+        .load::<(SavedView, String)>(conn)?;
+
+    views
+        .into_iter()
+        .map(|(view, display_name)| represent(view, display_name))
+        .collect()
+}
+
+fn find_by_id(id: Uuid, conn: &mut PgConnection) -> Result<Option<SavedView>, DbError> {
+    use crate::schema::saved_views;
+
+    let view = saved_views::table
+        .find(id)
+        .first::<SavedView>(conn)
+        .optional()?;
+
+    Ok(view)
+}
+
+fn with_owner(
+    view: SavedView,
+    conn: &mut PgConnection,
+) -> Result<SavedViewRepresentation, DbError> {
+    use crate::schema::users;
+
+    let display_name = users::table
+        .find(view.owner)
+        .select(users::display_name)
+        .first::<String>(conn)?;
+
+    represent(view, display_name)
+}
+
+fn add_a_view(
+    new_view: NewSavedView,
+    conn: &mut PgConnection,
+) -> Result<SavedViewRepresentation, DbError> {
+    use crate::schema::saved_views;
+
+    let view = diesel::insert_into(saved_views::table)
+        .values(&new_view)
+        .get_result::<SavedView>(conn)?;
+
+    with_owner(view, conn)
+}
+
+fn update_view(
+    id: Uuid,
+    changes: NewSavedView,
+    conn: &mut PgConnection,
+) -> Result<SavedViewRepresentation, DbError> {
+    use crate::schema::saved_views;
+
+    let view = diesel::update(saved_views::table.find(id))
+        .set(&changes)
+        .get_result::<SavedView>(conn)?;
+
+    with_owner(view, conn)
+}
+
+fn delete_view(id: Uuid, conn: &mut PgConnection) -> Result<usize, DbError> {
+    use crate::schema::saved_views;
+
+    let count = diesel::delete(saved_views::table.find(id)).execute(conn)?;
+
+    Ok(count)
+}
@@ -0,0 +1,170 @@
+use super::super::DbPool;
+
+use actix_web::{delete, get, post, web, Error, HttpResponse};
+use diesel::prelude::*;
+use shared::models::{response::Response, users::Role};
+use uuid::Uuid;
+
+use super::tickets::{check_ticket_access, ticket_exists};
+use crate::authentication::CurrentUser;
+use crate::models::{
+    users::User,
+    watchers::{NewTicketWatcher, TicketWatcher, WatcherPayload, WatcherRepresentation},
+    SuccessResponse,
+};
+
+type DbError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Everyone following a ticket
+#[get("/tickets/{id}/watchers")]
+async fn index(
+    id: web::Path<i32>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    check_ticket_access(&pool, *id, &caller).await?;
+
+    let watchers = web::block(move || {
+        let mut conn = pool.get()?;
+        find_by_ticket_id(id.into_inner(), &mut conn)
+    })
+    .await?
+    .map(|x| {
+        x.into_iter()
+            .map(WatcherRepresentation::from)
+            .collect::<Vec<WatcherRepresentation>>()
+    })
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(watchers))
+}
+
+/// Subscribes the caller, or another user when the caller can edit tickets
+#[post("/tickets/{id}/watchers")]
+async fn create(
+    id: web::Path<i32>,
+    payload: Option<web::Json<WatcherPayload>>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    let ticket_id = id.into_inner();
+    check_ticket_access(&pool, ticket_id, &caller).await?;
+
+    let payload = payload.map(|x| x.into_inner()).unwrap_or_default();
+    let watcher_id = payload.user_id.unwrap_or(caller.user_id);
+    if watcher_id != caller.user_id {
+        caller.ensure(Role::can_edit)?;
+    }
+
+    let exists = {
+        let pool = pool.clone();
+        web::block(move || {
+            let mut conn = pool.get()?;
+            ticket_exists(ticket_id, &mut conn)
+        })
+        .await?
+        .map_err(actix_web::error::ErrorInternalServerError)?
+    };
+    if !exists {
+        return Err(actix_web::error::ErrorNotFound("Ticket not found"));
+    }
+
+    let watchers = web::block(move || {
+        let mut conn = pool.get()?;
+        add_watcher(ticket_id, watcher_id, &mut conn)?;
+        find_by_ticket_id(ticket_id, &mut conn)
+    })
+    .await?
+    .map(|x| {
+        x.into_iter()
+            .map(WatcherRepresentation::from)
+            .collect::<Vec<WatcherRepresentation>>()
+    })
+    .map_err(|_| actix_web::error::ErrorBadRequest("Unable to add watcher"))?;
+
+    let response = Response {
+        success: true,
+        message: None,
+        data: Some(watchers),
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Unsubscribes a user, anyone can stop watching but removing others needs edit rights
+#[delete("/tickets/{id}/watchers/{user_id}")]
+async fn delete(
+    path: web::Path<(i32, Uuid)>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    let (ticket_id, watcher_id) = path.into_inner();
+    check_ticket_access(&pool, ticket_id, &caller).await?;
+    if watcher_id != caller.user_id {
+        caller.ensure(Role::can_edit)?;
+    }
+
+    let result = web::block(move || {
+        let mut conn = pool.get()?;
+        remove_watcher(ticket_id, watcher_id, &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let response = if result > 0 {
+        SuccessResponse {
+            success: true,
+            message: "Watcher removed".to_string(),
+        }
+    } else {
+        SuccessResponse {
+            success: false,
+            message: "Not watching this ticket".to_string(),
+        }
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Subscribes a user to a ticket, does nothing if they already watch it.
+/// Used when creating tickets and notes so authors follow up on their own work.
+pub fn add_watcher(id: i32, watcher: Uuid, conn: &mut PgConnection) -> Result<(), DbError> {
+    use crate::schema::ticket_watchers::dsl::*;
+
+    let new_watcher = NewTicketWatcher {
+        ticket_id: id,
+        user_id: watcher,
+        created_at: chrono::Utc::now().naive_utc(),
+    };
+
+    diesel::insert_into(ticket_watchers)
+        .values(&new_watcher)
+        .on_conflict_do_nothing()
+        .execute(conn)?;
+
+    Ok(())
+}
+
+fn remove_watcher(id: i32, watcher: Uuid, conn: &mut PgConnection) -> Result<usize, DbError> {
+    use crate::schema::ticket_watchers::dsl::*;
+
+    let count = diesel::delete(ticket_watchers.find((id, watcher))).execute(conn)?;
+
+    Ok(count)
+}
+
+fn find_by_ticket_id(
+    id: i32,
+    conn: &mut PgConnection,
+) -> Result<Vec<(TicketWatcher, User)>, DbError> {
+    use crate::schema::ticket_watchers::dsl::*;
+    use crate::schema::users::dsl::users;
+
+    let items = ticket_watchers
+        .filter(ticket_id.eq(id))
+        .inner_join(users)
+        .order(created_at.asc())
+        .load::<(TicketWatcher, User)>(conn)?;
+
+    Ok(items)
+}
@@ -0,0 +1,548 @@
+use super::super::DbPool;
+
+use actix_web::{delete, get, post, put, web, Error, HttpResponse};
+use diesel::prelude::*;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use shared::models::{
+    response::Response,
+    users::Role,
+    webhooks::{DeliveryStatus, WebhookEvent},
+    MAX_TITLE_LENGTH,
+};
+use uuid::Uuid;
+
+use super::tickets::find_by_id as find_ticket;
+use crate::authentication::CurrentUser;
+use crate::models::{
+    documents::Document,
+    notes::Note,
+    tickets::{NewTicketEvent, TicketEvent},
+    webhooks::{
+        NewWebhook, NewWebhookDelivery, Webhook, WebhookBody, WebhookDelivery, WebhookPayload,
+        WebhookRepresentation,
+    },
+    SuccessResponse,
+};
+
+type DbError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Deliveries shown in the log of a webhook
+const LOG_LENGTH: i64 = 100;
+
+/// Deliveries sent on each round of the dispatcher
+const BATCH_SIZE: i64 = 50;
+
+/// Attempts before a delivery is given up on, retries wait twice as long each time
+const MAX_ATTEMPTS: i32 = 8;
+const FIRST_RETRY_SECONDS: i64 = 30;
+
+const SECRET_LENGTH: usize = 32;
+
+/// What a delivery is about, loaded when the deliveries are queued
+pub enum WebhookSubject {
+    /// Sent with the events recorded by the change, if any
+    Ticket(i32, Vec<NewTicketEvent>),
+    Note(Uuid),
+    Document(Uuid),
+    /// What is left of a deleted ticket, note or document
+    Deleted(serde_json::Value),
+}
+
+/// Result of sending a delivery, failures carry the HTTP status if there was a response
+pub type Attempt = Result<i32, (Option<i32>, String)>;
+
+#[get("/webhooks")]
+async fn index(pool: web::Data<DbPool>, caller: CurrentUser) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::is_admin)?;
+
+    let hooks = web::block(move || {
+        let mut conn = pool.get()?;
+        find_all(&mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(hooks))
+}
+
+#[post("/webhooks")]
+async fn create(
+    pool: web::Data<DbPool>,
+    payload: web::Json<WebhookPayload>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::is_admin)?;
+
+    let mut payload = payload.into_inner();
+    if let Some(message) = validate(&mut payload) {
+        return Ok(HttpResponse::Ok().json(failure(message)));
+    }
+    if payload.secret.is_none() {
+        payload.secret = Some(generate_secret());
+    }
+
+    let result = web::block(move || {
+        let mut conn = pool.get()?;
+        save_webhook(None, payload, Some(caller.user_id), &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(respond(result)))
+}
+
+#[put("/webhooks/{id}")]
+async fn update(
+    id: web::Path<Uuid>,
+    payload: web::Json<WebhookPayload>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::is_admin)?;
+
+    let mut payload = payload.into_inner();
+    if let Some(message) = validate(&mut payload) {
+        return Ok(HttpResponse::Ok().json(failure(message)));
+    }
+
+    let result = web::block(move || {
+        let mut conn = pool.get()?;
+        save_webhook(Some(id.into_inner()), payload, None, &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(respond(result)))
+}
+
+/// Queued deliveries go with the webhook
+#[delete("/webhooks/{id}")]
+async fn delete(
+    id: web::Path<Uuid>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::is_admin)?;
+
+    let result = web::block(move || {
+        let mut conn = pool.get()?;
+        delete_webhook(id.into_inner(), &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let response = if result > 0 {
+        SuccessResponse {
+            success: true,
+            message: "Webhook deleted".to_string(),
+        }
+    } else {
+        SuccessResponse {
+            success: false,
+            message: "Webhook not found".to_string(),
+        }
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Delivery log of a webhook, latest first
+#[get("/webhooks/{id}/deliveries")]
+async fn deliveries(
+    id: web::Path<Uuid>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::is_admin)?;
+
+    let log = web::block(move || {
+        let mut conn = pool.get()?;
+        find_deliveries(id.into_inner(), &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(log))
+}
+
+/// Queues a delivery again with the same payload, starting over with its retries
+#[post("/webhook_deliveries/{id}/replay")]
+async fn replay(
+    id: web::Path<Uuid>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::is_admin)?;
+
+    let result = web::block(move || {
+        let mut conn = pool.get()?;
+        replay_delivery(id.into_inner(), &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let response = match result {
+        Ok(delivery) => Response {
+            success: true,
+            message: None,
+            data: Some(delivery),
+        },
+        Err(message) => Response {
+            success: false,
+            message: Some(message),
+            data: None,
+        },
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+fn failure(message: String) -> Response<WebhookRepresentation> {
+    Response {
+        success: false,
+        message: Some(message),
+        data: None,
+    }
+}
+
+fn respond(result: Result<WebhookRepresentation, String>) -> Response<WebhookRepresentation> {
+    match result {
+        Ok(hook) => Response {
+            success: true,
+            message: None,
+            data: Some(hook),
+        },
+        Err(message) => failure(message),
+    }
+}
+
+/// Checks the payload and tidies it up, an empty secret means no secret was given
+fn validate(payload: &mut WebhookPayload) -> Option<String> {
+    payload.name = payload.name.trim().to_string();
+    if payload.name.is_empty() {
+        return Some("Webhook name is required".to_string());
+    }
+    if payload.name.len() > MAX_TITLE_LENGTH {
+        return Some(format!(
+            "Webhook name is too long, max length is {}",
+            MAX_TITLE_LENGTH
+        ));
+    }
+
+    payload.url = payload.url.trim().to_string();
+    match reqwest::Url::parse(&payload.url) {
+        Ok(url) if url.scheme() == "http" || url.scheme() == "https" => (),
+        _ => return Some("The URL has to start with http:// or https://".to_string()),
+    }
+
+    payload.secret = payload
+        .secret
+        .as_ref()
+        .map(|secret| secret.trim().to_string())
+        .filter(|secret| !secret.is_empty());
+
+    //Kept in the order of the list, without duplicates
+    payload.events = WebhookEvent::iter()
+        .filter(|event| payload.events.contains(event))
+        .collect();
+    if payload.events.is_empty() {
+        return Some("Pick at least one event".to_string());
+    }
+
+    None
+}
+
+fn generate_secret() -> String {
+    rand::Rng::sample_iter(rand::thread_rng(), &rand::distributions::Alphanumeric)
+        .take(SECRET_LENGTH)
+        .map(char::from)
+        .collect()
+}
+
+/// Hex encoded HMAC-SHA256 of the body, sent as `X-Sumi-Signature: sha256=<signature>`
+pub fn sign(secret: &str, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(body.as_bytes());
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn save_webhook(
+    id: Option<Uuid>,
+    payload: WebhookPayload,
+    creator: Option<Uuid>,
+    conn: &mut PgConnection,
+) -> Result<Result<WebhookRepresentation, String>, DbError> {
+    use crate::schema::webhooks;
+
+    let mut query = webhooks::table
+        .filter(webhooks::name.eq(&payload.name))
+        .into_boxed();
+    if let Some(id) = id {
+        query = query.filter(webhooks::webhook_id.ne(id));
+    }
+    if query.count().get_result::<i64>(conn)? > 0 {
+        return Ok(Err(format!("A webhook named {} already exists", payload.name)));
+    }
+
+    let values = NewWebhook {
+        name: payload.name,
+        url: payload.url,
+        secret: payload.secret,
+        events: serde_json::to_string(&payload.events)?,
+        enabled: payload.enabled,
+        updated_at: chrono::Utc::now().naive_utc(),
+    };
+
+    let saved = match id {
+        Some(id) => diesel::update(webhooks::table.find(id))
+            .set(&values)
+            .get_result::<Webhook>(conn)
+            .optional()?,
+        None => diesel::insert_into(webhooks::table)
+            .values((&values, webhooks::created_by.eq(creator)))
+            .get_result::<Webhook>(conn)
+            .optional()?,
+    };
+
+    match saved {
+        Some(saved) => Ok(Ok(represent(saved)?)),
+        None => Ok(Err("Webhook not found".to_string())),
+    }
+}
+
+fn delete_webhook(id: Uuid, conn: &mut PgConnection) -> Result<usize, DbError> {
+    use crate::schema::webhooks::dsl::*;
+
+    let result = diesel::delete(webhooks.find(id)).execute(conn)?;
+
+    Ok(result)
+}
+
+fn represent(hook: Webhook) -> Result<WebhookRepresentation, DbError> {
+    Ok(WebhookRepresentation {
+        webhook_id: hook.webhook_id,
+        name: hook.name,
+        url: hook.url,
+        secret: hook.secret,
+        events: serde_json::from_str(&hook.events)?,
+        enabled: hook.enabled,
+        created_at: hook.created_at,
+    })
+}
+
+fn find_all(conn: &mut PgConnection) -> Result<Vec<WebhookRepresentation>, DbError> {
+    use crate::schema::webhooks::dsl::*;
+
+    webhooks
+        .order(name.asc())
+        .load::<Webhook>(conn)?
+        .into_iter()
+        .map(represent)
+        .collect()
+}
+
+fn find_deliveries(id: Uuid, conn: &mut PgConnection) -> Result<Vec<WebhookDelivery>, DbError> {
+    use crate::schema::webhook_deliveries::dsl::*;
+
+    let log = webhook_deliveries
+        .filter(webhook_id.eq(id))
+        .order(created_at.desc())
+        .limit(LOG_LENGTH)
+        .load::<WebhookDelivery>(conn)?;
+
+    Ok(log)
+}
+
+fn replay_delivery(
+    id: Uuid,
+    conn: &mut PgConnection,
+) -> Result<Result<WebhookDelivery, String>, DbError> {
+    use crate::schema::webhook_deliveries::dsl::*;
+
+    let Some(delivery) = webhook_deliveries
+        .find(id)
+        .first::<WebhookDelivery>(conn)
+        .optional()?
+    else {
+        return Ok(Err("Delivery not found".to_string()));
+    };
+    if delivery.status == DeliveryStatus::Pending.as_str() {
+        return Ok(Err("The delivery is still queued".to_string()));
+    }
+
+    let delivery = diesel::update(webhook_deliveries.find(id))
+        .set((
+            status.eq(DeliveryStatus::Pending.as_str()),
+            attempts.eq(0),
+            next_attempt_at.eq(Some(chrono::Utc::now().naive_utc())),
+            delivered_at.eq(None::<chrono::NaiveDateTime>),
+        ))
+        .get_result::<WebhookDelivery>(conn)?;
+
+    Ok(Ok(delivery))
+}
+
+/// Queues deliveries of a change for the webhooks subscribed to it, runs in the background
+pub fn publish(pool: DbPool, event: WebhookEvent, subject: WebhookSubject) {
+    actix_rt::spawn(async move {
+        let result = web::block(move || {
+            let mut conn = pool.get()?;
+            queue(event, subject, &mut conn)
+        })
+        .await;
+        match result {
+            Ok(Ok(_)) => (),
+            Ok(Err(e)) => log::error!("Unable to queue {} webhooks: {}", event.as_str(), e),
+            Err(e) => log::error!("Unable to queue {} webhooks: {}", event.as_str(), e),
+        }
+    });
+}
+
+/// Every subscribed webhook gets the same body, the subject is only loaded when there are any
+fn queue(
+    event: WebhookEvent,
+    subject: WebhookSubject,
+    conn: &mut PgConnection,
+) -> Result<usize, DbError> {
+    use crate::schema::{documents, notes, webhook_deliveries, webhooks};
+
+    let subscribed = webhooks::table
+        .filter(webhooks::enabled.eq(true))
+        .load::<Webhook>(conn)?
+        .into_iter()
+        .map(represent)
+        .filter(|hook| {
+            hook.as_ref()
+                .map_or(true, |hook| hook.events.contains(&event))
+        })
+        .collect::<Result<Vec<WebhookRepresentation>, DbError>>()?;
+    if subscribed.is_empty() {
+        return Ok(0);
+    }
+
+    //Subjects changed again or deleted in the meantime are sent as they are now, or not at all
+    let data = match subject {
+        WebhookSubject::Ticket(id, events) => {
+            let Some(ticket) = find_ticket(id, conn)?.into_iter().next() else {
+                return Ok(0);
+            };
+            let events = events
+                .into_iter()
+                .map(TicketEvent::from)
+                .collect::<Vec<TicketEvent>>();
+            serde_json::json!({ "ticket": ticket, "events": events })
+        }
+        WebhookSubject::Note(id) => {
+            let Some(note) = notes::table.find(id).first::<Note>(conn).optional()? else {
+                return Ok(0);
+            };
+            serde_json::json!({ "note": note })
+        }
+        WebhookSubject::Document(id) => {
+            let Some(document) = documents::table
+                .find(id)
+                .first::<Document>(conn)
+                .optional()?
+            else {
+                return Ok(0);
+            };
+            serde_json::json!({ "document": document })
+        }
+        WebhookSubject::Deleted(data) => data,
+    };
+
+    let time = chrono::Utc::now().naive_utc();
+    let body = serde_json::to_string(&WebhookBody {
+        event,
+        created_at: time,
+        data: &data,
+    })?;
+    let queued = subscribed
+        .iter()
+        .map(|hook| NewWebhookDelivery {
+            webhook_id: hook.webhook_id,
+            event: event.as_str().to_string(),
+            payload: body.clone(),
+            next_attempt_at: Some(time),
+            created_at: time,
+        })
+        .collect::<Vec<NewWebhookDelivery>>();
+
+    let result = diesel::insert_into(webhook_deliveries::table)
+        .values(&queued)
+        .execute(conn)?;
+
+    Ok(result)
+}
+
+/// Pending deliveries whose attempt is due, with the webhook to send them to.
+/// Deliveries of disabled webhooks wait until the webhook is enabled again.
+pub fn due_deliveries(
+    conn: &mut PgConnection,
+) -> Result<Vec<(WebhookDelivery, Webhook)>, DbError> {
+    use crate::schema::{webhook_deliveries, webhooks};
+
+    let due = webhook_deliveries::table
+        .inner_join(webhooks::table)
+        .filter(webhook_deliveries::status.eq(DeliveryStatus::Pending.as_str()))
+        .filter(webhook_deliveries::next_attempt_at.le(chrono::Utc::now().naive_utc()))
+        .filter(webhooks::enabled.eq(true))
+        .order(webhook_deliveries::next_attempt_at.asc())
+        .limit(BATCH_SIZE)
+        .load::<(WebhookDelivery, Webhook)>(conn)?;
+
+    Ok(due)
+}
+
+/// Records an attempt, failed deliveries are retried with exponential backoff until
+/// `MAX_ATTEMPTS` is reached
+pub fn record_attempt(id: Uuid, attempt: Attempt, conn: &mut PgConnection) -> Result<(), DbError> {
+    use crate::schema::webhook_deliveries::dsl::*;
+
+    let delivery = webhook_deliveries.find(id).first::<WebhookDelivery>(conn)?;
+    let tries = delivery.attempts + 1;
+    let now = chrono::Utc::now().naive_utc();
+
+    match attempt {
+        Ok(code) => {
+            diesel::update(webhook_deliveries.find(id))
+                .set((
+                    status.eq(DeliveryStatus::Delivered.as_str()),
+                    attempts.eq(tries),
+                    next_attempt_at.eq(None::<chrono::NaiveDateTime>),
+                    response_status.eq(Some(code)),
+                    error.eq(None::<String>),
+                    delivered_at.eq(Some(now)),
+                ))
+                .execute(conn)?;
+        }
+        Err((code, reason)) => {
+            let (new_status, next) = if tries >= MAX_ATTEMPTS {
+                (DeliveryStatus::Failed, None)
+            } else {
+                let delay = FIRST_RETRY_SECONDS << (tries - 1);
+                (
+                    DeliveryStatus::Pending,
+                    Some(now + chrono::Duration::seconds(delay)),
+                )
+            };
+            diesel::update(webhook_deliveries.find(id))
+                .set((
+                    status.eq(new_status.as_str()),
+                    attempts.eq(tries),
+                    next_attempt_at.eq(next),
+                    response_status.eq(code),
+                    error.eq(Some(reason)),
+                ))
+                .execute(conn)?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,640 @@
+use super::super::DbPool;
+
+use std::collections::HashMap;
+
+use actix_web::{delete, get, post, put, web, Error, HttpResponse};
+use diesel::prelude::*;
+use shared::models::{
+    automation::ConditionField, response::Response, users::Role, MAX_TITLE_LENGTH,
+};
+use uuid::Uuid;
+
+use super::automation::rename_in_rules;
+use super::sla::update_sla;
+use crate::authentication::CurrentUser;
+use crate::models::{
+    workflows::{
+        NewStatusTransition, NewTicketStatus, TicketPriority, TicketPriorityPayload, TicketStatus,
+        TicketStatusPayload, TicketStatusRepresentation,
+    },
+    SuccessResponse,
+};
+
+type DbError = Box<dyn std::error::Error + Send + Sync>;
+
+//Everyone who can see tickets needs the list to show, filter and change statuses
+#[get("/statuses")]
+async fn statuses(pool: web::Data<DbPool>, _caller: CurrentUser) -> Result<HttpResponse, Error> {
+    let statuses = web::block(move || {
+        let mut conn = pool.get()?;
+        find_all_statuses(&mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(statuses))
+}
+
+#[post("/statuses")]
+async fn create_status(
+    pool: web::Data<DbPool>,
+    payload: web::Json<TicketStatusPayload>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::is_admin)?;
+
+    let mut payload = payload.into_inner();
+    payload.name = payload.name.trim().to_string();
+    if let Some(message) = validate_name("Status", &payload.name) {
+        return Ok(HttpResponse::Ok().json(failure::<TicketStatusRepresentation>(message)));
+    }
+
+    let status = web::block(move || {
+        let mut conn = pool.get()?;
+        save_status(None, payload, &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(respond(status)))
+}
+
+/// Renaming a status renames it on every ticket and automation rule as well
+#[put("/statuses/{id}")]
+async fn update_status(
+    id: web::Path<Uuid>,
+    payload: web::Json<TicketStatusPayload>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::is_admin)?;
+
+    let mut payload = payload.into_inner();
+    payload.name = payload.name.trim().to_string();
+    if let Some(message) = validate_name("Status", &payload.name) {
+        return Ok(HttpResponse::Ok().json(failure::<TicketStatusRepresentation>(message)));
+    }
+
+    let status = web::block(move || {
+        let mut conn = pool.get()?;
+        save_status(Some(id.into_inner()), payload, &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(respond(status)))
+}
+
+/// Only unused statuses can be deleted, tickets would be left with an unknown status
+#[delete("/statuses/{id}")]
+async fn delete_status(
+    id: web::Path<Uuid>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::is_admin)?;
+
+    let result = web::block(move || {
+        let mut conn = pool.get()?;
+        remove_status(id.into_inner(), &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let response = match result {
+        Ok(()) => SuccessResponse {
+            success: true,
+            message: "Status deleted".to_string(),
+        },
+        Err(message) => SuccessResponse {
+            success: false,
+            message,
+        },
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+#[get("/priorities")]
+async fn priorities(pool: web::Data<DbPool>, _caller: CurrentUser) -> Result<HttpResponse, Error> {
+    let priorities = web::block(move || {
+        let mut conn = pool.get()?;
+        find_all_priorities(&mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(priorities))
+}
+
+#[post("/priorities")]
+async fn create_priority(
+    pool: web::Data<DbPool>,
+    payload: web::Json<TicketPriorityPayload>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::is_admin)?;
+
+    let mut payload = payload.into_inner();
+    payload.name = payload.name.trim().to_string();
+    if let Some(message) = validate_name("Priority", &payload.name) {
+        return Ok(HttpResponse::Ok().json(failure::<TicketPriority>(message)));
+    }
+
+    let priority = web::block(move || {
+        let mut conn = pool.get()?;
+        save_priority(None, payload, &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(respond(priority)))
+}
+
+/// Renaming a priority renames it on every ticket, SLA policy, recurring ticket and automation
+/// rule as well
+#[put("/priorities/{id}")]
+async fn update_priority(
+    id: web::Path<Uuid>,
+    payload: web::Json<TicketPriorityPayload>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::is_admin)?;
+
+    let mut payload = payload.into_inner();
+    payload.name = payload.name.trim().to_string();
+    if let Some(message) = validate_name("Priority", &payload.name) {
+        return Ok(HttpResponse::Ok().json(failure::<TicketPriority>(message)));
+    }
+
+    let priority = web::block(move || {
+        let mut conn = pool.get()?;
+        save_priority(Some(id.into_inner()), payload, &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    Ok(HttpResponse::Ok().json(respond(priority)))
+}
+
+#[delete("/priorities/{id}")]
+async fn delete_priority(
+    id: web::Path<Uuid>,
+    pool: web::Data<DbPool>,
+    caller: CurrentUser,
+) -> Result<HttpResponse, Error> {
+    caller.ensure(Role::is_admin)?;
+
+    let result = web::block(move || {
+        let mut conn = pool.get()?;
+        remove_priority(id.into_inner(), &mut conn)
+    })
+    .await?
+    .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    let response = match result {
+        Ok(()) => SuccessResponse {
+            success: true,
+            message: "Priority deleted".to_string(),
+        },
+        Err(message) => SuccessResponse {
+            success: false,
+            message,
+        },
+    };
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+fn failure<T>(message: String) -> Response<T> {
+    Response {
+        success: false,
+        message: Some(message),
+        data: None,
+    }
+}
+
+fn respond<T>(result: Result<T, String>) -> Response<T> {
+    match result {
+        Ok(item) => Response {
+            success: true,
+            message: None,
+            data: Some(item),
+        },
+        Err(message) => failure(message),
+    }
+}
+
+fn validate_name(kind: &str, name: &str) -> Option<String> {
+    if name.is_empty() {
+        return Some(format!("{} name is required", kind));
+    }
+    if name.len() > MAX_TITLE_LENGTH {
+        return Some(format!(
+            "{} name is too long, max length is {}",
+            kind, MAX_TITLE_LENGTH
+        ));
+    }
+
+    None
+}
+
+/// Checks the status and priority given for a ticket.
+/// A status change from `old_status` has to be one of its transitions, if it has any.
+/// An empty priority means none. The inner error is a message for the user.
+pub fn check_ticket_workflow(
+    old_status: Option<&str>,
+    new_status: Option<&str>,
+    new_priority: Option<&str>,
+    conn: &mut PgConnection,
+) -> Result<Result<(), String>, DbError> {
+    if let Some(new_status) = new_status.filter(|new_status| Some(*new_status) != old_status) {
+        let Some(to) = find_status_by_name(new_status, conn)? else {
+            return Ok(Err(format!("Unknown status: {}", new_status)));
+        };
+        //Tickets may still carry a status that was never configured, those can go anywhere
+        if let Some(from) = old_status
+            .map(|name| find_status_by_name(name, conn))
+            .transpose()?
+            .flatten()
+        {
+            let allowed = find_transitions(from.status_id, conn)?;
+            if !allowed.is_empty() && !allowed.contains(&to.status_id) {
+                return Ok(Err(format!(
+                    "Status cannot change from {} to {}",
+                    from.name, to.name
+                )));
+            }
+        }
+    }
+
+    if let Some(new_priority) = new_priority.filter(|new_priority| !new_priority.is_empty()) {
+        use crate::schema::ticket_priorities::dsl::*;
+
+        let exists = diesel::select(diesel::dsl::exists(
+            ticket_priorities.filter(name.eq(new_priority)),
+        ))
+        .get_result::<bool>(conn)?;
+        if !exists {
+            return Ok(Err(format!("Unknown priority: {}", new_priority)));
+        }
+    }
+
+    Ok(Ok(()))
+}
+
+/// Status given to new tickets
+pub fn default_status(conn: &mut PgConnection) -> Result<String, DbError> {
+    use crate::schema::ticket_statuses::dsl::*;
+
+    let status = ticket_statuses
+        .filter(is_default.eq(true))
+        .select(name)
+        .first::<String>(conn)?;
+
+    Ok(status)
+}
+
+/// First closed status in the list, used when tickets are closed by the system
+pub fn closed_status(conn: &mut PgConnection) -> Result<Option<String>, DbError> {
+    use crate::schema::ticket_statuses::dsl::*;
+
+    let status = ticket_statuses
+        .filter(is_closed.eq(true))
+        .order(position.asc())
+        .select(name)
+        .first::<String>(conn)
+        .optional()?;
+
+    Ok(status)
+}
+
+/// Priority given to tickets opened by mail, empty if there is no default
+pub fn default_priority(conn: &mut PgConnection) -> Result<String, DbError> {
+    use crate::schema::ticket_priorities::dsl::*;
+
+    let priority = ticket_priorities
+        .filter(is_default.eq(true))
+        .select(name)
+        .first::<String>(conn)
+        .optional()?;
+
+    Ok(priority.unwrap_or_default())
+}
+
+fn find_status_by_name(
+    status_name: &str,
+    conn: &mut PgConnection,
+) -> Result<Option<TicketStatus>, DbError> {
+    use crate::schema::ticket_statuses::dsl::*;
+
+    let status = ticket_statuses
+        .filter(name.eq(status_name))
+        .first::<TicketStatus>(conn)
+        .optional()?;
+
+    Ok(status)
+}
+
+fn find_transitions(id: Uuid, conn: &mut PgConnection) -> Result<Vec<Uuid>, DbError> {
+    use crate::schema::status_transitions::dsl::*;
+
+    let items = status_transitions
+        .filter(from_status.eq(id))
+        .select(to_status)
+        .load::<Uuid>(conn)?;
+
+    Ok(items)
+}
+
+fn find_all_statuses(conn: &mut PgConnection) -> Result<Vec<TicketStatusRepresentation>, DbError> {
+    use crate::schema::status_transitions;
+    use crate::schema::ticket_statuses::dsl::*;
+
+    let items = ticket_statuses
+        .order((position.asc(), name.asc()))
+        .load::<TicketStatus>(conn)?;
+
+    let mut transitions: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+    for (from, to) in status_transitions::table
+        .select((
+            status_transitions::from_status,
+            status_transitions::to_status,
+        ))
+        .load::<(Uuid, Uuid)>(conn)?
+    {
+        transitions.entry(from).or_default().push(to);
+    }
+
+    Ok(items
+        .into_iter()
+        .map(|status| {
+            let allowed = transitions.remove(&status.status_id).unwrap_or_default();
+            TicketStatusRepresentation::new(status, allowed)
+        })
+        .collect())
+}
+
+/// Adds a status (no `id`) or updates one, along with its transitions.
+/// Making a status the default takes it away from the previous one.
+fn save_status(
+    id: Option<Uuid>,
+    payload: TicketStatusPayload,
+    conn: &mut PgConnection,
+) -> Result<Result<TicketStatusRepresentation, String>, DbError> {
+    use crate::schema::status_transitions;
+    use crate::schema::ticket_statuses::dsl::*;
+
+    conn.transaction::<_, DbError, _>(|conn| {
+        let mut query = ticket_statuses.filter(name.eq(&payload.name)).into_boxed();
+        if let Some(id) = id {
+            query = query.filter(status_id.ne(id));
+        }
+        if query.count().get_result::<i64>(conn)? > 0 {
+            return Ok(Err("Status name is already in use".to_string()));
+        }
+
+        let known = ticket_statuses
+            .filter(status_id.eq_any(&payload.transitions))
+            .count()
+            .get_result::<i64>(conn)?;
+        if known as usize != payload.transitions.len() {
+            return Ok(Err("Unknown status in transitions".to_string()));
+        }
+
+        let old = match id {
+            Some(id) => match ticket_statuses
+                .find(id)
+                .first::<TicketStatus>(conn)
+                .optional()?
+            {
+                Some(old) => Some(old),
+                None => return Ok(Err("Status not found".to_string())),
+            },
+            None => None,
+        };
+        if old.as_ref().is_some_and(|old| old.is_default) && !payload.is_default {
+            return Ok(Err(
+                "One status has to be the default, make another status the default instead"
+                    .to_string(),
+            ));
+        }
+
+        if payload.is_default {
+            diesel::update(ticket_statuses.filter(is_default.eq(true)))
+                .set(is_default.eq(false))
+                .execute(conn)?;
+        }
+
+        let row = NewTicketStatus::from(&payload);
+        let status = match &old {
+            Some(old) => {
+                if old.name != payload.name {
+                    use crate::schema::tickets;
+
+                    diesel::update(tickets::table.filter(tickets::status.eq(&old.name)))
+                        .set(tickets::status.eq(&payload.name))
+                        .execute(conn)?;
+                    rename_in_rules(ConditionField::Status, &old.name, &payload.name, conn)?;
+                }
+                diesel::update(ticket_statuses.find(old.status_id))
+                    .set(&row)
+                    .get_result::<TicketStatus>(conn)?
+            }
+            None => diesel::insert_into(ticket_statuses)
+                .values(&row)
+                .get_result::<TicketStatus>(conn)?,
+        };
+
+        //SLA clocks of tickets in the status stop or resume right away
+        let sla_changed = old.as_ref().is_some_and(|old| {
+            old.pauses_sla != status.pauses_sla || old.is_closed != status.is_closed
+        });
+        if sla_changed {
+            use crate::schema::tickets;
+
+            let ids = tickets::table
+                .filter(tickets::status.eq(&status.name))
+                .select(tickets::ticket_id)
+                .load::<i32>(conn)?;
+            for id in ids {
+                update_sla(id, conn)?;
+            }
+        }
+
+        diesel::delete(
+            status_transitions::table.filter(status_transitions::from_status.eq(status.status_id)),
+        )
+        .execute(conn)?;
+        let transitions = payload
+            .transitions
+            .iter()
+            .filter(|to| **to != status.status_id)
+            .map(|to| NewStatusTransition {
+                from_status: status.status_id,
+                to_status: *to,
+            })
+            .collect::<Vec<NewStatusTransition>>();
+        diesel::insert_into(status_transitions::table)
+            .values(&transitions)
+            .on_conflict_do_nothing()
+            .execute(conn)?;
+
+        let allowed = transitions
+            .iter()
+            .map(|transition| transition.to_status)
+            .collect();
+        Ok(Ok(TicketStatusRepresentation::new(status, allowed)))
+    })
+}
+
+fn remove_status(id: Uuid, conn: &mut PgConnection) -> Result<Result<(), String>, DbError> {
+    use crate::schema::ticket_statuses::dsl::*;
+    use crate::schema::tickets;
+
+    let Some(status) = ticket_statuses
+        .find(id)
+        .first::<TicketStatus>(conn)
+        .optional()?
+    else {
+        return Ok(Err("Status not found".to_string()));
+    };
+    if status.is_default {
+        return Ok(Err("The default status cannot be deleted".to_string()));
+    }
+    let in_use = tickets::table
+        .filter(tickets::status.eq(&status.name))
+        .count()
+        .get_result::<i64>(conn)?;
+    if in_use > 0 {
+        return Ok(Err(format!("Status is used by {} tickets", in_use)));
+    }
+
+    diesel::delete(ticket_statuses.find(id)).execute(conn)?;
+
+    Ok(Ok(()))
+}
+
+fn find_all_priorities(conn: &mut PgConnection) -> Result<Vec<TicketPriority>, DbError> {
+    use crate::schema::ticket_priorities::dsl::*;
+
+    let items = ticket_priorities
+        .order((rank.asc(), name.asc()))
+        .load::<TicketPriority>(conn)?;
+
+    Ok(items)
+}
+
+/// Adds a priority (no `id`) or updates one.
+/// Making a priority the default takes it away from the previous one.
+fn save_priority(
+    id: Option<Uuid>,
+    payload: TicketPriorityPayload,
+    conn: &mut PgConnection,
+) -> Result<Result<TicketPriority, String>, DbError> {
+    use crate::schema::ticket_priorities::dsl::*;
+
+    conn.transaction::<_, DbError, _>(|conn| {
+        let mut query = ticket_priorities
+            .filter(name.eq(&payload.name))
+            .into_boxed();
+        if let Some(id) = id {
+            query = query.filter(priority_id.ne(id));
+        }
+        if query.count().get_result::<i64>(conn)? > 0 {
+            return Ok(Err("Priority name is already in use".to_string()));
+        }
+
+        let old = match id {
+            Some(id) => match ticket_priorities
+                .find(id)
+                .first::<TicketPriority>(conn)
+                .optional()?
+            {
+                Some(old) => Some(old),
+                None => return Ok(Err("Priority not found".to_string())),
+            },
+            None => None,
+        };
+
+        if payload.is_default {
+            diesel::update(ticket_priorities.filter(is_default.eq(true)))
+                .set(is_default.eq(false))
+                .execute(conn)?;
+        }
+
+        let priority = match &old {
+            Some(old) => {
+                if old.name != payload.name {
+                    use crate::schema::{recurring_tickets, sla_policies, tickets};
+
+                    diesel::update(tickets::table.filter(tickets::priority.eq(&old.name)))
+                        .set(tickets::priority.eq(&payload.name))
+                        .execute(conn)?;
+                    diesel::update(
+                        sla_policies::table.filter(sla_policies::priority.eq(&old.name)),
+                    )
+                    .set(sla_policies::priority.eq(&payload.name))
+                    .execute(conn)?;
+                    diesel::update(
+                        recurring_tickets::table.filter(recurring_tickets::priority.eq(&old.name)),
+                    )
+                    .set(recurring_tickets::priority.eq(&payload.name))
+                    .execute(conn)?;
+                    rename_in_rules(ConditionField::Priority, &old.name, &payload.name, conn)?;
+                }
+                diesel::update(ticket_priorities.find(old.priority_id))
+                    .set(&payload)
+                    .get_result::<TicketPriority>(conn)?
+            }
+            None => diesel::insert_into(ticket_priorities)
+                .values(&payload)
+                .get_result::<TicketPriority>(conn)?,
+        };
+
+        Ok(Ok(priority))
+    })
+}
+
+fn remove_priority(id: Uuid, conn: &mut PgConnection) -> Result<Result<(), String>, DbError> {
+    use crate::schema::ticket_priorities::dsl::*;
+    use crate::schema::{recurring_tickets, sla_policies, tickets};
+
+    let Some(priority) = ticket_priorities
+        .find(id)
+        .first::<TicketPriority>(conn)
+        .optional()?
+    else {
+        return Ok(Err("Priority not found".to_string()));
+    };
+    let in_use = tickets::table
+        .filter(tickets::priority.eq(&priority.name))
+        .count()
+        .get_result::<i64>(conn)?;
+    if in_use > 0 {
+        return Ok(Err(format!("Priority is used by {} tickets", in_use)));
+    }
+    //A policy without its priority would suddenly apply to every priority
+    let policies = sla_policies::table
+        .filter(sla_policies::priority.eq(&priority.name))
+        .count()
+        .get_result::<i64>(conn)?;
+    if policies > 0 {
+        return Ok(Err(format!("Priority is used by {} SLA policies", policies)));
+    }
+    let recurring = recurring_tickets::table
+        .filter(recurring_tickets::priority.eq(&priority.name))
+        .count()
+        .get_result::<i64>(conn)?;
+    if recurring > 0 {
+        return Ok(Err(format!(
+            "Priority is used by {} recurring tickets",
+            recurring
+        )));
+    }
+
+    diesel::delete(ticket_priorities.find(id)).execute(conn)?;
+
+    Ok(Ok(()))
+}
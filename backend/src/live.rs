@@ -0,0 +1,49 @@
+//! Server push for live updates.
+//!
+//! Clients keep a `/live` event stream open, handlers call [`Broadcaster::broadcast`]
+//! after a change so open tickets, lists and documents can refresh.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use actix_web_lab::sse::{self, ChannelStream, Sse};
+use shared::models::live::LiveEvent;
+
+/// Messages are dropped for clients that fall this far behind
+const CLIENT_BUFFER: usize = 32;
+const KEEP_ALIVE_SECONDS: u64 = 15;
+
+#[derive(Default)]
+pub struct Broadcaster {
+    clients: Mutex<Vec<sse::Sender>>,
+}
+
+impl Broadcaster {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Opens a new event stream for a client
+    pub fn subscribe(&self) -> Sse<ChannelStream> {
+        let (sender, stream) = sse::channel(CLIENT_BUFFER);
+        self.clients.lock().unwrap().push(sender);
+
+        stream.with_keep_alive(Duration::from_secs(KEEP_ALIVE_SECONDS))
+    }
+
+    /// Sends an event to every connected client and forgets the ones that disconnected.
+    /// Does not block, so it can be called from inside `web::block` as well.
+    pub fn broadcast(&self, event: LiveEvent) {
+        let data = match sse::Data::new_json(&event) {
+            Ok(data) => data,
+            Err(e) => return log::error!("Unable to serialize live event: {}", e),
+        };
+
+        self.clients.lock().unwrap().retain(|client| {
+            !matches!(
+                client.try_send(data.clone()),
+                Err(sse::TrySendError::Closed(_))
+            )
+        });
+    }
+}
@@ -0,0 +1,486 @@
+use super::super::DbPool;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use actix_web::web;
+use diesel::prelude::*;
+use mail_parser::{MessageParser, MimeHeaders};
+use shared::models::{
+    attachments::{format_size, is_image, ATTACHMENT_URL_SCHEME},
+    automation::AutomationTrigger,
+    live::LiveEvent,
+    notifications::NOTE_ADDED,
+    webhooks::WebhookEvent,
+    MAX_TITLE_LENGTH,
+};
+use uuid::Uuid;
+
+use super::{notify, Mailer};
+use crate::handlers::attachments::{check_file, new_attachment, save_attachment, AttachmentOwner};
+use crate::handlers::automation::automate;
+use crate::handlers::notifications::notify_followers;
+use crate::handlers::workflows::{default_priority, default_status};
+use crate::handlers::sla::update_sla;
+use crate::handlers::webhooks::{publish, WebhookSubject};
+use crate::live::Broadcaster;
+use crate::models::{
+    contacts::{Contact, NewContact},
+    notes::NewNote,
+    tickets::{NewTicket, Ticket},
+};
+use crate::storage::Storage;
+use crate::utils::sanitize_filename;
+
+type DbError = Box<dyn std::error::Error + Send + Sync>;
+
+const DEFAULT_POLL_SECONDS: u64 = 60;
+
+define_sql_function!(fn lower(x: diesel::sql_types::Text) -> diesel::sql_types::Text);
+
+/// Inbound settings, the poller only runs when MAIL_INBOUND_DIR is set
+#[derive(Clone, Debug)]
+struct InboundConfig {
+    /// Maildir root, messages are read from `new/` and moved to `cur/` or `failed/`
+    maildir: PathBuf,
+    interval: Duration,
+}
+
+impl InboundConfig {
+    fn from_env() -> Option<Self> {
+        let maildir = std::env::var("MAIL_INBOUND_DIR").ok()?;
+        let seconds = std::env::var("MAIL_POLL_INTERVAL")
+            .ok()
+            .and_then(|value| value.parse::<u64>().ok())
+            .unwrap_or(DEFAULT_POLL_SECONDS);
+
+        Some(Self {
+            maildir: PathBuf::from(maildir),
+            interval: Duration::from_secs(seconds.max(1)),
+        })
+    }
+}
+
+struct InboundMessage {
+    from_email: String,
+    from_name: Option<String>,
+    subject: String,
+    body: String,
+    attachments: Vec<InboundAttachment>,
+}
+
+struct InboundAttachment {
+    /// Picked up front so the text of the ticket or note can link to the attachment
+    attachment_id: Uuid,
+    filename: String,
+    contents: Vec<u8>,
+    /// The type of the file, or why it was not stored
+    mime_type: Result<String, String>,
+}
+
+/// Starts polling the configured Maildir in the background
+pub fn spawn_inbound_poller(
+    pool: DbPool,
+    mailer: Mailer,
+    broadcaster: web::Data<Broadcaster>,
+    storage: Arc<dyn Storage>,
+) {
+    let config = match InboundConfig::from_env() {
+        Some(config) => config,
+        None => {
+            log::info!("MAIL_INBOUND_DIR not set, inbound e-mail is disabled");
+            return;
+        }
+    };
+
+    log::info!("Polling {} for inbound e-mail", config.maildir.display());
+
+    actix_rt::spawn(async move {
+        let mut interval = actix_rt::time::interval(config.interval);
+        loop {
+            interval.tick().await;
+
+            let poll_pool = pool.clone();
+            let config = config.clone();
+            let storage = storage.clone();
+            match web::block(move || poll_maildir(&poll_pool, &config, storage.as_ref())).await {
+                Ok(Ok(imported)) => {
+                    for (ticket_id, reply) in imported {
+                        match reply {
+                            Some(note_id) => {
+                                broadcaster.broadcast(LiveEvent::NotesChanged {
+                                    ticket_id,
+                                    user_id: None,
+                                });
+                                publish(
+                                    pool.clone(),
+                                    WebhookEvent::NoteCreated,
+                                    WebhookSubject::Note(note_id),
+                                );
+                                notify::new_note(pool.clone(), mailer.clone(), note_id);
+                            }
+                            None => {
+                                broadcaster.broadcast(LiveEvent::TicketChanged {
+                                    ticket_id,
+                                    user_id: None,
+                                });
+                                publish(
+                                    pool.clone(),
+                                    WebhookEvent::TicketCreated,
+                                    WebhookSubject::Ticket(ticket_id, vec![]),
+                                );
+                                let triggers = vec![AutomationTrigger::TicketCreated];
+                                automate(&pool, &mailer, &broadcaster, ticket_id, triggers).await;
+                            }
+                        }
+                    }
+                }
+                Ok(Err(e)) => log::error!("Inbound e-mail poll failed: {}", e),
+                Err(e) => log::error!("Inbound e-mail poll failed: {}", e),
+            }
+        }
+    });
+}
+
+/// Imports everything waiting in `new/`, returns the tickets and the notes added as replies
+fn poll_maildir(
+    pool: &DbPool,
+    config: &InboundConfig,
+    storage: &dyn Storage,
+) -> Result<Vec<(i32, Option<Uuid>)>, DbError> {
+    let new_dir = config.maildir.join("new");
+    let cur_dir = config.maildir.join("cur");
+    let failed_dir = config.maildir.join("failed");
+    fs::create_dir_all(&cur_dir)?;
+    fs::create_dir_all(&failed_dir)?;
+
+    //Maildir file names start with the delivery time, so sorting keeps threads in order
+    let mut paths: Vec<PathBuf> = fs::read_dir(&new_dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .collect();
+    paths.sort();
+
+    let mut imported = Vec::new();
+    for path in paths {
+        let file_name = match path.file_name() {
+            Some(name) => name.to_string_lossy().to_string(),
+            None => continue,
+        };
+
+        match ingest_file(pool, storage, &path) {
+            Ok((ticket_id, reply)) => {
+                log::info!("Imported e-mail {} into ticket #{}", file_name, ticket_id);
+                imported.push((ticket_id, reply));
+                fs::rename(&path, cur_dir.join(format!("{}:2,S", file_name)))?;
+            }
+            Err(e) => {
+                log::error!("Unable to import e-mail {}: {}", file_name, e);
+                fs::rename(&path, failed_dir.join(&file_name))?;
+            }
+        }
+    }
+
+    Ok(imported)
+}
+
+/// Imports one message, returns the ticket it was filed under and the note for replies
+fn ingest_file(
+    pool: &DbPool,
+    storage: &dyn Storage,
+    path: &Path,
+) -> Result<(i32, Option<Uuid>), DbError> {
+    let raw = fs::read(path)?;
+    let message = parse_message(&raw)?;
+
+    let mut conn = pool.get()?;
+    conn.transaction::<_, DbError, _>(|conn| {
+        let contact = find_or_create_contact(&message, conn)?;
+        let text = compose_text(&message);
+
+        //Mail from anyone else referencing a ticket opens a new ticket for the sender
+        let existing = match ticket_reference(&message.subject) {
+            Some(id) => find_ticket(id, &contact, conn)?,
+            None => None,
+        };
+
+        let (ticket_id, reply) = match existing {
+            Some(ticket) => {
+                let note_id = add_reply(&ticket, &contact, &text, conn)?;
+                (ticket.ticket_id, Some(note_id))
+            }
+            None => (add_ticket(&message, &contact, &text, conn)?, None),
+        };
+
+        let owner = match reply {
+            Some(note_id) => AttachmentOwner::Note(ticket_id, note_id),
+            None => AttachmentOwner::Ticket(ticket_id),
+        };
+        save_attachments(storage, owner, &message.attachments, conn)?;
+
+        Ok((ticket_id, reply))
+    })
+}
+
+fn parse_message(raw: &[u8]) -> Result<InboundMessage, DbError> {
+    let message = MessageParser::default()
+        .parse(raw)
+        .ok_or("Message could not be parsed")?;
+
+    let sender = message
+        .from()
+        .and_then(|from| from.first())
+        .ok_or("Message has no sender")?;
+    let from_email = sender
+        .address()
+        .ok_or("Sender has no address")?
+        .trim()
+        .to_lowercase();
+
+    let attachments = message
+        .attachments()
+        .map(|part| {
+            let filename = sanitize_filename(part.attachment_name().unwrap_or("attachment"));
+            let contents = part.contents().to_vec();
+            InboundAttachment {
+                attachment_id: Uuid::new_v4(),
+                mime_type: check_file(&filename, contents.len()),
+                filename,
+                contents,
+            }
+        })
+        .collect();
+
+    Ok(InboundMessage {
+        from_email,
+        from_name: sender.name().map(|name| name.trim().to_string()),
+        subject: message.subject().unwrap_or_default().trim().to_string(),
+        //Quoted history is kept as-is, "> " lines render as block quotes in markdown
+        body: message
+            .body_text(0)
+            .map(|body| body.to_string())
+            .unwrap_or_default(),
+        attachments,
+    })
+}
+
+/// Finds the ticket number in subjects like "Re: [#42] Printer is jammed"
+fn ticket_reference(subject: &str) -> Option<i32> {
+    let start = subject.find("[#")? + 2;
+    let end = subject[start..].find(']')? + start;
+    subject[start..end].trim().parse().ok()
+}
+
+fn compose_text(message: &InboundMessage) -> String {
+    let mut text = message.body.trim_end().to_string();
+
+    if !message.attachments.is_empty() {
+        text.push_str("\n\n**Attachments:**\n");
+        for attachment in &message.attachments {
+            let size = format_size(attachment.contents.len() as i64);
+            let line = match &attachment.mime_type {
+                Ok(mime_type) if is_image(mime_type) => format!(
+                    "- {} ({})\n\n  ![{}]({}{})\n",
+                    attachment.filename,
+                    size,
+                    attachment.filename,
+                    ATTACHMENT_URL_SCHEME,
+                    attachment.attachment_id
+                ),
+                Ok(_) => format!(
+                    "- [{}]({}{}) ({})\n",
+                    attachment.filename, ATTACHMENT_URL_SCHEME, attachment.attachment_id, size
+                ),
+                Err(reason) => format!(
+                    "- {} ({}, not saved: {})\n",
+                    attachment.filename, size, reason
+                ),
+            };
+            text.push_str(&line);
+        }
+    }
+
+    text
+}
+
+fn truncate_title(subject: &str) -> String {
+    if subject.is_empty() {
+        return "(No subject)".to_string();
+    }
+
+    let mut end = subject.len().min(MAX_TITLE_LENGTH);
+    while !subject.is_char_boundary(end) {
+        end -= 1;
+    }
+    subject[..end].to_string()
+}
+
+/// Files that did not pass the size and type checks are only listed in the text
+fn save_attachments(
+    storage: &dyn Storage,
+    owner: AttachmentOwner,
+    attachments: &[InboundAttachment],
+    conn: &mut PgConnection,
+) -> Result<(), DbError> {
+    for attachment in attachments {
+        if let Ok(mime_type) = &attachment.mime_type {
+            let new_attachment = new_attachment(
+                attachment.attachment_id,
+                owner,
+                attachment.filename.clone(),
+                mime_type.clone(),
+                attachment.contents.len(),
+                None,
+            );
+            save_attachment(storage, new_attachment, &attachment.contents, conn)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn find_or_create_contact(
+    message: &InboundMessage,
+    conn: &mut PgConnection,
+) -> Result<Contact, DbError> {
+    use crate::schema::contacts::dsl::*;
+
+    let existing = contacts
+        .filter(lower(email).eq(&message.from_email))
+        .first::<Contact>(conn)
+        .optional()?;
+    if let Some(contact) = existing {
+        return Ok(contact);
+    }
+
+    let name = match &message.from_name {
+        Some(name) if !name.is_empty() => name.as_str(),
+        _ => message.from_email.as_str(),
+    };
+    let new_contact = NewContact {
+        display_name: name,
+        email: &message.from_email,
+    };
+
+    let contact = diesel::insert_into(contacts)
+        .values(&new_contact)
+        .get_result(conn)?;
+
+    Ok(contact)
+}
+
+/// The ticket a reply from `sender` is added to, replies to a merged ticket go to the
+/// ticket it was merged into. Only the contact of a ticket along the way or a user may
+/// reply.
+fn find_ticket(
+    id: i32,
+    sender: &Contact,
+    conn: &mut PgConnection,
+) -> Result<Option<Ticket>, DbError> {
+    use crate::schema::tickets::dsl::*;
+
+    let mut ticket = tickets.find(id).first::<Ticket>(conn).optional()?;
+    let mut allowed = false;
+    while let Some(current) = &ticket {
+        allowed |= current.contact == Some(sender.contact_id);
+        match current.merged_into {
+            Some(merged) => ticket = tickets.find(merged).first::<Ticket>(conn).optional()?,
+            None => break,
+        }
+    }
+
+    if ticket.is_none() || allowed || is_user(&sender.email, conn)? {
+        return Ok(ticket);
+    }
+
+    log::warn!(
+        "{} is not the requester of ticket #{}, filing the e-mail as a new ticket",
+        sender.email,
+        id
+    );
+    Ok(None)
+}
+
+fn is_user(address: &str, conn: &mut PgConnection) -> Result<bool, DbError> {
+    use crate::schema::users::dsl::*;
+
+    let count: i64 = users
+        .filter(lower(email).eq(address.to_lowercase()))
+        .count()
+        .get_result(conn)?;
+
+    Ok(count > 0)
+}
+
+fn add_ticket(
+    message: &InboundMessage,
+    sender: &Contact,
+    text: &str,
+    conn: &mut PgConnection,
+) -> Result<i32, DbError> {
+    use crate::schema::tickets::dsl::*;
+
+    let time = chrono::Utc::now().naive_utc();
+    let new_ticket = NewTicket {
+        title: truncate_title(&message.subject),
+        assignee: None,
+        contact: Some(sender.contact_id),
+        description: text.to_string(),
+        created_at: time,
+        updated_at: time,
+        due_date: None,
+        priority: default_priority(conn)?,
+        status: default_status(conn)?,
+        created_by: None,
+        updated_by: None,
+        revision: time,
+        revision_by: None,
+        project: None,
+        project_number: None,
+    };
+
+    let result: Ticket = diesel::insert_into(tickets)
+        .values(&new_ticket)
+        .get_result(conn)?;
+    update_sla(result.ticket_id, conn)?;
+
+    Ok(result.ticket_id)
+}
+
+fn add_reply(
+    parent: &Ticket,
+    sender: &Contact,
+    body: &str,
+    conn: &mut PgConnection,
+) -> Result<Uuid, DbError> {
+    use crate::schema::notes::dsl::*;
+
+    let new_note = NewNote {
+        note_id: Uuid::new_v4(),
+        ticket: parent.ticket_id,
+        owner: None,
+        text: body,
+        time: 0,
+        created_at: chrono::Utc::now().naive_utc(),
+        contact: Some(sender.contact_id),
+        is_public: true,
+    };
+
+    diesel::insert_into(notes).values(&new_note).execute(conn)?;
+    notify_followers(
+        parent.ticket_id,
+        None,
+        &[(NOTE_ADDED.to_string(), new_note.note_id.to_string())],
+        conn,
+    )?;
+
+    use crate::schema::tickets::dsl::*;
+    diesel::update(tickets.find(parent.ticket_id))
+        .set(crate::schema::tickets::updated_at.eq(new_note.created_at))
+        .execute(conn)?;
+
+    Ok(new_note.note_id)
+}
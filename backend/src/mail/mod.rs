@@ -0,0 +1,11 @@
+//! E-mail integration.
+//!
+//! Inbound messages are picked up from a Maildir and turned into tickets or notes,
+//! outbound notifications tell assignees, watchers and contacts about changes to their tickets.
+
+mod inbound;
+pub mod notify;
+mod outbound;
+
+pub use inbound::spawn_inbound_poller;
+pub use outbound::Mailer;
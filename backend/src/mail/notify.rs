@@ -0,0 +1,361 @@
+use super::super::DbPool;
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use actix_web::web;
+use diesel::prelude::*;
+use lettre::message::Mailbox;
+use shared::models::links::parse_link_event_data;
+use shared::models::tickets::{parse_tagged_event_data, TicketEventType};
+use shared::models::users::Role;
+use uuid::Uuid;
+
+use super::Mailer;
+use crate::models::{
+    contacts::Contact,
+    notes::Note,
+    tickets::{NewTicketEvent, Ticket},
+};
+
+type DbError = Box<dyn std::error::Error + Send + Sync>;
+
+const SUBJECT_TEMPLATE: &str = "[#{ticket_id}] {title}";
+
+const EVENTS_TEMPLATE: &str = "{actor} updated ticket #{ticket_id}: {title}
+
+{changes}
+
+View the ticket: {url}
+
+Keep [#{ticket_id}] in the subject when replying.
+";
+
+const NOTE_TEMPLATE: &str = "{actor} added a note to ticket #{ticket_id}: {title}
+
+{text}
+
+View the ticket: {url}
+
+Keep [#{ticket_id}] in the subject when replying.
+";
+
+/// Whoever caused the notification, they are never e-mailed about their own change
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Actor {
+    User(Uuid),
+    Contact(Uuid),
+    /// Changes made by automation rules
+    Automation,
+}
+
+/// Everything needed to address and render notifications for one ticket
+struct TicketContext {
+    ticket: Ticket,
+    recipients: Vec<Mailbox>,
+    /// The ticket's contact, their links point to the requester portal
+    requester: Option<Mailbox>,
+    user_names: HashMap<Uuid, String>,
+    actor_name: String,
+}
+
+/// Sends the events recorded by a ticket update, runs in the background
+pub fn ticket_events(
+    pool: DbPool,
+    mailer: Mailer,
+    ticket_id: i32,
+    events: Vec<NewTicketEvent>,
+    actor: Actor,
+) {
+    if !mailer.is_enabled() || events.is_empty() {
+        return;
+    }
+
+    actix_rt::spawn(async move {
+        let context = match web::block(move || {
+            let mut conn = pool.get()?;
+            load_context(ticket_id, actor, false, &mut conn)
+        })
+        .await
+        {
+            Ok(Ok(context)) => context,
+            Ok(Err(e)) => return log::error!("Unable to prepare notifications: {}", e),
+            Err(e) => return log::error!("Unable to prepare notifications: {}", e),
+        };
+
+        let changes = events
+            .iter()
+            .map(|event| format!("- {}", describe_event(event, &context.user_names)))
+            .collect::<Vec<String>>()
+            .join("\n");
+        let ticket_id = context.ticket.ticket_id.to_string();
+        let url = format!("{}/ticket/{}", mailer.base_url, ticket_id);
+        let values = [
+            ("actor", context.actor_name.as_str()),
+            ("ticket_id", ticket_id.as_str()),
+            ("title", context.ticket.title.as_str()),
+            ("changes", changes.as_str()),
+            ("url", url.as_str()),
+        ];
+
+        deliver(&mailer, &context, &values, EVENTS_TEMPLATE).await;
+    });
+}
+
+/// Sends a newly added note to everyone involved in the ticket, runs in the background.
+/// Internal notes only go to agents.
+pub fn new_note(pool: DbPool, mailer: Mailer, note_id: Uuid) {
+    if !mailer.is_enabled() {
+        return;
+    }
+
+    actix_rt::spawn(async move {
+        let loaded = web::block(move || {
+            let mut conn = pool.get()?;
+            let note = get_note(note_id, &mut conn)?;
+            let actor = match (note.owner, note.contact) {
+                (_, Some(contact)) => Actor::Contact(contact),
+                (Some(owner), None) => Actor::User(owner),
+                //Only automation rules add notes without an author
+                (None, None) => Actor::Automation,
+            };
+            let context = load_context(note.ticket, actor, !note.is_public, &mut conn)?;
+            Ok::<_, DbError>((note, context))
+        })
+        .await;
+        let (note, context) = match loaded {
+            Ok(Ok(loaded)) => loaded,
+            Ok(Err(e)) => return log::error!("Unable to prepare notifications: {}", e),
+            Err(e) => return log::error!("Unable to prepare notifications: {}", e),
+        };
+
+        let ticket_id = context.ticket.ticket_id.to_string();
+        let url = format!("{}/ticket/{}", mailer.base_url, ticket_id);
+        let values = [
+            ("actor", context.actor_name.as_str()),
+            ("ticket_id", ticket_id.as_str()),
+            ("title", context.ticket.title.as_str()),
+            ("text", note.text.as_str()),
+            ("url", url.as_str()),
+        ];
+
+        deliver(&mailer, &context, &values, NOTE_TEMPLATE).await;
+    });
+}
+
+async fn deliver(
+    mailer: &Mailer,
+    context: &TicketContext,
+    values: &[(&str, &str)],
+    template: &str,
+) {
+    let subject = render(SUBJECT_TEMPLATE, values);
+    let body = render(template, values);
+
+    for recipient in &context.recipients {
+        if let Err(e) = mailer.send(recipient.clone(), &subject, body.clone()).await {
+            log::error!("Unable to send notification to {}: {}", recipient, e);
+        }
+    }
+
+    if let Some(requester) = &context.requester {
+        let url = format!(
+            "{}/portal/ticket/{}",
+            mailer.base_url, context.ticket.ticket_id
+        );
+        let values: Vec<(&str, &str)> = values
+            .iter()
+            .map(|&(key, value)| match key {
+                "url" => (key, url.as_str()),
+                _ => (key, value),
+            })
+            .collect();
+        let body = render(template, &values);
+        if let Err(e) = mailer.send(requester.clone(), &subject, body).await {
+            log::error!("Unable to send notification to {}: {}", requester, e);
+        }
+    }
+}
+
+/// Replaces `{key}` placeholders in a single pass, so values are never expanded again
+fn render(template: &str, values: &[(&str, &str)]) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let value = after.find('}').and_then(|end| {
+            values
+                .iter()
+                .find(|(key, _)| *key == &after[..end])
+                .map(|(_, value)| (end, *value))
+        });
+        match value {
+            Some((end, value)) => {
+                output.push_str(value);
+                rest = &after[end + 1..];
+            }
+            None => {
+                output.push('{');
+                rest = after;
+            }
+        }
+    }
+    output.push_str(rest);
+
+    output
+}
+
+fn describe_event(event: &NewTicketEvent, user_names: &HashMap<Uuid, String>) -> String {
+    let data = event.event_data.as_str();
+    match TicketEventType::from_str(&event.event_type) {
+        Ok(TicketEventType::Assigned) => match Uuid::parse_str(data) {
+            Ok(id) => format!(
+                "Assigned to {}",
+                user_names.get(&id).map(String::as_str).unwrap_or("Unknown")
+            ),
+            Err(_) => "Unassigned".to_string(),
+        },
+        Ok(TicketEventType::StatusUpdated) => format!("Status changed to {}", data),
+        Ok(TicketEventType::PriorityUpdated) => format!("Priority changed to {}", data),
+        Ok(TicketEventType::TitleUpdated) => format!("Title changed to {}", data),
+        Ok(TicketEventType::DueDateUpdated) if data.is_empty() => "Due date removed".to_string(),
+        Ok(TicketEventType::DueDateUpdated) => format!("Due date changed to {}", data),
+        Ok(TicketEventType::Tagged) => match parse_tagged_event_data(data) {
+            (true, tag) => format!("Tag {} added", tag),
+            (false, tag) => format!("Tag {} removed", tag),
+        },
+        Ok(TicketEventType::SlaBreached) => format!("SLA {} target missed", data),
+        Ok(TicketEventType::TaskCompleted) => format!("Task completed: {}", data),
+        Ok(TicketEventType::TaskReopened) => format!("Task reopened: {}", data),
+        Ok(TicketEventType::Linked) => match parse_link_event_data(data) {
+            Some((link_type, other)) => format!("Linked: {} #{}", link_type.label(), other),
+            None => format!("Linked: {}", data),
+        },
+        Ok(TicketEventType::Unlinked) => match parse_link_event_data(data) {
+            Some((link_type, other)) => format!("Link removed: {} #{}", link_type.label(), other),
+            None => format!("Link removed: {}", data),
+        },
+        Ok(TicketEventType::ClosedAsDuplicate) => format!("Closed as a duplicate of #{}", data),
+        Ok(TicketEventType::Merged) => format!("Ticket #{} was merged into this ticket", data),
+        Ok(TicketEventType::MergedInto) => format!("Merged into #{}", data),
+        Err(_) => format!("{} {}", event.event_type, data),
+    }
+}
+
+/// With `internal` set, the requester and users with the requester role are left out
+fn load_context(
+    id: i32,
+    actor: Actor,
+    internal: bool,
+    conn: &mut PgConnection,
+) -> Result<TicketContext, DbError> {
+    use crate::schema::contacts::dsl::contacts;
+    use crate::schema::ticket_watchers::dsl as watchers;
+    use crate::schema::tickets::dsl::tickets;
+    use crate::schema::user_preferences::dsl as prefs;
+    use crate::schema::users::dsl as users;
+
+    let ticket = tickets.find(id).first::<Ticket>(conn)?;
+
+    let user_names: HashMap<Uuid, String> = users::users
+        .select((users::user_id, users::display_name))
+        .load::<(Uuid, String)>(conn)?
+        .into_iter()
+        .collect();
+
+    let mut recipients: Vec<(String, String)> = Vec::new();
+
+    //The assignee and watchers, users only get e-mail when they opted in
+    let mut followers: Vec<Uuid> = watchers::ticket_watchers
+        .filter(watchers::ticket_id.eq(ticket.ticket_id))
+        .select(watchers::user_id)
+        .load(conn)?;
+    followers.extend(ticket.assignee);
+    if let Actor::User(user_id) = actor {
+        followers.retain(|follower| *follower != user_id);
+    }
+    let mut subscribed = users::users
+        .inner_join(prefs::user_preferences)
+        .filter(users::user_id.eq_any(&followers))
+        .filter(prefs::email_notifications.eq(true))
+        .into_boxed();
+    if internal {
+        subscribed = subscribed.filter(users::access.ne(Role::Requester.as_str()));
+    }
+    let subscribed = subscribed
+        .select((users::display_name, users::email))
+        .load::<(String, String)>(conn)?;
+    recipients.extend(subscribed);
+
+    let contact = match ticket.contact {
+        Some(contact_id) => contacts
+            .find(contact_id)
+            .first::<Contact>(conn)
+            .optional()?,
+        None => None,
+    };
+    let requester = contact
+        .as_ref()
+        .filter(|contact| !internal && actor != Actor::Contact(contact.contact_id))
+        .map(|contact| (contact.display_name.clone(), contact.email.clone()));
+
+    let actor_name = match actor {
+        Actor::User(user_id) => user_names.get(&user_id).cloned(),
+        Actor::Contact(contact_id) => contacts
+            .find(contact_id)
+            .first::<Contact>(conn)
+            .optional()?
+            .map(|contact| contact.display_name),
+        Actor::Automation => Some("Automation".to_string()),
+    }
+    .unwrap_or_else(|| "Someone".to_string());
+
+    //The requester is checked first, so an address shared with a user gets the portal link
+    let mut seen = Vec::new();
+    let mut recipients = requester
+        .into_iter()
+        .map(|recipient| (true, recipient))
+        .chain(recipients.into_iter().map(|recipient| (false, recipient)))
+        .filter(|(_, (_, email))| {
+            let email = email.to_lowercase();
+            if email.is_empty() || seen.contains(&email) {
+                return false;
+            }
+            seen.push(email);
+            true
+        })
+        .filter_map(|(is_requester, (name, email))| match email.parse() {
+            Ok(address) => Some((is_requester, Mailbox::new(Some(name), address))),
+            Err(e) => {
+                log::warn!("Skipping notification to {}: {}", email, e);
+                None
+            }
+        })
+        .collect::<Vec<(bool, Mailbox)>>();
+    let requester = match recipients.first() {
+        Some((true, _)) => Some(recipients.remove(0).1),
+        _ => None,
+    };
+    let recipients = recipients
+        .into_iter()
+        .map(|(_, recipient)| recipient)
+        .collect();
+
+    Ok(TicketContext {
+        ticket,
+        recipients,
+        requester,
+        user_names,
+        actor_name,
+    })
+}
+
+fn get_note(id: Uuid, conn: &mut PgConnection) -> Result<Note, DbError> {
+    use crate::schema::notes::dsl::*;
+
+    let note = notes.find(id).first::<Note>(conn)?;
+
+    Ok(note)
+}
@@ -0,0 +1,106 @@
+use lettre::{
+    message::{header::ContentType, Mailbox},
+    transport::smtp::authentication::Credentials,
+    AsyncFileTransport, AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+
+type MailError = Box<dyn std::error::Error + Send + Sync>;
+
+const DEFAULT_SMTP_PORT: u16 = 587;
+
+#[derive(Clone)]
+enum Transport {
+    Smtp(AsyncSmtpTransport<Tokio1Executor>),
+    /// Writes each message as an .eml file, meant for testing
+    File(AsyncFileTransport<Tokio1Executor>),
+    Disabled,
+}
+
+/// Sends outgoing mail through the transport chosen by MAIL_TRANSPORT
+#[derive(Clone)]
+pub struct Mailer {
+    transport: Transport,
+    from: Mailbox,
+    /// Public address of the app, used for links in messages
+    pub base_url: String,
+}
+
+impl Mailer {
+    pub fn from_env(base_url: &str) -> Result<Self, MailError> {
+        let transport = match std::env::var("MAIL_TRANSPORT").ok().as_deref() {
+            Some("smtp") => Transport::Smtp(smtp_from_env()?),
+            Some("file") => {
+                let dir =
+                    std::env::var("MAIL_FILE_DIR").unwrap_or_else(|_| "./mail_outbox".to_string());
+                std::fs::create_dir_all(&dir)?;
+                Transport::File(AsyncFileTransport::new(dir))
+            }
+            None | Some("") | Some("none") => Transport::Disabled,
+            Some(other) => return Err(format!("Unknown MAIL_TRANSPORT: {}", other).into()),
+        };
+
+        let from = match std::env::var("MAIL_FROM") {
+            Ok(from) => from.parse()?,
+            Err(_) => {
+                let hostname = std::env::var("SERVER_FQDN").unwrap_or_else(|_| "localhost".into());
+                format!("Sumi <sumi@{}>", hostname).parse()?
+            }
+        };
+
+        Ok(Self {
+            transport,
+            from,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !matches!(self.transport, Transport::Disabled)
+    }
+
+    pub async fn send(&self, to: Mailbox, subject: &str, body: String) -> Result<(), MailError> {
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(body)?;
+
+        match &self.transport {
+            Transport::Smtp(transport) => {
+                transport.send(message).await?;
+            }
+            Transport::File(transport) => {
+                transport.send(message).await?;
+            }
+            Transport::Disabled => {}
+        }
+
+        Ok(())
+    }
+}
+
+fn smtp_from_env() -> Result<AsyncSmtpTransport<Tokio1Executor>, MailError> {
+    let host = std::env::var("SMTP_HOST").map_err(|_| "SMTP_HOST not set")?;
+    let port = match std::env::var("SMTP_PORT") {
+        Ok(port) => port.parse()?,
+        Err(_) => DEFAULT_SMTP_PORT,
+    };
+
+    //starttls (default), tls for implicit TLS, or none for local relays
+    let builder = match std::env::var("SMTP_TLS").as_deref() {
+        Ok("tls") => AsyncSmtpTransport::<Tokio1Executor>::relay(&host)?,
+        Ok("none") => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&host),
+        _ => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&host)?,
+    };
+    let mut builder = builder.port(port);
+
+    if let (Ok(username), Ok(password)) = (
+        std::env::var("SMTP_USERNAME"),
+        std::env::var("SMTP_PASSWORD"),
+    ) {
+        builder = builder.credentials(Credentials::new(username, password));
+    }
+
+    Ok(builder.build())
+}
@@ -1,7 +1,7 @@
 #[macro_use]
 extern crate diesel;
 
-use crate::authentication::reject_anonymous_users;
+use crate::authentication::{reject_anonymous_contacts, reject_anonymous_users};
 use actix_cors::Cors;
 use actix_session::storage::RedisSessionStore;
 use actix_session::SessionMiddleware;
@@ -18,9 +18,16 @@ use secrecy::{ExposeSecret, Secret};
 pub type DbPool = r2d2::Pool<ConnectionManager<PgConnection>>;
 
 mod authentication;
+mod automation;
 mod handlers;
+mod live;
+mod mail;
 mod models;
+mod recurring;
 mod schema;
+mod sla;
+mod storage;
+mod webhooks;
 pub mod utils;
 
 #[actix_web::main]
@@ -72,6 +79,20 @@ async fn main() -> std::io::Result<()> {
         .build(manager)
         .expect("Failed to create pool.");
 
+    let mailer = mail::Mailer::from_env(&url).expect("Invalid mail settings");
+    let broadcaster = web::Data::new(live::Broadcaster::new());
+    let storage = storage::from_env();
+    mail::spawn_inbound_poller(
+        pool.clone(),
+        mailer.clone(),
+        broadcaster.clone(),
+        storage.clone(),
+    );
+    sla::spawn_breach_checker(pool.clone(), broadcaster.clone());
+    recurring::spawn_scheduler(pool.clone(), mailer.clone(), broadcaster.clone());
+    automation::spawn_sweeper(pool.clone(), mailer.clone(), broadcaster.clone());
+    webhooks::spawn_dispatcher(pool.clone());
+
     let server = HttpServer::new(move || {
         let cors = Cors::default()
             .allowed_origin(url.as_str())
@@ -81,12 +102,31 @@ async fn main() -> std::io::Result<()> {
             .max_age(3600);
         App::new()
             .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(mailer.clone()))
+            .app_data(broadcaster.clone())
+            .app_data(web::Data::from(storage.clone()))
+            .app_data(handlers::attachments::form_config())
             .wrap(SessionMiddleware::new(
                 redis_store.clone(),
                 secret_key.clone(),
             ))
             .wrap(middleware::Logger::default())
             .wrap(cors)
+            //Registered first, the /api scope would otherwise take these requests
+            .service(
+                web::scope("/api/portal")
+                    .service(handlers::portal::login)
+                    .service(handlers::portal::magic_link)
+                    .service(handlers::portal::verify_magic_link)
+                    .service(handlers::portal::logout)
+                    .service(handlers::portal::me)
+                    .service(handlers::portal::set_password)
+                    .service(handlers::portal::own_tickets)
+                    .service(handlers::portal::create_ticket)
+                    .service(handlers::portal::own_ticket)
+                    .service(handlers::portal::reply)
+                    .wrap(from_fn(reject_anonymous_contacts)),
+            )
             .service(
                 web::scope("/api")
                     .service(handlers::auth::login)
@@ -94,6 +134,7 @@ async fn main() -> std::io::Result<()> {
                     .service(handlers::users::whoami)
                     .service(handlers::tickets::options)
                     .service(handlers::tickets::index)
+                    .service(handlers::bulk::bulk)
                     //.service(handlers::tickets::by_assignee)
                     .service(handlers::tickets::create)
                     .service(handlers::tickets::show)
@@ -127,6 +168,86 @@ async fn main() -> std::io::Result<()> {
                     .service(handlers::documents::update)
                     .service(handlers::documents::delete)
                     .service(handlers::documents::revisions)
+                    .service(handlers::comments::index)
+                    .service(handlers::comments::create)
+                    .service(handlers::comments::update)
+                    .service(handlers::comments::delete)
+                    .service(handlers::watchers::index)
+                    .service(handlers::watchers::create)
+                    .service(handlers::watchers::delete)
+                    .service(handlers::notifications::index)
+                    .service(handlers::notifications::unread_count)
+                    .service(handlers::notifications::mark_all_read)
+                    .service(handlers::notifications::mark_read)
+                    .service(handlers::projects::index)
+                    .service(handlers::projects::create)
+                    .service(handlers::projects::show)
+                    .service(handlers::projects::update)
+                    .service(handlers::projects::delete)
+                    .service(handlers::custom_fields::index)
+                    .service(handlers::custom_fields::create)
+                    .service(handlers::custom_fields::update)
+                    .service(handlers::custom_fields::delete)
+                    .service(handlers::tags::index)
+                    .service(handlers::tags::create)
+                    .service(handlers::tags::update)
+                    .service(handlers::tags::delete)
+                    .service(handlers::tags::ticket_tags)
+                    .service(handlers::tags::add_ticket_tag)
+                    .service(handlers::tags::remove_ticket_tag)
+                    .service(handlers::views::index)
+                    .service(handlers::views::create)
+                    .service(handlers::views::update)
+                    .service(handlers::views::delete)
+                    .service(handlers::tasks::index)
+                    .service(handlers::tasks::create)
+                    .service(handlers::tasks::reorder)
+                    .service(handlers::tasks::update)
+                    .service(handlers::tasks::delete)
+                    .service(handlers::links::index)
+                    .service(handlers::links::create)
+                    .service(handlers::links::delete)
+                    .service(handlers::merge::merge)
+                    .service(handlers::attachments::ticket_attachments)
+                    .service(handlers::attachments::add_ticket_attachment)
+                    .service(handlers::attachments::add_note_attachment)
+                    .service(handlers::attachments::document_attachments)
+                    .service(handlers::attachments::add_document_attachment)
+                    .service(handlers::attachments::download)
+                    .service(handlers::attachments::delete)
+                    .service(handlers::workflows::statuses)
+                    .service(handlers::workflows::create_status)
+                    .service(handlers::workflows::update_status)
+                    .service(handlers::workflows::delete_status)
+                    .service(handlers::workflows::priorities)
+                    .service(handlers::workflows::create_priority)
+                    .service(handlers::workflows::update_priority)
+                    .service(handlers::workflows::delete_priority)
+                    .service(handlers::sla::index)
+                    .service(handlers::sla::create)
+                    .service(handlers::sla::update)
+                    .service(handlers::sla::delete)
+                    .service(handlers::recurring::index)
+                    .service(handlers::recurring::create)
+                    .service(handlers::recurring::update)
+                    .service(handlers::recurring::delete)
+                    .service(handlers::recurring::runs)
+                    .service(handlers::automation::index)
+                    .service(handlers::automation::create)
+                    .service(handlers::automation::update)
+                    .service(handlers::automation::delete)
+                    .service(handlers::automation::runs)
+                    .service(handlers::webhooks::index)
+                    .service(handlers::webhooks::create)
+                    .service(handlers::webhooks::update)
+                    .service(handlers::webhooks::delete)
+                    .service(handlers::webhooks::deliveries)
+                    .service(handlers::webhooks::replay)
+                    .service(handlers::reports::time_report)
+                    .service(handlers::reports::time_report_csv)
+                    .service(handlers::search::search)
+                    .service(handlers::live::stream)
+                    .service(handlers::live::presence)
                     .wrap(from_fn(reject_anonymous_users)),
             )
             .service(
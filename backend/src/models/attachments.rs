@@ -0,0 +1,42 @@
+use actix_multipart::form::{bytes::Bytes, MultipartForm};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::schema::attachments;
+
+#[derive(Debug, Serialize, Queryable, Clone)]
+pub struct Attachment {
+    pub attachment_id: Uuid,
+    pub ticket_id: Option<i32>,
+    pub note_id: Option<Uuid>,
+    pub document_id: Option<Uuid>,
+    pub filename: String,
+    pub mime_type: String,
+    /// In bytes
+    pub size: i64,
+    /// Where the contents are kept, only the server needs to know
+    #[serde(skip_serializing)]
+    pub storage_key: String,
+    pub uploaded_by: Option<Uuid>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = attachments)]
+pub struct NewAttachment {
+    pub attachment_id: Uuid,
+    pub ticket_id: Option<i32>,
+    pub note_id: Option<Uuid>,
+    pub document_id: Option<Uuid>,
+    pub filename: String,
+    pub mime_type: String,
+    pub size: i64,
+    pub storage_key: String,
+    pub uploaded_by: Option<Uuid>,
+}
+
+/// Multipart upload with the file in a `file` field
+#[derive(Debug, MultipartForm)]
+pub struct AttachmentUpload {
+    pub file: Bytes,
+}
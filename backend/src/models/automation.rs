@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+use shared::models::automation::{AutomationAction, AutomationCondition, AutomationTrigger};
+use uuid::Uuid;
+
+use crate::schema::{automation_rules, automation_runs};
+
+#[derive(Debug, Serialize, Deserialize, Queryable, Clone)]
+pub struct AutomationRule {
+    pub rule_id: Uuid,
+    pub name: String,
+    pub enabled: bool,
+    /// `AutomationTrigger` as JSON
+    pub trigger: String,
+    /// `AutomationCondition` list as JSON
+    pub conditions: String,
+    /// `AutomationAction` list as JSON
+    pub actions: String,
+    pub created_by: Option<Uuid>,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Insertable, AsChangeset)]
+#[diesel(table_name = automation_rules)]
+pub struct NewAutomationRule {
+    pub name: String,
+    pub enabled: bool,
+    pub trigger: String,
+    pub conditions: String,
+    pub actions: String,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+/// Body for creating or updating a rule
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AutomationRulePayload {
+    pub name: String,
+    pub enabled: bool,
+    pub trigger: AutomationTrigger,
+    #[serde(default)]
+    pub conditions: Vec<AutomationCondition>,
+    pub actions: Vec<AutomationAction>,
+}
+
+/// Rule with its trigger, conditions and actions parsed
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AutomationRuleRepresentation {
+    pub rule_id: Uuid,
+    pub name: String,
+    pub enabled: bool,
+    pub trigger: AutomationTrigger,
+    pub conditions: Vec<AutomationCondition>,
+    pub actions: Vec<AutomationAction>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize, Queryable, Clone)]
+pub struct AutomationRun {
+    pub run_id: Uuid,
+    pub rule_id: Uuid,
+    /// Empty once the ticket was deleted
+    pub ticket_id: Option<i32>,
+    pub trigger: String,
+    pub success: bool,
+    pub message: String,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = automation_runs)]
+pub struct NewAutomationRun {
+    pub rule_id: Uuid,
+    pub ticket_id: Option<i32>,
+    pub trigger: String,
+    pub success: bool,
+    pub message: String,
+    pub created_at: chrono::NaiveDateTime,
+}
@@ -1 +1,71 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
+use crate::schema::comments;
+
+use super::{tickets::SomeUserRepresentation, users::User};
+
+#[derive(Debug, Serialize, Deserialize, Queryable, Clone)]
+pub struct Comment {
+    pub comment_id: Uuid,
+    pub document_id: Uuid,
+    pub author: Option<Uuid>,
+    pub text: String,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+    pub parent_id: Option<Uuid>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = comments)]
+pub struct NewComment<'a> {
+    pub comment_id: Uuid,
+    pub document_id: Uuid,
+    pub author: Option<Uuid>,
+    pub text: &'a str,
+    pub parent_id: Option<Uuid>,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CommentPayload {
+    pub text: String,
+    /// Comment being replied to, must belong to the same document
+    pub parent_id: Option<Uuid>,
+}
+
+/// Comments are returned as a flat list ordered by creation time,
+/// clients build the reply tree from `parent_id`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CommentRepresentation {
+    pub comment_id: Uuid,
+    pub document_id: Uuid,
+    pub parent_id: Option<Uuid>,
+    pub author: Option<SomeUserRepresentation>,
+    pub text: String,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+impl From<(Comment, Option<User>)> for CommentRepresentation {
+    fn from(values: (Comment, Option<User>)) -> Self {
+        let (comment, user) = values;
+        Self {
+            comment_id: comment.comment_id,
+            document_id: comment.document_id,
+            parent_id: comment.parent_id,
+            author: user.map(|user| SomeUserRepresentation {
+                user_id: Some(user.user_id),
+                username: Some(user.username),
+                display_name: Some(user.display_name),
+                email: Some(user.email),
+                created_at: Some(user.created_at),
+                access: Some(user.access),
+            }),
+            text: comment.text,
+            created_at: comment.created_at,
+            updated_at: comment.updated_at,
+        }
+    }
+}
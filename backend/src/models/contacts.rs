@@ -3,7 +3,7 @@ use uuid::Uuid;
 
 use crate::schema::contacts;
 
-#[derive(Debug, Serialize, Deserialize, Queryable)]
+#[derive(Debug, Serialize, Deserialize, Queryable, Clone)]
 pub struct Contact {
     pub contact_id: Uuid,
     pub display_name: String,
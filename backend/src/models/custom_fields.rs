@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::schema::{custom_fields, ticket_field_values};
+
+#[derive(Debug, Serialize, Deserialize, Queryable, Clone)]
+pub struct CustomField {
+    pub field_id: Uuid,
+    pub name: String,
+    /// See `CustomFieldType`
+    pub field_type: String,
+    /// Choices of select fields, Postgres arrays may hold nulls but these never do
+    pub options: Vec<Option<String>>,
+    pub required: bool,
+    pub position: i32,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize, Insertable, AsChangeset)]
+#[diesel(table_name = custom_fields)]
+pub struct CustomFieldPayload {
+    pub name: String,
+    pub field_type: String,
+    #[serde(default)]
+    pub options: Vec<Option<String>>,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default)]
+    pub position: i32,
+}
+
+#[derive(Debug, Insertable, AsChangeset)]
+#[diesel(table_name = ticket_field_values)]
+pub struct NewTicketFieldValue {
+    pub ticket_id: i32,
+    pub field_id: Uuid,
+    pub value: String,
+}
+
+/// Value of a custom field on a ticket, with the field for displaying it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TicketFieldRepresentation {
+    pub field_id: Uuid,
+    pub name: String,
+    pub field_type: String,
+    pub value: String,
+}
@@ -50,20 +50,6 @@ pub struct DocumentUpdatePayload {
     pub version: Option<chrono::NaiveDateTime>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Queryable)]
-pub struct DocumentRepresentation {
-    pub document_id: Uuid,
-    pub parent_id: Option<Uuid>,
-    pub url: String,
-    pub title: String,
-    pub content: String,
-    pub created_at: chrono::NaiveDateTime,
-    pub updated_at: chrono::NaiveDateTime,
-    pub created_by: Option<Uuid>,
-    pub updated_by: Option<Uuid>,
-    pub archived: bool,
-}
-
 //DocumentTreeInfo
 #[derive(Debug, Serialize, Deserialize, Queryable, Clone)]
 pub struct DocumentTreeInfo {
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+use shared::models::links::TicketLinkType;
+use uuid::Uuid;
+
+use crate::schema::ticket_links;
+
+/// Stored direction of a link, `link_type` is one of the stored types of `TicketLinkType`
+#[derive(Debug, Serialize, Deserialize, Queryable, Clone)]
+pub struct TicketLink {
+    pub link_id: Uuid,
+    pub source_id: i32,
+    pub target_id: i32,
+    pub link_type: String,
+    pub created_by: Option<Uuid>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = ticket_links)]
+pub struct NewTicketLink {
+    pub source_id: i32,
+    pub target_id: i32,
+    pub link_type: String,
+    pub created_by: Option<Uuid>,
+}
+
+/// Any link type can be sent, links are stored in one direction
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TicketLinkPayload {
+    pub link_type: TicketLinkType,
+    pub ticket_id: i32,
+}
+
+/// A link seen from one ticket, with the other ticket
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TicketLinkRepresentation {
+    pub link_id: Uuid,
+    pub link_type: TicketLinkType,
+    pub ticket_id: i32,
+    pub title: String,
+    pub status: String,
+    pub is_closed: bool,
+    pub created_at: chrono::NaiveDateTime,
+}
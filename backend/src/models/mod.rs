@@ -1,12 +1,29 @@
 use serde::{Deserialize, Serialize};
 
+pub mod attachments;
+pub mod automation;
 pub mod comments;
 pub mod contacts;
+pub mod custom_fields;
 pub mod documents;
+pub mod links;
 pub mod notes;
+pub mod notifications;
+pub mod portal;
+pub mod projects;
+pub mod recurring;
+pub mod reports;
+pub mod search;
 pub mod session;
+pub mod sla;
+pub mod tags;
+pub mod tasks;
 pub mod tickets;
 pub mod users;
+pub mod views;
+pub mod watchers;
+pub mod webhooks;
+pub mod workflows;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SuccessResponse {
@@ -3,7 +3,7 @@ use uuid::Uuid;
 
 use crate::schema::notes;
 
-use super::{tickets::SomeUserRepresentation, users::User};
+use super::{contacts::Contact, tickets::SomeUserRepresentation, users::User};
 
 #[derive(Debug, Serialize, Deserialize, Queryable, Clone)]
 pub struct Note {
@@ -14,6 +14,8 @@ pub struct Note {
     pub time: i32,
     pub created_at: chrono::NaiveDateTime,
     pub updated_at: chrono::NaiveDateTime,
+    pub contact: Option<Uuid>,
+    pub is_public: bool,
 }
 
 #[derive(Debug, Insertable)]
@@ -25,6 +27,8 @@ pub struct NewNote<'a> {
     pub text: &'a str,
     pub time: i32,
     pub created_at: chrono::NaiveDateTime,
+    pub contact: Option<Uuid>,
+    pub is_public: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -33,21 +37,30 @@ pub struct NotePayload {
     pub owner: Option<Uuid>,
     pub text: String,
     pub time: i32,
+    /// Internal notes are hidden from requesters, the author's preference applies when left out
+    #[serde(default)]
+    pub is_public: Option<bool>,
 }
 
+/// A note joined with its owner and, for e-mail replies, the sending contact
+pub type NoteWithAuthor = (Note, Option<User>, Option<Contact>);
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct NoteRepresentation {
     pub note_id: Uuid,
     pub ticket: i32,
     pub owner: Option<SomeUserRepresentation>,
+    /// Set when the note came in by e-mail from a contact
+    pub contact: Option<Contact>,
     pub text: String,
     pub time: i32,
+    pub is_public: bool,
     pub created_at: chrono::NaiveDateTime,
     pub updated_at: chrono::NaiveDateTime,
 }
 
-impl From<(Note, Option<User>)> for NoteRepresentation {
-    fn from(values: (Note, Option<User>)) -> Self {
+impl From<NoteWithAuthor> for NoteRepresentation {
+    fn from(values: NoteWithAuthor) -> Self {
         Self {
             note_id: values.0.note_id,
             ticket: values.0.ticket,
@@ -59,8 +72,10 @@ impl From<(Note, Option<User>)> for NoteRepresentation {
                 created_at: Some(values.1.clone().unwrap_or_default().created_at),
                 access: Some(values.1.clone().unwrap_or_default().access),
             }),
+            contact: values.2,
             text: values.0.text,
             time: values.0.time,
+            is_public: values.0.is_public,
             created_at: values.0.created_at,
             updated_at: values.0.updated_at,
         }
@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::schema::notifications;
+
+#[derive(Debug, Serialize, Deserialize, Queryable)]
+pub struct Notification {
+    pub notification_id: Uuid,
+    pub user_id: Uuid,
+    pub ticket_id: i32,
+    pub event_type: String,
+    pub event_data: String,
+    pub actor: Option<Uuid>,
+    pub created_at: chrono::NaiveDateTime,
+    pub read_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = notifications)]
+pub struct NewNotification {
+    pub notification_id: Uuid,
+    pub user_id: Uuid,
+    pub ticket_id: i32,
+    pub event_type: String,
+    pub event_data: String,
+    pub actor: Option<Uuid>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+/// Notification with the ticket title and actor name resolved for display
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NotificationRepresentation {
+    pub notification_id: Uuid,
+    pub ticket_id: i32,
+    pub ticket_title: String,
+    pub event_type: String,
+    pub event_data: String,
+    pub actor: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+    pub read_at: Option<chrono::NaiveDateTime>,
+}
+
+impl From<(Notification, String, Option<String>)> for NotificationRepresentation {
+    fn from((notification, ticket_title, actor): (Notification, String, Option<String>)) -> Self {
+        Self {
+            notification_id: notification.notification_id,
+            ticket_id: notification.ticket_id,
+            ticket_title,
+            event_type: notification.event_type,
+            event_data: notification.event_data,
+            actor,
+            created_at: notification.created_at,
+            read_at: notification.read_at,
+        }
+    }
+}
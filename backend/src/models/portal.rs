@@ -0,0 +1,96 @@
+use secrecy::Secret;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::schema::{portal_passwords, portal_tokens};
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = portal_tokens)]
+pub struct NewPortalToken {
+    /// SHA-256 of the token in the link, as hex
+    pub token_hash: String,
+    pub contact_id: Uuid,
+    pub expires_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Insertable, AsChangeset)]
+#[diesel(table_name = portal_passwords)]
+pub struct PortalPassword {
+    pub contact_id: Uuid,
+    pub password_hash: String,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+#[derive(Deserialize)]
+pub struct PortalLoginPayload {
+    pub email: String,
+    pub password: Secret<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MagicLinkPayload {
+    pub email: String,
+}
+
+#[derive(Deserialize)]
+pub struct MagicLinkVerifyPayload {
+    pub token: String,
+}
+
+#[derive(Deserialize)]
+pub struct PortalPasswordPayload {
+    pub password: Secret<String>,
+}
+
+/// The restricted form requesters submit tickets with
+#[derive(Debug, Deserialize)]
+pub struct PortalTicketPayload {
+    pub title: String,
+    pub description: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PortalReplyPayload {
+    pub text: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PortalContactRepresentation {
+    pub contact_id: Uuid,
+    pub display_name: String,
+    pub email: String,
+    /// Whether the requester can sign in with a password instead of a link
+    pub has_password: bool,
+}
+
+/// What requesters may see of a ticket, internal details like the assignee are left out
+#[derive(Debug, Serialize)]
+pub struct PortalTicketRepresentation {
+    pub ticket_id: i32,
+    pub title: String,
+    pub description: String,
+    pub status: String,
+    pub is_closed: bool,
+    pub merged_into: Option<i32>,
+    /// The ticket it was merged into belongs to the requester too and can be opened
+    pub merged_into_own: bool,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PortalNoteRepresentation {
+    pub note_id: Uuid,
+    /// Display name of the user or contact who wrote the note
+    pub author: String,
+    /// Written by the requester, through the portal or by e-mail
+    pub from_requester: bool,
+    pub text: String,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PortalTicketDetail {
+    pub ticket: PortalTicketRepresentation,
+    pub notes: Vec<PortalNoteRepresentation>,
+}
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::schema::projects;
+
+#[derive(Debug, Serialize, Deserialize, Queryable, Clone)]
+pub struct Project {
+    pub project_id: Uuid,
+    pub name: String,
+    pub key: String,
+    pub default_assignee: Option<Uuid>,
+    pub next_number: i32,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+impl Project {
+    /// Project-scoped ticket number as shown to users, e.g. NET-42
+    pub fn reference(&self, number: i32) -> String {
+        format!("{}-{}", self.key, number)
+    }
+}
+
+/// Body for creating or updating a project, also used as the row to insert
+#[derive(Debug, Serialize, Deserialize, Insertable, AsChangeset)]
+#[diesel(table_name = projects, treat_none_as_null = true)]
+pub struct ProjectPayload {
+    pub name: String,
+    pub key: String,
+    pub default_assignee: Option<Uuid>,
+}
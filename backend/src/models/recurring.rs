@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+use shared::models::recurring::Recurrence;
+use uuid::Uuid;
+
+use super::tasks::TicketTaskPayload;
+use crate::schema::{recurring_ticket_runs, recurring_tickets};
+
+#[derive(Debug, Serialize, Deserialize, Queryable, Clone)]
+pub struct RecurringTicket {
+    pub recurring_id: Uuid,
+    pub name: String,
+    pub title: String,
+    pub description: String,
+    pub assignee: Option<Uuid>,
+    pub priority: Option<String>,
+    /// `TicketTaskPayload` list as JSON
+    pub tasks: String,
+    /// `Recurrence` as JSON
+    pub recurrence: String,
+    pub start_at: chrono::NaiveDateTime,
+    pub utc_offset: i32,
+    pub next_run: Option<chrono::NaiveDateTime>,
+    pub enabled: bool,
+    pub created_by: Option<Uuid>,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Insertable, AsChangeset)]
+#[diesel(table_name = recurring_tickets, treat_none_as_null = true)]
+pub struct NewRecurringTicket {
+    pub name: String,
+    pub title: String,
+    pub description: String,
+    pub assignee: Option<Uuid>,
+    pub priority: Option<String>,
+    pub tasks: String,
+    pub recurrence: String,
+    pub start_at: chrono::NaiveDateTime,
+    pub utc_offset: i32,
+    pub next_run: Option<chrono::NaiveDateTime>,
+    pub enabled: bool,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+/// Body for creating or updating a recurring ticket, an empty priority means the default one
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RecurringTicketPayload {
+    pub name: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    pub assignee: Option<Uuid>,
+    pub priority: Option<String>,
+    #[serde(default)]
+    pub tasks: Vec<TicketTaskPayload>,
+    pub recurrence: Recurrence,
+    pub start_at: chrono::NaiveDateTime,
+    /// Seconds east of UTC of the time zone the schedule is in
+    #[serde(default)]
+    pub utc_offset: i32,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RecurringTicketRepresentation {
+    pub recurring_id: Uuid,
+    pub name: String,
+    pub title: String,
+    pub description: String,
+    pub assignee: Option<Uuid>,
+    pub priority: Option<String>,
+    pub tasks: Vec<TicketTaskPayload>,
+    pub recurrence: Recurrence,
+    pub start_at: chrono::NaiveDateTime,
+    pub utc_offset: i32,
+    pub next_run: Option<chrono::NaiveDateTime>,
+    pub enabled: bool,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+/// A ticket created by a recurring ticket, `ticket_id` is empty once the ticket is deleted
+#[derive(Debug, Serialize, Deserialize, Queryable, Clone)]
+pub struct RecurringTicketRun {
+    pub run_id: Uuid,
+    pub recurring_id: Uuid,
+    pub scheduled_for: chrono::NaiveDateTime,
+    pub ticket_id: Option<i32>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = recurring_ticket_runs)]
+pub struct NewRecurringTicketRun {
+    pub recurring_id: Uuid,
+    pub scheduled_for: chrono::NaiveDateTime,
+}
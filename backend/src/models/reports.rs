@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Time logged on one note
+#[derive(Debug, Serialize, Deserialize, Queryable, Clone)]
+pub struct TimeEntry {
+    pub note_id: Uuid,
+    pub ticket_id: i32,
+    pub title: String,
+    pub user_id: Option<Uuid>,
+    pub user_name: Option<String>,
+    pub contact_id: Option<Uuid>,
+    pub contact_name: Option<String>,
+    pub minutes: i32,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+/// Minutes logged by one user, on one ticket, for one contact or on one day
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TimeTotal {
+    /// Id of the user, ticket or contact, or the date. Empty for entries without one.
+    pub key: String,
+    pub label: String,
+    pub minutes: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TimeReport {
+    /// Oldest first
+    pub entries: Vec<TimeEntry>,
+    /// Largest first
+    pub totals: Vec<TimeTotal>,
+    pub total_minutes: i64,
+}
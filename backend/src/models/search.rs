@@ -0,0 +1,41 @@
+use diesel::sql_types::{Float4, Nullable, Text};
+use shared::models::search::{SearchEntity, SearchResult};
+use uuid::Uuid;
+
+/// Row returned by the raw search query in handlers::search
+#[derive(Debug, QueryableByName)]
+pub struct SearchHit {
+    #[diesel(sql_type = Text)]
+    pub entity_type: String,
+    #[diesel(sql_type = Nullable<diesel::sql_types::Int4>)]
+    pub ticket_id: Option<i32>,
+    #[diesel(sql_type = Nullable<diesel::sql_types::Uuid>)]
+    pub document_id: Option<Uuid>,
+    #[diesel(sql_type = Nullable<diesel::sql_types::Uuid>)]
+    pub note_id: Option<Uuid>,
+    #[diesel(sql_type = Text)]
+    pub title: String,
+    #[diesel(sql_type = Text)]
+    pub snippet: String,
+    #[diesel(sql_type = Float4)]
+    pub rank: f32,
+}
+
+impl From<SearchHit> for SearchResult {
+    fn from(hit: SearchHit) -> Self {
+        let entity_type = match hit.entity_type.as_str() {
+            "note" => SearchEntity::Note,
+            "document" => SearchEntity::Document,
+            _ => SearchEntity::Ticket,
+        };
+        Self {
+            entity_type,
+            ticket_id: hit.ticket_id,
+            document_id: hit.document_id,
+            note_id: hit.note_id,
+            title: hit.title,
+            snippet: hit.snippet,
+            rank: hit.rank,
+        }
+    }
+}
@@ -9,12 +9,15 @@ pub struct TypedSession(Session);
 
 impl TypedSession {
     const USER_ID_KEY: &'static str = "user_id";
+    /// Requesters signed in to the portal, never set together with a user id
+    const CONTACT_ID_KEY: &'static str = "contact_id";
 
     pub fn renew(&self) {
         self.0.renew();
     }
 
     pub fn insert_user_id(&self, user_id: Uuid) -> Result<(), actix_session::SessionInsertError> {
+        self.0.remove(Self::CONTACT_ID_KEY);
         self.0.insert(Self::USER_ID_KEY, user_id)
     }
 
@@ -22,6 +25,18 @@ impl TypedSession {
         self.0.get(Self::USER_ID_KEY)
     }
 
+    pub fn insert_contact_id(
+        &self,
+        contact_id: Uuid,
+    ) -> Result<(), actix_session::SessionInsertError> {
+        self.0.remove(Self::USER_ID_KEY);
+        self.0.insert(Self::CONTACT_ID_KEY, contact_id)
+    }
+
+    pub fn get_contact_id(&self) -> Result<Option<Uuid>, actix_session::SessionGetError> {
+        self.0.get(Self::CONTACT_ID_KEY)
+    }
+
     pub fn log_out(self) {
         self.0.purge()
     }
@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::schema::{sla_policies, ticket_slas};
+
+#[derive(Debug, Serialize, Deserialize, Queryable, Clone)]
+pub struct SlaPolicy {
+    pub policy_id: Uuid,
+    pub name: String,
+    pub priority: Option<String>,
+    pub project: Option<Uuid>,
+    pub contact: Option<Uuid>,
+    pub response_minutes: Option<i32>,
+    pub resolution_minutes: Option<i32>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+impl SlaPolicy {
+    /// Number of criteria set, the most specific matching policy wins
+    pub fn specificity(&self) -> usize {
+        [
+            self.priority.is_some(),
+            self.project.is_some(),
+            self.contact.is_some(),
+        ]
+        .into_iter()
+        .filter(|set| *set)
+        .count()
+    }
+}
+
+/// Body for creating or updating a policy, also used as the row to insert.
+/// Criteria left out match any ticket.
+#[derive(Debug, Serialize, Deserialize, Insertable, AsChangeset)]
+#[diesel(table_name = sla_policies, treat_none_as_null = true)]
+pub struct SlaPolicyPayload {
+    pub name: String,
+    pub priority: Option<String>,
+    pub project: Option<Uuid>,
+    pub contact: Option<Uuid>,
+    pub response_minutes: Option<i32>,
+    pub resolution_minutes: Option<i32>,
+}
+
+/// SLA clock of a ticket, also used as the row to insert
+#[derive(Debug, Serialize, Deserialize, Queryable, Insertable, AsChangeset, Clone)]
+#[diesel(table_name = ticket_slas, treat_none_as_null = true)]
+pub struct TicketSla {
+    pub ticket_id: i32,
+    pub policy_id: Uuid,
+    pub started_at: chrono::NaiveDateTime,
+    pub response_due: Option<chrono::NaiveDateTime>,
+    pub resolution_due: Option<chrono::NaiveDateTime>,
+    pub first_response_at: Option<chrono::NaiveDateTime>,
+    pub resolved_at: Option<chrono::NaiveDateTime>,
+    pub paused_at: Option<chrono::NaiveDateTime>,
+    pub paused_seconds: i32,
+    pub response_breached: bool,
+    pub resolution_breached: bool,
+}
+
+/// SLA of a ticket as shown with it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TicketSlaRepresentation {
+    pub policy_id: Uuid,
+    pub policy_name: String,
+    pub response_due: Option<chrono::NaiveDateTime>,
+    pub resolution_due: Option<chrono::NaiveDateTime>,
+    pub first_response_at: Option<chrono::NaiveDateTime>,
+    pub resolved_at: Option<chrono::NaiveDateTime>,
+    pub paused: bool,
+    pub response_breached: bool,
+    pub resolution_breached: bool,
+    /// See `SlaStatus`
+    pub sla_status: String,
+}
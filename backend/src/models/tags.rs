@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::schema::{tags, ticket_tags};
+
+#[derive(Debug, Serialize, Deserialize, Queryable, Clone)]
+pub struct Tag {
+    pub tag_id: Uuid,
+    pub name: String,
+    pub color: String,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+/// Body for creating or updating a tag, a missing color keeps the current (or default) one
+#[derive(Debug, Serialize, Deserialize, Insertable, AsChangeset)]
+#[diesel(table_name = tags)]
+pub struct TagPayload {
+    pub name: String,
+    pub color: Option<String>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = ticket_tags)]
+pub struct NewTicketTag {
+    pub ticket_id: i32,
+    pub tag_id: Uuid,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TicketTagPayload {
+    pub tag_id: Uuid,
+}
@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::schema::ticket_tasks;
+
+#[derive(Debug, Serialize, Deserialize, Queryable, Clone)]
+pub struct TicketTask {
+    pub task_id: Uuid,
+    pub ticket_id: i32,
+    pub text: String,
+    pub position: i32,
+    pub done: bool,
+    pub required: bool,
+    pub completed_at: Option<chrono::NaiveDateTime>,
+    pub completed_by: Option<Uuid>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = ticket_tasks)]
+pub struct NewTicketTask {
+    pub ticket_id: i32,
+    pub text: String,
+    pub position: i32,
+    pub required: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TicketTaskPayload {
+    pub text: String,
+    #[serde(default)]
+    pub required: bool,
+}
+
+/// Fields left out are kept, checking a task records who completed it
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TicketTaskUpdatePayload {
+    pub text: Option<String>,
+    pub done: Option<bool>,
+    pub required: Option<bool>,
+}
+
+#[derive(Debug, AsChangeset)]
+#[diesel(table_name = ticket_tasks)]
+pub struct UpdateTicketTask {
+    pub text: Option<String>,
+    pub done: Option<bool>,
+    pub required: Option<bool>,
+    pub completed_at: Option<Option<chrono::NaiveDateTime>>,
+    pub completed_by: Option<Option<Uuid>>,
+}
+
+/// Every task of the ticket in the new order
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TicketTaskOrderPayload {
+    pub task_ids: Vec<Uuid>,
+}
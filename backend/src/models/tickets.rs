@@ -1,7 +1,13 @@
+use std::collections::HashMap;
+
 use crate::schema::{ticket_events, ticket_revisions, tickets};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use super::custom_fields::TicketFieldRepresentation;
+use super::projects::Project;
+use super::sla::TicketSlaRepresentation;
+use super::tags::Tag;
 use super::users::User;
 
 #[derive(Debug, Serialize, Deserialize, Queryable)]
@@ -21,6 +27,10 @@ pub struct Ticket {
     pub updated_by: Option<Uuid>,
     pub revision: chrono::NaiveDateTime,
     pub revision_by: Option<Uuid>,
+    pub project: Option<Uuid>,
+    pub project_number: Option<i32>,
+    /// Set once the ticket was merged into another one
+    pub merged_into: Option<i32>,
 }
 
 #[derive(Debug, Insertable)]
@@ -39,10 +49,12 @@ pub struct NewTicket {
     pub updated_by: Option<Uuid>,
     pub revision: chrono::NaiveDateTime,
     pub revision_by: Option<Uuid>,
+    pub project: Option<Uuid>,
+    /// Filled in from the project when the ticket is added
+    pub project_number: Option<i32>,
 }
 
-#[derive(Debug, Serialize, Deserialize, AsChangeset)]
-#[diesel(table_name = tickets)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct TicketPayload {
     pub title: String,
     pub assignee: Option<Uuid>,
@@ -51,6 +63,10 @@ pub struct TicketPayload {
     pub due_date: Option<chrono::NaiveDateTime>,
     pub priority: String,
     pub status: String,
+    pub project: Option<Uuid>,
+    /// Values by custom field id
+    #[serde(default)]
+    pub custom_fields: HashMap<Uuid, String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -63,6 +79,31 @@ pub struct TicketUpdatePayload {
     pub priority: Option<String>,
     pub status: Option<String>,
     pub version: Option<chrono::NaiveDateTime>,
+    /// "" moves the ticket out of its project
+    pub project: Option<String>,
+    /// Values by custom field id, fields left out are kept and "" clears a value
+    pub custom_fields: Option<HashMap<Uuid, String>>,
+}
+
+/// Merges the ticket of the path into `target`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TicketMergePayload {
+    pub target: i32,
+}
+
+/// Changes applied to every listed ticket, fields left out are kept
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TicketBulkPayload {
+    pub ticket_ids: Vec<i32>,
+    pub status: Option<String>,
+    pub priority: Option<String>,
+    /// "" unassigns the tickets
+    pub assignee: Option<String>,
+    pub add_tag: Option<Uuid>,
+    pub remove_tag: Option<Uuid>,
+    /// Deletes the tickets instead of changing them, admins only
+    #[serde(default)]
+    pub delete: bool,
 }
 
 //Struct used to update ticket in database
@@ -78,6 +119,8 @@ pub struct UpdateTicket {
     pub priority: Option<String>,
     pub status: Option<String>,
     pub revision: Option<chrono::NaiveDateTime>,
+    pub project: Option<Option<Uuid>>,
+    pub project_number: Option<Option<i32>>,
 }
 
 #[derive(Debug, Serialize)]
@@ -101,10 +144,31 @@ pub struct TicketRepresentation {
     pub priority: String,
     pub status: String,
     pub revision: chrono::NaiveDateTime,
+    pub project: Option<Project>,
+    pub project_number: Option<i32>,
+    /// Project-scoped number like NET-42, when the ticket is in a project
+    pub reference: Option<String>,
+    /// The ticket this one was merged into
+    pub merged_into: Option<i32>,
+    /// Filled in separately, the join would repeat the ticket for every tag
+    pub tags: Vec<Tag>,
+    /// Filled in separately like the tags, in field order
+    pub custom_fields: Vec<TicketFieldRepresentation>,
+    /// Filled in separately, only when an SLA policy applies
+    pub sla: Option<TicketSlaRepresentation>,
+    /// Minutes logged on the notes of the ticket
+    pub time_spent: i64,
+    /// Checked tasks of the task list
+    pub tasks_done: i64,
+    pub tasks_total: i64,
 }
 
-impl From<(Ticket, Option<User>)> for TicketRepresentation {
-    fn from(values: (Ticket, Option<User>)) -> Self {
+impl From<(Ticket, Option<User>, Option<Project>)> for TicketRepresentation {
+    fn from(values: (Ticket, Option<User>, Option<Project>)) -> Self {
+        let reference = match (&values.2, values.0.project_number) {
+            (Some(project), Some(number)) => Some(project.reference(number)),
+            _ => None,
+        };
         Self {
             ticket_id: values.0.ticket_id,
             title: values.0.title,
@@ -130,6 +194,16 @@ impl From<(Ticket, Option<User>)> for TicketRepresentation {
             priority: values.0.priority,
             status: values.0.status,
             revision: values.0.revision,
+            project: values.2,
+            project_number: values.0.project_number,
+            reference,
+            merged_into: values.0.merged_into,
+            tags: vec![],
+            custom_fields: vec![],
+            sla: None,
+            time_spent: 0,
+            tasks_done: 0,
+            tasks_total: 0,
         }
     }
 }
@@ -174,8 +248,21 @@ pub struct TicketEvent {
     pub created_at: chrono::NaiveDateTime,
 }
 
+impl From<NewTicketEvent> for TicketEvent {
+    fn from(event: NewTicketEvent) -> Self {
+        Self {
+            event_id: event.event_id,
+            ticket_id: event.ticket_id,
+            event_type: event.event_type,
+            event_data: event.event_data,
+            user_id: event.user_id,
+            created_at: event.created_at,
+        }
+    }
+}
+
 //New ticket event
-#[derive(Debug, Insertable)]
+#[derive(Clone, Debug, Insertable)]
 #[diesel(table_name = ticket_events)]
 pub struct NewTicketEvent {
     pub event_id: Uuid,
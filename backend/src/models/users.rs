@@ -85,6 +85,8 @@ pub struct UserPreferences {
     pub theme: Option<String>,
     pub locale: Option<String>,
     pub timezone: Option<String>,
+    pub email_notifications: bool,
+    pub notes_public: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -92,6 +94,9 @@ pub struct UserPreferencesRepresentation {
     pub theme: Option<String>,
     pub locale: Option<String>,
     pub timezone: Option<String>,
+    pub email_notifications: bool,
+    /// Whether new notes are public unless the author picks otherwise
+    pub notes_public: bool,
 }
 
 #[derive(Debug, Insertable, AsChangeset, Deserialize)]
@@ -100,4 +105,6 @@ pub struct UpdateUserPreferences {
     pub theme: Option<String>,
     pub locale: Option<String>,
     pub timezone: Option<String>,
+    pub email_notifications: Option<bool>,
+    pub notes_public: Option<bool>,
 }
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+use shared::models::{tickets::TicketFilterPayload, users::UserDisplay};
+use uuid::Uuid;
+
+use crate::schema::saved_views;
+
+#[derive(Debug, Serialize, Deserialize, Queryable, Clone)]
+pub struct SavedView {
+    pub view_id: Uuid,
+    pub owner: Uuid,
+    pub name: String,
+    /// `TicketFilterPayload` as JSON
+    pub filter: String,
+    pub shared: bool,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Insertable, AsChangeset)]
+#[diesel(table_name = saved_views)]
+pub struct NewSavedView {
+    pub owner: Uuid,
+    pub name: String,
+    pub filter: String,
+    pub shared: bool,
+}
+
+/// Body for creating or updating a view
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SavedViewPayload {
+    pub name: String,
+    pub filter: TicketFilterPayload,
+    #[serde(default)]
+    pub shared: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SavedViewRepresentation {
+    pub view_id: Uuid,
+    pub name: String,
+    pub filter: TicketFilterPayload,
+    pub shared: bool,
+    pub owner: UserDisplay,
+    pub created_at: chrono::NaiveDateTime,
+}
@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::schema::ticket_watchers;
+
+use super::users::User;
+
+#[derive(Debug, Serialize, Deserialize, Queryable)]
+pub struct TicketWatcher {
+    pub ticket_id: i32,
+    pub user_id: Uuid,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = ticket_watchers)]
+pub struct NewTicketWatcher {
+    pub ticket_id: i32,
+    pub user_id: Uuid,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+/// Body for subscribing, leave `user_id` out to watch the ticket yourself
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct WatcherPayload {
+    pub user_id: Option<Uuid>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WatcherRepresentation {
+    pub user_id: Uuid,
+    pub username: String,
+    pub display_name: String,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+impl From<(TicketWatcher, User)> for WatcherRepresentation {
+    fn from((watcher, user): (TicketWatcher, User)) -> Self {
+        Self {
+            user_id: watcher.user_id,
+            username: user.username,
+            display_name: user.display_name,
+            created_at: watcher.created_at,
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+use shared::models::webhooks::WebhookEvent;
+use uuid::Uuid;
+
+use crate::schema::{webhook_deliveries, webhooks};
+
+#[derive(Debug, Serialize, Deserialize, Queryable, Clone)]
+pub struct Webhook {
+    pub webhook_id: Uuid,
+    pub name: String,
+    pub url: String,
+    pub secret: String,
+    /// `WebhookEvent` list as JSON
+    pub events: String,
+    pub enabled: bool,
+    pub created_by: Option<Uuid>,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Insertable, AsChangeset)]
+#[diesel(table_name = webhooks)]
+pub struct NewWebhook {
+    pub name: String,
+    pub url: String,
+    /// None keeps the secret when updating
+    pub secret: Option<String>,
+    pub events: String,
+    pub enabled: bool,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+/// Body for creating or updating a webhook
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WebhookPayload {
+    pub name: String,
+    pub url: String,
+    /// Generated when creating a webhook without one, kept when updating without one
+    #[serde(default)]
+    pub secret: Option<String>,
+    pub events: Vec<WebhookEvent>,
+    pub enabled: bool,
+}
+
+/// Webhook with its events parsed, only shown to admins
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WebhookRepresentation {
+    pub webhook_id: Uuid,
+    pub name: String,
+    pub url: String,
+    pub secret: String,
+    pub events: Vec<WebhookEvent>,
+    pub enabled: bool,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize, Queryable, Clone)]
+pub struct WebhookDelivery {
+    pub delivery_id: Uuid,
+    pub webhook_id: Uuid,
+    pub event: String,
+    pub payload: String,
+    /// `DeliveryStatus` name
+    pub status: String,
+    pub attempts: i32,
+    pub next_attempt_at: Option<chrono::NaiveDateTime>,
+    pub response_status: Option<i32>,
+    pub error: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+    pub delivered_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = webhook_deliveries)]
+pub struct NewWebhookDelivery {
+    pub webhook_id: Uuid,
+    pub event: String,
+    pub payload: String,
+    pub next_attempt_at: Option<chrono::NaiveDateTime>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+/// Body of every delivery, `data` depends on the event
+#[derive(Debug, Serialize)]
+pub struct WebhookBody<'a> {
+    pub event: WebhookEvent,
+    pub created_at: chrono::NaiveDateTime,
+    pub data: &'a serde_json::Value,
+}
@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::schema::{status_transitions, ticket_priorities, ticket_statuses};
+
+#[derive(Debug, Serialize, Deserialize, Queryable, Clone)]
+pub struct TicketStatus {
+    pub status_id: Uuid,
+    pub name: String,
+    pub is_closed: bool,
+    pub is_default: bool,
+    pub position: i32,
+    pub created_at: chrono::NaiveDateTime,
+    pub pauses_sla: bool,
+}
+
+/// Body for creating or updating a status
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TicketStatusPayload {
+    pub name: String,
+    #[serde(default)]
+    pub is_closed: bool,
+    #[serde(default)]
+    pub is_default: bool,
+    #[serde(default)]
+    pub position: i32,
+    /// Stops the SLA clock while tickets are in this status
+    #[serde(default)]
+    pub pauses_sla: bool,
+    /// Statuses a ticket may change to from this one, empty allows any
+    #[serde(default)]
+    pub transitions: Vec<Uuid>,
+}
+
+#[derive(Debug, Insertable, AsChangeset)]
+#[diesel(table_name = ticket_statuses)]
+pub struct NewTicketStatus {
+    pub name: String,
+    pub is_closed: bool,
+    pub is_default: bool,
+    pub position: i32,
+    pub pauses_sla: bool,
+}
+
+impl From<&TicketStatusPayload> for NewTicketStatus {
+    fn from(payload: &TicketStatusPayload) -> Self {
+        NewTicketStatus {
+            name: payload.name.clone(),
+            is_closed: payload.is_closed,
+            is_default: payload.is_default,
+            position: payload.position,
+            pauses_sla: payload.pauses_sla,
+        }
+    }
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = status_transitions)]
+pub struct NewStatusTransition {
+    pub from_status: Uuid,
+    pub to_status: Uuid,
+}
+
+/// A status with the statuses it may change to
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TicketStatusRepresentation {
+    pub status_id: Uuid,
+    pub name: String,
+    pub is_closed: bool,
+    pub is_default: bool,
+    pub position: i32,
+    pub pauses_sla: bool,
+    pub transitions: Vec<Uuid>,
+}
+
+impl TicketStatusRepresentation {
+    pub fn new(status: TicketStatus, transitions: Vec<Uuid>) -> Self {
+        TicketStatusRepresentation {
+            status_id: status.status_id,
+            name: status.name,
+            is_closed: status.is_closed,
+            is_default: status.is_default,
+            position: status.position,
+            pauses_sla: status.pauses_sla,
+            transitions,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Queryable, Clone)]
+pub struct TicketPriority {
+    pub priority_id: Uuid,
+    pub name: String,
+    pub rank: i32,
+    pub is_default: bool,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+/// Body for creating or updating a priority, also used as the row to insert
+#[derive(Debug, Serialize, Deserialize, Insertable, AsChangeset)]
+#[diesel(table_name = ticket_priorities)]
+pub struct TicketPriorityPayload {
+    pub name: String,
+    #[serde(default)]
+    pub rank: i32,
+    #[serde(default)]
+    pub is_default: bool,
+}
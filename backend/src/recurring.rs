@@ -0,0 +1,55 @@
+//! Background scheduler for recurring tickets.
+//!
+//! Runs are recorded in the database, so runs missed while the backend was down are
+//! created on the first check after it starts again.
+
+use std::time::Duration;
+
+use actix_web::web;
+use shared::models::{automation::AutomationTrigger, live::LiveEvent, webhooks::WebhookEvent};
+
+use crate::handlers::automation::automate;
+use crate::handlers::recurring::run_due;
+use crate::handlers::webhooks::{publish, WebhookSubject};
+use crate::live::Broadcaster;
+use crate::mail::Mailer;
+use crate::DbPool;
+
+const CHECK_INTERVAL_SECONDS: u64 = 60;
+
+/// Starts creating the tickets of recurring tickets in the background
+pub fn spawn_scheduler(pool: DbPool, mailer: Mailer, broadcaster: web::Data<Broadcaster>) {
+    actix_rt::spawn(async move {
+        //The first tick completes right away, which catches up on missed runs at startup
+        let mut interval = actix_rt::time::interval(Duration::from_secs(CHECK_INTERVAL_SECONDS));
+        loop {
+            interval.tick().await;
+
+            let run_pool = pool.clone();
+            let result = web::block(move || {
+                let mut conn = run_pool.get()?;
+                run_due(&mut conn)
+            })
+            .await;
+            match result {
+                Ok(Ok(created)) => {
+                    for ticket_id in created {
+                        broadcaster.broadcast(LiveEvent::TicketChanged {
+                            ticket_id,
+                            user_id: None,
+                        });
+                        publish(
+                            pool.clone(),
+                            WebhookEvent::TicketCreated,
+                            WebhookSubject::Ticket(ticket_id, vec![]),
+                        );
+                        let triggers = vec![AutomationTrigger::TicketCreated];
+                        automate(&pool, &mailer, &broadcaster, ticket_id, triggers).await;
+                    }
+                }
+                Ok(Err(e)) => log::error!("Recurring ticket run failed: {}", e),
+                Err(e) => log::error!("Recurring ticket run failed: {}", e),
+            }
+        }
+    });
+}
@@ -1,5 +1,46 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    attachments (attachment_id) {
+        attachment_id -> Uuid,
+        ticket_id -> Nullable<Int4>,
+        note_id -> Nullable<Uuid>,
+        document_id -> Nullable<Uuid>,
+        filename -> Text,
+        mime_type -> Text,
+        size -> Int8,
+        storage_key -> Text,
+        uploaded_by -> Nullable<Uuid>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    automation_rules (rule_id) {
+        rule_id -> Uuid,
+        name -> Text,
+        enabled -> Bool,
+        trigger -> Text,
+        conditions -> Text,
+        actions -> Text,
+        created_by -> Nullable<Uuid>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    automation_runs (run_id) {
+        run_id -> Uuid,
+        rule_id -> Uuid,
+        ticket_id -> Nullable<Int4>,
+        trigger -> Text,
+        success -> Bool,
+        message -> Text,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     comments (comment_id) {
         comment_id -> Uuid,
@@ -8,6 +49,7 @@ diesel::table! {
         text -> Text,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        parent_id -> Nullable<Uuid>,
     }
 }
 
@@ -20,6 +62,18 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    custom_fields (field_id) {
+        field_id -> Uuid,
+        name -> Text,
+        field_type -> Text,
+        options -> Array<Nullable<Text>>,
+        required -> Bool,
+        position -> Int4,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     document_revisions (revision_id) {
         revision_id -> Uuid,
@@ -54,6 +108,119 @@ diesel::table! {
         time -> Int4,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        contact -> Nullable<Uuid>,
+        is_public -> Bool,
+    }
+}
+
+diesel::table! {
+    notifications (notification_id) {
+        notification_id -> Uuid,
+        user_id -> Uuid,
+        ticket_id -> Int4,
+        event_type -> Text,
+        event_data -> Text,
+        actor -> Nullable<Uuid>,
+        created_at -> Timestamp,
+        read_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    portal_passwords (contact_id) {
+        contact_id -> Uuid,
+        password_hash -> Text,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    portal_tokens (token_hash) {
+        token_hash -> Text,
+        contact_id -> Uuid,
+        expires_at -> Timestamp,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    projects (project_id) {
+        project_id -> Uuid,
+        name -> Text,
+        key -> Text,
+        default_assignee -> Nullable<Uuid>,
+        next_number -> Int4,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    recurring_ticket_runs (run_id) {
+        run_id -> Uuid,
+        recurring_id -> Uuid,
+        scheduled_for -> Timestamp,
+        ticket_id -> Nullable<Int4>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    recurring_tickets (recurring_id) {
+        recurring_id -> Uuid,
+        name -> Text,
+        title -> Text,
+        description -> Text,
+        assignee -> Nullable<Uuid>,
+        priority -> Nullable<Text>,
+        tasks -> Text,
+        recurrence -> Text,
+        start_at -> Timestamp,
+        utc_offset -> Int4,
+        next_run -> Nullable<Timestamp>,
+        enabled -> Bool,
+        created_by -> Nullable<Uuid>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    saved_views (view_id) {
+        view_id -> Uuid,
+        owner -> Uuid,
+        name -> Text,
+        filter -> Text,
+        shared -> Bool,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    sla_policies (policy_id) {
+        policy_id -> Uuid,
+        name -> Text,
+        priority -> Nullable<Text>,
+        project -> Nullable<Uuid>,
+        contact -> Nullable<Uuid>,
+        response_minutes -> Nullable<Int4>,
+        resolution_minutes -> Nullable<Int4>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    status_transitions (from_status, to_status) {
+        from_status -> Uuid,
+        to_status -> Uuid,
+    }
+}
+
+diesel::table! {
+    tags (tag_id) {
+        tag_id -> Uuid,
+        name -> Text,
+        color -> Text,
+        created_at -> Timestamp,
     }
 }
 
@@ -68,6 +235,36 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    ticket_field_values (ticket_id, field_id) {
+        ticket_id -> Int4,
+        field_id -> Uuid,
+        value -> Text,
+    }
+}
+
+diesel::table! {
+    ticket_links (link_id) {
+        link_id -> Uuid,
+        source_id -> Int4,
+        target_id -> Int4,
+        #[max_length = 16]
+        link_type -> Varchar,
+        created_by -> Nullable<Uuid>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    ticket_priorities (priority_id) {
+        priority_id -> Uuid,
+        name -> Text,
+        rank -> Int4,
+        is_default -> Bool,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     ticket_revisions (revision_id) {
         revision_id -> Uuid,
@@ -78,6 +275,64 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    ticket_slas (ticket_id) {
+        ticket_id -> Int4,
+        policy_id -> Uuid,
+        started_at -> Timestamp,
+        response_due -> Nullable<Timestamp>,
+        resolution_due -> Nullable<Timestamp>,
+        first_response_at -> Nullable<Timestamp>,
+        resolved_at -> Nullable<Timestamp>,
+        paused_at -> Nullable<Timestamp>,
+        paused_seconds -> Int4,
+        response_breached -> Bool,
+        resolution_breached -> Bool,
+    }
+}
+
+diesel::table! {
+    ticket_statuses (status_id) {
+        status_id -> Uuid,
+        name -> Text,
+        is_closed -> Bool,
+        is_default -> Bool,
+        position -> Int4,
+        created_at -> Timestamp,
+        pauses_sla -> Bool,
+    }
+}
+
+diesel::table! {
+    ticket_tags (ticket_id, tag_id) {
+        ticket_id -> Int4,
+        tag_id -> Uuid,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    ticket_tasks (task_id) {
+        task_id -> Uuid,
+        ticket_id -> Int4,
+        text -> Text,
+        position -> Int4,
+        done -> Bool,
+        required -> Bool,
+        completed_at -> Nullable<Timestamp>,
+        completed_by -> Nullable<Uuid>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    ticket_watchers (ticket_id, user_id) {
+        ticket_id -> Int4,
+        user_id -> Uuid,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     tickets (ticket_id) {
         ticket_id -> Int4,
@@ -95,6 +350,9 @@ diesel::table! {
         updated_by -> Nullable<Uuid>,
         revision -> Timestamp,
         revision_by -> Nullable<Uuid>,
+        project -> Nullable<Uuid>,
+        project_number -> Nullable<Int4>,
+        merged_into -> Nullable<Int4>,
     }
 }
 
@@ -104,6 +362,8 @@ diesel::table! {
         theme -> Nullable<Text>,
         locale -> Nullable<Text>,
         timezone -> Nullable<Text>,
+        email_notifications -> Bool,
+        notes_public -> Bool,
     }
 }
 
@@ -119,28 +379,113 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    webhook_deliveries (delivery_id) {
+        delivery_id -> Uuid,
+        webhook_id -> Uuid,
+        event -> Text,
+        payload -> Text,
+        status -> Text,
+        attempts -> Int4,
+        next_attempt_at -> Nullable<Timestamp>,
+        response_status -> Nullable<Int4>,
+        error -> Nullable<Text>,
+        created_at -> Timestamp,
+        delivered_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    webhooks (webhook_id) {
+        webhook_id -> Uuid,
+        name -> Text,
+        url -> Text,
+        secret -> Text,
+        events -> Text,
+        enabled -> Bool,
+        created_by -> Nullable<Uuid>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::joinable!(attachments -> documents (document_id));
+diesel::joinable!(attachments -> notes (note_id));
+diesel::joinable!(attachments -> tickets (ticket_id));
+diesel::joinable!(attachments -> users (uploaded_by));
+diesel::joinable!(automation_rules -> users (created_by));
+diesel::joinable!(automation_runs -> automation_rules (rule_id));
+diesel::joinable!(automation_runs -> tickets (ticket_id));
 diesel::joinable!(comments -> documents (document_id));
 diesel::joinable!(comments -> users (author));
 diesel::joinable!(document_revisions -> documents (document_id));
 diesel::joinable!(document_revisions -> users (updated_by));
+diesel::joinable!(notes -> contacts (contact));
 diesel::joinable!(notes -> users (owner));
+diesel::joinable!(notifications -> tickets (ticket_id));
+diesel::joinable!(portal_passwords -> contacts (contact_id));
+diesel::joinable!(portal_tokens -> contacts (contact_id));
+diesel::joinable!(projects -> users (default_assignee));
+diesel::joinable!(recurring_ticket_runs -> recurring_tickets (recurring_id));
+diesel::joinable!(recurring_ticket_runs -> tickets (ticket_id));
+diesel::joinable!(saved_views -> users (owner));
+diesel::joinable!(sla_policies -> contacts (contact));
+diesel::joinable!(sla_policies -> projects (project));
 diesel::joinable!(ticket_events -> tickets (ticket_id));
 diesel::joinable!(ticket_events -> users (user_id));
+diesel::joinable!(ticket_field_values -> custom_fields (field_id));
+diesel::joinable!(ticket_field_values -> tickets (ticket_id));
+diesel::joinable!(ticket_links -> users (created_by));
 diesel::joinable!(ticket_revisions -> tickets (ticket_id));
 diesel::joinable!(ticket_revisions -> users (updated_by));
+diesel::joinable!(ticket_slas -> sla_policies (policy_id));
+diesel::joinable!(ticket_slas -> tickets (ticket_id));
+diesel::joinable!(ticket_tags -> tags (tag_id));
+diesel::joinable!(ticket_tags -> tickets (ticket_id));
+diesel::joinable!(ticket_tasks -> tickets (ticket_id));
+diesel::joinable!(ticket_tasks -> users (completed_by));
+diesel::joinable!(ticket_watchers -> tickets (ticket_id));
+diesel::joinable!(ticket_watchers -> users (user_id));
 diesel::joinable!(tickets -> contacts (contact));
+diesel::joinable!(tickets -> projects (project));
 diesel::joinable!(tickets -> users (assignee));
 diesel::joinable!(user_preferences -> users (user_id));
+diesel::joinable!(webhook_deliveries -> webhooks (webhook_id));
+diesel::joinable!(webhooks -> users (created_by));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    attachments,
+    automation_rules,
+    automation_runs,
     comments,
     contacts,
+    custom_fields,
     document_revisions,
     documents,
     notes,
+    notifications,
+    portal_passwords,
+    portal_tokens,
+    projects,
+    recurring_ticket_runs,
+    recurring_tickets,
+    saved_views,
+    sla_policies,
+    status_transitions,
+    tags,
     ticket_events,
+    ticket_field_values,
+    ticket_links,
+    ticket_priorities,
     ticket_revisions,
+    ticket_slas,
+    ticket_statuses,
+    ticket_tags,
+    ticket_tasks,
+    ticket_watchers,
     tickets,
     user_preferences,
     users,
+    webhook_deliveries,
+    webhooks,
 );
@@ -0,0 +1,44 @@
+//! Background check for missed SLA targets.
+//!
+//! Targets are also checked whenever a ticket changes, this catches the ones that pass
+//! while nobody touches the ticket.
+
+use std::time::Duration;
+
+use actix_web::web;
+use shared::models::live::LiveEvent;
+
+use crate::handlers::sla::check_breaches;
+use crate::live::Broadcaster;
+use crate::DbPool;
+
+const CHECK_INTERVAL_SECONDS: u64 = 60;
+
+/// Starts checking running SLA clocks in the background
+pub fn spawn_breach_checker(pool: DbPool, broadcaster: web::Data<Broadcaster>) {
+    actix_rt::spawn(async move {
+        let mut interval = actix_rt::time::interval(Duration::from_secs(CHECK_INTERVAL_SECONDS));
+        loop {
+            interval.tick().await;
+
+            let check_pool = pool.clone();
+            let result = web::block(move || {
+                let mut conn = check_pool.get()?;
+                check_breaches(&mut conn)
+            })
+            .await;
+            match result {
+                Ok(Ok(breached)) => {
+                    for ticket_id in breached {
+                        broadcaster.broadcast(LiveEvent::TicketChanged {
+                            ticket_id,
+                            user_id: None,
+                        });
+                    }
+                }
+                Ok(Err(e)) => log::error!("SLA breach check failed: {}", e),
+                Err(e) => log::error!("SLA breach check failed: {}", e),
+            }
+        }
+    });
+}
@@ -0,0 +1,72 @@
+//! Blob storage for attachments.
+//!
+//! Handlers only see the [`Storage`] trait and refer to blobs by key, the `attachments`
+//! table keeps the key with the file name and type. Files are kept on the local filesystem
+//! for now, other backends only have to implement the trait.
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+pub trait Storage: Send + Sync {
+    fn put(&self, key: &str, contents: &[u8]) -> io::Result<()>;
+    fn get(&self, key: &str) -> io::Result<Vec<u8>>;
+    /// Deleting a blob that is already gone is not an error
+    fn delete(&self, key: &str) -> io::Result<()>;
+}
+
+/// Keeps blobs as files in one directory, named after their key
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    /// Keys are generated by the server, anything that could leave the directory is refused
+    fn path(&self, key: &str) -> io::Result<PathBuf> {
+        let valid = !key.is_empty()
+            && key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Invalid storage key {}", key),
+            ));
+        }
+
+        Ok(self.root.join(key))
+    }
+}
+
+impl Storage for LocalStorage {
+    fn put(&self, key: &str, contents: &[u8]) -> io::Result<()> {
+        let path = self.path(key)?;
+        fs::create_dir_all(&self.root)?;
+        fs::write(path, contents)
+    }
+
+    fn get(&self, key: &str) -> io::Result<Vec<u8>> {
+        fs::read(self.path(key)?)
+    }
+
+    fn delete(&self, key: &str) -> io::Result<()> {
+        match fs::remove_file(self.path(key)?) {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    }
+}
+
+/// Storage from ATTACHMENT_DIR, falls back to the old MAIL_ATTACHMENT_DIR setting
+pub fn from_env() -> Arc<dyn Storage> {
+    let root = std::env::var("ATTACHMENT_DIR")
+        .or_else(|_| std::env::var("MAIL_ATTACHMENT_DIR"))
+        .unwrap_or_else(|_| "./attachments".to_string());
+
+    Arc::new(LocalStorage::new(PathBuf::from(root)))
+}
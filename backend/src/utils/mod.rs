@@ -21,3 +21,24 @@ pub fn parse_uuid(input: &Option<String>) -> Result<Option<Option<Uuid>>, actix_
         }
     }
 }
+
+/// Keeps letters, digits, dots, dashes and underscores of an uploaded file name
+pub fn sanitize_filename(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '.' || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let cleaned = cleaned.trim_start_matches('.');
+
+    if cleaned.is_empty() {
+        "attachment".to_string()
+    } else {
+        cleaned.to_string()
+    }
+}
@@ -0,0 +1,109 @@
+//! Background delivery of queued webhooks.
+//!
+//! Deliveries are queued in the database when something changes and sent from here, so
+//! they survive restarts. A delivery interrupted by a restart is sent again, receivers
+//! should expect the same `X-Sumi-Delivery` more than once.
+
+use std::time::Duration;
+
+use actix_web::web;
+
+use crate::handlers::webhooks::{due_deliveries, record_attempt, sign, Attempt};
+use crate::models::webhooks::{Webhook, WebhookDelivery};
+use crate::DbPool;
+
+const CHECK_INTERVAL_SECONDS: u64 = 10;
+const REQUEST_TIMEOUT_SECONDS: u64 = 10;
+
+/// Longest part of a response body kept in the delivery log
+const MAX_ERROR_LENGTH: usize = 500;
+
+/// Starts sending queued webhook deliveries in the background
+pub fn spawn_dispatcher(pool: DbPool) {
+    let client = match reqwest::Client::builder()
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECONDS))
+        .user_agent("sumi-webhooks")
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => return log::error!("Unable to start webhook deliveries: {}", e),
+    };
+
+    actix_rt::spawn(async move {
+        let mut interval = actix_rt::time::interval(Duration::from_secs(CHECK_INTERVAL_SECONDS));
+        loop {
+            interval.tick().await;
+
+            let check_pool = pool.clone();
+            let result = web::block(move || {
+                let mut conn = check_pool.get()?;
+                due_deliveries(&mut conn)
+            })
+            .await;
+            let due = match result {
+                Ok(Ok(due)) => due,
+                Ok(Err(e)) => {
+                    log::error!("Loading webhook deliveries failed: {}", e);
+                    continue;
+                }
+                Err(e) => {
+                    log::error!("Loading webhook deliveries failed: {}", e);
+                    continue;
+                }
+            };
+
+            for (delivery, hook) in due {
+                let id = delivery.delivery_id;
+                let attempt = send(&client, &delivery, &hook).await;
+                if let Err((_, reason)) = &attempt {
+                    log::warn!("Webhook delivery {} to {} failed: {}", id, hook.url, reason);
+                }
+
+                let record_pool = pool.clone();
+                let result = web::block(move || {
+                    let mut conn = record_pool.get()?;
+                    record_attempt(id, attempt, &mut conn)
+                })
+                .await;
+                match result {
+                    Ok(Ok(())) => (),
+                    Ok(Err(e)) => log::error!("Recording webhook delivery failed: {}", e),
+                    Err(e) => log::error!("Recording webhook delivery failed: {}", e),
+                }
+            }
+        }
+    });
+}
+
+/// Posts the payload signed with the secret of the webhook, any 2xx response counts
+async fn send(client: &reqwest::Client, delivery: &WebhookDelivery, hook: &Webhook) -> Attempt {
+    let response = client
+        .post(&hook.url)
+        .header("Content-Type", "application/json")
+        .header("X-Sumi-Event", &delivery.event)
+        .header("X-Sumi-Delivery", delivery.delivery_id.to_string())
+        .header(
+            "X-Sumi-Signature",
+            format!("sha256={}", sign(&hook.secret, &delivery.payload)),
+        )
+        .body(delivery.payload.clone())
+        .send()
+        .await
+        .map_err(|e| (None, e.to_string()))?;
+
+    let code = response.status().as_u16() as i32;
+    if response.status().is_success() {
+        return Ok(code);
+    }
+
+    let body = response.text().await.unwrap_or_default();
+    let reason = match body.trim() {
+        "" => format!("HTTP {}", code),
+        body => format!(
+            "HTTP {}: {}",
+            code,
+            body.chars().take(MAX_ERROR_LENGTH).collect::<String>()
+        ),
+    };
+    Err((Some(code), reason))
+}
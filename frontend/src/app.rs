@@ -4,6 +4,7 @@ use yew_router::prelude::*;
 
 use crate::components::nav::Navigation;
 use crate::contexts::language::LanguageProvider;
+use crate::contexts::live::LiveProvider;
 use crate::contexts::theme::ThemeProvider;
 use crate::contexts::time::TimeContextProvider;
 use crate::contexts::user::UserContextProvider;
@@ -17,12 +18,14 @@ pub fn app() -> Html {
         <GlobalStyle />
           <TimeContextProvider>
                 <UserContextProvider>
+                    <LiveProvider>
                     <BrowserRouter>
                         <LanguageProvider>
                             <Navigation />
                             <Switch<AppRoute> render={switch} />
                         </LanguageProvider>
                     </BrowserRouter>
+                    </LiveProvider>
                 </UserContextProvider>
             </TimeContextProvider>
         </ThemeProvider>
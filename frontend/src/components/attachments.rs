@@ -0,0 +1,218 @@
+use shared::models::attachments::{format_size, is_image, ATTACHMENT_URL_SCHEME};
+use shared::models::live::LiveEvent;
+use uuid::Uuid;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::contexts::live::use_live_events;
+use crate::hooks::{use_language_context, use_user_context};
+use crate::services::attachments::*;
+use crate::types::{AttachmentInfo, Error};
+use shared::models::response::Response;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum AttachmentOwner {
+    Ticket(i32),
+    Document(Uuid),
+}
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub owner: AttachmentOwner,
+}
+
+/// Files picked in a file input, in the order they were selected
+pub fn selected_files(input: &HtmlInputElement) -> Vec<web_sys::File> {
+    let Some(list) = input.files() else {
+        return vec![];
+    };
+    (0..list.length()).filter_map(|i| list.get(i)).collect()
+}
+
+/// Message for a failed upload, prefixed with the file name
+pub fn upload_error(
+    filename: &str,
+    result: Result<Response<AttachmentInfo>, Error>,
+) -> Option<String> {
+    match result {
+        Ok(response) if response.success => None,
+        Ok(response) => Some(format!(
+            "{}: {}",
+            filename,
+            response.message.unwrap_or_default()
+        )),
+        Err(e) => Some(format!("{}: {}", filename, e)),
+    }
+}
+
+/// Files of a ticket (including those added to its notes) or a wiki document.
+/// Images get a preview, agents can remove any file and uploaders their own.
+#[function_component(AttachmentList)]
+pub fn attachment_list(props: &Props) -> Html {
+    let language = use_language_context();
+    let user_ctx = use_user_context();
+    let attachments = use_state(Vec::<AttachmentInfo>::new);
+    //Bumped after our own changes and on live events, reloads the list
+    let version = use_state(|| 0_u32);
+    let uploading = use_state(|| false);
+    let error = use_state(String::new);
+    let can_edit = user_ctx.role().can_edit();
+    //Customers can add files to their own tickets
+    let can_upload = match props.owner {
+        AttachmentOwner::Ticket(_) => user_ctx.role().can_create_tickets(),
+        AttachmentOwner::Document(_) => can_edit,
+    };
+
+    {
+        let attachments = attachments.clone();
+        use_effect_with((props.owner, *version), move |(owner, _)| {
+            let owner = *owner;
+            wasm_bindgen_futures::spawn_local(async move {
+                let result = match owner {
+                    AttachmentOwner::Ticket(ticket_id) => get_ticket_attachments(ticket_id).await,
+                    AttachmentOwner::Document(document_id) => {
+                        get_document_attachments(document_id).await
+                    }
+                };
+                match result {
+                    Ok(list) => attachments.set(list),
+                    Err(e) => log::error!("Error loading attachments: {}", e),
+                }
+            });
+            || ()
+        })
+    }
+
+    //Files added to notes only show up through the live events, so our own changes count too
+    {
+        let version = version.clone();
+        let owner = props.owner;
+        use_live_events(Callback::from(move |event| match (event, owner) {
+            (LiveEvent::TicketChanged { ticket_id, .. }, AttachmentOwner::Ticket(id))
+            | (LiveEvent::NotesChanged { ticket_id, .. }, AttachmentOwner::Ticket(id))
+                if ticket_id == id =>
+            {
+                version.set(*version + 1);
+            }
+            (LiveEvent::DocumentChanged { document_id, .. }, AttachmentOwner::Document(id))
+                if document_id == id =>
+            {
+                version.set(*version + 1);
+            }
+            _ => (),
+        }));
+    }
+
+    let onchange_file = {
+        let owner = props.owner;
+        let version = version.clone();
+        let uploading = uploading.clone();
+        let error = error.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let files = selected_files(&input);
+            input.set_value("");
+            if files.is_empty() {
+                return;
+            }
+            let version = version.clone();
+            let uploading = uploading.clone();
+            let error = error.clone();
+            uploading.set(true);
+            wasm_bindgen_futures::spawn_local(async move {
+                let mut errors = vec![];
+                for file in files {
+                    let filename = file.name();
+                    let result = match owner {
+                        AttachmentOwner::Ticket(ticket_id) => {
+                            upload_ticket_attachment(ticket_id, file).await
+                        }
+                        AttachmentOwner::Document(document_id) => {
+                            upload_document_attachment(document_id, file).await
+                        }
+                    };
+                    errors.extend(upload_error(&filename, result));
+                }
+                error.set(errors.join(", "));
+                uploading.set(false);
+                version.set(*version + 1);
+            });
+        })
+    };
+
+    let onclick_delete = |attachment: &AttachmentInfo| {
+        let attachment_id = attachment.attachment_id;
+        let version = version.clone();
+        let error = error.clone();
+        Callback::from(move |_: MouseEvent| {
+            let version = version.clone();
+            let error = error.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match delete_attachment(attachment_id).await {
+                    Ok(_) => error.set(String::new()),
+                    Err(e) => error.set(e.to_string()),
+                }
+                version.set(*version + 1);
+            });
+        })
+    };
+
+    //Selects the markdown so it can be copied into a description or note
+    let onclick_markdown = Callback::from(|e: MouseEvent| {
+        let input: HtmlInputElement = e.target_unchecked_into();
+        input.select();
+    });
+
+    if attachments.is_empty() && !can_upload {
+        return html! {};
+    }
+
+    html! {
+        <div class="attachments">
+            <div><b>{ language.get("Attachments") }</b></div>
+            { for attachments.iter().map(|attachment| {
+                let url = attachment_url(attachment.attachment_id);
+                let image = is_image(&attachment.mime_type);
+                let markdown = format!(
+                    "{}[{}]({}{})",
+                    if image { "!" } else { "" },
+                    attachment.filename,
+                    ATTACHMENT_URL_SCHEME,
+                    attachment.attachment_id
+                );
+                let can_delete = can_edit || attachment.uploaded_by == Some(user_ctx.user_id);
+                html! {
+                    <div class="attachment">
+                        if image {
+                            <a href={url.clone()} target="_blank">
+                                <img src={url.clone()} alt={attachment.filename.clone()}
+                                    style="max-height: 64px; max-width: 128px; vertical-align: middle;" />
+                            </a>
+                        }
+                        <a href={url} target="_blank">{ &attachment.filename }</a>
+                        { format!(" ({})", format_size(attachment.size)) }
+                        if attachment.note_id.is_some() {
+                            { format!(" - {}", language.get("Note")) }
+                        }
+                        if can_edit {
+                            <input type="text" readonly=true value={markdown} onclick={onclick_markdown.clone()}
+                                title={language.get("Markdown")} style="width: 160px; margin: 0px 8px;" />
+                        }
+                        if can_delete {
+                            <button class="page-btn" onclick={onclick_delete(attachment)}>{ "✘" }</button>
+                        }
+                    </div>
+                }
+            })}
+            if can_upload {
+                <div>
+                    <input type="file" multiple=true disabled={*uploading} onchange={onchange_file} />
+                    if *uploading {
+                        { language.get("Uploading...") }
+                    }
+                </div>
+                <span class="error">{ error.to_string() }</span>
+            }
+        </div>
+    }
+}
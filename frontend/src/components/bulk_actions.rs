@@ -0,0 +1,187 @@
+use uuid::Uuid;
+use web_sys::HtmlSelectElement;
+use yew::prelude::*;
+
+use crate::components::confirmation::Confirmation;
+use crate::hooks::{use_language_context, use_user_context};
+use crate::services::tickets::bulk_update;
+use crate::types::{PriorityInfo, StatusInfo, TagInfo, TicketBulkInfo, UserRepresentation};
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    /// Ticket ids the change applies to
+    pub selected: Vec<i32>,
+    pub statuses: Vec<StatusInfo>,
+    pub priorities: Vec<PriorityInfo>,
+    pub users: Vec<UserRepresentation>,
+    pub tags: Vec<TagInfo>,
+    /// Called once the tickets were changed or deleted
+    pub callback: Callback<()>,
+}
+
+/// Changes status, priority, assignee or tags of the selected tickets at once,
+/// admins can also delete them
+#[function_component(BulkActions)]
+pub fn bulk_actions(props: &Props) -> Html {
+    let language = use_language_context();
+    let user_ctx = use_user_context();
+    let change = use_state(TicketBulkInfo::default);
+    let error = use_state(String::new);
+    let confirmation_pending = use_state(|| false);
+    let busy = use_state(|| false);
+
+    //Every select uses "" to leave the field as it is
+    let onchange = |set: fn(&mut TicketBulkInfo, String)| {
+        let change = change.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlSelectElement = e.target_unchecked_into();
+            let mut updated = (*change).clone();
+            set(&mut updated, input.value());
+            change.set(updated);
+        })
+    };
+    let onchange_status = onchange(|change, value| {
+        change.status = Some(value).filter(|value| !value.is_empty());
+    });
+    let onchange_priority = onchange(|change, value| {
+        change.priority = Some(value).filter(|value| !value.is_empty());
+    });
+    //"unassigned" clears the assignee, the API takes an empty string for it
+    let onchange_assignee = onchange(|change, value| {
+        change.assignee = match value.as_str() {
+            "" => None,
+            "unassigned" => Some(String::new()),
+            _ => Some(value),
+        };
+    });
+    let onchange_add_tag = onchange(|change, value| {
+        change.add_tag = Uuid::parse_str(&value).ok();
+    });
+    let onchange_remove_tag = onchange(|change, value| {
+        change.remove_tag = Uuid::parse_str(&value).ok();
+    });
+
+    let submit = {
+        let selected = props.selected.clone();
+        let change = change.clone();
+        let error = error.clone();
+        let busy = busy.clone();
+        let callback = props.callback.clone();
+        Callback::from(move |delete: bool| {
+            let info = TicketBulkInfo {
+                ticket_ids: selected.clone(),
+                delete,
+                ..(*change).clone()
+            };
+            let change = change.clone();
+            let error = error.clone();
+            let busy = busy.clone();
+            let callback = callback.clone();
+            busy.set(true);
+            wasm_bindgen_futures::spawn_local(async move {
+                match bulk_update(&info).await {
+                    Ok(response) if response.success => {
+                        error.set(String::new());
+                        change.set(TicketBulkInfo::default());
+                        callback.emit(());
+                    }
+                    Ok(response) => error.set(response.message.unwrap_or_default()),
+                    Err(e) => error.set(e.to_string()),
+                }
+                busy.set(false);
+            });
+        })
+    };
+
+    let onclick_apply = {
+        let submit = submit.clone();
+        Callback::from(move |_: MouseEvent| submit.emit(false))
+    };
+
+    let onclick_delete = {
+        let confirmation_pending = confirmation_pending.clone();
+        Callback::from(move |_: MouseEvent| confirmation_pending.set(true))
+    };
+
+    let message = format!(
+        "{} ({})",
+        language.get("Are you sure you want to delete the selected tickets?"),
+        props.selected.len()
+    );
+
+    html! {
+        <div class="bulk-actions" style="display: flex; align-items: center; margin: 4px 0px;">
+            <b>{ format!("{} {}", props.selected.len(), language.get("selected")) }</b>
+            <label style="margin-left: 8px;">{ format!("{}: ", language.get("Status")) }</label>
+            <select onchange={onchange_status}>
+                <option value="" selected={change.status.is_none()}>{ "-" }</option>
+                { for props.statuses.iter().map(|status| html! {
+                    <option value={status.name.clone()} selected={change.status.as_ref() == Some(&status.name)}>
+                        { status.name.clone() }
+                    </option>
+                })}
+            </select>
+            <label style="margin-left: 8px;">{ format!("{}: ", language.get("Priority")) }</label>
+            <select onchange={onchange_priority}>
+                <option value="" selected={change.priority.is_none()}>{ "-" }</option>
+                { for props.priorities.iter().map(|priority| html! {
+                    <option value={priority.name.clone()} selected={change.priority.as_ref() == Some(&priority.name)}>
+                        { priority.name.clone() }
+                    </option>
+                })}
+            </select>
+            <label style="margin-left: 8px;">{ format!("{}: ", language.get("Assignee")) }</label>
+            <select onchange={onchange_assignee}>
+                <option value="" selected={change.assignee.is_none()}>{ "-" }</option>
+                <option value="unassigned" selected={change.assignee.as_deref() == Some("")}>
+                    { format!("({})", language.get("Unassigned")) }
+                </option>
+                { for props.users.iter().map(|user| {
+                    let user_id = user.user_id.to_string();
+                    html! {
+                        <option value={user_id.clone()} selected={change.assignee.as_ref() == Some(&user_id)}>
+                            { user.display_name.clone() }
+                        </option>
+                    }
+                })}
+            </select>
+            if !props.tags.is_empty() {
+                <label style="margin-left: 8px;">{ format!("{}: ", language.get("Add tag")) }</label>
+                <select onchange={onchange_add_tag}>
+                    <option value="" selected={change.add_tag.is_none()}>{ "-" }</option>
+                    { for props.tags.iter().map(|tag| html! {
+                        <option value={tag.tag_id.to_string()} selected={change.add_tag == Some(tag.tag_id)}>
+                            { tag.name.clone() }
+                        </option>
+                    })}
+                </select>
+                <label style="margin-left: 8px;">{ format!("{}: ", language.get("Remove tag")) }</label>
+                <select onchange={onchange_remove_tag}>
+                    <option value="" selected={change.remove_tag.is_none()}>{ "-" }</option>
+                    { for props.tags.iter().map(|tag| html! {
+                        <option value={tag.tag_id.to_string()} selected={change.remove_tag == Some(tag.tag_id)}>
+                            { tag.name.clone() }
+                        </option>
+                    })}
+                </select>
+            }
+            <button class="btn" style="margin-left: 8px;" onclick={onclick_apply} disabled={*busy}>
+                { language.get("Apply") }
+            </button>
+            if user_ctx.role().is_admin() {
+                <button class="btn" style="margin-left: 8px;" onclick={onclick_delete} disabled={*busy}>
+                    { language.get("Delete") }
+                </button>
+            }
+            <span class="error" style="margin-left: 8px;">{ error.to_string() }</span>
+            if *confirmation_pending {
+                <Confirmation message={message} callback={Callback::from(move |confirmed| {
+                    if confirmed {
+                        submit.emit(true);
+                    }
+                    confirmation_pending.set(false);
+                })} />
+            }
+        </div>
+    }
+}
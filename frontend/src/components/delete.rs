@@ -3,23 +3,53 @@ use yew::prelude::*;
 
 use crate::components::confirmation::Confirmation;
 use crate::hooks::use_language_context;
+use crate::services::automation::delete_automation_rule;
+use crate::services::comments::delete_comment;
+use crate::services::custom_fields::delete_custom_field;
 use crate::services::documents::delete_document;
 use crate::services::notes::delete_note;
+use crate::services::projects::delete_project;
+use crate::services::recurring::delete_recurring_ticket;
+use crate::services::sla::delete_sla_policy;
+use crate::services::tags::delete_tag;
 use crate::services::tickets::delete_ticket;
+use crate::services::webhooks::delete_webhook;
+use crate::services::workflows::{delete_priority, delete_status};
 
 #[derive(Clone, PartialEq)]
 pub enum ItemTypes {
+    AutomationRule,
+    //comments are addressed through their document
+    Comment { document_id: Uuid },
+    CustomField,
     Document,
     Note,
+    Priority,
+    Project,
+    RecurringTicket,
+    SlaPolicy,
+    Status,
+    Tag,
     Ticket,
+    Webhook,
 }
 
 impl ItemTypes {
     pub fn to_string(&self) -> String {
         match self {
+            ItemTypes::AutomationRule => "automation rule",
+            ItemTypes::Comment { .. } => "comment",
+            ItemTypes::CustomField => "field",
             ItemTypes::Document => "document",
             ItemTypes::Note => "note",
+            ItemTypes::Priority => "priority",
+            ItemTypes::Project => "project",
+            ItemTypes::RecurringTicket => "recurring ticket",
+            ItemTypes::SlaPolicy => "SLA policy",
+            ItemTypes::Status => "status",
+            ItemTypes::Tag => "tag",
             ItemTypes::Ticket => "ticket",
+            ItemTypes::Webhook => "webhook",
         }
         .to_string()
     }
@@ -49,15 +79,46 @@ pub fn delete_item(props: &Props) -> Html {
             if **delete_confirmation {
                 wasm_bindgen_futures::spawn_local(async move {
                     let result = match props.item_type {
+                        ItemTypes::AutomationRule => {
+                            delete_automation_rule(Uuid::parse_str(&props.item_id).unwrap()).await
+                        }
+                        ItemTypes::Comment { document_id } => {
+                            delete_comment(document_id, Uuid::parse_str(&props.item_id).unwrap())
+                                .await
+                        }
+                        ItemTypes::CustomField => {
+                            delete_custom_field(Uuid::parse_str(&props.item_id).unwrap()).await
+                        }
                         ItemTypes::Document => {
                             delete_document(Uuid::parse_str(&props.item_id).unwrap()).await
                         }
                         ItemTypes::Note => {
                             delete_note(Uuid::parse_str(&props.item_id).unwrap()).await
                         }
+                        ItemTypes::Priority => {
+                            delete_priority(Uuid::parse_str(&props.item_id).unwrap()).await
+                        }
+                        ItemTypes::Project => {
+                            delete_project(Uuid::parse_str(&props.item_id).unwrap()).await
+                        }
+                        ItemTypes::RecurringTicket => {
+                            delete_recurring_ticket(Uuid::parse_str(&props.item_id).unwrap()).await
+                        }
+                        ItemTypes::SlaPolicy => {
+                            delete_sla_policy(Uuid::parse_str(&props.item_id).unwrap()).await
+                        }
+                        ItemTypes::Status => {
+                            delete_status(Uuid::parse_str(&props.item_id).unwrap()).await
+                        }
+                        ItemTypes::Tag => {
+                            delete_tag(Uuid::parse_str(&props.item_id).unwrap()).await
+                        }
                         ItemTypes::Ticket => {
                             delete_ticket(props.item_id.parse::<i32>().unwrap()).await
                         }
+                        ItemTypes::Webhook => {
+                            delete_webhook(Uuid::parse_str(&props.item_id).unwrap()).await
+                        }
                     };
                     if let Ok(_) = result {
                         deleted_item.set(Some(props.item_id));
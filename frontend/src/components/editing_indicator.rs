@@ -0,0 +1,36 @@
+use shared::models::live::PresenceTarget;
+use stylist::{style, yew::styled_component};
+use yew::prelude::*;
+
+use crate::contexts::live::use_editors;
+use crate::hooks::use_language_context;
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub target: PresenceTarget,
+}
+
+/// Shows who else has the ticket or document open in an editor
+#[styled_component(EditingIndicator)]
+pub fn editing_indicator(props: &Props) -> Html {
+    let language = use_language_context();
+    let editors = use_editors(props.target.clone());
+
+    let style = style!(
+        r#"
+        color: #838383;
+        font-style: italic;
+        "#
+    )
+    .expect("Failed to parse style");
+
+    if editors.is_empty() {
+        html! {}
+    } else {
+        html! {
+            <div class={style}>
+                { format!("{} {}", editors.join(", "), language.get("is editing")) }
+            </div>
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::hooks::use_language_context;
+use crate::{
+    services::users::{get_user_preferences, update_user_preferences},
+    types::UserPreferences,
+};
+
+/// Opt in or out of e-mail about assigned tickets
+#[function_component(EmailNotifications)]
+pub fn email_notifications() -> Html {
+    let language = use_language_context();
+    let enabled = use_state(|| false);
+
+    {
+        let enabled = enabled.clone();
+        use_effect_with((), move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Ok(prefs) = get_user_preferences().await {
+                    enabled.set(prefs.email_notifications.unwrap_or(false));
+                }
+            });
+            || ()
+        })
+    }
+
+    let onchange = {
+        let enabled = enabled.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let checked = input.checked();
+            enabled.set(checked);
+            wasm_bindgen_futures::spawn_local(async move {
+                let prefs = UserPreferences {
+                    email_notifications: Some(checked),
+                    ..Default::default()
+                };
+                update_user_preferences(prefs).await.unwrap();
+            });
+        })
+    };
+
+    html!(
+        <form>
+            <label>
+                <input type="checkbox" checked={*enabled} {onchange} />
+                {language.get("E-mail notifications")}
+            </label>
+        </form>
+    )
+}
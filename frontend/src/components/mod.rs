@@ -1,9 +1,17 @@
+pub mod attachments;
+pub mod bulk_actions;
 pub mod confirmation;
 pub mod delete;
+pub mod editing_indicator;
+pub mod email_notifications;
 pub mod loading;
 pub mod logout;
 pub mod nav;
+pub mod note_visibility;
+pub mod notification_bell;
 pub mod select_locale;
 pub mod select_theme;
+pub mod sla;
+pub mod tag_chip;
 pub mod ticket_list;
 pub mod time_format;
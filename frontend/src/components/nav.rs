@@ -1,7 +1,10 @@
+use shared::models::search::SearchQuery;
 use stylist::{style, yew::styled_component};
+use web_sys::HtmlInputElement;
 use yew::prelude::*;
 use yew_router::prelude::*;
 
+use crate::components::notification_bell::NotificationBell;
 use crate::components::select_theme::ThemeToggle;
 use crate::contexts::theme::use_theme;
 use crate::hooks::use_language_context;
@@ -13,6 +16,8 @@ pub fn navigation() -> Html {
     let user_ctx = use_user_context();
     let language = use_language_context();
     let theme = use_theme();
+    let navigator = use_navigator().unwrap();
+    let search_text = use_state(String::new);
 
     //when use_route changes, we change the active tabs style to selected
     let route = match use_route::<AppRoute>() {
@@ -79,6 +84,13 @@ pub fn navigation() -> Html {
             text-decoration: none;
             border: 1px solid transparent;
         }
+        .nav-search {
+            margin: 0 8px 12px;
+        }
+        .nav-search input {
+            width: 100%;
+            box-sizing: border-box;
+        }
         .nav-theme-toggle {
             font-size: 16px;
             padding-bottom: 4px;
@@ -102,6 +114,30 @@ pub fn navigation() -> Html {
     )
     .expect("Failed to parse style");
 
+    let oninput_search = {
+        let search_text = search_text.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            search_text.set(input.value());
+        })
+    };
+    let onsubmit_search = {
+        let search_text = search_text.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            if search_text.trim().is_empty() {
+                return;
+            }
+            let query = SearchQuery {
+                q: (*search_text).clone(),
+                limit: None,
+            };
+            if let Err(e) = navigator.push_with_query(&AppRoute::Search, &query) {
+                log::error!("Unable to open search: {}", e);
+            }
+        })
+    };
+
     if user_ctx.is_authenticated() {
         html! {
             <div class={style}>
@@ -117,6 +153,11 @@ pub fn navigation() -> Html {
                                 { user_ctx.display_name.clone() }
                             </Link<AppRoute>>
                         </div>
+                        <NotificationBell />
+                        <form class="nav-search" onsubmit={onsubmit_search}>
+                            <input type="search" placeholder={language.get("Search")}
+                                value={(*search_text).clone()} oninput={oninput_search} />
+                        </form>
                         <div class="nav-headers">
                             <Link<AppRoute> to={AppRoute::Home} classes={
                                 if route == AppRoute::Home {
@@ -127,6 +168,7 @@ pub fn navigation() -> Html {
                                 }>
                                 { language.get("Tickets") }
                             </Link<AppRoute>>
+                            if user_ctx.role().can_view() {
                             <Link<AppRoute> to={AppRoute::WikiHome} classes={
                                 if route == AppRoute::WikiHome {
                                 "selected nav-link"
@@ -136,6 +178,8 @@ pub fn navigation() -> Html {
                                 }>
                                 { language.get("Wiki") }
                             </Link<AppRoute>>
+                            }
+                            if user_ctx.role().can_view() {
                             <Link<AppRoute> to={AppRoute::Users} classes={
                                 if route == AppRoute::Users {
                                 "selected nav-link"
@@ -145,6 +189,18 @@ pub fn navigation() -> Html {
                                 }>
                                 { language.get("Users") }
                             </Link<AppRoute>>
+                            }
+                            if user_ctx.role().can_view() {
+                            <Link<AppRoute> to={AppRoute::Timesheet} classes={
+                                if route == AppRoute::Timesheet {
+                                "selected nav-link"
+                                } else {
+                                "nav-link"
+                                }
+                                }>
+                                { language.get("Timesheet") }
+                            </Link<AppRoute>>
+                            }
                             <Link<AppRoute> to={AppRoute::SettingsRoot} classes={
                                 if route == AppRoute::Settings || route == AppRoute::SettingsRoot {
                                 "selected nav-link"
@@ -0,0 +1,52 @@
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::hooks::use_language_context;
+use crate::{
+    services::users::{get_user_preferences, update_user_preferences},
+    types::UserPreferences,
+};
+
+/// Whether new notes are visible to the requester unless chosen otherwise
+#[function_component(NoteVisibility)]
+pub fn note_visibility() -> Html {
+    let language = use_language_context();
+    let public = use_state(|| true);
+
+    {
+        let public = public.clone();
+        use_effect_with((), move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Ok(prefs) = get_user_preferences().await {
+                    public.set(prefs.notes_public.unwrap_or(true));
+                }
+            });
+            || ()
+        })
+    }
+
+    let onchange = {
+        let public = public.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let checked = input.checked();
+            public.set(checked);
+            wasm_bindgen_futures::spawn_local(async move {
+                let prefs = UserPreferences {
+                    notes_public: Some(checked),
+                    ..Default::default()
+                };
+                update_user_preferences(prefs).await.unwrap();
+            });
+        })
+    };
+
+    html!(
+        <form>
+            <label>
+                <input type="checkbox" checked={*public} {onchange} />
+                {language.get("New notes are visible to the requester")}
+            </label>
+        </form>
+    )
+}
@@ -0,0 +1,248 @@
+use gloo::timers::callback::Interval;
+use shared::models::notifications::{NotificationQuery, AUTOMATION_NOTICE, NOTE_ADDED};
+use shared::models::tickets::parse_tagged_event_data;
+use stylist::{style, yew::styled_component};
+use uuid::Uuid;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::components::sla::sla_breach_message;
+use crate::components::time_format::TimeFormat;
+use crate::contexts::theme::use_theme;
+use crate::hooks::{use_language_context, use_user_context, LanguageContext};
+use crate::routes::ticket::link_event_message;
+use crate::routes::AppRoute;
+use crate::services::notifications::*;
+use crate::types::NotificationInfo;
+
+/// How often the unread count is refreshed
+const POLL_MILLIS: u32 = 60_000;
+const LIST_LIMIT: i64 = 20;
+
+/// Bell with the unread count, opens a list of recent notifications linking to their tickets
+#[styled_component(NotificationBell)]
+pub fn notification_bell() -> Html {
+    let theme = use_theme();
+    let language = use_language_context();
+    let user_ctx = use_user_context();
+    let unread = use_state(|| 0_i64);
+    let open = use_state(|| false);
+    let notifications = use_state(Vec::<NotificationInfo>::new);
+
+    {
+        let unread = unread.clone();
+        use_effect_with((), move |_| {
+            let refresh = move || {
+                let unread = unread.clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    if let Ok(count) = get_unread_count().await {
+                        unread.set(count.unread);
+                    }
+                });
+            };
+            refresh();
+            let interval = Interval::new(POLL_MILLIS, refresh);
+            move || drop(interval)
+        })
+    }
+
+    let onclick_bell = {
+        let open = open.clone();
+        let notifications = notifications.clone();
+        Callback::from(move |_| {
+            if !*open {
+                let notifications = notifications.clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    let query = NotificationQuery {
+                        unread: None,
+                        limit: Some(LIST_LIMIT),
+                    };
+                    match get_notifications(&query).await {
+                        Ok(list) => notifications.set(list),
+                        Err(e) => log::error!("Error loading notifications: {}", e),
+                    }
+                });
+            }
+            open.set(!*open);
+        })
+    };
+
+    let onclick_read_all = {
+        let unread = unread.clone();
+        let notifications = notifications.clone();
+        Callback::from(move |_| {
+            let unread = unread.clone();
+            let notifications = notifications.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                if mark_all_read().await.is_ok() {
+                    unread.set(0);
+                    let now = chrono::Utc::now().naive_utc();
+                    notifications.set(
+                        notifications
+                            .iter()
+                            .cloned()
+                            .map(|mut notification| {
+                                notification.read_at.get_or_insert(now);
+                                notification
+                            })
+                            .collect(),
+                    );
+                }
+            });
+        })
+    };
+
+    let onclick_item = {
+        let unread = unread.clone();
+        let open = open.clone();
+        Callback::from(move |notification: NotificationInfo| {
+            open.set(false);
+            if notification.read_at.is_some() {
+                return;
+            }
+            let unread = unread.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                if mark_read(notification.notification_id).await.is_ok() {
+                    unread.set((*unread - 1).max(0));
+                }
+            });
+        })
+    };
+
+    let style = style!(
+        r#"
+        .bell {
+            display: flex;
+            justify-content: center;
+            margin-bottom: 12px;
+        }
+        .bell button {
+            background: none;
+            border: none;
+            color: inherit;
+            cursor: pointer;
+            font-size: 18px;
+        }
+        .bell-count {
+            font-size: 12px;
+            font-weight: bold;
+            margin-left: 2px;
+        }
+        .bell-list {
+            margin: 0 8px 12px;
+            border: 1px solid ${border};
+            border-radius: 8px;
+            max-height: 50vh;
+            overflow-y: auto;
+            font-size: 14px;
+        }
+        .bell-item {
+            padding: 4px 8px;
+            border-bottom: 1px solid ${border};
+        }
+        .bell-item.unread {
+            font-weight: bold;
+        }
+        .bell-item a {
+            display: block;
+            text-decoration: none;
+        }
+        .bell-actions {
+            padding: 4px 8px;
+            text-align: right;
+        }
+        "#,
+        border = theme.border.clone(),
+    )
+    .expect("Failed to parse style");
+
+    html! {
+        <div class={style}>
+            <div class="bell">
+                <button onclick={onclick_bell} title={language.get("Notifications")}>
+                    { "🔔" }
+                    if *unread > 0 {
+                        <span class="bell-count">{ *unread }</span>
+                    }
+                </button>
+            </div>
+            if *open {
+                <div class="bell-list">
+                    if notifications.is_empty() {
+                        <div class="bell-item">{ language.get("No notifications") }</div>
+                    }
+                    { for notifications.iter().map(|notification| {
+                        let onclick = {
+                            let onclick_item = onclick_item.clone();
+                            let notification = notification.clone();
+                            Callback::from(move |_| onclick_item.emit(notification.clone()))
+                        };
+                        html! {
+                            <div class={classes!("bell-item", notification.read_at.is_none().then_some("unread"))}
+                                {onclick}>
+                                <Link<AppRoute> to={AppRoute::Ticket { ticket_id: notification.ticket_id }}>
+                                    { format!("#{} {}", notification.ticket_id, notification.ticket_title) }
+                                    <div>{ describe(notification, user_ctx.user_id, &language) }</div>
+                                    <TimeFormat time={notification.created_at} />
+                                </Link<AppRoute>>
+                            </div>
+                        }
+                    })}
+                    if *unread > 0 {
+                        <div class="bell-actions">
+                            <button class="page-btn" onclick={onclick_read_all}>
+                                { language.get("Mark all as read") }
+                            </button>
+                        </div>
+                    }
+                </div>
+            }
+        </div>
+    }
+}
+
+fn describe(
+    notification: &NotificationInfo,
+    user_id: Uuid,
+    language: &LanguageContext,
+) -> String {
+    let actor = notification
+        .actor
+        .clone()
+        .unwrap_or_else(|| language.get("Someone"));
+    let data = &notification.event_data;
+
+    match notification.event_type.as_str() {
+        "assigned" if data.is_empty() => format!("{} {}", actor, language.get("unassigned ticket")),
+        "assigned" if data == &user_id.to_string() => {
+            format!("{} {}", actor, language.get("assigned ticket to you"))
+        }
+        "assigned" => format!("{} {}", actor, language.get("reassigned ticket")),
+        "status_updated" => format!("{} {} {}", actor, language.get("updated ticket status to"), data),
+        "priority_updated" => format!(
+            "{} {} {}",
+            actor,
+            language.get("updated ticket priority to"),
+            data
+        ),
+        "title_updated" => format!("{} {} {}", actor, language.get("updated ticket title to"), data),
+        "due_date_updated" => format!("{} {}", actor, language.get("updated due date")),
+        "tagged" => match parse_tagged_event_data(data) {
+            (true, tag) => format!("{} {} {}", actor, language.get("added tag"), tag),
+            (false, tag) => format!("{} {} {}", actor, language.get("removed tag"), tag),
+        },
+        "task_completed" => format!("{} {} {}", actor, language.get("completed task"), data),
+        "task_reopened" => format!("{} {} {}", actor, language.get("reopened task"), data),
+        "sla_breached" => sla_breach_message(data, language),
+        "merged" => format!("{} {} #{}", actor, language.get("merged ticket"), data),
+        "merged_into" => format!("{} {} #{}", actor, language.get("merged this ticket into"), data),
+        "linked" | "unlinked" | "closed_as_duplicate" => format!(
+            "{} {}",
+            actor,
+            link_event_message(&notification.event_type, data, language)
+        ),
+        NOTE_ADDED => format!("{} {}", actor, language.get("added a note")),
+        AUTOMATION_NOTICE => data.clone(),
+        _ => language.get("Unknown event"),
+    }
+}
@@ -0,0 +1,33 @@
+use shared::models::sla::{SlaStatus, RESPONSE_TARGET};
+
+use crate::hooks::LanguageContext;
+use crate::types::TicketSlaInfo;
+
+/// Translated name of an SLA status like "at_risk"
+pub(crate) fn sla_label(sla_status: &str, language: &LanguageContext) -> String {
+    match sla_status.parse::<SlaStatus>() {
+        Ok(status) => language.get(status.label()),
+        Err(_) => sla_status.to_string(),
+    }
+}
+
+/// Describes an `sla_breached` event, the data is the missed target
+pub(crate) fn sla_breach_message(target: &str, language: &LanguageContext) -> String {
+    if target == RESPONSE_TARGET {
+        language.get("SLA response target missed")
+    } else {
+        language.get("SLA resolution target missed")
+    }
+}
+
+/// The next pending SLA deadline, none once the ticket is resolved
+pub fn next_due(sla: &TicketSlaInfo) -> Option<chrono::NaiveDateTime> {
+    if sla.resolved_at.is_some() {
+        return None;
+    }
+    let response_due = sla.response_due.filter(|_| sla.first_response_at.is_none());
+    [response_due, sla.resolution_due]
+        .into_iter()
+        .flatten()
+        .min()
+}
@@ -0,0 +1,80 @@
+use stylist::{style, yew::styled_component};
+use yew::prelude::*;
+
+use crate::types::TagInfo;
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub tag: TagInfo,
+    /// Makes the chip clickable, e.g. to filter by the tag
+    #[prop_or_default]
+    pub onclick: Option<Callback<TagInfo>>,
+    /// Shows a remove button
+    #[prop_or_default]
+    pub onremove: Option<Callback<TagInfo>>,
+}
+
+/// A tag as a small label in its own color
+#[styled_component(TagChip)]
+pub fn tag_chip(props: &Props) -> Html {
+    let style = style!(
+        r#"
+        display: inline-block;
+        margin: 1px 4px 1px 0px;
+        padding: 0px 8px;
+        border-radius: 10px;
+        font-size: 0.85em;
+        white-space: nowrap;
+        background-color: ${bg};
+        color: ${fg};
+        .tag-remove {
+            margin-left: 4px;
+            cursor: pointer;
+        }
+        "#,
+        bg = props.tag.color.clone(),
+        fg = text_color(&props.tag.color),
+    )
+    .expect("Failed to parse style");
+
+    let onclick = props.onclick.clone().map(|callback| {
+        let tag = props.tag.clone();
+        Callback::from(move |e: MouseEvent| {
+            e.prevent_default();
+            callback.emit(tag.clone());
+        })
+    });
+
+    let onclick_remove = props.onremove.clone().map(|callback| {
+        let tag = props.tag.clone();
+        Callback::from(move |e: MouseEvent| {
+            e.stop_propagation();
+            callback.emit(tag.clone());
+        })
+    });
+
+    html! {
+        <span class={style} {onclick} style={ if props.onclick.is_some() { "cursor: pointer;" } else { "" } }>
+            { &props.tag.name }
+            if let Some(onclick_remove) = onclick_remove {
+                <span class="tag-remove" onclick={onclick_remove}>{ "✘" }</span>
+            }
+        </span>
+    }
+}
+
+/// Black or white, whichever is easier to read on `color` (#rrggbb)
+fn text_color(color: &str) -> &'static str {
+    let channel = |i: usize| {
+        color
+            .get(i..i + 2)
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            .unwrap_or(128) as f64
+    };
+    let luminance = 0.299 * channel(1) + 0.587 * channel(3) + 0.114 * channel(5);
+    if luminance > 150.0 {
+        "#000"
+    } else {
+        "#fff"
+    }
+}
@@ -1,6 +1,8 @@
 use chrono::Local;
 use gloo::utils::document;
-use shared::models::tickets::TicketFilterPayload;
+use shared::models::custom_fields::{CustomFieldType, FIELD_SORT_PREFIX};
+use shared::models::sla::SlaStatus;
+use shared::models::tickets::{TicketFilterPayload, MAX_BULK_TICKETS};
 use stylist::{style, yew::styled_component};
 use uuid::Uuid;
 use wasm_bindgen::JsCast;
@@ -9,17 +11,25 @@ use web_sys::HtmlSelectElement;
 use yew::prelude::*;
 use yew::suspense::use_future;
 use yew::suspense::use_future_with;
-use yew_router::prelude::use_navigator;
-use yew_router::prelude::Link;
+use yew_router::prelude::{use_location, use_navigator, Link, Location};
 
+use shared::models::live::LiveEvent;
+
+use crate::components::bulk_actions::BulkActions;
 use crate::components::loading::Loading;
+use crate::components::sla::{next_due, sla_label};
+use crate::components::tag_chip::TagChip;
+use crate::contexts::live::use_live_events;
 use crate::contexts::theme::use_theme;
 use crate::contexts::time::use_time;
 use crate::hooks::use_language_context;
 use crate::hooks::use_user_context;
 use crate::routes::AppRoute;
-use crate::services::{tickets::*, users::get_users};
-use crate::types::TicketListInfo;
+use crate::services::{
+    custom_fields::get_custom_fields, projects::get_projects, tags::get_tags, tickets::*,
+    users::get_users, workflows::{get_priorities, get_statuses},
+};
+use crate::types::{TagInfo, TicketFieldInfo, TicketListInfo, UserRepresentation};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Filter {
@@ -28,6 +38,7 @@ pub struct Filter {
     pub priority: Option<String>,
 }
 
+/// Status categories, the filter also takes the name of a single status
 #[derive(Clone, Debug, PartialEq)]
 pub enum StatusFilter {
     Open,
@@ -37,8 +48,8 @@ pub enum StatusFilter {
 impl ToString for StatusFilter {
     fn to_string(&self) -> String {
         match self {
-            StatusFilter::Open => "Open".to_string(),
-            StatusFilter::Closed => "Closed".to_string(),
+            StatusFilter::Open => "open".to_string(),
+            StatusFilter::Closed => "closed".to_string(),
         }
     }
 }
@@ -56,23 +67,81 @@ pub enum TicketListSort {
     ByPriorityAsc,
 }
 
+/// Open tickets assigned to the user, most urgent first
+pub fn default_filter(user_id: Uuid) -> TicketFilterPayload {
+    TicketFilterPayload {
+        assignee: Some(user_id),
+        status: Some(StatusFilter::Open.to_string()),
+        page: Some(1),
+        per_page: Some(50),
+        sort_by: Some("priority".to_string()),
+        sort_order: Some("asc".to_string()),
+        search: None,
+        watching: None,
+        project: None,
+        tags: None,
+        exclude_tags: None,
+        field: None,
+        field_value: None,
+        sla_status: None,
+        breached: None,
+    }
+}
+
+/// Filter kept in the query string of the ticket list, None without a query
+pub fn filter_from_location(location: &Location) -> Option<TicketFilterPayload> {
+    if location.query_str().trim_start_matches('?').is_empty() {
+        return None;
+    }
+    location.query::<TicketFilterPayload>().ok()
+}
+
 #[styled_component(TicketList)]
 pub fn ticket_list() -> Html {
     let user_ctx = use_user_context();
     let language = use_language_context();
     let theme = use_theme();
     let navigator = use_navigator().unwrap();
+    let location = use_location();
     let ticket_list = use_state(|| TicketListInfo::default());
-    let filter = use_state(|| TicketFilterPayload {
-        assignee: Some(user_ctx.user_id.clone()),
-        status: Some(StatusFilter::Open.to_string()),
-        page: Some(1),
-        per_page: Some(50),
-        sort_by: Some("priority".to_string()),
-        sort_order: Some("asc".to_string()),
-        search: None,
-    });
+    //The filter is mirrored in the URL so lists can be bookmarked, shared and saved as views
+    let url_filter = location.as_ref().and_then(filter_from_location);
+    let filter = {
+        let url_filter = url_filter.clone();
+        let user_id = user_ctx.user_id;
+        use_state(move || url_filter.unwrap_or_else(|| default_filter(user_id)))
+    };
     let loading = use_state(|| false);
+
+    //Following a link or going back in history changes the URL, a URL without a query resets the filter
+    {
+        let filter = filter.clone();
+        let loading = loading.clone();
+        let user_id = user_ctx.user_id;
+        use_effect_with(url_filter.clone(), move |url_filter| {
+            let wanted = url_filter
+                .clone()
+                .unwrap_or_else(|| default_filter(user_id));
+            if wanted != *filter {
+                loading.set(true);
+                filter.set(wanted);
+            }
+            || ()
+        });
+    }
+
+    {
+        let navigator = navigator.clone();
+        let url_filter = url_filter.clone();
+        use_effect_with((*filter).clone(), move |filter| {
+            if url_filter.as_ref() != Some(filter) {
+                if let Err(e) = navigator.replace_with_query(&AppRoute::Home, filter) {
+                    log::error!("Error updating the URL: {}", e);
+                }
+            }
+            || ()
+        });
+    }
     let time_ctx = use_time();
 
     let users = use_future(|| async { get_users().await.unwrap_or_default() });
@@ -82,13 +151,97 @@ pub fn ticket_list() -> Html {
         Err(_) => vec![],
     };
 
+    let projects = match use_future(|| async { get_projects().await.unwrap_or_default() }) {
+        Ok(projects) => projects.clone(),
+        Err(_) => vec![],
+    };
+
+    let tags = match use_future(|| async { get_tags().await.unwrap_or_default() }) {
+        Ok(tags) => tags.clone(),
+        Err(_) => vec![],
+    };
+
+    let custom_fields = match use_future(|| async { get_custom_fields().await.unwrap_or_default() }) {
+        Ok(fields) => fields.clone(),
+        Err(_) => vec![],
+    };
+
+    let statuses = match use_future(|| async { get_statuses().await.unwrap_or_default() }) {
+        Ok(statuses) => statuses.clone(),
+        Err(_) => vec![],
+    };
+
+    let priorities = match use_future(|| async { get_priorities().await.unwrap_or_default() }) {
+        Ok(priorities) => priorities.clone(),
+        Err(_) => vec![],
+    };
+
+    //Bumped whenever any ticket changes, refetches the current page
+    let version = use_state(|| 0_u32);
+    {
+        let version = version.clone();
+        use_live_events(Callback::from(move |event| {
+            if let LiveEvent::TicketChanged { .. } = event {
+                version.set(*version + 1);
+            }
+        }));
+    }
+
+    //Ticket ids checked for bulk changes, only those on the current page are used
+    let selected = use_state(Vec::<i32>::new);
+    let can_edit = user_ctx.role().can_edit();
+    let selection = ticket_list
+        .tickets
+        .iter()
+        .map(|ticket| ticket.ticket_id)
+        .filter(|id| selected.contains(id))
+        .collect::<Vec<i32>>();
+
+    let onchange_select = |ticket_id: i32| {
+        let selected = selected.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut ids = (*selected).clone();
+            ids.retain(|id| *id != ticket_id);
+            if input.checked() && ids.len() < MAX_BULK_TICKETS {
+                ids.push(ticket_id);
+            }
+            selected.set(ids);
+        })
+    };
+
+    let onchange_select_all = {
+        let selected = selected.clone();
+        let ids = ticket_list
+            .tickets
+            .iter()
+            .map(|ticket| ticket.ticket_id)
+            .take(MAX_BULK_TICKETS)
+            .collect::<Vec<i32>>();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            selected.set(if input.checked() { ids.clone() } else { vec![] });
+        })
+    };
+
+    //Reload right away, live events may arrive later
+    let callback_bulk = {
+        let selected = selected.clone();
+        let version = version.clone();
+        Callback::from(move |_| {
+            selected.set(vec![]);
+            version.set(*version + 1);
+        })
+    };
+
     //API call to get (filtered) tickets
     {
         let filter = &*filter.clone();
         let loading = loading.clone();
         let ticket_list = ticket_list.clone();
-        match use_future_with(filter.clone(), |filter| async move {
-            let result = get_filtered(&filter).await;
+        match use_future_with((filter.clone(), *version), |deps| async move {
+            let filter = &deps.0;
+            let result = get_filtered(filter).await;
             match result {
                 Ok(tickets) => {
                     loading.set(false);
@@ -112,24 +265,19 @@ pub fn ticket_list() -> Html {
         Callback::from(move |e: Event| {
             let input: HtmlSelectElement = e.target_unchecked_into();
             let value = input.value();
-            let mut new_filter = TicketFilterPayload {
-                assignee: filter.assignee.clone(),
-                status: filter.status.clone(),
-                page: filter.page.clone(),
-                per_page: filter.per_page.clone(),
-                sort_by: filter.sort_by.clone(),
-                sort_order: filter.sort_order.clone(),
-                search: filter.search.clone(),
-            };
-            if value == "unassigned".to_string() {
-                new_filter.assignee = Some(Uuid::nil());
-            } else if value == "all".to_string() {
-                new_filter.assignee = None;
-            } else {
-                new_filter.assignee = Some(Uuid::parse_str(&value).unwrap());
-            }
-            loading.set(true);
-            filter.set(new_filter);
+            set_filter(&filter, &loading, move |new_filter| {
+                new_filter.watching = None;
+                match value.as_str() {
+                    "unassigned" => new_filter.assignee = Some(Uuid::nil()),
+                    //Tickets the user follows, regardless of assignee
+                    "watching" => {
+                        new_filter.assignee = None;
+                        new_filter.watching = Some(true);
+                    }
+                    "all" => new_filter.assignee = None,
+                    _ => new_filter.assignee = Some(Uuid::parse_str(&value).unwrap()),
+                }
+            });
         })
     };
 
@@ -139,15 +287,124 @@ pub fn ticket_list() -> Html {
         Callback::from(move |e: Event| {
             let input: HtmlSelectElement = e.target_unchecked_into();
             let value = input.value();
-            loading.set(true);
-            filter.set(TicketFilterPayload {
-                assignee: filter.assignee.clone(),
-                status: Some(value),
-                page: filter.page.clone(),
-                per_page: filter.per_page.clone(),
-                sort_by: filter.sort_by.clone(),
-                sort_order: filter.sort_order.clone(),
-                search: filter.search.clone(),
+            set_filter(&filter, &loading, move |new_filter| {
+                new_filter.status = Some(value);
+            });
+        })
+    };
+
+    let onclick_filter_project = {
+        let filter = filter.clone();
+        let loading = loading.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlSelectElement = e.target_unchecked_into();
+            let value = input.value();
+            set_filter(&filter, &loading, move |new_filter| {
+                new_filter.page = Some(1);
+                new_filter.project = match value.as_str() {
+                    "none" => Some(Uuid::nil()),
+                    "all" => None,
+                    _ => Uuid::parse_str(&value).ok(),
+                };
+            });
+        })
+    };
+
+    //Tag filters take a single tag from the list, "" clears them
+    let onclick_filter_tag = {
+        let filter = filter.clone();
+        let loading = loading.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlSelectElement = e.target_unchecked_into();
+            let value = input.value();
+            set_filter(&filter, &loading, move |new_filter| {
+                new_filter.page = Some(1);
+                new_filter.tags = Some(value).filter(|value| !value.is_empty());
+            });
+        })
+    };
+
+    let onclick_filter_exclude_tag = {
+        let filter = filter.clone();
+        let loading = loading.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlSelectElement = e.target_unchecked_into();
+            let value = input.value();
+            set_filter(&filter, &loading, move |new_filter| {
+                new_filter.page = Some(1);
+                new_filter.exclude_tags = Some(value).filter(|value| !value.is_empty());
+            });
+        })
+    };
+
+    //SLA statuses, or "not_breached" for every ticket that has not missed a target
+    let onclick_filter_sla = {
+        let filter = filter.clone();
+        let loading = loading.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlSelectElement = e.target_unchecked_into();
+            let value = input.value();
+            let not_breached = value == "not_breached";
+            set_filter(&filter, &loading, move |new_filter| {
+                new_filter.page = Some(1);
+                new_filter.sla_status =
+                    Some(value).filter(|value| !value.is_empty() && !not_breached);
+                new_filter.breached = if not_breached { Some(false) } else { None };
+            });
+        })
+    };
+
+    //Clicking a tag on a ticket shows only tickets with that tag
+    let onclick_tag = {
+        let filter = filter.clone();
+        let loading = loading.clone();
+        Callback::from(move |tag: TagInfo| {
+            if filter.tags.as_deref() == Some(tag.name.as_str()) {
+                return;
+            }
+            set_filter(&filter, &loading, move |new_filter| {
+                new_filter.page = Some(1);
+                new_filter.tags = Some(tag.name);
+            });
+        })
+    };
+
+    //An empty value finds tickets where the field is not filled in
+    let onsubmit_field_filter = {
+        let filter = filter.clone();
+        let loading = loading.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let field: HtmlSelectElement = document()
+                .get_element_by_id("field-filter")
+                .unwrap()
+                .unchecked_into();
+            let value: HtmlInputElement = document()
+                .get_element_by_id("field-value")
+                .unwrap()
+                .unchecked_into();
+            let field = Uuid::parse_str(&field.value()).ok();
+            set_filter(&filter, &loading, move |new_filter| {
+                new_filter.page = Some(1);
+                new_filter.field = field;
+                new_filter.field_value = field.map(|_| value.value());
+            });
+        })
+    };
+
+    let onclick_clear_field_filter = {
+        let filter = filter.clone();
+        let loading = loading.clone();
+        Callback::from(move |_| {
+            let value: HtmlInputElement = document()
+                .get_element_by_id("field-value")
+                .unwrap()
+                .unchecked_into();
+            value.set_value("");
+            set_filter(&filter, &loading, move |new_filter| {
+                new_filter.page = Some(1);
+                new_filter.field = None;
+                new_filter.field_value = None;
             });
         })
     };
@@ -204,15 +461,21 @@ pub fn ticket_list() -> Html {
         td.priority {
             text-align: center;
         }
-        td.priority-High {
+        td.priority-level-0 {
             background-color: rgb(255 31 31 / 40%);
         }
-        td.priority-Medium {
+        td.priority-level-1 {
             background-color: rgb(255 127 31 / 40%);
         }
         td.overdue {
             background-color: rgb(255 31 31 / 40%);
         }
+        td.sla-breached {
+            background-color: rgb(255 31 31 / 40%);
+        }
+        td.sla-at_risk {
+            background-color: rgb(255 127 31 / 40%);
+        }
     "#,
         table_header = theme.secondary_background.clone(),
         bg = theme.background.clone(),
@@ -223,20 +486,11 @@ pub fn ticket_list() -> Html {
         let filter = filter.clone();
         let loading = loading.clone();
         Callback::from(move |_| {
-            let mut new_filter = TicketFilterPayload {
-                assignee: filter.assignee.clone(),
-                status: filter.status.clone(),
-                page: filter.page.clone(),
-                per_page: filter.per_page.clone(),
-                sort_by: filter.sort_by.clone(),
-                sort_order: filter.sort_order.clone(),
-                search: filter.search.clone(),
-            };
-            if new_filter.page.unwrap() > 1 {
-                new_filter.page = Some(new_filter.page.unwrap() - 1);
-            }
-            loading.set(true);
-            filter.set(new_filter);
+            set_filter(&filter, &loading, |new_filter| {
+                if new_filter.page.unwrap() > 1 {
+                    new_filter.page = Some(new_filter.page.unwrap() - 1);
+                }
+            });
         })
     };
 
@@ -244,18 +498,9 @@ pub fn ticket_list() -> Html {
         let filter = filter.clone();
         let loading = loading.clone();
         Callback::from(move |_| {
-            let mut new_filter = TicketFilterPayload {
-                assignee: filter.assignee.clone(),
-                status: filter.status.clone(),
-                page: filter.page.clone(),
-                per_page: filter.per_page.clone(),
-                sort_by: filter.sort_by.clone(),
-                sort_order: filter.sort_order.clone(),
-                search: filter.search.clone(),
-            };
-            new_filter.page = Some(new_filter.page.unwrap() + 1);
-            loading.set(true);
-            filter.set(new_filter);
+            set_filter(&filter, &loading, |new_filter| {
+                new_filter.page = Some(new_filter.page.unwrap() + 1);
+            });
         })
     };
 
@@ -271,15 +516,9 @@ pub fn ticket_list() -> Html {
             match input.value().parse::<i64>() {
                 Ok(value) => {
                     if value > 0 && value != filter.per_page.unwrap() {
-                        loading.set(true);
-                        filter.set(TicketFilterPayload {
-                            assignee: filter.assignee.clone(),
-                            status: filter.status.clone(),
-                            page: Some(1),
-                            per_page: Some(value),
-                            sort_by: filter.sort_by.clone(),
-                            sort_order: filter.sort_order.clone(),
-                            search: filter.search.clone(),
+                        set_filter(&filter, &loading, move |new_filter| {
+                            new_filter.page = Some(1);
+                            new_filter.per_page = Some(value);
                         });
                     }
                 }
@@ -301,28 +540,14 @@ pub fn ticket_list() -> Html {
             //If Some(filter.search) matches value, do nothing.  If search is something, change it, if it is "", set it to None
             if filter.search.is_some() {
                 if filter.search.as_ref().unwrap() != &value {
-                    loading.set(true);
-                    filter.set(TicketFilterPayload {
-                        assignee: filter.assignee.clone(),
-                        status: filter.status.clone(),
-                        page: filter.page.clone(),
-                        per_page: filter.per_page.clone(),
-                        sort_by: filter.sort_by.clone(),
-                        sort_order: filter.sort_order.clone(),
-                        search: Some(value),
+                    set_filter(&filter, &loading, move |new_filter| {
+                        new_filter.search = Some(value);
                     });
                 }
             } else {
                 if value != "" {
-                    loading.set(true);
-                    filter.set(TicketFilterPayload {
-                        assignee: filter.assignee.clone(),
-                        status: filter.status.clone(),
-                        page: filter.page.clone(),
-                        per_page: filter.per_page.clone(),
-                        sort_by: filter.sort_by.clone(),
-                        sort_order: filter.sort_order.clone(),
-                        search: Some(value),
+                    set_filter(&filter, &loading, move |new_filter| {
+                        new_filter.search = Some(value);
                     });
                 }
             }
@@ -340,15 +565,8 @@ pub fn ticket_list() -> Html {
                     .unwrap()
                     .unchecked_into();
                 input.set_value("");
-                loading.set(true);
-                filter.set(TicketFilterPayload {
-                    assignee: filter.assignee.clone(),
-                    status: filter.status.clone(),
-                    page: filter.page.clone(),
-                    per_page: filter.per_page.clone(),
-                    sort_by: filter.sort_by.clone(),
-                    sort_order: filter.sort_order.clone(),
-                    search: None,
+                set_filter(&filter, &loading, move |new_filter| {
+                    new_filter.search = None;
                 });
             }
         })
@@ -365,26 +583,86 @@ pub fn ticket_list() -> Html {
                 <div>
                     <label style="margin-left: 8px;" for="assignee">{"Assignee: "}</label>
                     <select name="assignee" id="assignee" onchange={onclick_filter_assignee}>
-                        <option value={user_ctx.user_id.to_string()} selected=true>{user_ctx.display_name.clone()}
+                        <option value={user_ctx.user_id.to_string()} selected={filter.watching.is_none() && filter.assignee == Some(user_ctx.user_id)}>{user_ctx.display_name.clone()}
                         </option>
-                        <option value="all">{"(All)"}</option>
-                        <option value="unassigned">{"(Unassigned)"}</option>
+                        <option value="all" selected={filter.watching.is_none() && filter.assignee.is_none()}>{"(All)"}</option>
+                        <option value="unassigned" selected={filter.watching.is_none() && filter.assignee == Some(Uuid::nil())}>{"(Unassigned)"}</option>
+                        <option value="watching" selected={filter.watching == Some(true)}>{format!("({})", language.get("Watching"))}</option>
                         { for userlist.iter().map(|user| html! {
                         if user.user_id != user_ctx.user_id {
-                        <option value={user.user_id.to_string()}>{user.display_name.clone()}</option>
+                        <option value={user.user_id.to_string()} selected={filter.watching.is_none() && filter.assignee == Some(user.user_id)}>{user.display_name.clone()}</option>
                         }
                         })}
                     </select>
                     <label style="margin-left: 8px;" for="status">{"Status: "}</label>
                     <select name="status" id="status" onchange={onclick_filter_status}>
-                        <option value="Open" selected=true>{language.get("Open")}</option>
-                        <option value="Closed">{language.get("Closed")}</option>
+                        <option value={StatusFilter::Open.to_string()} selected={filter.status == Some(StatusFilter::Open.to_string())}>{format!("({})", language.get("Open"))}</option>
+                        <option value={StatusFilter::Closed.to_string()} selected={filter.status == Some(StatusFilter::Closed.to_string())}>{format!("({})", language.get("Closed"))}</option>
+                        <option value="" selected={filter.status.as_deref().unwrap_or_default().is_empty()}>{"(All)"}</option>
+                        { for statuses.iter().map(|status| html! {
+                        <option value={status.name.clone()} selected={filter.status.as_ref() == Some(&status.name)}>{status.name.clone()}</option>
+                        })}
+                    </select>
+                    if !projects.is_empty() {
+                        <label style="margin-left: 8px;" for="project">{format!("{}: ", language.get("Project"))}</label>
+                        <select name="project" id="project" onchange={onclick_filter_project}>
+                            <option value="all" selected={filter.project.is_none()}>{"(All)"}</option>
+                            <option value="none" selected={filter.project == Some(Uuid::nil())}>{format!("({})", language.get("No project"))}</option>
+                            { for projects.iter().map(|project| html! {
+                            <option value={project.project_id.to_string()} selected={filter.project == Some(project.project_id)}>{project.name.clone()}</option>
+                            })}
+                        </select>
+                    }
+                    <label style="margin-left: 8px;" for="sla">{"SLA: "}</label>
+                    <select name="sla" id="sla" onchange={onclick_filter_sla}>
+                        <option value="" selected={filter.sla_status.is_none() && filter.breached.is_none()}>{"(All)"}</option>
+                        <option value="not_breached" selected={filter.breached == Some(false)}>{format!("({})", language.get("Not breached"))}</option>
+                        { for SlaStatus::iter().map(|status| html! {
+                        <option value={status.to_string()} selected={filter.sla_status.as_deref() == Some(status.as_str())}>{language.get(status.label())}</option>
+                        })}
                     </select>
+                    if !tags.is_empty() {
+                        <label style="margin-left: 8px;" for="tag">{format!("{}: ", language.get("Tag"))}</label>
+                        <select name="tag" id="tag" onchange={onclick_filter_tag}>
+                            <option value="" selected={filter.tags.is_none()}>{"(All)"}</option>
+                            { for tags.iter().map(|tag| html! {
+                            <option value={tag.name.clone()} selected={filter.tags.as_deref() == Some(tag.name.as_str())}>{tag.name.clone()}</option>
+                            })}
+                        </select>
+                        <label style="margin-left: 8px;" for="exclude-tag">{format!("{}: ", language.get("Exclude tag"))}</label>
+                        <select name="exclude-tag" id="exclude-tag" onchange={onclick_filter_exclude_tag}>
+                            <option value="" selected={filter.exclude_tags.is_none()}>{"(None)"}</option>
+                            { for tags.iter().map(|tag| html! {
+                            <option value={tag.name.clone()} selected={filter.exclude_tags.as_deref() == Some(tag.name.as_str())}>{tag.name.clone()}</option>
+                            })}
+                        </select>
+                    }
                 </div>
+                if !custom_fields.is_empty() {
+                    <form onsubmit={onsubmit_field_filter} style="margin-left: 32px;">
+                        <select id="field-filter">
+                            <option value="" selected={filter.field.is_none()}>{format!("({})", language.get("Field"))}</option>
+                            { for custom_fields.iter().map(|field| html! {
+                            <option value={field.field_id.to_string()} selected={filter.field == Some(field.field_id)}>{field.name.clone()}</option>
+                            })}
+                        </select>
+                        <input style="margin: 0px; width: 120px;" type="text" id="field-value" placeholder={language.get("Value")}
+                            value={filter.field_value.clone().unwrap_or_default()} />
+                        <button class="page-btn" type="submit">
+                            { "✔" }
+                        </button>
+                        if filter.field.is_some() {
+                            <button class="page-btn" type="button" onclick={onclick_clear_field_filter}>
+                                { "✘" }
+                            </button>
+                        }
+                    </form>
+                }
                 <div>
                 <form onsubmit={onclick_search} style="margin-left: 32px;">
                     <label for="search">{"Filter: "}</label>
-                    <input style="margin: 0px;" type="text" id="search" placeholder={language.get("Filter")} />
+                    <input style="margin: 0px;" type="text" id="search" placeholder={language.get("Filter")}
+                        value={filter.search.clone().unwrap_or_default()} />
                     <button class="page-btn" type="submit">
                         { language.get("✔") }
                     </button>
@@ -401,10 +679,21 @@ pub fn ticket_list() -> Html {
                </form>
                 </div>
             </div>
+            if !selection.is_empty() {
+                <BulkActions selected={selection.clone()} statuses={statuses.clone()}
+                    priorities={priorities.clone()} users={userlist.clone()} tags={tags.clone()}
+                    callback={callback_bulk} />
+            }
             <div class={ticket_table_style}>
                 <table class="table ticket-table">
                     <thead>
                         <tr>
+                            if can_edit {
+                                <th scope="col">
+                                    <input type="checkbox" onchange={onchange_select_all}
+                                        checked={!ticket_list.tickets.is_empty() && selection.len() == ticket_list.tickets.len().min(MAX_BULK_TICKETS)} />
+                                </th>
+                            }
                             <th onclick={onclick_sort_by("ticket_id", &filter, &loading)} scope="col">{language.get("No.")}{if filter.sort_by.clone().unwrap() == "ticket_id" {if filter.sort_order.clone().unwrap() == "asc" {html! {"▼"}} else {html! {"▲"}}} else {html! {"　"}}}</th>
                             <th onclick={onclick_sort_by("title", &filter, &loading)} scope="col">{language.get("Title")}{if filter.sort_by.clone().unwrap() == "title" {if filter.sort_order.clone().unwrap() == "asc" {html! {"▼"}} else {html! {"▲"}}} else {html! {"　"}}}</th>
                             <th onclick={onclick_sort_by("assignee", &filter, &loading)} scope="col">{language.get("Assignee")}{if filter.sort_by.clone().unwrap() == "assignee" {if filter.sort_order.clone().unwrap() == "asc" {html! {"▼"}} else {html! {"▲"}}} else {html! {"　"}}}</th>
@@ -412,6 +701,13 @@ pub fn ticket_list() -> Html {
                             <th onclick={onclick_sort_by("updated_at", &filter, &loading)} scope="col">{language.get("Updated")}{if filter.sort_by.clone().unwrap() == "updated_at" {if filter.sort_order.clone().unwrap() == "asc" {html! {"▼"}} else {html! {"▲"}}} else {html! {"　"}}}</th>
                             <th onclick={onclick_sort_by("due_date", &filter, &loading)} scope="col">{language.get("Due")}{if filter.sort_by.clone().unwrap() == "due_date" {if filter.sort_order.clone().unwrap() == "asc" {html! {"▼"}} else {html! {"▲"}}} else {html! {"　"}}}</th>
                             <th onclick={onclick_sort_by("priority", &filter, &loading)} scope="col">{language.get("Priority")}{if filter.sort_by.clone().unwrap() == "priority" {if filter.sort_order.clone().unwrap() == "asc" {html! {"▼"}} else {html! {"▲"}}} else {html! {"　"}}}</th>
+                            <th onclick={onclick_sort_by("resolution_due", &filter, &loading)} scope="col">{"SLA"}{if filter.sort_by.clone().unwrap() == "resolution_due" {if filter.sort_order.clone().unwrap() == "asc" {html! {"▼"}} else {html! {"▲"}}} else {html! {"　"}}}</th>
+                            { for custom_fields.iter().map(|field| {
+                                let sort_key = format!("{}{}", FIELD_SORT_PREFIX, field.field_id);
+                                html! {
+                                <th onclick={onclick_sort_by(&sort_key, &filter, &loading)} scope="col">{field.name.clone()}{if filter.sort_by.clone().unwrap() == sort_key {if filter.sort_order.clone().unwrap() == "asc" {html! {"▼"}} else {html! {"▲"}}} else {html! {"　"}}}</th>
+                                }
+                            })}
                         </tr>
                     </thead>
                     {
@@ -422,10 +718,19 @@ pub fn ticket_list() -> Html {
                         {for ticket_list.tickets.iter().map(|ticket| {
                         html! {
                         <tr class="ticket-row">
+                            if can_edit {
+                                <td>
+                                    <input type="checkbox" onchange={onchange_select(ticket.ticket_id)}
+                                        checked={selection.contains(&ticket.ticket_id)} />
+                                </td>
+                            }
                             <td>
                                 <div>
                                     { &ticket.ticket_id }
                                 </div>
+                                if let Some(reference) = &ticket.reference {
+                                    <div class="info">{ reference }</div>
+                                }
                             </td>
                             <td>
                                 <div>
@@ -435,6 +740,18 @@ pub fn ticket_list() -> Html {
                                         { &ticket.title }
                                     </Link<AppRoute>>
                                 </div>
+                                if ticket.tasks_total > 0 {
+                                    <div class="info" title={language.get("Tasks")}>
+                                        { format!("☑ {}/{}", ticket.tasks_done, ticket.tasks_total) }
+                                    </div>
+                                }
+                                if !ticket.tags.is_empty() {
+                                    <div>
+                                        { for ticket.tags.iter().map(|tag| html! {
+                                            <TagChip tag={tag.clone()} onclick={onclick_tag.clone()} />
+                                        })}
+                                    </div>
+                                }
                             </td>
                             <td>
                                 <div class="info">
@@ -477,11 +794,35 @@ pub fn ticket_list() -> Html {
                                     }}
                                 </span>
                             </td>
-                            <td class={format!("priority priority-{}", ticket.priority.clone())}>
+                            //The two most urgent priorities are highlighted
+                            <td class={match priorities.iter().position(|priority| priority.name == ticket.priority) {
+                                Some(level) => format!("priority priority-level-{}", level),
+                                None => "priority".to_string(),
+                            }}>
                                 <span>
                                     { &ticket.priority }
                                 </span>
                             </td>
+                            <td class={ticket.sla.as_ref().map(|sla| format!("sla-{}", sla.sla_status)).unwrap_or_default()}>
+                                if let Some(sla) = &ticket.sla {
+                                    <div class="info">{ sla_label(&sla.sla_status, &language) }</div>
+                                    if let Some(due) = next_due(sla) {
+                                        <span class="date">
+                                            { time_ctx.convert_to_local(&due).format("%Y/%m/%d %H:%M").to_string() }
+                                        </span>
+                                    }
+                                }
+                            </td>
+                            { for custom_fields.iter().map(|field| {
+                                let value = ticket.custom_fields.iter().find(|value| value.field_id == field.field_id);
+                                html! {
+                                <td>
+                                    <span class="info">
+                                        { value.map(|value| display_field_value(value, &userlist)).unwrap_or_default() }
+                                    </span>
+                                </td>
+                                }
+                            })}
                             // <td>
                             //     <div class="edit-button">
                             //         <Link<AppRoute>
@@ -560,29 +901,42 @@ fn onclick_sort_by(
     let loading = loading.clone();
     Callback::from(move |_| {
         if !*loading {
-            let mut new_filter = TicketFilterPayload {
-                assignee: filter.assignee.clone(),
-                status: filter.status.clone(),
-                page: filter.page.clone(),
-                per_page: filter.per_page.clone(),
-                sort_by: Some(sort_by.clone()),
-                sort_order: filter.sort_order.clone(),
-                search: filter.search.clone(),
-            };
-            //if the new sort_by is different from the old filter.sort_by, then set the sort_order to "asc"
-            if new_filter.sort_by.clone().unwrap_or_default()
-                != filter.sort_by.clone().unwrap_or_default()
-            {
-                new_filter.sort_order = Some("asc".to_string());
-            } else {
-                if new_filter.sort_order.unwrap_or("asc".to_string()) == "asc" {
+            set_filter(&filter, &loading, |new_filter| {
+                //if the new sort_by is different from the old filter.sort_by, then set the sort_order to "asc"
+                if new_filter.sort_by.as_deref() != Some(sort_by.as_str()) {
+                    new_filter.sort_order = Some("asc".to_string());
+                } else if new_filter.sort_order.as_deref().unwrap_or("asc") == "asc" {
                     new_filter.sort_order = Some("desc".to_string());
                 } else {
                     new_filter.sort_order = Some("asc".to_string());
                 }
-            }
-            loading.set(true);
-            filter.set(new_filter);
+                new_filter.sort_by = Some(sort_by.clone());
+            });
         }
     })
 }
+
+/// Applies `change` to a copy of the current filter, which reloads the list
+fn set_filter(
+    filter: &UseStateHandle<TicketFilterPayload>,
+    loading: &UseStateHandle<bool>,
+    change: impl FnOnce(&mut TicketFilterPayload),
+) {
+    let mut new_filter = (**filter).clone();
+    change(&mut new_filter);
+    loading.set(true);
+    filter.set(new_filter);
+}
+
+/// User fields hold the user id, everything else is shown as stored
+fn display_field_value(value: &TicketFieldInfo, userlist: &[UserRepresentation]) -> String {
+    if value.field_type == CustomFieldType::User.as_str() {
+        if let Some(user) = userlist
+            .iter()
+            .find(|user| user.user_id.to_string() == value.value)
+        {
+            return user.display_name.clone();
+        }
+    }
+    value.value.clone()
+}
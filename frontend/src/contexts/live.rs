@@ -0,0 +1,265 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use gloo::timers::callback::Interval;
+use shared::models::live::{LiveEvent, PresenceTarget};
+use uuid::Uuid;
+use wasm_bindgen::{closure::Closure, JsCast};
+use web_sys::{EventSource, EventSourceInit, MessageEvent};
+use yew::prelude::*;
+
+use crate::services::live::send_presence;
+use crate::services::requests::api_url;
+use crate::types::MyUser;
+
+/// How often an open editor repeats its presence
+const PRESENCE_MILLIS: u32 = 30_000;
+/// Editors not heard from for this long are assumed to have left
+const PRESENCE_TIMEOUT_MILLIS: f64 = 75_000.0;
+const PRUNE_MILLIS: u32 = 15_000;
+
+/// Listeners by subscription id
+type Listeners = Rc<RefCell<Vec<(usize, Callback<LiveEvent>)>>>;
+
+/// An open stream, the closure has to live as long as the source
+type Stream = (EventSource, Closure<dyn FnMut(MessageEvent)>);
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub children: Children,
+}
+
+/// Shares one event stream between every component listening for live updates
+#[derive(Clone, Default)]
+pub struct LiveContext {
+    listeners: Listeners,
+    next_id: Rc<Cell<usize>>,
+}
+
+impl PartialEq for LiveContext {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.listeners, &other.listeners)
+    }
+}
+
+impl LiveContext {
+    fn subscribe(&self, callback: Callback<LiveEvent>) -> usize {
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+        self.listeners.borrow_mut().push((id, callback));
+        id
+    }
+
+    fn unsubscribe(&self, id: usize) {
+        self.listeners
+            .borrow_mut()
+            .retain(|(listener, _)| *listener != id);
+    }
+
+    fn dispatch(&self, event: LiveEvent) {
+        //Listeners may subscribe or unsubscribe while handling the event
+        let listeners: Vec<Callback<LiveEvent>> = self
+            .listeners
+            .borrow()
+            .iter()
+            .map(|(_, callback)| callback.clone())
+            .collect();
+        for callback in listeners {
+            callback.emit(event.clone());
+        }
+    }
+}
+
+/// Opens the stream while someone is logged in, must be placed inside UserContextProvider
+#[function_component(LiveProvider)]
+pub fn live_provider(props: &Props) -> Html {
+    let user = use_context::<UseStateHandle<MyUser>>().expect("No user context found");
+    let live = use_state(LiveContext::default);
+
+    {
+        let live = (*live).clone();
+        use_effect_with(user.user_id, move |user_id| {
+            let stream = if user_id.is_nil() {
+                None
+            } else {
+                open_stream(live)
+            };
+            move || {
+                if let Some((source, _onmessage)) = stream {
+                    source.close();
+                }
+            }
+        });
+    }
+
+    html! {
+        <ContextProvider<LiveContext> context={(*live).clone()}>
+            { for props.children.iter() }
+        </ContextProvider<LiveContext>>
+    }
+}
+
+fn open_stream(live: LiveContext) -> Option<Stream> {
+    let init = EventSourceInit::new();
+    //The API may be on another port, the session cookie is still needed
+    init.set_with_credentials(true);
+    let source = match EventSource::new_with_event_source_init_dict(&api_url("/live"), &init) {
+        Ok(source) => source,
+        Err(e) => {
+            log::error!("Unable to open live updates: {:?}", e);
+            return None;
+        }
+    };
+
+    let onmessage = Closure::<dyn FnMut(MessageEvent)>::new(move |message: MessageEvent| {
+        let Some(data) = message.data().as_string() else {
+            return;
+        };
+        match serde_json::from_str::<LiveEvent>(&data) {
+            Ok(event) => live.dispatch(event),
+            Err(e) => log::error!("Unable to parse live event: {}", e),
+        }
+    });
+    source.set_onmessage(Some(onmessage.as_ref().unchecked_ref()));
+
+    Some((source, onmessage))
+}
+
+/// Calls `callback` with every live event while the component is mounted
+#[hook]
+pub fn use_live_events(callback: Callback<LiveEvent>) {
+    let live = use_context::<LiveContext>().expect("No live context found");
+    //Always emit to the latest callback so it sees the current props
+    let latest = use_mut_ref(|| callback.clone());
+    *latest.borrow_mut() = callback;
+
+    use_effect_with((), move |_| {
+        let id = live.subscribe(Callback::from(move |event| latest.borrow().emit(event)));
+        move || live.unsubscribe(id)
+    });
+}
+
+/// Tells other users the current user is editing `target` until it is unset or the component unmounts
+#[hook]
+pub fn use_presence(target: Option<PresenceTarget>) {
+    let user = use_context::<UseStateHandle<MyUser>>().expect("No user context found");
+    let target = target.filter(|_| user.role().can_edit());
+
+    use_effect_with(target, |target| {
+        let interval = target.clone().map(|target| {
+            announce(target.clone(), true);
+            Interval::new(PRESENCE_MILLIS, move || announce(target.clone(), true))
+        });
+        let target = target.clone();
+        move || {
+            drop(interval);
+            if let Some(target) = target {
+                announce(target, false);
+            }
+        }
+    });
+}
+
+fn announce(target: PresenceTarget, editing: bool) {
+    wasm_bindgen_futures::spawn_local(async move {
+        if let Err(e) = send_presence(target, editing).await {
+            log::error!("Error sending presence: {}", e);
+        }
+    });
+}
+
+#[derive(Default, PartialEq)]
+struct Editors {
+    /// User, display name and when they were last heard from
+    seen: Vec<(Uuid, String, f64)>,
+}
+
+enum EditorsAction {
+    Seen {
+        user_id: Uuid,
+        display_name: String,
+        editing: bool,
+    },
+    Prune,
+    Clear,
+}
+
+impl Reducible for Editors {
+    type Action = EditorsAction;
+
+    fn reduce(self: Rc<Self>, action: Self::Action) -> Rc<Self> {
+        let now = js_sys::Date::now();
+        let mut seen = self.seen.clone();
+
+        match action {
+            EditorsAction::Seen {
+                user_id,
+                display_name,
+                editing,
+            } => {
+                seen.retain(|(editor, _, _)| *editor != user_id);
+                if editing {
+                    seen.push((user_id, display_name, now));
+                }
+            }
+            EditorsAction::Prune => {
+                if seen
+                    .iter()
+                    .all(|(_, _, at)| now - at < PRESENCE_TIMEOUT_MILLIS)
+                {
+                    return self;
+                }
+                seen.retain(|(_, _, at)| now - at < PRESENCE_TIMEOUT_MILLIS);
+            }
+            EditorsAction::Clear => seen.clear(),
+        }
+
+        Rc::new(Self { seen })
+    }
+}
+
+/// Display names of the other users currently editing `target`
+#[hook]
+pub fn use_editors(target: PresenceTarget) -> Vec<String> {
+    let user = use_context::<UseStateHandle<MyUser>>().expect("No user context found");
+    let editors = use_reducer(Editors::default);
+
+    {
+        let editors = editors.dispatcher();
+        let user_id = user.user_id;
+        let target = target.clone();
+        use_live_events(Callback::from(move |event| {
+            if let LiveEvent::Presence {
+                target: edited,
+                user_id: editor,
+                display_name,
+                editing,
+            } = event
+            {
+                if edited == target && editor != user_id {
+                    editors.dispatch(EditorsAction::Seen {
+                        user_id: editor,
+                        display_name,
+                        editing,
+                    });
+                }
+            }
+        }));
+    }
+
+    {
+        let editors = editors.dispatcher();
+        use_effect_with(target, move |_| {
+            editors.dispatch(EditorsAction::Clear);
+            let interval =
+                Interval::new(PRUNE_MILLIS, move || editors.dispatch(EditorsAction::Prune));
+            move || drop(interval)
+        });
+    }
+
+    editors
+        .seen
+        .iter()
+        .map(|(_, display_name, _)| display_name.clone())
+        .collect()
+}
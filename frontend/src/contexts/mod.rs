@@ -1,4 +1,5 @@
 pub mod language;
+pub mod live;
 pub mod theme;
 pub mod user;
 pub mod time;
\ No newline at end of file
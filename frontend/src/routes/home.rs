@@ -1,21 +1,83 @@
+use shared::models::tickets::TicketFilterPayload;
+use stylist::style;
+use stylist::yew::styled_component;
+use web_sys::HtmlInputElement;
 use yew::prelude::*;
-use yew_router::prelude::Redirect;
+use yew_router::prelude::{use_location, use_navigator, Redirect};
 
-use crate::components::ticket_list::TicketList;
-use crate::hooks::use_user_context;
+use crate::components::ticket_list::{default_filter, filter_from_location, TicketList};
+use crate::contexts::theme::use_theme;
+use crate::hooks::{use_language_context, use_user_context};
+use crate::services::views::{create_view, delete_view, get_views, update_view};
+use crate::types::{SavedViewCreateInfo, SavedViewInfo};
 
 use super::AppRoute;
 
-#[function_component(Home)]
+#[styled_component(Home)]
 pub fn home() -> Html {
     let user_ctx = use_user_context();
+    let theme = use_theme();
+
+    let style = style! {
+        r#"
+        .home {
+            display: flex;
+            flex-direction: row;
+        }
+        .views {
+            flex-shrink: 0;
+            width: 200px;
+            padding: 4px 8px;
+            border-right: 1px solid #777;
+        }
+        .views ul {
+            list-style: none;
+            padding: 0px;
+            margin: 4px 0px 12px 0px;
+        }
+        .views li {
+            display: flex;
+            align-items: center;
+            padding: 2px 0px;
+        }
+        .view-link {
+            flex: 1;
+            padding: 2px 8px;
+            cursor: pointer;
+            border: 1px solid transparent;
+            overflow: hidden;
+            text-overflow: ellipsis;
+        }
+        .selected {
+            border: 1px solid ${border};
+            border-radius: 8px;
+            background: ${bg};
+        }
+        .views input[type="text"] {
+            width: 100%;
+            margin: 4px 0px;
+        }
+        .home-list {
+            flex: 1;
+            min-width: 0;
+        }
+        "#,
+        bg = theme.background.clone(),
+        border = theme.border.clone(),
+    }
+    .expect("Failed to parse style");
 
     //loading is done as part of user context
     if user_ctx.is_authenticated() {
         html! {
-        <div>
-            <TicketList />
-         </div>
+        <div class={style}>
+            <div class="home">
+                <ViewsSidebar />
+                <div class="home-list">
+                    <TicketList />
+                </div>
+            </div>
+        </div>
         }
     } else {
         html! {
@@ -23,3 +85,215 @@ pub fn home() -> Html {
         }
     }
 }
+
+/// Saved ticket list filters, the user's own views first, then views others shared
+#[function_component(ViewsSidebar)]
+fn views_sidebar() -> Html {
+    let user_ctx = use_user_context();
+    let language = use_language_context();
+    let navigator = use_navigator().unwrap();
+    let location = use_location();
+    let views = use_state(Vec::<SavedViewInfo>::new);
+    let version = use_state(|| 0_u32);
+    let name = use_state(String::new);
+    let shared = use_state(|| false);
+    let error = use_state(String::new);
+    let role = user_ctx.role();
+
+    //Views always open on the first page, so the page is left out when comparing
+    let current = location.as_ref().and_then(filter_from_location);
+    let is_current = |filter: &TicketFilterPayload| {
+        current.as_ref().is_some_and(|current| {
+            TicketFilterPayload {
+                page: filter.page,
+                ..current.clone()
+            } == *filter
+        })
+    };
+
+    {
+        let views = views.clone();
+        use_effect_with(*version, move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                match get_views().await {
+                    Ok(list) => views.set(list),
+                    Err(e) => log::error!("Error loading views: {}", e),
+                }
+            });
+            || ()
+        })
+    }
+
+    let onclick_view = |filter: &TicketFilterPayload| {
+        let navigator = navigator.clone();
+        let filter = filter.clone();
+        Callback::from(move |_: MouseEvent| {
+            if let Err(e) = navigator.push_with_query(&AppRoute::Home, &filter) {
+                log::error!("Error opening view: {}", e);
+            }
+        })
+    };
+
+    let onclick_default = {
+        let navigator = navigator.clone();
+        Callback::from(move |_: MouseEvent| navigator.push(&AppRoute::Home))
+    };
+
+    let oninput_name = {
+        let name = name.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            name.set(input.value());
+        })
+    };
+
+    let onchange_shared = {
+        let shared = shared.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            shared.set(input.checked());
+        })
+    };
+
+    let onsubmit = {
+        let current = current.clone();
+        let name = name.clone();
+        let shared = shared.clone();
+        let error = error.clone();
+        let version = version.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let Some(filter) = current.clone() else {
+                return;
+            };
+            let info = SavedViewCreateInfo {
+                name: (*name).clone(),
+                filter,
+                shared: *shared,
+            };
+            let name = name.clone();
+            let shared = shared.clone();
+            let error = error.clone();
+            let version = version.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match create_view(&info).await {
+                    Ok(response) if response.success => {
+                        name.set(String::new());
+                        shared.set(false);
+                        error.set(String::new());
+                        version.set(*version + 1);
+                    }
+                    Ok(response) => error.set(response.message.unwrap_or_default()),
+                    Err(e) => error.set(e.to_string()),
+                }
+            });
+        })
+    };
+
+    //Replaces the filter of a view with the current one
+    let onclick_update = |view: &SavedViewInfo| {
+        let current = current.clone();
+        let view = view.clone();
+        let error = error.clone();
+        let version = version.clone();
+        Callback::from(move |_: MouseEvent| {
+            let Some(filter) = current.clone() else {
+                return;
+            };
+            let info = SavedViewCreateInfo {
+                name: view.name.clone(),
+                filter,
+                shared: view.shared,
+            };
+            let view_id = view.view_id;
+            let error = error.clone();
+            let version = version.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match update_view(view_id, &info).await {
+                    Ok(response) if response.success => {
+                        error.set(String::new());
+                        version.set(*version + 1);
+                    }
+                    Ok(response) => error.set(response.message.unwrap_or_default()),
+                    Err(e) => error.set(e.to_string()),
+                }
+            });
+        })
+    };
+
+    let onclick_delete = |view: &SavedViewInfo| {
+        let view_id = view.view_id;
+        let error = error.clone();
+        let version = version.clone();
+        Callback::from(move |_: MouseEvent| {
+            let error = error.clone();
+            let version = version.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match delete_view(view_id).await {
+                    Ok(response) if response.success => version.set(*version + 1),
+                    Ok(response) => error.set(response.message),
+                    Err(e) => error.set(e.to_string()),
+                }
+            });
+        })
+    };
+
+    let render_view = |view: &SavedViewInfo| {
+        let is_owner = view.owner.user_id == user_ctx.user_id;
+        html! {
+            <li>
+                <span class={if is_current(&view.filter) { "view-link selected" } else { "view-link" }}
+                    onclick={onclick_view(&view.filter)}
+                    title={if is_owner { view.name.clone() } else { format!("{} ({})", view.name, view.owner.display_name) }}>
+                    { &view.name }
+                </span>
+                if is_owner {
+                    <button class="page-btn" title={language.get("Save current filter")}
+                        onclick={onclick_update(view)}>{ "↻" }</button>
+                }
+                if is_owner || (role.is_admin() && view.shared) {
+                    <button class="page-btn" onclick={onclick_delete(view)}>{ "✘" }</button>
+                }
+            </li>
+        }
+    };
+
+    let (own, others): (Vec<&SavedViewInfo>, Vec<&SavedViewInfo>) = views
+        .iter()
+        .partition(|view| view.owner.user_id == user_ctx.user_id);
+
+    html! {
+        <div class="views">
+            <div><b>{ language.get("Views") }</b></div>
+            <ul>
+                <li>
+                    <span class={if is_current(&default_filter(user_ctx.user_id)) { "view-link selected" } else { "view-link" }}
+                        onclick={onclick_default}>
+                        { language.get("Default") }
+                    </span>
+                </li>
+                { for own.into_iter().map(render_view) }
+            </ul>
+            if !others.is_empty() {
+                <div><b>{ language.get("Shared views") }</b></div>
+                <ul>
+                    { for others.into_iter().map(render_view) }
+                </ul>
+            }
+            <form {onsubmit}>
+                <input type="text" placeholder={language.get("View name")} value={(*name).clone()}
+                    oninput={oninput_name} />
+                if role.can_edit() {
+                    <label>
+                        <input type="checkbox" checked={*shared} onchange={onchange_shared} />
+                        { language.get("Share with everyone") }
+                    </label>
+                }
+                <button class="btn" type="submit" disabled={current.is_none()}>
+                    { language.get("Save view") }
+                </button>
+            </form>
+            <span class="error">{ error.to_string() }</span>
+        </div>
+    }
+}
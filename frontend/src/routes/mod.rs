@@ -1,9 +1,12 @@
 pub mod home;
 pub mod login;
 pub mod new_user;
+pub mod portal;
+pub mod search;
 pub mod settings;
 pub mod ticket;
 pub mod ticket_editor;
+pub mod timesheet;
 pub mod users;
 pub mod wiki;
 
@@ -13,9 +16,12 @@ use yew_router::prelude::*;
 use home::Home;
 use login::Login;
 use new_user::NewUser;
+use portal::{Portal, PortalLogin, PortalTicket};
+use search::Search;
 use settings::Settings;
 use ticket::Ticket;
 use ticket_editor::TicketEditor;
+use timesheet::Timesheet;
 use users::Users;
 use wiki::Wiki;
 
@@ -41,6 +47,16 @@ pub enum AppRoute {
     Settings,
     #[at("/users")]
     Users,
+    #[at("/search")]
+    Search,
+    #[at("/reports/time")]
+    Timesheet,
+    #[at("/portal")]
+    Portal,
+    #[at("/portal/ticket/:ticket_id")]
+    PortalTicket { ticket_id: i32 },
+    #[at("/portal/login/:token")]
+    PortalLogin { token: String },
     #[at("/")]
     Home,
     #[not_found]
@@ -56,6 +72,22 @@ pub enum SettingsRoute {
     Account { user_id: uuid::Uuid },
     #[at("/settings/tickets")]
     Tickets,
+    #[at("/settings/projects")]
+    Projects,
+    #[at("/settings/tags")]
+    Tags,
+    #[at("/settings/fields")]
+    CustomFields,
+    #[at("/settings/workflow")]
+    Workflow,
+    #[at("/settings/sla")]
+    Sla,
+    #[at("/settings/automation")]
+    Automation,
+    #[at("/settings/webhooks")]
+    Webhooks,
+    #[at("/settings/recurring")]
+    Recurring,
     #[not_found]
     #[at("/settings/404")]
     NotFound,
@@ -77,6 +109,11 @@ pub fn switch(route: AppRoute) -> Html {
             html! { <Switch<SettingsRoute> render={switch_settings} /> }
         }
         AppRoute::Users => html! {<Users />},
+        AppRoute::Search => html! {<Search />},
+        AppRoute::Timesheet => html! {<Timesheet />},
+        AppRoute::Portal => html! {<Portal />},
+        AppRoute::PortalTicket { ticket_id } => html! {<PortalTicket ticket_id={ticket_id} />},
+        AppRoute::PortalLogin { token } => html! {<PortalLogin token={token} />},
         AppRoute::NotFound => html! { "Page not found" },
         AppRoute::WikiHome => html! {<Wiki document_id={None}/>},
         AppRoute::WikiDoc { document_id } => html!(<Wiki document_id={Some(document_id.clone())}/>),
@@ -88,6 +125,14 @@ pub fn switch_settings(route: SettingsRoute) -> Html {
         SettingsRoute::Profile => html! {<Settings />},
         SettingsRoute::Account { user_id: _ } => html! {<Settings />},
         SettingsRoute::Tickets => html! {<Settings />},
+        SettingsRoute::Projects => html! {<Settings />},
+        SettingsRoute::Tags => html! {<Settings />},
+        SettingsRoute::CustomFields => html! {<Settings />},
+        SettingsRoute::Workflow => html! {<Settings />},
+        SettingsRoute::Sla => html! {<Settings />},
+        SettingsRoute::Automation => html! {<Settings />},
+        SettingsRoute::Webhooks => html! {<Settings />},
+        SettingsRoute::Recurring => html! {<Settings />},
         SettingsRoute::NotFound => html! {<Redirect<AppRoute> to={AppRoute::NotFound}/>},
     }
 }
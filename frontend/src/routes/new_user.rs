@@ -1,4 +1,5 @@
-use web_sys::HtmlInputElement;
+use shared::models::users::Role;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
 
 use yew::prelude::*;
 use yew_router::prelude::*;
@@ -14,7 +15,10 @@ use crate::types::RegisterInfo;
 pub fn new_user() -> Html {
     let language = use_language_context();
     let navigator = use_navigator().unwrap();
-    let register_info = use_state(RegisterInfo::default);
+    let register_info = use_state(|| RegisterInfo {
+        access: Role::default().to_string(),
+        ..Default::default()
+    });
     let submitted = use_state(|| false);
     let error = use_state(|| String::new());
 
@@ -30,7 +34,7 @@ pub fn new_user() -> Html {
                         display_name: register_info.display_name.clone(),
                         email: register_info.email.clone(),
                         password: register_info.password.clone(),
-                        access: "1".to_string(),
+                        access: register_info.access.clone(),
                     };
                     let result = create(request).await;
                     if let Err(err) = result {
@@ -99,6 +103,15 @@ pub fn new_user() -> Html {
             register_info.set(info);
         })
     };
+    let onchange_access = {
+        let register_info = register_info.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlSelectElement = e.target_unchecked_into();
+            let mut info = (*register_info).clone();
+            info.access = input.value();
+            register_info.set(info);
+        })
+    };
 
     html! {
         <div>
@@ -144,6 +157,16 @@ pub fn new_user() -> Html {
                             oninput={oninput_password}
                             />
                     </div>
+                    <div>
+                        <label>{ format!("{}:", language.get("Role")) }</label>
+                        <select onchange={onchange_access}>
+                            { for Role::iter().map(|role| html! {
+                                <option value={role.as_str()} selected={register_info.access == role.as_str()}>
+                                    { language.get(role.label()) }
+                                </option>
+                            })}
+                        </select>
+                    </div>
                     <button
                         class="btn"
                         type="submit"
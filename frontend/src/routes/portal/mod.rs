@@ -0,0 +1,290 @@
+mod sign_in;
+mod ticket;
+
+pub use sign_in::PortalLogin;
+pub use ticket::PortalTicket;
+
+use stylist::yew::styled_component;
+use stylist::{style, Style};
+use web_sys::{HtmlInputElement, HtmlTextAreaElement};
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::components::loading::Loading;
+use crate::components::time_format::TimeFormat;
+use crate::hooks::use_language_context;
+use crate::routes::AppRoute;
+use crate::services::portal::*;
+use crate::types::{
+    Error, PortalContactInfo, PortalPasswordInfo, PortalTicketCreateInfo, PortalTicketInfo,
+};
+use sign_in::SignIn;
+
+/// Shared by the portal pages, requesters never see the navigation so its space is taken back
+fn portal_style() -> Style {
+    style!(
+        r#"
+        margin-left: -200px;
+        .portal {
+            max-width: 800px;
+            margin: 0 auto;
+            padding: 12px;
+        }
+        .portal-header {
+            display: flex;
+            align-items: center;
+            justify-content: space-between;
+            border-bottom: 1px solid #777;
+            margin-bottom: 12px;
+        }
+        .portal input[type="text"], .portal input[type="password"], .portal textarea {
+            width: 100%;
+            box-sizing: border-box;
+            margin-bottom: 4px;
+        }
+        .portal table {
+            width: 100%;
+            border-collapse: collapse;
+        }
+        .portal td, .portal th {
+            text-align: left;
+            padding: 4px;
+        }
+        .closed {
+            opacity: 0.6;
+        }
+        .note {
+            padding: 4px 8px;
+            margin-bottom: 8px;
+            border-left: 3px solid #777;
+        }
+        .requester {
+            border-left-color: #5243c2;
+        }
+        .message {
+            margin-left: 8px;
+        }
+        "#
+    )
+    .expect("Failed to parse style")
+}
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct HeaderProps {
+    pub contact: PortalContactInfo,
+}
+
+/// Name of the requester and a way to sign out, on top of every portal page
+#[function_component(PortalHeader)]
+pub fn portal_header(props: &HeaderProps) -> Html {
+    let language = use_language_context();
+
+    let onclick_logout = Callback::from(|_: MouseEvent| {
+        wasm_bindgen_futures::spawn_local(async move {
+            if let Err(e) = portal_logout().await {
+                log::error!("Error signing out: {}", e);
+            }
+            //Reloading leaves no state of the previous requester behind
+            let _ = web_sys::window().unwrap().location().reload();
+        });
+    });
+
+    html! {
+        <div class="portal-header">
+            <h2><Link<AppRoute> to={AppRoute::Portal}>{ language.get("Support portal") }</Link<AppRoute>></h2>
+            <span>
+                { &props.contact.display_name }
+                <button class="btn" style="margin-left: 8px;" onclick={onclick_logout}>
+                    { language.get("Sign out") }
+                </button>
+            </span>
+        </div>
+    }
+}
+
+/// Landing page of the requester portal: their tickets, a form for a new one and their password
+#[styled_component(Portal)]
+pub fn portal() -> Html {
+    let language = use_language_context();
+    let navigator = use_navigator().unwrap();
+    let contact = use_state(|| None::<PortalContactInfo>);
+    let signed_out = use_state(|| false);
+    let tickets = use_state(Vec::<PortalTicketInfo>::new);
+    let version = use_state(|| 0_u32);
+    let create_info = use_state(PortalTicketCreateInfo::default);
+    let error = use_state(String::new);
+    let password = use_state(String::new);
+    let password_message = use_state(String::new);
+
+    {
+        let contact = contact.clone();
+        let signed_out = signed_out.clone();
+        let tickets = tickets.clone();
+        use_effect_with(*version, move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                match portal_me().await {
+                    Ok(me) => {
+                        contact.set(Some(me));
+                        signed_out.set(false);
+                    }
+                    Err(Error::Unauthorized) => return signed_out.set(true),
+                    Err(e) => return log::error!("Error loading portal: {}", e),
+                }
+                match portal_tickets().await {
+                    Ok(list) => tickets.set(list),
+                    Err(e) => log::error!("Error loading tickets: {}", e),
+                }
+            });
+            || ()
+        })
+    }
+
+    let callback_signed_in = {
+        let version = version.clone();
+        Callback::from(move |_| version.set(*version + 1))
+    };
+
+    let oninput_title = {
+        let create_info = create_info.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut info = (*create_info).clone();
+            info.title = input.value();
+            create_info.set(info);
+        })
+    };
+
+    let oninput_description = {
+        let create_info = create_info.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlTextAreaElement = e.target_unchecked_into();
+            let mut info = (*create_info).clone();
+            info.description = input.value();
+            create_info.set(info);
+        })
+    };
+
+    let onsubmit = {
+        let create_info = create_info.clone();
+        let error = error.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let info = (*create_info).clone();
+            let create_info = create_info.clone();
+            let error = error.clone();
+            let navigator = navigator.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match create_portal_ticket(&info).await {
+                    Ok(response) if response.success => {
+                        create_info.set(PortalTicketCreateInfo::default());
+                        if let Some(ticket) = response.data {
+                            navigator.push(&AppRoute::PortalTicket {
+                                ticket_id: ticket.ticket_id,
+                            });
+                        }
+                    }
+                    Ok(response) => error.set(response.message.unwrap_or_default()),
+                    Err(e) => error.set(e.to_string()),
+                }
+            });
+        })
+    };
+
+    let oninput_password = {
+        let password = password.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            password.set(input.value());
+        })
+    };
+
+    let onsubmit_password = {
+        let password = password.clone();
+        let password_message = password_message.clone();
+        let version = version.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let info = PortalPasswordInfo {
+                password: (*password).clone(),
+            };
+            let password = password.clone();
+            let password_message = password_message.clone();
+            let version = version.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match set_portal_password(&info).await {
+                    Ok(response) => {
+                        if response.success {
+                            password.set(String::new());
+                            version.set(*version + 1);
+                        }
+                        password_message.set(response.message);
+                    }
+                    Err(e) => password_message.set(e.to_string()),
+                }
+            });
+        })
+    };
+
+    if *signed_out {
+        return html! {
+            <div class={portal_style()}>
+                <div class="portal">
+                    <SignIn callback={callback_signed_in} />
+                </div>
+            </div>
+        };
+    }
+    let Some(contact) = (*contact).clone() else {
+        return html! { <Loading /> };
+    };
+
+    html! {
+        <div class={portal_style()}>
+            <div class="portal">
+                <PortalHeader contact={contact.clone()} />
+                <h3>{ language.get("New request") }</h3>
+                <form {onsubmit}>
+                    <input type="text" placeholder={language.get("Title")} value={create_info.title.clone()}
+                        oninput={oninput_title} />
+                    <textarea rows="6" placeholder={language.get("Describe the issue")}
+                        value={create_info.description.clone()} oninput={oninput_description}>
+                    </textarea>
+                    <button class="btn" type="submit">{ language.get("Submit") }</button>
+                    <span class="error message">{ error.to_string() }</span>
+                </form>
+                <h3>{ language.get("My requests") }</h3>
+                if tickets.is_empty() {
+                    <p>{ language.get("You have not submitted any requests yet") }</p>
+                } else {
+                    <table>
+                        <tr>
+                            <th>{ "#" }</th>
+                            <th>{ language.get("Title") }</th>
+                            <th>{ language.get("Status") }</th>
+                            <th>{ language.get("Updated") }</th>
+                        </tr>
+                        { for tickets.iter().map(|ticket| html! {
+                            <tr class={if ticket.is_closed { "closed" } else { "" }}>
+                                <td>{ ticket.ticket_id }</td>
+                                <td>
+                                    <Link<AppRoute> to={AppRoute::PortalTicket { ticket_id: ticket.ticket_id }}>
+                                        { &ticket.title }
+                                    </Link<AppRoute>>
+                                </td>
+                                <td>{ &ticket.status }</td>
+                                <td><TimeFormat time={ticket.updated_at} /></td>
+                            </tr>
+                        })}
+                    </table>
+                }
+                <h3>{ language.get(if contact.has_password { "Change password" } else { "Set a password" }) }</h3>
+                <form onsubmit={onsubmit_password}>
+                    <input type="password" placeholder={language.get("Password")} value={(*password).clone()}
+                        oninput={oninput_password} />
+                    <button class="btn" type="submit">{ language.get("Save") }</button>
+                    <span class="message">{ password_message.to_string() }</span>
+                </form>
+            </div>
+        </div>
+    }
+}
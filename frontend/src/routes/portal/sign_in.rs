@@ -0,0 +1,140 @@
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use super::portal_style;
+use crate::hooks::use_language_context;
+use crate::routes::AppRoute;
+use crate::services::portal::{portal_login, send_magic_link, verify_magic_link};
+use crate::types::{MagicLinkInfo, MagicLinkVerifyInfo, PortalLoginInfo};
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    /// Called once the requester is signed in
+    pub callback: Callback<()>,
+}
+
+/// Sign in with a password, or ask for a link by e-mail when there is none yet
+#[function_component(SignIn)]
+pub fn sign_in(props: &Props) -> Html {
+    let language = use_language_context();
+    let login_info = use_state(PortalLoginInfo::default);
+    let message = use_state(String::new);
+
+    let oninput_email = {
+        let login_info = login_info.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut info = (*login_info).clone();
+            info.email = input.value();
+            login_info.set(info);
+        })
+    };
+
+    let oninput_password = {
+        let login_info = login_info.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut info = (*login_info).clone();
+            info.password = input.value();
+            login_info.set(info);
+        })
+    };
+
+    let onsubmit = {
+        let login_info = login_info.clone();
+        let message = message.clone();
+        let callback = props.callback.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let info = (*login_info).clone();
+            let message = message.clone();
+            let callback = callback.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match portal_login(&info).await {
+                    Ok(response) if response.success => callback.emit(()),
+                    Ok(response) => message.set(response.message),
+                    Err(e) => message.set(e.to_string()),
+                }
+            });
+        })
+    };
+
+    let onclick_link = {
+        let login_info = login_info.clone();
+        let message = message.clone();
+        Callback::from(move |_: MouseEvent| {
+            let info = MagicLinkInfo {
+                email: login_info.email.clone(),
+            };
+            let message = message.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match send_magic_link(&info).await {
+                    Ok(response) => message.set(response.message),
+                    Err(e) => message.set(e.to_string()),
+                }
+            });
+        })
+    };
+
+    html! {
+        <div>
+            <h2>{ language.get("Support portal") }</h2>
+            <form {onsubmit}>
+                <input type="text" placeholder={language.get("E-mail address")} value={login_info.email.clone()}
+                    oninput={oninput_email} />
+                <input type="password" placeholder={language.get("Password")} value={login_info.password.clone()}
+                    oninput={oninput_password} />
+                <button class="btn" type="submit">{ language.get("Login") }</button>
+                <button class="btn" type="button" style="margin-left: 8px;" onclick={onclick_link}
+                    disabled={login_info.email.trim().is_empty()}>
+                    { language.get("E-mail me a sign-in link") }
+                </button>
+            </form>
+            <p class="message">{ message.to_string() }</p>
+        </div>
+    }
+}
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct LoginProps {
+    pub token: String,
+}
+
+/// Target of the e-mailed sign-in link, continues to the portal once the link is accepted
+#[function_component(PortalLogin)]
+pub fn portal_login_page(props: &LoginProps) -> Html {
+    let language = use_language_context();
+    let navigator = use_navigator().unwrap();
+    let error = use_state(String::new);
+
+    {
+        let error = error.clone();
+        use_effect_with(props.token.clone(), move |token| {
+            let info = MagicLinkVerifyInfo {
+                token: token.clone(),
+            };
+            wasm_bindgen_futures::spawn_local(async move {
+                match verify_magic_link(&info).await {
+                    Ok(response) if response.success => navigator.replace(&AppRoute::Portal),
+                    Ok(response) => error.set(response.message),
+                    Err(e) => error.set(e.to_string()),
+                }
+            });
+            || ()
+        })
+    }
+
+    html! {
+        <div class={portal_style()}>
+            <div class="portal">
+                if error.is_empty() {
+                    <p>{ language.get("Signing in...") }</p>
+                } else {
+                    <p class="error">{ error.to_string() }</p>
+                    <Link<AppRoute> to={AppRoute::Portal}>{ language.get("Back to the portal") }</Link<AppRoute>>
+                }
+            </div>
+        </div>
+    }
+}
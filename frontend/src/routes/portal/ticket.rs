@@ -0,0 +1,159 @@
+use web_sys::HtmlTextAreaElement;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use super::sign_in::SignIn;
+use super::{portal_style, PortalHeader};
+use crate::components::loading::Loading;
+use crate::components::time_format::TimeFormat;
+use crate::hooks::use_language_context;
+use crate::routes::AppRoute;
+use crate::services::portal::{portal_me, portal_reply, portal_ticket};
+use crate::types::{Error, PortalContactInfo, PortalReplyInfo, PortalTicketDetailInfo};
+use crate::utils::markdown_to_html;
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub ticket_id: i32,
+}
+
+/// One of the requester's tickets with the conversation so far and a reply box
+#[function_component(PortalTicket)]
+pub fn portal_ticket_page(props: &Props) -> Html {
+    let language = use_language_context();
+    let contact = use_state(|| None::<PortalContactInfo>);
+    let detail = use_state(|| None::<PortalTicketDetailInfo>);
+    let load_error = use_state(|| None::<Error>);
+    let version = use_state(|| 0_u32);
+    let reply = use_state(String::new);
+    let error = use_state(String::new);
+
+    {
+        let contact = contact.clone();
+        let detail = detail.clone();
+        let load_error = load_error.clone();
+        use_effect_with((props.ticket_id, *version), move |(ticket_id, _)| {
+            let ticket_id = *ticket_id;
+            wasm_bindgen_futures::spawn_local(async move {
+                let result = match portal_me().await {
+                    Ok(me) => {
+                        contact.set(Some(me));
+                        portal_ticket(ticket_id).await
+                    }
+                    Err(e) => Err(e),
+                };
+                match result {
+                    Ok(loaded) => {
+                        detail.set(Some(loaded));
+                        load_error.set(None);
+                    }
+                    Err(e) => load_error.set(Some(e)),
+                }
+            });
+            || ()
+        })
+    }
+
+    let callback_signed_in = {
+        let version = version.clone();
+        Callback::from(move |_| version.set(*version + 1))
+    };
+
+    let oninput_reply = {
+        let reply = reply.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlTextAreaElement = e.target_unchecked_into();
+            reply.set(input.value());
+        })
+    };
+
+    let onsubmit = {
+        let ticket_id = props.ticket_id;
+        let reply = reply.clone();
+        let error = error.clone();
+        let version = version.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let info = PortalReplyInfo {
+                text: (*reply).clone(),
+            };
+            let reply = reply.clone();
+            let error = error.clone();
+            let version = version.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match portal_reply(ticket_id, &info).await {
+                    Ok(response) if response.success => {
+                        reply.set(String::new());
+                        error.set(String::new());
+                        version.set(*version + 1);
+                    }
+                    Ok(response) => error.set(response.message.unwrap_or_default()),
+                    Err(e) => error.set(e.to_string()),
+                }
+            });
+        })
+    };
+
+    let body = match (&*load_error, &*detail, &*contact) {
+        (Some(Error::Unauthorized), _, _) => html! { <SignIn callback={callback_signed_in} /> },
+        (Some(Error::NotFound), _, _) => html! {
+            <>
+                <p>{ language.get("Ticket not found") }</p>
+                <Link<AppRoute> to={AppRoute::Portal}>{ language.get("Back to the portal") }</Link<AppRoute>>
+            </>
+        },
+        (Some(e), _, _) => html! { <p class="error">{ e.to_string() }</p> },
+        (None, Some(detail), Some(contact)) => {
+            let ticket = &detail.ticket;
+            html! {
+                <>
+                    <PortalHeader contact={contact.clone()} />
+                    <h3>{ format!("#{} {}", ticket.ticket_id, ticket.title) }</h3>
+                    <p>
+                        { format!("{}: {} · ", language.get("Status"), ticket.status) }
+                        <TimeFormat time={ticket.created_at} />
+                    </p>
+                    <div>{ markdown_to_html(&ticket.description) }</div>
+                    <hr />
+                    { for detail.notes.iter().map(|note| html! {
+                        <div class={if note.from_requester { "note requester" } else { "note" }}>
+                            <b>{ &note.author }</b>
+                            <span class="message"><TimeFormat time={note.created_at} /></span>
+                            <div>{ markdown_to_html(&note.text) }</div>
+                        </div>
+                    })}
+                    if let Some(merged_into) = ticket.merged_into {
+                        <p>
+                            { language.get("This request was merged into") }
+                            { " " }
+                            if ticket.merged_into_own {
+                                <Link<AppRoute> to={AppRoute::PortalTicket { ticket_id: merged_into }}>
+                                    { format!("#{}", merged_into) }
+                                </Link<AppRoute>>
+                            } else {
+                                { format!("#{}", merged_into) }
+                            }
+                        </p>
+                    } else {
+                        <form {onsubmit}>
+                            <textarea rows="4" placeholder={language.get("Reply")} value={(*reply).clone()}
+                                oninput={oninput_reply}>
+                            </textarea>
+                            <button class="btn" type="submit">{ language.get("Send") }</button>
+                            <span class="error message">{ error.to_string() }</span>
+                        </form>
+                    }
+                </>
+            }
+        }
+        _ => html! { <Loading /> },
+    };
+
+    html! {
+        <div class={portal_style()}>
+            <div class="portal">
+                { body }
+            </div>
+        </div>
+    }
+}
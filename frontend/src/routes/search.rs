@@ -0,0 +1,115 @@
+use shared::models::search::{SearchEntity, SearchQuery, SearchResult};
+use stylist::style;
+use stylist::yew::styled_component;
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::contexts::theme::use_theme;
+use crate::hooks::use_language_context;
+use crate::routes::AppRoute;
+use crate::services::search::search;
+use crate::utils::highlight_to_html;
+
+/// Results page for the global search box, the terms come from `?q=`
+#[styled_component(Search)]
+pub fn search_results() -> Html {
+    let theme = use_theme();
+    let language = use_language_context();
+    let location = use_location();
+    let results = use_state(|| None::<Vec<SearchResult>>);
+    let error = use_state(String::new);
+
+    let query = location
+        .and_then(|location| location.query::<SearchQuery>().ok())
+        .unwrap_or_default();
+
+    {
+        let results = results.clone();
+        let error = error.clone();
+        use_effect_with(query.clone(), move |query| {
+            let query = query.clone();
+            results.set(None);
+            wasm_bindgen_futures::spawn_local(async move {
+                match search(&query).await {
+                    Ok(hits) => {
+                        error.set(String::new());
+                        results.set(Some(hits));
+                    }
+                    Err(e) => error.set(e.to_string()),
+                }
+            });
+            || ()
+        })
+    }
+
+    let style = style!(
+        r#"
+        .search-result {
+            padding: 0.5rem 0;
+            border-bottom: 1px solid ${border};
+        }
+        .search-type {
+            font-size: 0.8rem;
+            color: #838383;
+            margin-right: 0.5rem;
+            text-transform: uppercase;
+        }
+        .search-snippet {
+            margin-top: 0.25rem;
+        }
+        mark {
+            background: ${mark};
+            color: inherit;
+        }
+        "#,
+        border = theme.border.clone(),
+        mark = theme.secondary_background.clone(),
+    )
+    .expect("Failed to parse style");
+
+    html! {
+        <div class={style}>
+            <h1>{ format!("{}: {}", language.get("Search"), query.q) }</h1>
+            <div class="error">
+                { error.to_string() }
+            </div>
+            { match &*results {
+                None => html! { <div>{ language.get("Loading...") }</div> },
+                Some(hits) if hits.is_empty() => html! { <div>{ language.get("No results") }</div> },
+                Some(hits) => html! {
+                    { for hits.iter().map(|hit| html! {
+                        <div class="search-result">
+                            <span class="search-type">{ language.get(entity_label(&hit.entity_type)) }</span>
+                            { result_link(hit) }
+                            <div class="search-snippet">
+                                { highlight_to_html(&hit.snippet) }
+                            </div>
+                        </div>
+                    })}
+                },
+            }}
+        </div>
+    }
+}
+
+fn entity_label(entity: &SearchEntity) -> &'static str {
+    match entity {
+        SearchEntity::Ticket => "Ticket",
+        SearchEntity::Note => "Note",
+        SearchEntity::Document => "Document",
+    }
+}
+
+fn result_link(hit: &SearchResult) -> Html {
+    match (&hit.entity_type, hit.ticket_id, hit.document_id) {
+        (SearchEntity::Document, _, Some(document_id)) => html! {
+            <Link<AppRoute> to={AppRoute::WikiDoc { document_id }}>{ hit.title.clone() }</Link<AppRoute>>
+        },
+        (_, Some(ticket_id), _) => html! {
+            <Link<AppRoute> to={AppRoute::Ticket { ticket_id }}>
+                { format!("#{} {}", ticket_id, hit.title) }
+            </Link<AppRoute>>
+        },
+        _ => html! { hit.title.clone() },
+    }
+}
@@ -1,9 +1,12 @@
-use web_sys::HtmlInputElement;
+use shared::models::users::Role;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
 
 use yew::prelude::*;
 use yew_router::prelude::use_navigator;
 
+use crate::components::email_notifications::EmailNotifications;
 use crate::components::logout::Logout;
+use crate::components::note_visibility::NoteVisibility;
 use crate::components::select_locale::SelectLanguage;
 use crate::hooks::{use_language_context, use_user_context};
 use crate::services::users::*;
@@ -56,6 +59,7 @@ pub fn account(props: &Props) -> Html {
         let error = error.clone();
         let password = password.clone();
         let user_id = user_id.clone();
+        let user_ctx = user_ctx.clone();
         use_effect_with(*submitted.clone(),move |submitted| {
             if *submitted {
                 wasm_bindgen_futures::spawn_local(async move {
@@ -137,6 +141,15 @@ pub fn account(props: &Props) -> Html {
             update_info.set(info);
         })
     };
+    let onchange_access = {
+        let update_info = update_info.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlSelectElement = e.target_unchecked_into();
+            let mut info = (*update_info).clone();
+            info.access = input.value();
+            update_info.set(info);
+        })
+    };
     let oninput_password = {
         let password = password.clone();
         Callback::from(move |e: InputEvent| {
@@ -175,6 +188,19 @@ pub fn account(props: &Props) -> Html {
                         <input type="email" placeholder="E-mail" value={update_info.email.clone()}
                             oninput={oninput_email} />
                     </div>
+                    //Only admins can change roles, the backend rejects it for everyone else
+                    if user_ctx.role().is_admin() {
+                    <div>
+                        <label>{ format!("{}:", language.get("Role")) }</label>
+                        <select onchange={onchange_access}>
+                            { for Role::iter().map(|role| html! {
+                                <option value={role.as_str()} selected={update_info.access == role.as_str()}>
+                                    { language.get(role.label()) }
+                                </option>
+                            })}
+                        </select>
+                    </div>
+                    }
                     <div>
                         <label>{ format!("{}:", language.get("Password")) }</label>
                         <input type="password" placeholder="New Password" value={(*password).clone()}
@@ -197,6 +223,10 @@ pub fn account(props: &Props) -> Html {
                 html! {
                     <div>
                         <SelectLanguage />
+                        <EmailNotifications />
+                        if user_ctx.role().can_edit() {
+                            <NoteVisibility />
+                        }
                         <hr />
                         <Logout />
                     </div>
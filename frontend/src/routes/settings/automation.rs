@@ -0,0 +1,638 @@
+use shared::models::{
+    automation::{
+        AutomationAction, AutomationCondition, AutomationTrigger, ConditionField,
+        ConditionOperator, MAX_IDLE_HOURS, TRIGGER_EVENT_TYPES,
+    },
+    users::UserDisplay,
+};
+use uuid::Uuid;
+use web_sys::{HtmlInputElement, HtmlSelectElement, HtmlTextAreaElement};
+use yew::prelude::*;
+use yew_router::prelude::Link;
+
+use crate::components::delete::{DeleteItem, ItemTypes};
+use crate::components::time_format::TimeFormat;
+use crate::hooks::{use_language_context, use_user_context, LanguageContext};
+use crate::routes::AppRoute;
+use crate::services::automation::*;
+use crate::services::projects::get_projects;
+use crate::services::users::get_display_names;
+use crate::services::workflows::{get_priorities, get_statuses};
+use crate::types::{
+    AutomationRuleCreateInfo, AutomationRuleInfo, AutomationRunInfo, PriorityInfo, ProjectInfo,
+    StatusInfo,
+};
+
+const ACTION_KINDS: [(&str, &str); 5] = [
+    ("set_status", "Set status"),
+    ("set_priority", "Set priority"),
+    ("assign", "Assign"),
+    ("add_note", "Add note"),
+    ("notify", "Notify"),
+];
+
+/// Rules changing tickets when they are created, updated or left alone
+#[function_component(AutomationSettings)]
+pub fn automation_settings() -> Html {
+    let user_ctx = use_user_context();
+    let language = use_language_context();
+    let rules = use_state(Vec::<AutomationRuleInfo>::new);
+    let statuses = use_state(Vec::<StatusInfo>::new);
+    let priorities = use_state(Vec::<PriorityInfo>::new);
+    let projects = use_state(Vec::<ProjectInfo>::new);
+    let users = use_state(Vec::<UserDisplay>::new);
+    //Bumped after every change to reload the list
+    let version = use_state(|| 0_u32);
+    //None while adding a new rule
+    let editing = use_state(|| None::<Uuid>);
+    let info = use_state(new_info);
+    let error = use_state(String::new);
+    //Execution log of the rule whose log is open
+    let log = use_state(|| None::<(Uuid, Vec<AutomationRunInfo>)>);
+
+    {
+        let rules = rules.clone();
+        use_effect_with(*version, move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                match get_automation_rules().await {
+                    Ok(list) => rules.set(list),
+                    Err(e) => log::error!("Error loading automation rules: {}", e),
+                }
+            });
+            || ()
+        })
+    }
+
+    {
+        let statuses = statuses.clone();
+        let priorities = priorities.clone();
+        let projects = projects.clone();
+        let users = users.clone();
+        use_effect_with((), move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                statuses.set(get_statuses().await.unwrap_or_default());
+                priorities.set(get_priorities().await.unwrap_or_default());
+                projects.set(get_projects().await.unwrap_or_default());
+                users.set(get_display_names().await.unwrap_or_default());
+            });
+            || ()
+        })
+    }
+
+    if !user_ctx.role().is_admin() {
+        return html! {};
+    }
+
+    let oninput_name = on_input(&info, |info, value| info.name = value);
+
+    let onclick_enabled = {
+        let info = info.clone();
+        Callback::from(move |_: MouseEvent| {
+            let mut new_info = (*info).clone();
+            new_info.enabled = !new_info.enabled;
+            info.set(new_info);
+        })
+    };
+
+    let onchange_trigger = on_change(&info, |info, value| {
+        info.trigger = match value.as_str() {
+            "ticket_event" => AutomationTrigger::TicketEvent {
+                event_type: TRIGGER_EVENT_TYPES[0].to_string(),
+            },
+            "idle" => AutomationTrigger::Idle { hours: 24 },
+            _ => AutomationTrigger::TicketCreated,
+        };
+    });
+
+    let onchange_event = on_change(&info, |info, value| {
+        info.trigger = AutomationTrigger::TicketEvent { event_type: value };
+    });
+
+    let oninput_hours = on_input(&info, |info, value| {
+        info.trigger = AutomationTrigger::Idle {
+            hours: value.parse().unwrap_or(0),
+        };
+    });
+
+    let onclick_add_condition = {
+        let info = info.clone();
+        Callback::from(move |_: MouseEvent| {
+            let mut new_info = (*info).clone();
+            new_info.conditions.push(AutomationCondition {
+                field: ConditionField::Title,
+                operator: ConditionOperator::Contains,
+                value: String::new(),
+            });
+            info.set(new_info);
+        })
+    };
+
+    let onclick_add_action = {
+        let info = info.clone();
+        let statuses = statuses.clone();
+        Callback::from(move |_: MouseEvent| {
+            let mut new_info = (*info).clone();
+            new_info.actions.push(AutomationAction::SetStatus {
+                status: statuses.first().map(|status| status.name.clone()).unwrap_or_default(),
+            });
+            info.set(new_info);
+        })
+    };
+
+    let reset = {
+        let info = info.clone();
+        let editing = editing.clone();
+        Callback::from(move |_| {
+            editing.set(None);
+            info.set(new_info());
+        })
+    };
+
+    let onsubmit = {
+        let info = info.clone();
+        let editing = editing.clone();
+        let error = error.clone();
+        let version = version.clone();
+        let reset = reset.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let info = (*info).clone();
+            let editing = editing.clone();
+            let error = error.clone();
+            let version = version.clone();
+            let reset = reset.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let result = match *editing {
+                    Some(rule_id) => update_automation_rule(rule_id, &info).await,
+                    None => create_automation_rule(&info).await,
+                };
+                match result {
+                    Ok(response) if response.success => {
+                        error.set(String::new());
+                        reset.emit(());
+                        version.set(*version + 1);
+                    }
+                    Ok(response) => {
+                        error.set(response.message.unwrap_or("Unknown error".to_string()))
+                    }
+                    Err(e) => error.set(e.to_string()),
+                }
+            });
+        })
+    };
+
+    let callback_deleted = {
+        let version = version.clone();
+        let log = log.clone();
+        Callback::from(move |_| {
+            log.set(None);
+            version.set(*version + 1);
+        })
+    };
+
+    //Options for the value of a condition or action naming a user, empty is nobody
+    let user_options = |selected: &str, empty: String| {
+        html! {
+            <>
+                <option value="" selected={selected.is_empty()}>{ empty }</option>
+                { for users.iter().map(|user| html! {
+                    <option value={user.user_id.to_string()} selected={selected == user.user_id.to_string()}>
+                        { &user.display_name }
+                    </option>
+                })}
+            </>
+        }
+    };
+    let status_options = |selected: &str| {
+        html! {
+            { for statuses.iter().map(|status| html! {
+                <option value={status.name.clone()} selected={selected == status.name}>{ &status.name }</option>
+            })}
+        }
+    };
+    let priority_options = |selected: &str| {
+        html! {
+            { for priorities.iter().map(|priority| html! {
+                <option value={priority.name.clone()} selected={selected == priority.name}>{ &priority.name }</option>
+            })}
+        }
+    };
+
+    html! {
+        <div>
+            <h1>{ language.get("Automation") }</h1>
+            <p>{ language.get("Enabled rules run in this order. Changes made by rules do not trigger other rules.") }</p>
+            <table>
+                <thead>
+                    <tr>
+                        <th>{ language.get("Name") }</th>
+                        <th>{ language.get("Trigger") }</th>
+                        <th>{ language.get("Conditions") }</th>
+                        <th>{ language.get("Actions") }</th>
+                        <th>{ language.get("Enabled") }</th>
+                        <th></th>
+                    </tr>
+                </thead>
+                <tbody>
+                    { for rules.iter().map(|rule| {
+                        let onclick_edit = {
+                            let info = info.clone();
+                            let editing = editing.clone();
+                            let rule = rule.clone();
+                            Callback::from(move |_| {
+                                editing.set(Some(rule.rule_id));
+                                info.set(AutomationRuleCreateInfo {
+                                    name: rule.name.clone(),
+                                    enabled: rule.enabled,
+                                    trigger: rule.trigger.clone(),
+                                    conditions: rule.conditions.clone(),
+                                    actions: rule.actions.clone(),
+                                });
+                            })
+                        };
+                        let onclick_log = {
+                            let log = log.clone();
+                            let rule_id = rule.rule_id;
+                            Callback::from(move |_| {
+                                if log.as_ref().is_some_and(|(open, _)| *open == rule_id) {
+                                    return log.set(None);
+                                }
+                                let log = log.clone();
+                                wasm_bindgen_futures::spawn_local(async move {
+                                    match get_automation_runs(rule_id).await {
+                                        Ok(runs) => log.set(Some((rule_id, runs))),
+                                        Err(e) => log::error!("Error loading runs: {}", e),
+                                    }
+                                });
+                            })
+                        };
+                        let actions = rule
+                            .actions
+                            .iter()
+                            .map(|action| action_label(action, &language))
+                            .collect::<Vec<String>>()
+                            .join(", ");
+                        html! {
+                            <tr>
+                                <td>{ &rule.name }</td>
+                                <td>{ describe(&rule.trigger, &language) }</td>
+                                <td>{ rule.conditions.len() }</td>
+                                <td>{ actions }</td>
+                                <td>{ if rule.enabled { language.get("Yes") } else { language.get("No") } }</td>
+                                <td>
+                                    <button class="btn" onclick={onclick_edit}>{ language.get("Edit") }</button>
+                                    <button class="btn" onclick={onclick_log}>{ language.get("Log") }</button>
+                                    <DeleteItem item_id={rule.rule_id.to_string()} item_type={ItemTypes::AutomationRule}
+                                        callback={callback_deleted.clone()} />
+                                </td>
+                            </tr>
+                        }
+                    })}
+                </tbody>
+            </table>
+            if let Some((_, runs)) = &*log {
+                <h3>{ language.get("Execution log") }</h3>
+                if runs.is_empty() {
+                    <p>{ language.get("This rule has not run yet") }</p>
+                }
+                <table>
+                    <tbody>
+                        { for runs.iter().map(|run| html! {
+                            <tr>
+                                <td><TimeFormat time={run.created_at} /></td>
+                                <td>
+                                    if let Some(ticket_id) = run.ticket_id {
+                                        <Link<AppRoute> to={AppRoute::Ticket { ticket_id }}>{ format!("#{}", ticket_id) }</Link<AppRoute>>
+                                    } else {
+                                        { language.get("Deleted") }
+                                    }
+                                </td>
+                                <td>{ language.get(trigger_label(&run.trigger)) }</td>
+                                <td>{ if run.success { language.get("Done") } else { language.get("Failed") } }</td>
+                                <td>{ &run.message }</td>
+                            </tr>
+                        })}
+                    </tbody>
+                </table>
+            }
+            <h3>
+                { if editing.is_some() { language.get("Edit rule") } else { language.get("New rule") } }
+            </h3>
+            <div class="error">{ error.to_string() }</div>
+            <form {onsubmit}>
+                <div>
+                    <input type="text" placeholder={language.get("Name")} value={info.name.clone()} oninput={oninput_name} />
+                    <label style="margin-left: 8px;">
+                        <input type="checkbox" checked={info.enabled} onclick={onclick_enabled} />
+                        { language.get("Enabled") }
+                    </label>
+                </div>
+                <h4>{ language.get("When") }</h4>
+                <div>
+                    <select onchange={onchange_trigger}>
+                        { for [
+                            ("ticket_created", "A ticket is created"),
+                            ("ticket_event", "A ticket is updated"),
+                            ("idle", "A ticket is not updated for"),
+                        ].into_iter().map(|(value, label)| html! {
+                            <option value={value} selected={info.trigger.kind() == value}>
+                                { language.get(label) }
+                            </option>
+                        })}
+                    </select>
+                    { match &info.trigger {
+                        AutomationTrigger::TicketEvent { event_type } => html! {
+                            <select style="margin-left: 8px;" onchange={onchange_event}>
+                                { for TRIGGER_EVENT_TYPES.iter().map(|allowed| html! {
+                                    <option value={allowed.to_string()} selected={*event_type == allowed.to_string()}>
+                                        { language.get(trigger_label(&allowed.to_string())) }
+                                    </option>
+                                })}
+                            </select>
+                        },
+                        AutomationTrigger::Idle { hours } => html! {
+                            <label style="margin-left: 8px;">
+                                <input type="number" min="1" max={MAX_IDLE_HOURS.to_string()} style="width: 64px;" value={hours.to_string()}
+                                    oninput={oninput_hours} />
+                                { format!(" {}", language.get("hours")) }
+                            </label>
+                        },
+                        AutomationTrigger::TicketCreated => html! {},
+                    }}
+                </div>
+                <h4>{ language.get("If all of these match") }</h4>
+                { for info.conditions.iter().enumerate().map(|(index, condition)| {
+                    //Changing the field starts from an empty value
+                    let onchange_field = on_change(&info, move |info, value| {
+                        if let Some(field) = ConditionField::iter().find(|field| field.as_str() == value) {
+                            info.conditions[index].field = field;
+                            info.conditions[index].value = String::new();
+                        }
+                    });
+                    let onchange_operator = on_change(&info, move |info, value| {
+                        if let Some(operator) = ConditionOperator::iter().find(|operator| operator.as_str() == value) {
+                            info.conditions[index].operator = operator;
+                        }
+                    });
+                    let oninput_value = on_input(&info, move |info, value| info.conditions[index].value = value);
+                    let onchange_value = on_change(&info, move |info, value| info.conditions[index].value = value);
+                    let onclick_remove = {
+                        let info = info.clone();
+                        Callback::from(move |_: MouseEvent| {
+                            let mut new_info = (*info).clone();
+                            new_info.conditions.remove(index);
+                            info.set(new_info);
+                        })
+                    };
+                    let value = condition.value.as_str();
+                    html! {
+                        <div>
+                            <select onchange={onchange_field}>
+                                { for ConditionField::iter().map(|field| html! {
+                                    <option value={field.as_str()} selected={condition.field == field}>
+                                        { language.get(field.label()) }
+                                    </option>
+                                })}
+                            </select>
+                            <select style="margin-left: 8px;" onchange={onchange_operator}>
+                                { for ConditionOperator::iter().map(|operator| html! {
+                                    <option value={operator.as_str()} selected={condition.operator == operator}>
+                                        { language.get(operator.label()) }
+                                    </option>
+                                })}
+                            </select>
+                            { match condition.field {
+                                ConditionField::Status => html! {
+                                    <select style="margin-left: 8px;" onchange={onchange_value}>
+                                        <option value="" selected={value.is_empty()}>{ "" }</option>
+                                        { status_options(value) }
+                                    </select>
+                                },
+                                ConditionField::Priority => html! {
+                                    <select style="margin-left: 8px;" onchange={onchange_value}>
+                                        <option value="" selected={value.is_empty()}>{ "" }</option>
+                                        { priority_options(value) }
+                                    </select>
+                                },
+                                ConditionField::Assignee => html! {
+                                    <select style="margin-left: 8px;" onchange={onchange_value}>
+                                        { user_options(value, language.get("Unassigned")) }
+                                    </select>
+                                },
+                                ConditionField::Project => html! {
+                                    <select style="margin-left: 8px;" onchange={onchange_value}>
+                                        <option value="" selected={value.is_empty()}>{ language.get("No project") }</option>
+                                        { for projects.iter().map(|project| html! {
+                                            <option value={project.project_id.to_string()}
+                                                selected={value == project.project_id.to_string()}>
+                                                { &project.name }
+                                            </option>
+                                        })}
+                                    </select>
+                                },
+                                ConditionField::Title | ConditionField::Description => html! {
+                                    <input type="text" style="margin-left: 8px;" value={condition.value.clone()}
+                                        oninput={oninput_value} />
+                                },
+                            }}
+                            <button class="btn" type="button" onclick={onclick_remove}>{ language.get("Remove") }</button>
+                        </div>
+                    }
+                })}
+                <button class="btn" type="button" onclick={onclick_add_condition}>{ language.get("Add condition") }</button>
+                <h4>{ language.get("Then") }</h4>
+                { for info.actions.iter().enumerate().map(|(index, action)| {
+                    //Switching the kind starts from the first value on offer
+                    let onchange_kind = {
+                        let statuses = statuses.clone();
+                        let priorities = priorities.clone();
+                        on_change(&info, move |info, value| {
+                            info.actions[index] = match value.as_str() {
+                                "set_status" => AutomationAction::SetStatus {
+                                    status: statuses.first().map(|status| status.name.clone()).unwrap_or_default(),
+                                },
+                                "set_priority" => AutomationAction::SetPriority {
+                                    priority: priorities.first().map(|priority| priority.name.clone()).unwrap_or_default(),
+                                },
+                                "assign" => AutomationAction::Assign { user_id: None },
+                                "add_note" => AutomationAction::AddNote { text: String::new(), is_public: false },
+                                _ => AutomationAction::Notify { user_id: None, message: String::new() },
+                            };
+                        })
+                    };
+                    let onchange_value = on_change(&info, move |info, value| {
+                        match &mut info.actions[index] {
+                            AutomationAction::SetStatus { status } => *status = value,
+                            AutomationAction::SetPriority { priority } => *priority = value,
+                            AutomationAction::Assign { user_id } | AutomationAction::Notify { user_id, .. } => {
+                                *user_id = value.parse().ok()
+                            }
+                            AutomationAction::AddNote { .. } => (),
+                        }
+                    });
+                    let oninput_text = {
+                        let info = info.clone();
+                        Callback::from(move |e: InputEvent| {
+                            let input: HtmlTextAreaElement = e.target_unchecked_into();
+                            let mut new_info = (*info).clone();
+                            match &mut new_info.actions[index] {
+                                AutomationAction::AddNote { text, .. } => *text = input.value(),
+                                AutomationAction::Notify { message, .. } => *message = input.value(),
+                                _ => (),
+                            }
+                            info.set(new_info);
+                        })
+                    };
+                    let onclick_public = {
+                        let info = info.clone();
+                        Callback::from(move |_: MouseEvent| {
+                            let mut new_info = (*info).clone();
+                            if let AutomationAction::AddNote { is_public, .. } = &mut new_info.actions[index] {
+                                *is_public = !*is_public;
+                            }
+                            info.set(new_info);
+                        })
+                    };
+                    let onclick_remove = {
+                        let info = info.clone();
+                        Callback::from(move |_: MouseEvent| {
+                            let mut new_info = (*info).clone();
+                            new_info.actions.remove(index);
+                            info.set(new_info);
+                        })
+                    };
+                    html! {
+                        <div>
+                            <select onchange={onchange_kind}>
+                                { for ACTION_KINDS.iter().map(|(value, label)| html! {
+                                    <option value={*value} selected={action.kind() == *value}>
+                                        { language.get(label) }
+                                    </option>
+                                })}
+                            </select>
+                            { match action {
+                                AutomationAction::SetStatus { status } => html! {
+                                    <select style="margin-left: 8px;" onchange={onchange_value}>
+                                        { status_options(status) }
+                                    </select>
+                                },
+                                AutomationAction::SetPriority { priority } => html! {
+                                    <select style="margin-left: 8px;" onchange={onchange_value}>
+                                        { priority_options(priority) }
+                                    </select>
+                                },
+                                AutomationAction::Assign { user_id } => html! {
+                                    <select style="margin-left: 8px;" onchange={onchange_value}>
+                                        { user_options(&user_id.map(|user_id| user_id.to_string()).unwrap_or_default(),
+                                            language.get("Unassigned")) }
+                                    </select>
+                                },
+                                AutomationAction::AddNote { text, is_public } => html! {
+                                    <>
+                                        <label style="margin-left: 8px;">
+                                            <input type="checkbox" checked={*is_public} onclick={onclick_public} />
+                                            { language.get("Public") }
+                                        </label>
+                                        <div>
+                                            <textarea rows="3" cols="60" placeholder={language.get("Note")}
+                                                value={text.clone()} oninput={oninput_text}>
+                                            </textarea>
+                                        </div>
+                                    </>
+                                },
+                                AutomationAction::Notify { user_id, message } => html! {
+                                    <>
+                                        <select style="margin-left: 8px;" onchange={onchange_value}>
+                                            { user_options(&user_id.map(|user_id| user_id.to_string()).unwrap_or_default(),
+                                                language.get("Assignee")) }
+                                        </select>
+                                        <div>
+                                            <textarea rows="2" cols="60" placeholder={language.get("Message")}
+                                                value={message.clone()} oninput={oninput_text}>
+                                            </textarea>
+                                        </div>
+                                    </>
+                                },
+                            }}
+                            <button class="btn" type="button" onclick={onclick_remove}>{ language.get("Remove") }</button>
+                        </div>
+                    }
+                })}
+                <button class="btn" type="button" onclick={onclick_add_action}>{ language.get("Add action") }</button>
+                <div>
+                    <button class="btn" type="submit">{ language.get("Save") }</button>
+                    if editing.is_some() {
+                        <button class="btn" type="button" onclick={reset.reform(|_: MouseEvent| ())}>{ language.get("Cancel") }</button>
+                    }
+                </div>
+            </form>
+        </div>
+    }
+}
+
+fn new_info() -> AutomationRuleCreateInfo {
+    AutomationRuleCreateInfo {
+        enabled: true,
+        ..Default::default()
+    }
+}
+
+/// Callback setting a field of the form from a text input
+fn on_input(
+    info: &UseStateHandle<AutomationRuleCreateInfo>,
+    set: impl Fn(&mut AutomationRuleCreateInfo, String) + 'static,
+) -> Callback<InputEvent> {
+    let info = info.clone();
+    Callback::from(move |e: InputEvent| {
+        let input: HtmlInputElement = e.target_unchecked_into();
+        let mut new_info = (*info).clone();
+        set(&mut new_info, input.value());
+        info.set(new_info);
+    })
+}
+
+/// Callback setting a field of the form from a select
+fn on_change(
+    info: &UseStateHandle<AutomationRuleCreateInfo>,
+    set: impl Fn(&mut AutomationRuleCreateInfo, String) + 'static,
+) -> Callback<Event> {
+    let info = info.clone();
+    Callback::from(move |e: Event| {
+        let input: HtmlSelectElement = e.target_unchecked_into();
+        let mut new_info = (*info).clone();
+        set(&mut new_info, input.value());
+        info.set(new_info);
+    })
+}
+
+/// Label of a trigger as recorded in the execution log
+fn trigger_label(trigger: &str) -> &'static str {
+    match trigger {
+        "ticket_created" => "Ticket created",
+        "assigned" => "Assignee changed",
+        "status_updated" => "Status changed",
+        "priority_updated" => "Priority changed",
+        "title_updated" => "Title changed",
+        "due_date_updated" => "Due date changed",
+        "idle" => "Idle",
+        _ => "Unknown event",
+    }
+}
+
+fn describe(trigger: &AutomationTrigger, language: &LanguageContext) -> String {
+    match trigger {
+        AutomationTrigger::TicketCreated => language.get("Ticket created"),
+        AutomationTrigger::TicketEvent { event_type } => language.get(trigger_label(event_type)),
+        AutomationTrigger::Idle { hours } => {
+            format!("{}: {} {}", language.get("Idle"), hours, language.get("hours"))
+        }
+    }
+}
+
+fn action_label(action: &AutomationAction, language: &LanguageContext) -> String {
+    ACTION_KINDS
+        .iter()
+        .find(|(kind, _)| *kind == action.kind())
+        .map(|(_, label)| language.get(label))
+        .unwrap_or_default()
+}
@@ -0,0 +1,239 @@
+use shared::models::custom_fields::CustomFieldType;
+use uuid::Uuid;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+use crate::components::delete::{DeleteItem, ItemTypes};
+use crate::hooks::{use_language_context, use_user_context};
+use crate::services::custom_fields::*;
+use crate::types::{CustomFieldCreateInfo, CustomFieldInfo};
+
+fn new_field() -> CustomFieldCreateInfo {
+    CustomFieldCreateInfo {
+        field_type: CustomFieldType::Text.to_string(),
+        ..Default::default()
+    }
+}
+
+/// Admin list of custom ticket fields with a form to add or edit one
+#[function_component(CustomFieldSettings)]
+pub fn custom_field_settings() -> Html {
+    let user_ctx = use_user_context();
+    let language = use_language_context();
+    let fields = use_state(Vec::<CustomFieldInfo>::new);
+    //Bumped after every change to reload the list
+    let version = use_state(|| 0_u32);
+    //None while adding a new field
+    let editing = use_state(|| None::<Uuid>);
+    let info = use_state(new_field);
+    //Select options are edited one per line
+    let options = use_state(String::new);
+    let error = use_state(String::new);
+
+    {
+        let fields = fields.clone();
+        use_effect_with(*version, move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                match get_custom_fields().await {
+                    Ok(list) => fields.set(list),
+                    Err(e) => log::error!("Error loading custom fields: {}", e),
+                }
+            });
+            || ()
+        })
+    }
+
+    let oninput_name = {
+        let info = info.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut new_info = (*info).clone();
+            new_info.name = input.value();
+            info.set(new_info);
+        })
+    };
+
+    let onselect_type = {
+        let info = info.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlSelectElement = e.target_unchecked_into();
+            let mut new_info = (*info).clone();
+            new_info.field_type = input.value();
+            info.set(new_info);
+        })
+    };
+
+    let oninput_options = {
+        let options = options.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            options.set(input.value());
+        })
+    };
+
+    let onchange_required = {
+        let info = info.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut new_info = (*info).clone();
+            new_info.required = input.checked();
+            info.set(new_info);
+        })
+    };
+
+    let oninput_position = {
+        let info = info.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut new_info = (*info).clone();
+            new_info.position = input.value().parse().unwrap_or_default();
+            info.set(new_info);
+        })
+    };
+
+    let reset = {
+        let info = info.clone();
+        let options = options.clone();
+        let editing = editing.clone();
+        Callback::from(move |_| {
+            editing.set(None);
+            info.set(new_field());
+            options.set(String::new());
+        })
+    };
+
+    let onsubmit = {
+        let info = info.clone();
+        let options = options.clone();
+        let editing = editing.clone();
+        let error = error.clone();
+        let version = version.clone();
+        let reset = reset.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let mut info = (*info).clone();
+            info.options = options.lines().map(|line| line.to_string()).collect();
+            let editing = editing.clone();
+            let error = error.clone();
+            let version = version.clone();
+            let reset = reset.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let result = match *editing {
+                    Some(field_id) => update_custom_field(field_id, &info).await,
+                    None => create_custom_field(&info).await,
+                };
+                match result {
+                    Ok(response) if response.success => {
+                        error.set(String::new());
+                        reset.emit(());
+                        version.set(*version + 1);
+                    }
+                    Ok(response) => {
+                        error.set(response.message.unwrap_or("Unknown error".to_string()))
+                    }
+                    Err(e) => error.set(e.to_string()),
+                }
+            });
+        })
+    };
+
+    let callback_deleted = {
+        let version = version.clone();
+        Callback::from(move |_| version.set(*version + 1))
+    };
+
+    if !user_ctx.role().is_admin() {
+        return html! {};
+    }
+
+    let is_select = info.field_type == CustomFieldType::Select.as_str();
+
+    html! {
+        <div>
+            <h1>{ language.get("Custom fields") }</h1>
+            <table>
+                <thead>
+                    <tr>
+                        <th>{ language.get("Name") }</th>
+                        <th>{ language.get("Type") }</th>
+                        <th>{ language.get("Required") }</th>
+                        <th></th>
+                    </tr>
+                </thead>
+                <tbody>
+                    { for fields.iter().map(|field| {
+                        let onclick_edit = {
+                            let info = info.clone();
+                            let options = options.clone();
+                            let editing = editing.clone();
+                            let field = field.clone();
+                            Callback::from(move |_| {
+                                editing.set(Some(field.field_id));
+                                options.set(field.options.join("\n"));
+                                info.set(CustomFieldCreateInfo {
+                                    name: field.name.clone(),
+                                    field_type: field.field_type.clone(),
+                                    options: field.options.clone(),
+                                    required: field.required,
+                                    position: field.position,
+                                });
+                            })
+                        };
+                        let type_label = field
+                            .field_type
+                            .parse::<CustomFieldType>()
+                            .map(|field_type| language.get(field_type.label()))
+                            .unwrap_or(field.field_type.clone());
+                        html! {
+                            <tr>
+                                <td>{ &field.name }</td>
+                                <td>{ type_label }</td>
+                                <td>{ if field.required { "✔" } else { "" } }</td>
+                                <td>
+                                    <button class="btn" onclick={onclick_edit}>{ language.get("Edit") }</button>
+                                    <DeleteItem item_id={field.field_id.to_string()} item_type={ItemTypes::CustomField}
+                                        callback={callback_deleted.clone()} />
+                                </td>
+                            </tr>
+                        }
+                    })}
+                </tbody>
+            </table>
+            <h3>
+                { if editing.is_some() { language.get("Edit field") } else { language.get("New field") } }
+            </h3>
+            <div class="error">{ error.to_string() }</div>
+            <form {onsubmit}>
+                <div>
+                    <input type="text" placeholder={language.get("Name")} value={info.name.clone()} oninput={oninput_name} />
+                    //The type cannot be changed once there may be values
+                    <select onchange={onselect_type} disabled={editing.is_some()}>
+                        { for CustomFieldType::iter().map(|field_type| html! {
+                            <option value={field_type.to_string()} selected={info.field_type == field_type.as_str()}>
+                                { language.get(field_type.label()) }
+                            </option>
+                        })}
+                    </select>
+                    <label>
+                        <input type="checkbox" checked={info.required} onchange={onchange_required} />
+                        { language.get("Required") }
+                    </label>
+                    <label style="margin-left: 8px;">
+                        { format!("{}: ", language.get("Position")) }
+                        <input type="number" style="width: 64px;" value={info.position.to_string()} oninput={oninput_position} />
+                    </label>
+                </div>
+                if is_select {
+                    <div>
+                        <textarea rows="4" placeholder={language.get("Options, one per line")}
+                            value={(*options).clone()} oninput={oninput_options} />
+                    </div>
+                }
+                <button class="btn" type="submit">{ language.get("Save") }</button>
+                if editing.is_some() {
+                    <button class="btn" type="button" onclick={reset.reform(|_: MouseEvent| ())}>{ language.get("Cancel") }</button>
+                }
+            </form>
+        </div>
+    }
+}
@@ -1,5 +1,13 @@
 mod account;
+mod automation;
+mod custom_fields;
 mod nav;
+mod projects;
+mod recurring;
+mod sla;
+mod tags;
+mod webhooks;
+mod workflows;
 
 use stylist::style;
 use yew::prelude::*;
@@ -8,7 +16,15 @@ use yew_router::prelude::use_route;
 use crate::contexts::theme;
 use crate::routes::SettingsRoute;
 use crate::routes::settings::account::AccountSettings;
+use crate::routes::settings::automation::AutomationSettings;
+use crate::routes::settings::custom_fields::CustomFieldSettings;
 use crate::routes::settings::nav::SettingsNav;
+use crate::routes::settings::projects::ProjectSettings;
+use crate::routes::settings::recurring::RecurringSettings;
+use crate::routes::settings::sla::SlaSettings;
+use crate::routes::settings::tags::TagSettings;
+use crate::routes::settings::webhooks::WebhookSettings;
+use crate::routes::settings::workflows::WorkflowSettings;
 
 use super::AppRoute;
 
@@ -96,6 +112,54 @@ pub fn settings() -> Html {
                                 <h1>{ "Tickets" }</h1>
                             </div>
                         }
+                    } else if let SettingsRoute::Projects = route {
+                        html!{
+                            <div class="settings-body-header">
+                                <ProjectSettings />
+                            </div>
+                        }
+                    } else if let SettingsRoute::Tags = route {
+                        html!{
+                            <div class="settings-body-header">
+                                <TagSettings />
+                            </div>
+                        }
+                    } else if let SettingsRoute::CustomFields = route {
+                        html!{
+                            <div class="settings-body-header">
+                                <CustomFieldSettings />
+                            </div>
+                        }
+                    } else if let SettingsRoute::Workflow = route {
+                        html!{
+                            <div class="settings-body-header">
+                                <WorkflowSettings />
+                            </div>
+                        }
+                    } else if let SettingsRoute::Sla = route {
+                        html!{
+                            <div class="settings-body-header">
+                                <SlaSettings />
+                            </div>
+                        }
+                    } else if let SettingsRoute::Automation = route {
+                        html!{
+                            <div class="settings-body-header">
+                                <AutomationSettings />
+                            </div>
+                        }
+                    } else if let SettingsRoute::Webhooks = route {
+                        html!{
+                            <div class="settings-body-header">
+                                <WebhookSettings />
+                            </div>
+                        }
+                    } else if let SettingsRoute::Recurring = route {
+                        html!{
+                            <div class="settings-body-header">
+                                <RecurringSettings />
+                            </div>
+                        }
                     } else if let SettingsRoute::Account { user_id } = route {
                         html!{
                             <div class="settings-body-header">
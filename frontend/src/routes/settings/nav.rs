@@ -27,6 +27,50 @@ pub fn settings_nav() -> Html {
                             { "Tickets" }
                         </Link<SettingsRoute>>
                     </li>
+                    if user_ctx.role().is_admin() {
+                    <li>
+                        <Link<SettingsRoute> to={SettingsRoute::Projects} classes="nav-link">
+                            { language.get("Projects") }
+                        </Link<SettingsRoute>>
+                    </li>
+                    <li>
+                        <Link<SettingsRoute> to={SettingsRoute::Tags} classes="nav-link">
+                            { language.get("Tags") }
+                        </Link<SettingsRoute>>
+                    </li>
+                    <li>
+                        <Link<SettingsRoute> to={SettingsRoute::CustomFields} classes="nav-link">
+                            { language.get("Custom fields") }
+                        </Link<SettingsRoute>>
+                    </li>
+                    <li>
+                        <Link<SettingsRoute> to={SettingsRoute::Workflow} classes="nav-link">
+                            { language.get("Workflow") }
+                        </Link<SettingsRoute>>
+                    </li>
+                    <li>
+                        <Link<SettingsRoute> to={SettingsRoute::Sla} classes="nav-link">
+                            { language.get("SLA") }
+                        </Link<SettingsRoute>>
+                    </li>
+                    <li>
+                        <Link<SettingsRoute> to={SettingsRoute::Automation} classes="nav-link">
+                            { language.get("Automation") }
+                        </Link<SettingsRoute>>
+                    </li>
+                    <li>
+                        <Link<SettingsRoute> to={SettingsRoute::Webhooks} classes="nav-link">
+                            { language.get("Webhooks") }
+                        </Link<SettingsRoute>>
+                    </li>
+                    }
+                    if user_ctx.role().can_edit() {
+                    <li>
+                        <Link<SettingsRoute> to={SettingsRoute::Recurring} classes="nav-link">
+                            { language.get("Recurring tickets") }
+                        </Link<SettingsRoute>>
+                    </li>
+                    }
                     <li>
                         <Link<SettingsRoute> to={SettingsRoute::Account { user_id: user_ctx.user_id.clone() }} classes="nav-link">
                             { "Account" }
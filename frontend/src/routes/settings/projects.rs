@@ -0,0 +1,193 @@
+use uuid::Uuid;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+use yew::suspense::use_future;
+
+use crate::components::delete::{DeleteItem, ItemTypes};
+use crate::hooks::{use_language_context, use_user_context};
+use crate::services::projects::*;
+use crate::services::users::get_display_names;
+use crate::types::{ProjectCreateInfo, ProjectInfo};
+
+/// Admin list of projects with a form to add or edit one
+#[function_component(ProjectSettings)]
+pub fn project_settings() -> Html {
+    let user_ctx = use_user_context();
+    let language = use_language_context();
+    let projects = use_state(Vec::<ProjectInfo>::new);
+    //Bumped after every change to reload the list
+    let version = use_state(|| 0_u32);
+    //None while adding a new project
+    let editing = use_state(|| None::<Uuid>);
+    let info = use_state(ProjectCreateInfo::default);
+    let error = use_state(String::new);
+
+    let userlist = match use_future(|| async { get_display_names().await.unwrap_or_default() }) {
+        Ok(users) => users.clone(),
+        Err(_) => vec![],
+    };
+
+    {
+        let projects = projects.clone();
+        use_effect_with(*version, move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                match get_projects().await {
+                    Ok(list) => projects.set(list),
+                    Err(e) => log::error!("Error loading projects: {}", e),
+                }
+            });
+            || ()
+        })
+    }
+
+    let oninput_name = {
+        let info = info.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut new_info = (*info).clone();
+            new_info.name = input.value();
+            info.set(new_info);
+        })
+    };
+
+    let oninput_key = {
+        let info = info.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut new_info = (*info).clone();
+            new_info.key = input.value().to_uppercase();
+            info.set(new_info);
+        })
+    };
+
+    let onselect_assignee = {
+        let info = info.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlSelectElement = e.target_unchecked_into();
+            let mut new_info = (*info).clone();
+            new_info.default_assignee = input.value().parse().ok();
+            info.set(new_info);
+        })
+    };
+
+    let onsubmit = {
+        let info = info.clone();
+        let editing = editing.clone();
+        let error = error.clone();
+        let version = version.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let info = info.clone();
+            let editing = editing.clone();
+            let error = error.clone();
+            let version = version.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let result = match *editing {
+                    Some(project_id) => update_project(project_id, &info).await,
+                    None => create_project(&info).await,
+                };
+                match result {
+                    Ok(response) if response.success => {
+                        error.set(String::new());
+                        editing.set(None);
+                        info.set(ProjectCreateInfo::default());
+                        version.set(*version + 1);
+                    }
+                    Ok(response) => {
+                        error.set(response.message.unwrap_or("Unknown error".to_string()))
+                    }
+                    Err(e) => error.set(e.to_string()),
+                }
+            });
+        })
+    };
+
+    let onclick_cancel = {
+        let info = info.clone();
+        let editing = editing.clone();
+        Callback::from(move |_| {
+            editing.set(None);
+            info.set(ProjectCreateInfo::default());
+        })
+    };
+
+    let callback_deleted = {
+        let version = version.clone();
+        Callback::from(move |_| version.set(*version + 1))
+    };
+
+    if !user_ctx.role().is_admin() {
+        return html! {};
+    }
+
+    html! {
+        <div>
+            <h1>{ language.get("Projects") }</h1>
+            <table>
+                <thead>
+                    <tr>
+                        <th>{ language.get("Name") }</th>
+                        <th>{ language.get("Key") }</th>
+                        <th>{ language.get("Default assignee") }</th>
+                        <th></th>
+                    </tr>
+                </thead>
+                <tbody>
+                    { for projects.iter().map(|project| {
+                        let onclick_edit = {
+                            let info = info.clone();
+                            let editing = editing.clone();
+                            let project = project.clone();
+                            Callback::from(move |_| {
+                                editing.set(Some(project.project_id));
+                                info.set(ProjectCreateInfo {
+                                    name: project.name.clone(),
+                                    key: project.key.clone(),
+                                    default_assignee: project.default_assignee,
+                                });
+                            })
+                        };
+                        let assignee = userlist
+                            .iter()
+                            .find(|user| Some(user.user_id) == project.default_assignee)
+                            .map(|user| user.display_name.clone())
+                            .unwrap_or_default();
+                        html! {
+                            <tr>
+                                <td>{ &project.name }</td>
+                                <td>{ &project.key }</td>
+                                <td>{ assignee }</td>
+                                <td>
+                                    <button class="btn" onclick={onclick_edit}>{ language.get("Edit") }</button>
+                                    <DeleteItem item_id={project.project_id.to_string()} item_type={ItemTypes::Project}
+                                        callback={callback_deleted.clone()} />
+                                </td>
+                            </tr>
+                        }
+                    })}
+                </tbody>
+            </table>
+            <h3>
+                { if editing.is_some() { language.get("Edit project") } else { language.get("New project") } }
+            </h3>
+            <div class="error">{ error.to_string() }</div>
+            <form {onsubmit}>
+                <input type="text" placeholder={language.get("Name")} value={info.name.clone()} oninput={oninput_name} />
+                <input type="text" placeholder={language.get("Key")} value={info.key.clone()} oninput={oninput_key}
+                    maxlength="10" style="width: 80px;" />
+                <select onchange={onselect_assignee}>
+                    <option value="" selected={info.default_assignee.is_none()}>{ language.get("Unassigned") }</option>
+                    { for userlist.iter().map(|user| html! {
+                        <option value={user.user_id.to_string()} selected={info.default_assignee == Some(user.user_id)}>
+                            { user.display_name.clone() }
+                        </option>
+                    })}
+                </select>
+                <button class="btn" type="submit">{ language.get("Save") }</button>
+                if editing.is_some() {
+                    <button class="btn" type="button" onclick={onclick_cancel}>{ language.get("Cancel") }</button>
+                }
+            </form>
+        </div>
+    }
+}
@@ -0,0 +1,554 @@
+use chrono::{Local, TimeZone};
+use shared::models::{
+    recurring::{Recurrence, MAX_DAILY_INTERVAL, MAX_WEEKLY_INTERVAL, WEEKDAYS},
+    users::UserDisplay,
+};
+use uuid::Uuid;
+use web_sys::{HtmlInputElement, HtmlSelectElement, HtmlTextAreaElement};
+use yew::prelude::*;
+use yew_router::prelude::Link;
+
+use crate::components::delete::{DeleteItem, ItemTypes};
+use crate::components::time_format::TimeFormat;
+use crate::contexts::time::use_time;
+use crate::hooks::{use_language_context, use_user_context, LanguageContext};
+use crate::routes::AppRoute;
+use crate::services::recurring::*;
+use crate::services::users::get_display_names;
+use crate::services::workflows::get_priorities;
+use crate::types::{
+    PriorityInfo, RecurringTicketCreateInfo, RecurringTicketInfo, RecurringTicketRunInfo,
+    TaskCreateInfo,
+};
+
+const DATETIME_INPUT_FORMAT: &str = "%Y-%m-%dT%H:%M";
+
+/// Tickets created on a schedule, with their preset fields and checklist
+#[function_component(RecurringSettings)]
+pub fn recurring_settings() -> Html {
+    let user_ctx = use_user_context();
+    let language = use_language_context();
+    let time_ctx = use_time();
+    let recurring = use_state(Vec::<RecurringTicketInfo>::new);
+    let priorities = use_state(Vec::<PriorityInfo>::new);
+    let users = use_state(Vec::<UserDisplay>::new);
+    //Bumped after every change to reload the list
+    let version = use_state(|| 0_u32);
+    //None while adding a new recurring ticket
+    let editing = use_state(|| None::<Uuid>);
+    let info = use_state(new_info);
+    let error = use_state(String::new);
+    //Runs of the recurring ticket whose history is open
+    let history = use_state(|| None::<(Uuid, Vec<RecurringTicketRunInfo>)>);
+
+    {
+        let recurring = recurring.clone();
+        use_effect_with(*version, move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                match get_recurring_tickets().await {
+                    Ok(list) => recurring.set(list),
+                    Err(e) => log::error!("Error loading recurring tickets: {}", e),
+                }
+            });
+            || ()
+        })
+    }
+
+    {
+        let priorities = priorities.clone();
+        let users = users.clone();
+        use_effect_with((), move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                priorities.set(get_priorities().await.unwrap_or_default());
+                users.set(get_display_names().await.unwrap_or_default());
+            });
+            || ()
+        })
+    }
+
+    if !user_ctx.role().can_edit() {
+        return html! {};
+    }
+
+    let oninput_name = on_input(&info, |info, value| info.name = value);
+    let oninput_title = on_input(&info, |info, value| info.title = value);
+
+    let oninput_description = {
+        let info = info.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlTextAreaElement = e.target_unchecked_into();
+            let mut new_info = (*info).clone();
+            new_info.description = input.value();
+            info.set(new_info);
+        })
+    };
+
+    let onchange_assignee = {
+        let info = info.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlSelectElement = e.target_unchecked_into();
+            let mut new_info = (*info).clone();
+            new_info.assignee = input.value().parse().ok();
+            info.set(new_info);
+        })
+    };
+
+    let onchange_priority = {
+        let info = info.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlSelectElement = e.target_unchecked_into();
+            let mut new_info = (*info).clone();
+            new_info.priority = Some(input.value()).filter(|value| !value.is_empty());
+            info.set(new_info);
+        })
+    };
+
+    //Switching the frequency starts from its simplest schedule
+    let onchange_frequency = {
+        let info = info.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlSelectElement = e.target_unchecked_into();
+            let mut new_info = (*info).clone();
+            new_info.recurrence = match input.value().as_str() {
+                "daily" => Recurrence::Daily { interval: 1 },
+                "weekly" => Recurrence::Weekly {
+                    interval: 1,
+                    weekdays: vec![0],
+                },
+                "monthly" => Recurrence::Monthly { day: 1 },
+                _ => Recurrence::FirstBusinessDay,
+            };
+            info.set(new_info);
+        })
+    };
+
+    let oninput_interval = on_input(&info, |info, value| {
+        let value = value.parse().unwrap_or(0);
+        match &mut info.recurrence {
+            Recurrence::Daily { interval } | Recurrence::Weekly { interval, .. } => {
+                *interval = value
+            }
+            _ => (),
+        }
+    });
+
+    let oninput_day = on_input(&info, |info, value| {
+        if let Recurrence::Monthly { day } = &mut info.recurrence {
+            *day = value.parse().unwrap_or(0);
+        }
+    });
+
+    let oninput_start = {
+        let info = info.clone();
+        let time_ctx = time_ctx.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let Ok(start) =
+                chrono::NaiveDateTime::parse_from_str(&input.value(), DATETIME_INPUT_FORMAT)
+            else {
+                return;
+            };
+            let mut new_info = (*info).clone();
+            new_info.start_at = time_ctx.convert_to_utc(&start);
+            info.set(new_info);
+        })
+    };
+
+    let onclick_enabled = {
+        let info = info.clone();
+        Callback::from(move |_: MouseEvent| {
+            let mut new_info = (*info).clone();
+            new_info.enabled = !new_info.enabled;
+            info.set(new_info);
+        })
+    };
+
+    let onclick_add_task = {
+        let info = info.clone();
+        Callback::from(move |_: MouseEvent| {
+            let mut new_info = (*info).clone();
+            new_info.tasks.push(TaskCreateInfo::default());
+            info.set(new_info);
+        })
+    };
+
+    let reset = {
+        let info = info.clone();
+        let editing = editing.clone();
+        Callback::from(move |_| {
+            editing.set(None);
+            info.set(new_info());
+        })
+    };
+
+    let onsubmit = {
+        let info = info.clone();
+        let editing = editing.clone();
+        let error = error.clone();
+        let version = version.clone();
+        let reset = reset.clone();
+        let time_ctx = time_ctx.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            //Saved schedules follow the time zone of whoever saved them last
+            let info = RecurringTicketCreateInfo {
+                utc_offset: time_ctx.offset(),
+                ..(*info).clone()
+            };
+            let editing = editing.clone();
+            let error = error.clone();
+            let version = version.clone();
+            let reset = reset.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let result = match *editing {
+                    Some(recurring_id) => update_recurring_ticket(recurring_id, &info).await,
+                    None => create_recurring_ticket(&info).await,
+                };
+                match result {
+                    Ok(response) if response.success => {
+                        error.set(String::new());
+                        reset.emit(());
+                        version.set(*version + 1);
+                    }
+                    Ok(response) => {
+                        error.set(response.message.unwrap_or("Unknown error".to_string()))
+                    }
+                    Err(e) => error.set(e.to_string()),
+                }
+            });
+        })
+    };
+
+    let callback_deleted = {
+        let version = version.clone();
+        let history = history.clone();
+        Callback::from(move |_| {
+            history.set(None);
+            version.set(*version + 1);
+        })
+    };
+
+    let user_name = |user_id: &Option<Uuid>| {
+        user_id
+            .and_then(|user_id| users.iter().find(|user| user.user_id == user_id))
+            .map(|user| user.display_name.clone())
+            .unwrap_or_default()
+    };
+    let default = language.get("Default");
+
+    html! {
+        <div>
+            <h1>{ language.get("Recurring tickets") }</h1>
+            <table>
+                <thead>
+                    <tr>
+                        <th>{ language.get("Name") }</th>
+                        <th>{ language.get("Schedule") }</th>
+                        <th>{ language.get("Assignee") }</th>
+                        <th>{ language.get("Priority") }</th>
+                        <th>{ language.get("Next run") }</th>
+                        <th></th>
+                    </tr>
+                </thead>
+                <tbody>
+                    { for recurring.iter().map(|item| {
+                        let onclick_edit = {
+                            let info = info.clone();
+                            let editing = editing.clone();
+                            let item = item.clone();
+                            Callback::from(move |_| {
+                                editing.set(Some(item.recurring_id));
+                                info.set(RecurringTicketCreateInfo {
+                                    name: item.name.clone(),
+                                    title: item.title.clone(),
+                                    description: item.description.clone(),
+                                    assignee: item.assignee,
+                                    priority: item.priority.clone(),
+                                    tasks: item.tasks.clone(),
+                                    recurrence: item.recurrence.clone(),
+                                    start_at: item.start_at,
+                                    utc_offset: item.utc_offset,
+                                    enabled: item.enabled,
+                                });
+                            })
+                        };
+                        let onclick_history = {
+                            let history = history.clone();
+                            let recurring_id = item.recurring_id;
+                            Callback::from(move |_| {
+                                if history.as_ref().is_some_and(|(open, _)| *open == recurring_id) {
+                                    return history.set(None);
+                                }
+                                let history = history.clone();
+                                wasm_bindgen_futures::spawn_local(async move {
+                                    match get_recurring_ticket_runs(recurring_id).await {
+                                        Ok(runs) => history.set(Some((recurring_id, runs))),
+                                        Err(e) => log::error!("Error loading runs: {}", e),
+                                    }
+                                });
+                            })
+                        };
+                        html! {
+                            <tr>
+                                <td>{ &item.name }</td>
+                                <td>{ describe(&item.recurrence, &language) }</td>
+                                <td>{ user_name(&item.assignee) }</td>
+                                <td>{ item.priority.clone().unwrap_or(default.clone()) }</td>
+                                <td>
+                                    if let Some(next_run) = item.next_run {
+                                        <TimeFormat time={next_run} />
+                                    } else {
+                                        { language.get("Disabled") }
+                                    }
+                                </td>
+                                <td>
+                                    <button class="btn" onclick={onclick_edit}>{ language.get("Edit") }</button>
+                                    <button class="btn" onclick={onclick_history}>{ language.get("History") }</button>
+                                    <DeleteItem item_id={item.recurring_id.to_string()} item_type={ItemTypes::RecurringTicket}
+                                        callback={callback_deleted.clone()} />
+                                </td>
+                            </tr>
+                        }
+                    })}
+                </tbody>
+            </table>
+            if let Some((_, runs)) = &*history {
+                <h3>{ language.get("Created tickets") }</h3>
+                if runs.is_empty() {
+                    <p>{ language.get("No tickets were created yet") }</p>
+                }
+                <ul>
+                    { for runs.iter().map(|run| html! {
+                        <li>
+                            <TimeFormat time={run.scheduled_for} />
+                            { " " }
+                            if let Some(ticket_id) = run.ticket_id {
+                                <Link<AppRoute> to={AppRoute::Ticket { ticket_id }}>{ format!("#{}", ticket_id) }</Link<AppRoute>>
+                            } else {
+                                { language.get("Deleted") }
+                            }
+                        </li>
+                    })}
+                </ul>
+            }
+            <h3>
+                { if editing.is_some() { language.get("Edit recurring ticket") } else { language.get("New recurring ticket") } }
+            </h3>
+            <div class="error">{ error.to_string() }</div>
+            <form {onsubmit}>
+                <div>
+                    <input type="text" placeholder={language.get("Name")} value={info.name.clone()} oninput={oninput_name} />
+                    <label style="margin-left: 8px;">
+                        <input type="checkbox" checked={info.enabled} onclick={onclick_enabled} />
+                        { language.get("Enabled") }
+                    </label>
+                </div>
+                <div>
+                    <label>
+                        { format!("{}: ", language.get("Repeat")) }
+                        <select onchange={onchange_frequency}>
+                            { for [
+                                ("daily", "Daily"),
+                                ("weekly", "Weekly"),
+                                ("monthly", "Monthly"),
+                                ("first_business_day", "First business day of the month"),
+                            ].into_iter().map(|(value, label)| html! {
+                                <option value={value} selected={info.recurrence.frequency() == value}>
+                                    { language.get(label) }
+                                </option>
+                            })}
+                        </select>
+                    </label>
+                    { match &info.recurrence {
+                        Recurrence::Daily { interval } | Recurrence::Weekly { interval, .. } => {
+                            let (unit, max) = if matches!(info.recurrence, Recurrence::Daily { .. }) {
+                                ("days", MAX_DAILY_INTERVAL)
+                            } else {
+                                ("weeks", MAX_WEEKLY_INTERVAL)
+                            };
+                            html! {
+                                <label style="margin-left: 8px;">
+                                    { format!("{}: ", language.get("Every")) }
+                                    <input type="number" min="1" max={max.to_string()} style="width: 64px;" value={interval.to_string()}
+                                        oninput={oninput_interval} />
+                                    { format!(" {}", language.get(unit)) }
+                                </label>
+                            }
+                        }
+                        Recurrence::Monthly { day } => html! {
+                            <label style="margin-left: 8px;">
+                                { format!("{}: ", language.get("Day of the month")) }
+                                <input type="number" min="1" max="31" style="width: 64px;" value={day.to_string()}
+                                    oninput={oninput_day} />
+                            </label>
+                        },
+                        Recurrence::FirstBusinessDay => html! {},
+                    }}
+                    <label style="margin-left: 8px;">
+                        { format!("{}: ", language.get("Starts")) }
+                        <input type="datetime-local" style="width: fit-content;"
+                            value={time_ctx.convert_to_local(&info.start_at).format(DATETIME_INPUT_FORMAT).to_string()}
+                            oninput={oninput_start} />
+                    </label>
+                </div>
+                if let Recurrence::Weekly { weekdays, .. } = &info.recurrence {
+                    <div>
+                        { for WEEKDAYS.iter().enumerate().map(|(day, name)| {
+                            let day = day as u32;
+                            let onclick = {
+                                let info = info.clone();
+                                Callback::from(move |_: MouseEvent| {
+                                    let mut new_info = (*info).clone();
+                                    if let Recurrence::Weekly { weekdays, .. } = &mut new_info.recurrence {
+                                        match weekdays.iter().position(|weekday| *weekday == day) {
+                                            Some(index) => { weekdays.remove(index); }
+                                            None => weekdays.push(day),
+                                        }
+                                    }
+                                    info.set(new_info);
+                                })
+                            };
+                            html! {
+                                <label style="margin-right: 8px;">
+                                    <input type="checkbox" checked={weekdays.contains(&day)} {onclick} />
+                                    { language.get(name) }
+                                </label>
+                            }
+                        })}
+                    </div>
+                }
+                <h4>{ language.get("Ticket") }</h4>
+                <div>
+                    <input type="text" placeholder={language.get("Title")} value={info.title.clone()} oninput={oninput_title} />
+                    <label style="margin-left: 8px;">
+                        { format!("{}: ", language.get("Assignee")) }
+                        <select onchange={onchange_assignee}>
+                            <option value="" selected={info.assignee.is_none()}>{ "" }</option>
+                            { for users.iter().map(|user| html! {
+                                <option value={user.user_id.to_string()} selected={info.assignee == Some(user.user_id)}>
+                                    { &user.display_name }
+                                </option>
+                            })}
+                        </select>
+                    </label>
+                    <label style="margin-left: 8px;">
+                        { format!("{}: ", language.get("Priority")) }
+                        <select onchange={onchange_priority}>
+                            <option value="" selected={info.priority.is_none()}>{ default.clone() }</option>
+                            { for priorities.iter().map(|priority| html! {
+                                <option value={priority.name.clone()} selected={info.priority.as_ref() == Some(&priority.name)}>
+                                    { &priority.name }
+                                </option>
+                            })}
+                        </select>
+                    </label>
+                </div>
+                <div>
+                    <textarea rows="4" cols="60" placeholder={language.get("Description")}
+                        value={info.description.clone()} oninput={oninput_description}>
+                    </textarea>
+                </div>
+                { for info.tasks.iter().enumerate().map(|(index, task)| {
+                    let oninput_task = on_input(&info, move |info, value| info.tasks[index].text = value);
+                    let onclick_required = {
+                        let info = info.clone();
+                        Callback::from(move |_: MouseEvent| {
+                            let mut new_info = (*info).clone();
+                            new_info.tasks[index].required = !new_info.tasks[index].required;
+                            info.set(new_info);
+                        })
+                    };
+                    let onclick_remove = {
+                        let info = info.clone();
+                        Callback::from(move |_: MouseEvent| {
+                            let mut new_info = (*info).clone();
+                            new_info.tasks.remove(index);
+                            info.set(new_info);
+                        })
+                    };
+                    html! {
+                        <div>
+                            <input type="text" placeholder={language.get("Task")} value={task.text.clone()} oninput={oninput_task} />
+                            <label style="margin-left: 8px;">
+                                <input type="checkbox" checked={task.required} onclick={onclick_required} />
+                                { language.get("Required") }
+                            </label>
+                            <button class="btn" type="button" onclick={onclick_remove}>{ language.get("Remove") }</button>
+                        </div>
+                    }
+                })}
+                <button class="btn" type="button" onclick={onclick_add_task}>{ language.get("Add task") }</button>
+                <div>
+                    <button class="btn" type="submit">{ language.get("Save") }</button>
+                    if editing.is_some() {
+                        <button class="btn" type="button" onclick={reset.reform(|_: MouseEvent| ())}>{ language.get("Cancel") }</button>
+                    }
+                </div>
+            </form>
+        </div>
+    }
+}
+
+/// New recurring tickets start today at 9:00 local time
+fn new_info() -> RecurringTicketCreateInfo {
+    let start = Local::now().date_naive().and_hms_opt(9, 0, 0).unwrap_or_default();
+    let start = Local
+        .from_local_datetime(&start)
+        .earliest()
+        .map(|start| start.naive_utc())
+        .unwrap_or(start);
+    RecurringTicketCreateInfo {
+        start_at: start,
+        enabled: true,
+        ..Default::default()
+    }
+}
+
+/// Callback setting a field of the form from a text input
+fn on_input(
+    info: &UseStateHandle<RecurringTicketCreateInfo>,
+    set: impl Fn(&mut RecurringTicketCreateInfo, String) + 'static,
+) -> Callback<InputEvent> {
+    let info = info.clone();
+    Callback::from(move |e: InputEvent| {
+        let input: HtmlInputElement = e.target_unchecked_into();
+        let mut new_info = (*info).clone();
+        set(&mut new_info, input.value());
+        info.set(new_info);
+    })
+}
+
+fn describe(recurrence: &Recurrence, language: &LanguageContext) -> String {
+    match recurrence {
+        Recurrence::Daily { interval: 1 } => language.get("Daily"),
+        Recurrence::Daily { interval } => format!(
+            "{}: {} {}",
+            language.get("Every"),
+            interval,
+            language.get("days")
+        ),
+        Recurrence::Weekly { interval, weekdays } => {
+            let days = weekdays
+                .iter()
+                .filter_map(|day| WEEKDAYS.get(*day as usize))
+                .map(|name| language.get(name))
+                .collect::<Vec<String>>()
+                .join(", ");
+            if *interval == 1 {
+                format!("{}: {}", language.get("Weekly"), days)
+            } else {
+                format!(
+                    "{}: {} {} ({})",
+                    language.get("Every"),
+                    interval,
+                    language.get("weeks"),
+                    days
+                )
+            }
+        }
+        Recurrence::Monthly { day } => {
+            format!("{}: {}", language.get("Monthly"), day)
+        }
+        Recurrence::FirstBusinessDay => language.get("First business day of the month"),
+    }
+}
@@ -0,0 +1,265 @@
+use uuid::Uuid;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+use crate::components::delete::{DeleteItem, ItemTypes};
+use crate::hooks::{use_language_context, use_user_context};
+use crate::services::projects::get_projects;
+use crate::services::sla::*;
+use crate::services::workflows::get_priorities;
+use crate::types::{PriorityInfo, ProjectInfo, SlaPolicyCreateInfo, SlaPolicyInfo};
+
+/// Admin list of SLA policies, the most specific policy matching a ticket applies
+#[function_component(SlaSettings)]
+pub fn sla_settings() -> Html {
+    let user_ctx = use_user_context();
+    let language = use_language_context();
+    let policies = use_state(Vec::<SlaPolicyInfo>::new);
+    let priorities = use_state(Vec::<PriorityInfo>::new);
+    let projects = use_state(Vec::<ProjectInfo>::new);
+    //Bumped after every change to reload the list
+    let version = use_state(|| 0_u32);
+    //None while adding a new policy
+    let editing = use_state(|| None::<Uuid>);
+    let info = use_state(SlaPolicyCreateInfo::default);
+    let error = use_state(String::new);
+
+    {
+        let policies = policies.clone();
+        use_effect_with(*version, move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                match get_sla_policies().await {
+                    Ok(list) => policies.set(list),
+                    Err(e) => log::error!("Error loading SLA policies: {}", e),
+                }
+            });
+            || ()
+        })
+    }
+
+    {
+        let priorities = priorities.clone();
+        let projects = projects.clone();
+        use_effect_with((), move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                priorities.set(get_priorities().await.unwrap_or_default());
+                projects.set(get_projects().await.unwrap_or_default());
+            });
+            || ()
+        })
+    }
+
+    if !user_ctx.role().is_admin() {
+        return html! {};
+    }
+
+    let oninput_name = {
+        let info = info.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut new_info = (*info).clone();
+            new_info.name = input.value();
+            info.set(new_info);
+        })
+    };
+
+    let onchange_priority = {
+        let info = info.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlSelectElement = e.target_unchecked_into();
+            let mut new_info = (*info).clone();
+            new_info.priority = Some(input.value()).filter(|value| !value.is_empty());
+            info.set(new_info);
+        })
+    };
+
+    let onchange_project = {
+        let info = info.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlSelectElement = e.target_unchecked_into();
+            let mut new_info = (*info).clone();
+            new_info.project = input.value().parse().ok();
+            info.set(new_info);
+        })
+    };
+
+    //Empty targets are not tracked
+    let oninput_response = {
+        let info = info.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut new_info = (*info).clone();
+            new_info.response_minutes = input.value().parse().ok();
+            info.set(new_info);
+        })
+    };
+
+    let oninput_resolution = {
+        let info = info.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut new_info = (*info).clone();
+            new_info.resolution_minutes = input.value().parse().ok();
+            info.set(new_info);
+        })
+    };
+
+    let reset = {
+        let info = info.clone();
+        let editing = editing.clone();
+        Callback::from(move |_| {
+            editing.set(None);
+            info.set(SlaPolicyCreateInfo::default());
+        })
+    };
+
+    let onsubmit = {
+        let info = info.clone();
+        let editing = editing.clone();
+        let error = error.clone();
+        let version = version.clone();
+        let reset = reset.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let info = info.clone();
+            let editing = editing.clone();
+            let error = error.clone();
+            let version = version.clone();
+            let reset = reset.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let result = match *editing {
+                    Some(policy_id) => update_sla_policy(policy_id, &info).await,
+                    None => create_sla_policy(&info).await,
+                };
+                match result {
+                    Ok(response) if response.success => {
+                        error.set(String::new());
+                        reset.emit(());
+                        version.set(*version + 1);
+                    }
+                    Ok(response) => {
+                        error.set(response.message.unwrap_or("Unknown error".to_string()))
+                    }
+                    Err(e) => error.set(e.to_string()),
+                }
+            });
+        })
+    };
+
+    let callback_deleted = {
+        let version = version.clone();
+        Callback::from(move |_| version.set(*version + 1))
+    };
+
+    let any = language.get("Any");
+    let project_name = |project_id: &Option<Uuid>| match project_id {
+        Some(project_id) => projects
+            .iter()
+            .find(|project| project.project_id == *project_id)
+            .map(|project| project.name.clone())
+            .unwrap_or_default(),
+        None => any.clone(),
+    };
+    let minutes = |minutes: Option<i32>| {
+        minutes
+            .map(|minutes| minutes.to_string())
+            .unwrap_or_default()
+    };
+
+    html! {
+        <div>
+            <h1>{ language.get("SLA policies") }</h1>
+            <table>
+                <thead>
+                    <tr>
+                        <th>{ language.get("Name") }</th>
+                        <th>{ language.get("Priority") }</th>
+                        <th>{ language.get("Project") }</th>
+                        <th>{ language.get("Response (minutes)") }</th>
+                        <th>{ language.get("Resolution (minutes)") }</th>
+                        <th></th>
+                    </tr>
+                </thead>
+                <tbody>
+                    { for policies.iter().map(|policy| {
+                        let onclick_edit = {
+                            let info = info.clone();
+                            let editing = editing.clone();
+                            let policy = policy.clone();
+                            Callback::from(move |_| {
+                                editing.set(Some(policy.policy_id));
+                                info.set(SlaPolicyCreateInfo {
+                                    name: policy.name.clone(),
+                                    priority: policy.priority.clone(),
+                                    project: policy.project,
+                                    contact: policy.contact,
+                                    response_minutes: policy.response_minutes,
+                                    resolution_minutes: policy.resolution_minutes,
+                                });
+                            })
+                        };
+                        html! {
+                            <tr>
+                                <td>{ &policy.name }</td>
+                                <td>{ policy.priority.clone().unwrap_or(any.clone()) }</td>
+                                <td>{ project_name(&policy.project) }</td>
+                                <td>{ minutes(policy.response_minutes) }</td>
+                                <td>{ minutes(policy.resolution_minutes) }</td>
+                                <td>
+                                    <button class="btn" onclick={onclick_edit}>{ language.get("Edit") }</button>
+                                    <DeleteItem item_id={policy.policy_id.to_string()} item_type={ItemTypes::SlaPolicy}
+                                        callback={callback_deleted.clone()} />
+                                </td>
+                            </tr>
+                        }
+                    })}
+                </tbody>
+            </table>
+            <h3>
+                { if editing.is_some() { language.get("Edit SLA policy") } else { language.get("New SLA policy") } }
+            </h3>
+            <div class="error">{ error.to_string() }</div>
+            <form {onsubmit}>
+                <div>
+                    <input type="text" placeholder={language.get("Name")} value={info.name.clone()} oninput={oninput_name} />
+                    <label style="margin-left: 8px;">
+                        { format!("{}: ", language.get("Priority")) }
+                        <select onchange={onchange_priority}>
+                            <option value="" selected={info.priority.is_none()}>{ any.clone() }</option>
+                            { for priorities.iter().map(|priority| html! {
+                                <option value={priority.name.clone()} selected={info.priority.as_ref() == Some(&priority.name)}>
+                                    { &priority.name }
+                                </option>
+                            })}
+                        </select>
+                    </label>
+                    <label style="margin-left: 8px;">
+                        { format!("{}: ", language.get("Project")) }
+                        <select onchange={onchange_project}>
+                            <option value="" selected={info.project.is_none()}>{ any.clone() }</option>
+                            { for projects.iter().map(|project| html! {
+                                <option value={project.project_id.to_string()} selected={info.project == Some(project.project_id)}>
+                                    { &project.name }
+                                </option>
+                            })}
+                        </select>
+                    </label>
+                </div>
+                <div>
+                    <label>
+                        { format!("{}: ", language.get("Response (minutes)")) }
+                        <input type="number" min="1" style="width: 96px;" value={minutes(info.response_minutes)} oninput={oninput_response} />
+                    </label>
+                    <label style="margin-left: 8px;">
+                        { format!("{}: ", language.get("Resolution (minutes)")) }
+                        <input type="number" min="1" style="width: 96px;" value={minutes(info.resolution_minutes)} oninput={oninput_resolution} />
+                    </label>
+                </div>
+                <button class="btn" type="submit">{ language.get("Save") }</button>
+                if editing.is_some() {
+                    <button class="btn" type="button" onclick={reset.reform(|_: MouseEvent| ())}>{ language.get("Cancel") }</button>
+                }
+            </form>
+        </div>
+    }
+}
@@ -0,0 +1,161 @@
+use uuid::Uuid;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::components::delete::{DeleteItem, ItemTypes};
+use crate::components::tag_chip::TagChip;
+use crate::hooks::{use_language_context, use_user_context};
+use crate::services::tags::*;
+use crate::types::{TagCreateInfo, TagInfo};
+
+const DEFAULT_COLOR: &str = "#808080";
+
+/// Admin list of tags with a form to add one or change its name and color
+#[function_component(TagSettings)]
+pub fn tag_settings() -> Html {
+    let user_ctx = use_user_context();
+    let language = use_language_context();
+    let tags = use_state(Vec::<TagInfo>::new);
+    //Bumped after every change to reload the list
+    let version = use_state(|| 0_u32);
+    //None while adding a new tag
+    let editing = use_state(|| None::<Uuid>);
+    let info = use_state(|| TagCreateInfo {
+        name: String::new(),
+        color: Some(DEFAULT_COLOR.to_string()),
+    });
+    let error = use_state(String::new);
+
+    {
+        let tags = tags.clone();
+        use_effect_with(*version, move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                match get_tags().await {
+                    Ok(list) => tags.set(list),
+                    Err(e) => log::error!("Error loading tags: {}", e),
+                }
+            });
+            || ()
+        })
+    }
+
+    let oninput_name = {
+        let info = info.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut new_info = (*info).clone();
+            new_info.name = input.value();
+            info.set(new_info);
+        })
+    };
+
+    let oninput_color = {
+        let info = info.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut new_info = (*info).clone();
+            new_info.color = Some(input.value());
+            info.set(new_info);
+        })
+    };
+
+    let reset = {
+        let info = info.clone();
+        let editing = editing.clone();
+        Callback::from(move |_| {
+            editing.set(None);
+            info.set(TagCreateInfo {
+                name: String::new(),
+                color: Some(DEFAULT_COLOR.to_string()),
+            });
+        })
+    };
+
+    let onsubmit = {
+        let info = info.clone();
+        let editing = editing.clone();
+        let error = error.clone();
+        let version = version.clone();
+        let reset = reset.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let info = info.clone();
+            let editing = editing.clone();
+            let error = error.clone();
+            let version = version.clone();
+            let reset = reset.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let result = match *editing {
+                    Some(tag_id) => update_tag(tag_id, &info).await,
+                    None => create_tag(&info).await,
+                };
+                match result {
+                    Ok(response) if response.success => {
+                        error.set(String::new());
+                        reset.emit(());
+                        version.set(*version + 1);
+                    }
+                    Ok(response) => {
+                        error.set(response.message.unwrap_or("Unknown error".to_string()))
+                    }
+                    Err(e) => error.set(e.to_string()),
+                }
+            });
+        })
+    };
+
+    let callback_deleted = {
+        let version = version.clone();
+        Callback::from(move |_| version.set(*version + 1))
+    };
+
+    if !user_ctx.role().is_admin() {
+        return html! {};
+    }
+
+    html! {
+        <div>
+            <h1>{ language.get("Tags") }</h1>
+            <table>
+                <tbody>
+                    { for tags.iter().map(|tag| {
+                        let onclick_edit = {
+                            let info = info.clone();
+                            let editing = editing.clone();
+                            let tag = tag.clone();
+                            Callback::from(move |_| {
+                                editing.set(Some(tag.tag_id));
+                                info.set(TagCreateInfo {
+                                    name: tag.name.clone(),
+                                    color: Some(tag.color.clone()),
+                                });
+                            })
+                        };
+                        html! {
+                            <tr>
+                                <td><TagChip tag={tag.clone()} /></td>
+                                <td>
+                                    <button class="btn" onclick={onclick_edit}>{ language.get("Edit") }</button>
+                                    <DeleteItem item_id={tag.tag_id.to_string()} item_type={ItemTypes::Tag}
+                                        callback={callback_deleted.clone()} />
+                                </td>
+                            </tr>
+                        }
+                    })}
+                </tbody>
+            </table>
+            <h3>
+                { if editing.is_some() { language.get("Edit tag") } else { language.get("New tag") } }
+            </h3>
+            <div class="error">{ error.to_string() }</div>
+            <form {onsubmit}>
+                <input type="text" placeholder={language.get("Name")} value={info.name.clone()} oninput={oninput_name} />
+                <input type="color" value={info.color.clone().unwrap_or(DEFAULT_COLOR.to_string())} oninput={oninput_color} />
+                <button class="btn" type="submit">{ language.get("Save") }</button>
+                if editing.is_some() {
+                    <button class="btn" type="button" onclick={reset.reform(|_: MouseEvent| ())}>{ language.get("Cancel") }</button>
+                }
+            </form>
+        </div>
+    }
+}
@@ -0,0 +1,341 @@
+use shared::models::webhooks::{DeliveryStatus, WebhookEvent};
+use uuid::Uuid;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::components::delete::{DeleteItem, ItemTypes};
+use crate::components::time_format::TimeFormat;
+use crate::hooks::{use_language_context, use_user_context};
+use crate::services::webhooks::*;
+use crate::types::{WebhookCreateInfo, WebhookDeliveryInfo, WebhookInfo};
+
+/// Endpoints receiving signed changes of tickets, notes and documents
+#[function_component(WebhookSettings)]
+pub fn webhook_settings() -> Html {
+    let user_ctx = use_user_context();
+    let language = use_language_context();
+    let webhooks = use_state(Vec::<WebhookInfo>::new);
+    //Bumped after every change to reload the list
+    let version = use_state(|| 0_u32);
+    //None while adding a new webhook
+    let editing = use_state(|| None::<Uuid>);
+    let info = use_state(new_info);
+    let error = use_state(String::new);
+    //Delivery log of the webhook whose log is open
+    let log = use_state(|| None::<(Uuid, Vec<WebhookDeliveryInfo>)>);
+
+    {
+        let webhooks = webhooks.clone();
+        use_effect_with(*version, move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                match get_webhooks().await {
+                    Ok(list) => webhooks.set(list),
+                    Err(e) => log::error!("Error loading webhooks: {}", e),
+                }
+            });
+            || ()
+        })
+    }
+
+    if !user_ctx.role().is_admin() {
+        return html! {};
+    }
+
+    let oninput_name = on_input(&info, |info, value| info.name = value);
+    let oninput_url = on_input(&info, |info, value| info.url = value);
+    let oninput_secret = on_input(&info, |info, value| info.secret = value);
+
+    let onclick_enabled = {
+        let info = info.clone();
+        Callback::from(move |_: MouseEvent| {
+            let mut new_info = (*info).clone();
+            new_info.enabled = !new_info.enabled;
+            info.set(new_info);
+        })
+    };
+
+    let reset = {
+        let info = info.clone();
+        let editing = editing.clone();
+        Callback::from(move |_| {
+            editing.set(None);
+            info.set(new_info());
+        })
+    };
+
+    let onsubmit = {
+        let info = info.clone();
+        let editing = editing.clone();
+        let error = error.clone();
+        let version = version.clone();
+        let reset = reset.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let info = (*info).clone();
+            let editing = editing.clone();
+            let error = error.clone();
+            let version = version.clone();
+            let reset = reset.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let result = match *editing {
+                    Some(webhook_id) => update_webhook(webhook_id, &info).await,
+                    None => create_webhook(&info).await,
+                };
+                match result {
+                    Ok(response) if response.success => {
+                        error.set(String::new());
+                        reset.emit(());
+                        version.set(*version + 1);
+                    }
+                    Ok(response) => {
+                        error.set(response.message.unwrap_or("Unknown error".to_string()))
+                    }
+                    Err(e) => error.set(e.to_string()),
+                }
+            });
+        })
+    };
+
+    let callback_deleted = {
+        let version = version.clone();
+        let log = log.clone();
+        Callback::from(move |_| {
+            log.set(None);
+            version.set(*version + 1);
+        })
+    };
+
+    html! {
+        <div>
+            <h1>{ language.get("Webhooks") }</h1>
+            <p>{ language.get("Changes are posted as JSON signed with the secret in the X-Sumi-Signature header. Failed deliveries are retried with growing delays.") }</p>
+            <table>
+                <thead>
+                    <tr>
+                        <th>{ language.get("Name") }</th>
+                        <th>{ language.get("URL") }</th>
+                        <th>{ language.get("Secret") }</th>
+                        <th>{ language.get("Events") }</th>
+                        <th>{ language.get("Enabled") }</th>
+                        <th></th>
+                    </tr>
+                </thead>
+                <tbody>
+                    { for webhooks.iter().map(|webhook| {
+                        let onclick_edit = {
+                            let info = info.clone();
+                            let editing = editing.clone();
+                            let webhook = webhook.clone();
+                            Callback::from(move |_| {
+                                editing.set(Some(webhook.webhook_id));
+                                info.set(WebhookCreateInfo {
+                                    name: webhook.name.clone(),
+                                    url: webhook.url.clone(),
+                                    secret: String::new(),
+                                    events: webhook.events.clone(),
+                                    enabled: webhook.enabled,
+                                });
+                            })
+                        };
+                        let onclick_log = {
+                            let log = log.clone();
+                            let webhook_id = webhook.webhook_id;
+                            Callback::from(move |_| {
+                                if log.as_ref().is_some_and(|(open, _)| *open == webhook_id) {
+                                    return log.set(None);
+                                }
+                                load_deliveries(webhook_id, log.clone());
+                            })
+                        };
+                        let events = webhook
+                            .events
+                            .iter()
+                            .map(|event| language.get(event.label()))
+                            .collect::<Vec<String>>()
+                            .join(", ");
+                        html! {
+                            <tr>
+                                <td>{ &webhook.name }</td>
+                                <td>{ &webhook.url }</td>
+                                <td><code>{ &webhook.secret }</code></td>
+                                <td>{ events }</td>
+                                <td>{ if webhook.enabled { language.get("Yes") } else { language.get("No") } }</td>
+                                <td>
+                                    <button class="btn" onclick={onclick_edit}>{ language.get("Edit") }</button>
+                                    <button class="btn" onclick={onclick_log}>{ language.get("Deliveries") }</button>
+                                    <DeleteItem item_id={webhook.webhook_id.to_string()} item_type={ItemTypes::Webhook}
+                                        callback={callback_deleted.clone()} />
+                                </td>
+                            </tr>
+                        }
+                    })}
+                </tbody>
+            </table>
+            if let Some((webhook_id, deliveries)) = &*log {
+                <h3>{ language.get("Delivery log") }</h3>
+                if deliveries.is_empty() {
+                    <p>{ language.get("Nothing was sent to this webhook yet") }</p>
+                }
+                <table>
+                    <tbody>
+                        { for deliveries.iter().map(|delivery| {
+                            //Replaying reloads the log to show the delivery queued again
+                            let onclick_replay = {
+                                let log = log.clone();
+                                let error = error.clone();
+                                let webhook_id = *webhook_id;
+                                let delivery_id = delivery.delivery_id;
+                                Callback::from(move |_: MouseEvent| {
+                                    let log = log.clone();
+                                    let error = error.clone();
+                                    wasm_bindgen_futures::spawn_local(async move {
+                                        match replay_webhook_delivery(delivery_id).await {
+                                            Ok(response) if response.success => {
+                                                error.set(String::new());
+                                                load_deliveries(webhook_id, log);
+                                            }
+                                            Ok(response) => error.set(
+                                                response.message.unwrap_or("Unknown error".to_string()),
+                                            ),
+                                            Err(e) => error.set(e.to_string()),
+                                        }
+                                    });
+                                })
+                            };
+                            let response = match (&delivery.response_status, &delivery.error) {
+                                (_, Some(error)) => error.clone(),
+                                (Some(code), None) => code.to_string(),
+                                (None, None) => String::new(),
+                            };
+                            html! {
+                                <tr>
+                                    <td><TimeFormat time={delivery.created_at} /></td>
+                                    <td>{ &delivery.event }</td>
+                                    <td>{ language.get(status_label(delivery.status)) }</td>
+                                    <td>{ format!("{} {}", delivery.attempts, language.get("attempts")) }</td>
+                                    <td>{ response }</td>
+                                    <td>
+                                        if let (DeliveryStatus::Pending, Some(next_attempt_at)) = (delivery.status, delivery.next_attempt_at) {
+                                            { format!("{} ", language.get("Next attempt")) }
+                                            <TimeFormat time={next_attempt_at} />
+                                        } else if let Some(delivered_at) = delivery.delivered_at {
+                                            <TimeFormat time={delivered_at} />
+                                        }
+                                    </td>
+                                    <td>
+                                        <details>
+                                            <summary>{ language.get("Payload") }</summary>
+                                            <pre>{ &delivery.payload }</pre>
+                                        </details>
+                                    </td>
+                                    <td>
+                                        if delivery.status != DeliveryStatus::Pending {
+                                            <button class="btn" onclick={onclick_replay}>{ language.get("Replay") }</button>
+                                        }
+                                    </td>
+                                </tr>
+                            }
+                        })}
+                    </tbody>
+                </table>
+            }
+            <h3>
+                { if editing.is_some() { language.get("Edit webhook") } else { language.get("New webhook") } }
+            </h3>
+            <div class="error">{ error.to_string() }</div>
+            <form {onsubmit}>
+                <div>
+                    <input type="text" placeholder={language.get("Name")} value={info.name.clone()} oninput={oninput_name} />
+                    <label style="margin-left: 8px;">
+                        <input type="checkbox" checked={info.enabled} onclick={onclick_enabled} />
+                        { language.get("Enabled") }
+                    </label>
+                </div>
+                <div>
+                    <input type="text" size="60" placeholder="https://" value={info.url.clone()} oninput={oninput_url} />
+                </div>
+                <div>
+                    <input type="text" size="60" value={info.secret.clone()} oninput={oninput_secret}
+                        placeholder={
+                            if editing.is_some() {
+                                language.get("Secret, the current one is kept when empty")
+                            } else {
+                                language.get("Secret, generated when empty")
+                            }
+                        } />
+                </div>
+                <h4>{ language.get("Events") }</h4>
+                { for WebhookEvent::iter().map(|event| {
+                    let onclick_event = {
+                        let info = info.clone();
+                        Callback::from(move |_: MouseEvent| {
+                            let mut new_info = (*info).clone();
+                            match new_info.events.iter().position(|selected| *selected == event) {
+                                Some(index) => {
+                                    new_info.events.remove(index);
+                                }
+                                None => new_info.events.push(event),
+                            }
+                            info.set(new_info);
+                        })
+                    };
+                    html! {
+                        <div>
+                            <label>
+                                <input type="checkbox" checked={info.events.contains(&event)} onclick={onclick_event} />
+                                { format!("{} ({})", language.get(event.label()), event.as_str()) }
+                            </label>
+                        </div>
+                    }
+                })}
+                <div>
+                    <button class="btn" type="submit">{ language.get("Save") }</button>
+                    if editing.is_some() {
+                        <button class="btn" type="button" onclick={reset.reform(|_: MouseEvent| ())}>{ language.get("Cancel") }</button>
+                    }
+                </div>
+            </form>
+        </div>
+    }
+}
+
+fn new_info() -> WebhookCreateInfo {
+    WebhookCreateInfo {
+        enabled: true,
+        ..Default::default()
+    }
+}
+
+fn load_deliveries(
+    webhook_id: Uuid,
+    log: UseStateHandle<Option<(Uuid, Vec<WebhookDeliveryInfo>)>>,
+) {
+    wasm_bindgen_futures::spawn_local(async move {
+        match get_webhook_deliveries(webhook_id).await {
+            Ok(deliveries) => log.set(Some((webhook_id, deliveries))),
+            Err(e) => log::error!("Error loading deliveries: {}", e),
+        }
+    });
+}
+
+/// Callback setting a field of the form from a text input
+fn on_input(
+    info: &UseStateHandle<WebhookCreateInfo>,
+    set: impl Fn(&mut WebhookCreateInfo, String) + 'static,
+) -> Callback<InputEvent> {
+    let info = info.clone();
+    Callback::from(move |e: InputEvent| {
+        let input: HtmlInputElement = e.target_unchecked_into();
+        let mut new_info = (*info).clone();
+        set(&mut new_info, input.value());
+        info.set(new_info);
+    })
+}
+
+fn status_label(status: DeliveryStatus) -> &'static str {
+    match status {
+        DeliveryStatus::Pending => "Pending",
+        DeliveryStatus::Delivered => "Delivered",
+        DeliveryStatus::Failed => "Failed",
+    }
+}
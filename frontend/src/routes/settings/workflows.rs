@@ -0,0 +1,438 @@
+use uuid::Uuid;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::components::delete::{DeleteItem, ItemTypes};
+use crate::hooks::{use_language_context, use_user_context};
+use crate::services::workflows::*;
+use crate::types::{PriorityCreateInfo, PriorityInfo, StatusCreateInfo, StatusInfo};
+
+/// Admin lists of ticket statuses and priorities
+#[function_component(WorkflowSettings)]
+pub fn workflow_settings() -> Html {
+    let user_ctx = use_user_context();
+
+    if !user_ctx.role().is_admin() {
+        return html! {};
+    }
+
+    html! {
+        <div>
+            <StatusSettings />
+            <PrioritySettings />
+        </div>
+    }
+}
+
+/// Statuses with their category, order and allowed transitions
+#[function_component(StatusSettings)]
+fn status_settings() -> Html {
+    let language = use_language_context();
+    let statuses = use_state(Vec::<StatusInfo>::new);
+    //Bumped after every change to reload the list
+    let version = use_state(|| 0_u32);
+    //None while adding a new status
+    let editing = use_state(|| None::<Uuid>);
+    let info = use_state(StatusCreateInfo::default);
+    let error = use_state(String::new);
+
+    {
+        let statuses = statuses.clone();
+        use_effect_with(*version, move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                match get_statuses().await {
+                    Ok(list) => statuses.set(list),
+                    Err(e) => log::error!("Error loading statuses: {}", e),
+                }
+            });
+            || ()
+        })
+    }
+
+    let oninput_name = {
+        let info = info.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut new_info = (*info).clone();
+            new_info.name = input.value();
+            info.set(new_info);
+        })
+    };
+
+    let onchange_closed = {
+        let info = info.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut new_info = (*info).clone();
+            new_info.is_closed = input.checked();
+            info.set(new_info);
+        })
+    };
+
+    let onchange_default = {
+        let info = info.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut new_info = (*info).clone();
+            new_info.is_default = input.checked();
+            info.set(new_info);
+        })
+    };
+
+    let onchange_pauses_sla = {
+        let info = info.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut new_info = (*info).clone();
+            new_info.pauses_sla = input.checked();
+            info.set(new_info);
+        })
+    };
+
+    let oninput_position = {
+        let info = info.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut new_info = (*info).clone();
+            new_info.position = input.value().parse().unwrap_or_default();
+            info.set(new_info);
+        })
+    };
+
+    let ontoggle_transition = {
+        let info = info.clone();
+        Callback::from(move |status_id: Uuid| {
+            let mut new_info = (*info).clone();
+            if new_info.transitions.contains(&status_id) {
+                new_info.transitions.retain(|id| *id != status_id);
+            } else {
+                new_info.transitions.push(status_id);
+            }
+            info.set(new_info);
+        })
+    };
+
+    let reset = {
+        let info = info.clone();
+        let editing = editing.clone();
+        Callback::from(move |_| {
+            editing.set(None);
+            info.set(StatusCreateInfo::default());
+        })
+    };
+
+    let onsubmit = {
+        let info = info.clone();
+        let editing = editing.clone();
+        let error = error.clone();
+        let version = version.clone();
+        let reset = reset.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let info = info.clone();
+            let editing = editing.clone();
+            let error = error.clone();
+            let version = version.clone();
+            let reset = reset.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let result = match *editing {
+                    Some(status_id) => update_status(status_id, &info).await,
+                    None => create_status(&info).await,
+                };
+                match result {
+                    Ok(response) if response.success => {
+                        error.set(String::new());
+                        reset.emit(());
+                        version.set(*version + 1);
+                    }
+                    Ok(response) => {
+                        error.set(response.message.unwrap_or("Unknown error".to_string()))
+                    }
+                    Err(e) => error.set(e.to_string()),
+                }
+            });
+        })
+    };
+
+    let callback_deleted = {
+        let version = version.clone();
+        Callback::from(move |_| version.set(*version + 1))
+    };
+
+    let status_name = |status_id: &Uuid| {
+        statuses
+            .iter()
+            .find(|status| status.status_id == *status_id)
+            .map(|status| status.name.clone())
+            .unwrap_or_default()
+    };
+
+    html! {
+        <div>
+            <h1>{ language.get("Statuses") }</h1>
+            <table>
+                <thead>
+                    <tr>
+                        <th>{ language.get("Name") }</th>
+                        <th>{ language.get("Closed") }</th>
+                        <th>{ language.get("Default") }</th>
+                        <th>{ language.get("Pauses SLA") }</th>
+                        <th>{ language.get("Can change to") }</th>
+                        <th></th>
+                    </tr>
+                </thead>
+                <tbody>
+                    { for statuses.iter().map(|status| {
+                        let onclick_edit = {
+                            let info = info.clone();
+                            let editing = editing.clone();
+                            let status = status.clone();
+                            Callback::from(move |_| {
+                                editing.set(Some(status.status_id));
+                                info.set(StatusCreateInfo {
+                                    name: status.name.clone(),
+                                    is_closed: status.is_closed,
+                                    is_default: status.is_default,
+                                    position: status.position,
+                                    pauses_sla: status.pauses_sla,
+                                    transitions: status.transitions.clone(),
+                                });
+                            })
+                        };
+                        html! {
+                            <tr>
+                                <td>{ &status.name }</td>
+                                <td>{ if status.is_closed { "✔" } else { "" } }</td>
+                                <td>{ if status.is_default { "✔" } else { "" } }</td>
+                                <td>{ if status.pauses_sla { "✔" } else { "" } }</td>
+                                <td>
+                                    { if status.transitions.is_empty() {
+                                        language.get("Any")
+                                    } else {
+                                        status.transitions.iter().map(status_name).collect::<Vec<String>>().join(", ")
+                                    } }
+                                </td>
+                                <td>
+                                    <button class="btn" onclick={onclick_edit}>{ language.get("Edit") }</button>
+                                    <DeleteItem item_id={status.status_id.to_string()} item_type={ItemTypes::Status}
+                                        callback={callback_deleted.clone()} />
+                                </td>
+                            </tr>
+                        }
+                    })}
+                </tbody>
+            </table>
+            <h3>
+                { if editing.is_some() { language.get("Edit status") } else { language.get("New status") } }
+            </h3>
+            <div class="error">{ error.to_string() }</div>
+            <form {onsubmit}>
+                <div>
+                    <input type="text" placeholder={language.get("Name")} value={info.name.clone()} oninput={oninput_name} />
+                    <label>
+                        <input type="checkbox" checked={info.is_closed} onchange={onchange_closed} />
+                        { language.get("Closed") }
+                    </label>
+                    <label style="margin-left: 8px;">
+                        <input type="checkbox" checked={info.is_default} onchange={onchange_default} />
+                        { language.get("Default") }
+                    </label>
+                    <label style="margin-left: 8px;">
+                        <input type="checkbox" checked={info.pauses_sla} onchange={onchange_pauses_sla} />
+                        { language.get("Pauses SLA") }
+                    </label>
+                    <label style="margin-left: 8px;">
+                        { format!("{}: ", language.get("Position")) }
+                        <input type="number" style="width: 64px;" value={info.position.to_string()} oninput={oninput_position} />
+                    </label>
+                </div>
+                //Nothing checked allows every status
+                <div>
+                    { format!("{}: ", language.get("Can change to")) }
+                    { for statuses.iter().filter(|status| Some(status.status_id) != *editing).map(|status| {
+                        let status_id = status.status_id;
+                        let ontoggle = ontoggle_transition.reform(move |_: Event| status_id);
+                        html! {
+                            <label style="margin-right: 8px;">
+                                <input type="checkbox" checked={info.transitions.contains(&status_id)} onchange={ontoggle} />
+                                { &status.name }
+                            </label>
+                        }
+                    })}
+                </div>
+                <button class="btn" type="submit">{ language.get("Save") }</button>
+                if editing.is_some() {
+                    <button class="btn" type="button" onclick={reset.reform(|_: MouseEvent| ())}>{ language.get("Cancel") }</button>
+                }
+            </form>
+        </div>
+    }
+}
+
+/// Priorities ordered by rank
+#[function_component(PrioritySettings)]
+fn priority_settings() -> Html {
+    let language = use_language_context();
+    let priorities = use_state(Vec::<PriorityInfo>::new);
+    //Bumped after every change to reload the list
+    let version = use_state(|| 0_u32);
+    //None while adding a new priority
+    let editing = use_state(|| None::<Uuid>);
+    let info = use_state(PriorityCreateInfo::default);
+    let error = use_state(String::new);
+
+    {
+        let priorities = priorities.clone();
+        use_effect_with(*version, move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                match get_priorities().await {
+                    Ok(list) => priorities.set(list),
+                    Err(e) => log::error!("Error loading priorities: {}", e),
+                }
+            });
+            || ()
+        })
+    }
+
+    let oninput_name = {
+        let info = info.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut new_info = (*info).clone();
+            new_info.name = input.value();
+            info.set(new_info);
+        })
+    };
+
+    let oninput_rank = {
+        let info = info.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut new_info = (*info).clone();
+            new_info.rank = input.value().parse().unwrap_or_default();
+            info.set(new_info);
+        })
+    };
+
+    let onchange_default = {
+        let info = info.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut new_info = (*info).clone();
+            new_info.is_default = input.checked();
+            info.set(new_info);
+        })
+    };
+
+    let reset = {
+        let info = info.clone();
+        let editing = editing.clone();
+        Callback::from(move |_| {
+            editing.set(None);
+            info.set(PriorityCreateInfo::default());
+        })
+    };
+
+    let onsubmit = {
+        let info = info.clone();
+        let editing = editing.clone();
+        let error = error.clone();
+        let version = version.clone();
+        let reset = reset.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let info = info.clone();
+            let editing = editing.clone();
+            let error = error.clone();
+            let version = version.clone();
+            let reset = reset.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let result = match *editing {
+                    Some(priority_id) => update_priority(priority_id, &info).await,
+                    None => create_priority(&info).await,
+                };
+                match result {
+                    Ok(response) if response.success => {
+                        error.set(String::new());
+                        reset.emit(());
+                        version.set(*version + 1);
+                    }
+                    Ok(response) => {
+                        error.set(response.message.unwrap_or("Unknown error".to_string()))
+                    }
+                    Err(e) => error.set(e.to_string()),
+                }
+            });
+        })
+    };
+
+    let callback_deleted = {
+        let version = version.clone();
+        Callback::from(move |_| version.set(*version + 1))
+    };
+
+    html! {
+        <div>
+            <h1>{ language.get("Priorities") }</h1>
+            <table>
+                <thead>
+                    <tr>
+                        <th>{ language.get("Name") }</th>
+                        <th>{ language.get("Rank") }</th>
+                        <th>{ language.get("Default") }</th>
+                        <th></th>
+                    </tr>
+                </thead>
+                <tbody>
+                    { for priorities.iter().map(|priority| {
+                        let onclick_edit = {
+                            let info = info.clone();
+                            let editing = editing.clone();
+                            let priority = priority.clone();
+                            Callback::from(move |_| {
+                                editing.set(Some(priority.priority_id));
+                                info.set(PriorityCreateInfo {
+                                    name: priority.name.clone(),
+                                    rank: priority.rank,
+                                    is_default: priority.is_default,
+                                });
+                            })
+                        };
+                        html! {
+                            <tr>
+                                <td>{ &priority.name }</td>
+                                <td>{ priority.rank }</td>
+                                <td>{ if priority.is_default { "✔" } else { "" } }</td>
+                                <td>
+                                    <button class="btn" onclick={onclick_edit}>{ language.get("Edit") }</button>
+                                    <DeleteItem item_id={priority.priority_id.to_string()} item_type={ItemTypes::Priority}
+                                        callback={callback_deleted.clone()} />
+                                </td>
+                            </tr>
+                        }
+                    })}
+                </tbody>
+            </table>
+            <h3>
+                { if editing.is_some() { language.get("Edit priority") } else { language.get("New priority") } }
+            </h3>
+            <div class="error">{ error.to_string() }</div>
+            <form {onsubmit}>
+                <input type="text" placeholder={language.get("Name")} value={info.name.clone()} oninput={oninput_name} />
+                //Lower ranks are more urgent
+                <label style="margin-left: 8px;">
+                    { format!("{}: ", language.get("Rank")) }
+                    <input type="number" style="width: 64px;" value={info.rank.to_string()} oninput={oninput_rank} />
+                </label>
+                <label style="margin-left: 8px;">
+                    <input type="checkbox" checked={info.is_default} onchange={onchange_default} />
+                    { language.get("Default") }
+                </label>
+                <button class="btn" type="submit">{ language.get("Save") }</button>
+                if editing.is_some() {
+                    <button class="btn" type="button" onclick={reset.reform(|_: MouseEvent| ())}>{ language.get("Cancel") }</button>
+                }
+            </form>
+        </div>
+    }
+}
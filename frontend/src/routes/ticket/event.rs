@@ -1,10 +1,13 @@
+use shared::models::tickets::parse_tagged_event_data;
 use shared::models::users::UserDisplay;
 use stylist::yew::styled_component;
 use yew::prelude::*;
 
+use crate::components::sla::sla_breach_message;
 use crate::components::time_format::TimeFormat;
 use crate::contexts::time::use_time;
 use crate::hooks::use_language_context;
+use crate::routes::ticket::link_event_message;
 use crate::types::events::TicketEvent;
 
 #[derive(Properties, Clone, PartialEq)]
@@ -83,6 +86,20 @@ pub fn event(props: &Props) -> Html {
                 )
             }
         }
+        "tagged" => match parse_tagged_event_data(&event.event_data) {
+            (true, tag) => format!("{} {} {}", actor_display, language.get("added tag"), tag),
+            (false, tag) => format!("{} {} {}", actor_display, language.get("removed tag"), tag),
+        },
+        "task_completed" => format!("{} {} {}", actor_display, language.get("completed task"), event.event_data),
+        "task_reopened" => format!("{} {} {}", actor_display, language.get("reopened task"), event.event_data),
+        "sla_breached" => sla_breach_message(&event.event_data, &language),
+        "merged" => format!("{} {} #{}", actor_display, language.get("merged ticket"), &event.event_data),
+        "merged_into" => format!("{} {} #{}", actor_display, language.get("merged this ticket into"), &event.event_data),
+        "linked" | "unlinked" | "closed_as_duplicate" => format!(
+            "{} {}",
+            actor_display,
+            link_event_message(&event.event_type, &event.event_data, &language)
+        ),
         _ => "Unknown event".to_string(),
     };
 
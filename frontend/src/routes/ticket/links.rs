@@ -0,0 +1,207 @@
+use shared::models::links::{parse_link_event_data, TicketLinkType};
+use shared::models::response::Response;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::hooks::{use_language_context, use_user_context, LanguageContext};
+use crate::routes::AppRoute;
+use crate::services::links::{create_link, delete_link, get_links};
+use crate::types::{Error, TicketLinkCreateInfo, TicketLinkInfo};
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub ticket_id: i32,
+    /// Bumped when someone else changes the ticket, reloads the links
+    pub version: u32,
+    /// Receives the links of the ticket after a change
+    pub callback: Callback<Vec<TicketLinkInfo>>,
+}
+
+/// Describes `linked`, `unlinked` and `closed_as_duplicate` events, without the actor
+pub(crate) fn link_event_message(
+    event_type: &str,
+    data: &str,
+    language: &LanguageContext,
+) -> String {
+    match (event_type, parse_link_event_data(data)) {
+        ("linked", Some((link_type, other))) => format!(
+            "{} {} #{}",
+            language.get("linked ticket:"),
+            language.get(link_type.label()),
+            other
+        ),
+        ("unlinked", Some((link_type, other))) => format!(
+            "{} {} #{}",
+            language.get("removed link:"),
+            language.get(link_type.label()),
+            other
+        ),
+        ("closed_as_duplicate", _) => {
+            format!(
+                "{} #{}",
+                language.get("closed ticket as a duplicate of"),
+                data
+            )
+        }
+        _ => language.get("Unknown event"),
+    }
+}
+
+/// Links to other tickets grouped by type, agents can add and remove links
+#[function_component(TicketLinks)]
+pub fn ticket_links(props: &Props) -> Html {
+    let language = use_language_context();
+    let user_ctx = use_user_context();
+    let links = use_state(Vec::<TicketLinkInfo>::new);
+    let link_type = use_state(|| TicketLinkType::RelatesTo);
+    let other = use_state(String::new);
+    let error = use_state(String::new);
+    let can_edit = user_ctx.role().can_edit();
+
+    {
+        let links = links.clone();
+        use_effect_with((props.ticket_id, props.version), move |(ticket_id, _)| {
+            let ticket_id = *ticket_id;
+            wasm_bindgen_futures::spawn_local(async move {
+                match get_links(ticket_id).await {
+                    Ok(list) => links.set(list),
+                    Err(e) => log::error!("Error loading links: {}", e),
+                }
+            });
+            || ()
+        })
+    }
+
+    //Every change responds with the whole list
+    let apply = {
+        let links = links.clone();
+        let error = error.clone();
+        let callback = props.callback.clone();
+        Callback::from(
+            move |result: Result<Response<Vec<TicketLinkInfo>>, Error>| match result {
+                Ok(response) if response.success => {
+                    let list = response.data.unwrap_or_default();
+                    error.set(String::new());
+                    links.set(list.clone());
+                    callback.emit(list);
+                }
+                Ok(response) => error.set(response.message.unwrap_or_default()),
+                Err(e) => error.set(e.to_string()),
+            },
+        )
+    };
+
+    let onchange_type = {
+        let link_type = link_type.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlSelectElement = e.target_unchecked_into();
+            if let Ok(value) = input.value().parse() {
+                link_type.set(value);
+            }
+        })
+    };
+
+    let oninput_other = {
+        let other = other.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            other.set(input.value());
+        })
+    };
+
+    let onsubmit = {
+        let ticket_id = props.ticket_id;
+        let link_type = link_type.clone();
+        let other = other.clone();
+        let error = error.clone();
+        let apply = apply.clone();
+        let language = language.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let Ok(other_id) = other.trim().trim_start_matches('#').parse::<i32>() else {
+                error.set(language.get("Enter a ticket number"));
+                return;
+            };
+            let info = TicketLinkCreateInfo {
+                link_type: *link_type,
+                ticket_id: other_id,
+            };
+            let other = other.clone();
+            let apply = apply.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let result = create_link(ticket_id, &info).await;
+                if matches!(&result, Ok(response) if response.success) {
+                    other.set(String::new());
+                }
+                apply.emit(result);
+            });
+        })
+    };
+
+    let onclick_delete = |link: &TicketLinkInfo| {
+        let ticket_id = props.ticket_id;
+        let link_id = link.link_id;
+        let apply = apply.clone();
+        Callback::from(move |_: MouseEvent| {
+            let apply = apply.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                apply.emit(delete_link(ticket_id, link_id).await);
+            });
+        })
+    };
+
+    if links.is_empty() && !can_edit {
+        return html! {};
+    }
+
+    html! {
+        <div class="links">
+            <div><b>{ language.get("Links") }</b></div>
+            { for TicketLinkType::iter().map(|kind| {
+                let group = links.iter().filter(|link| link.link_type == kind).collect::<Vec<_>>();
+                if group.is_empty() {
+                    return html! {};
+                }
+                html! {
+                    <div class="link-group">
+                        { format!("{}: ", language.get(kind.label())) }
+                        { for group.into_iter().map(|link| html! {
+                            <span class="link">
+                                <Link<AppRoute> to={AppRoute::Ticket { ticket_id: link.ticket_id }}>
+                                    { format!("#{}", link.ticket_id) }
+                                </Link<AppRoute>>
+                                { " " }
+                                if link.is_closed {
+                                    <s>{ &link.title }</s>
+                                } else {
+                                    { &link.title }
+                                }
+                                { format!(" ({})", link.status) }
+                                if can_edit {
+                                    <button class="page-btn" onclick={onclick_delete(link)}>{ "✘" }</button>
+                                }
+                                { " " }
+                            </span>
+                        })}
+                    </div>
+                }
+            })}
+            if can_edit {
+                <form {onsubmit}>
+                    <select onchange={onchange_type}>
+                        { for TicketLinkType::iter().map(|kind| html! {
+                            <option value={kind.as_str()} selected={kind == *link_type}>
+                                { language.get(kind.label()) }
+                            </option>
+                        })}
+                    </select>
+                    <input type="text" placeholder="#" value={(*other).clone()}
+                        oninput={oninput_other} style="width: 80px; margin: 0px 8px;" />
+                    <button class="btn" type="submit">{ language.get("Add") }</button>
+                </form>
+                <span class="error">{ error.to_string() }</span>
+            }
+        </div>
+    }
+}
@@ -1,18 +1,22 @@
 use stylist::style;
 use stylist::yew::styled_component;
+use web_sys::HtmlInputElement;
 use yew::prelude::*;
 use yew_router::prelude::*;
 
 use crate::components::delete::DeleteItem;
-use crate::hooks::use_language_context;
+use crate::hooks::{use_language_context, use_user_context};
 use crate::routes::AppRoute;
-use crate::services::tickets::update_status;
-use crate::types::{TicketInfo, TicketStatusInfo};
+use crate::services::tickets::{merge_ticket, update_status};
+use crate::services::watchers::{add_watcher, get_watchers, remove_watcher};
+use crate::types::{StatusInfo, TicketInfo, TicketMergeInfo, TicketStatusInfo, WatcherInfo};
 
 #[derive(Properties, Clone, PartialEq)]
 pub struct Props {
     pub ticket_id: i32,
     pub ticket_status: String,
+    /// Configured statuses, used to find where closing and re-opening go
+    pub statuses: Vec<StatusInfo>,
     pub callback: Callback<TicketInfo>,
 }
 
@@ -22,6 +26,66 @@ pub fn ticket_menu(props: &Props) -> Html {
     let navigator = use_navigator().unwrap();
     let dropdown = use_state(|| false);
     let language = use_language_context();
+    let user_ctx = use_user_context();
+    let role = user_ctx.role();
+    let watchers = use_state(Vec::<WatcherInfo>::new);
+    //Ticket number to merge into, the form is shown while it is set
+    let merge_target = use_state(|| None::<String>);
+    let merge_error = use_state(String::new);
+
+    {
+        let watchers = watchers.clone();
+        use_effect_with(props.ticket_id, move |ticket_id| {
+            let ticket_id = *ticket_id;
+            wasm_bindgen_futures::spawn_local(async move {
+                match get_watchers(ticket_id).await {
+                    Ok(list) => watchers.set(list),
+                    Err(e) => log::error!("Error loading watchers: {}", e),
+                }
+            });
+            || ()
+        })
+    }
+
+    let watching = watchers.iter().any(|watcher| watcher.user_id == user_ctx.user_id);
+
+    //Closing goes to the first allowed closed status, re-opening to the default status
+    let current = props.statuses.iter().find(|status| status.name == props.ticket_status);
+    let closed = current.is_some_and(|status| status.is_closed);
+    let toggle_target = props
+        .statuses
+        .iter()
+        .filter(|status| if closed { status.is_default } else { status.is_closed })
+        .find(|status| current.map_or(true, |current| current.allows(status)))
+        .map(|status| status.name.clone());
+
+    let onclick_watch = {
+        let ticket_id = props.ticket_id;
+        let user_id = user_ctx.user_id;
+        let watchers = watchers.clone();
+        Callback::from(move |_| {
+            let watchers = watchers.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                if watching {
+                    match remove_watcher(ticket_id, user_id).await {
+                        Ok(_) => watchers.set(
+                            watchers
+                                .iter()
+                                .filter(|watcher| watcher.user_id != user_id)
+                                .cloned()
+                                .collect(),
+                        ),
+                        Err(e) => log::error!("Error removing watcher: {}", e),
+                    }
+                } else {
+                    match add_watcher(ticket_id, None).await {
+                        Ok(result) => watchers.set(result.data.unwrap_or_default()),
+                        Err(e) => log::error!("Error adding watcher: {}", e),
+                    }
+                }
+            });
+        })
+    };
 
     let callback_deleted = {
         let navigator = navigator.clone();
@@ -29,6 +93,54 @@ pub fn ticket_menu(props: &Props) -> Html {
             navigator.push(&AppRoute::Home);
         })
     };
+    let onclick_merge = {
+        let merge_target = merge_target.clone();
+        Callback::from(move |_| {
+            merge_target.set(if merge_target.is_some() { None } else { Some(String::new()) });
+        })
+    };
+
+    let oninput_merge = {
+        let merge_target = merge_target.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            merge_target.set(Some(input.value()));
+        })
+    };
+
+    //Opens the target ticket once merged
+    let onsubmit_merge = {
+        let ticket_id = props.ticket_id;
+        let merge_target = merge_target.clone();
+        let merge_error = merge_error.clone();
+        let navigator = navigator.clone();
+        let language = language.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let target = merge_target
+                .as_deref()
+                .and_then(|target| target.trim().trim_start_matches('#').parse::<i32>().ok());
+            let Some(target) = target else {
+                merge_error.set(language.get("Enter a ticket number"));
+                return;
+            };
+            let merge_target = merge_target.clone();
+            let merge_error = merge_error.clone();
+            let navigator = navigator.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match merge_ticket(ticket_id, &TicketMergeInfo { target }).await {
+                    Ok(result) if result.success => {
+                        merge_target.set(None);
+                        merge_error.set(String::new());
+                        navigator.push(&AppRoute::Ticket { ticket_id: target });
+                    }
+                    Ok(result) => merge_error.set(result.message.unwrap_or_default()),
+                    Err(e) => merge_error.set(e.to_string()),
+                }
+            });
+        })
+    };
+
     // use display: none; if not using state
     let style = style!(
         r#"
@@ -40,6 +152,10 @@ pub fn ticket_menu(props: &Props) -> Html {
             padding: 6px 8px;
             font-size: 20px;
           }
+        .watchers {
+            padding: 4px 8px;
+            font-size: 0.9rem;
+          }
           "#,
     )
     .expect("Failed to parse style");
@@ -61,17 +177,14 @@ pub fn ticket_menu(props: &Props) -> Html {
 
     let onclick_toggle_status = {
         let ticket_id = props.ticket_id.clone();
-        let ticket_status = props.ticket_status.clone();
+        let toggle_target = toggle_target.clone();
         let props = props.clone();
         Callback::from(move |_| {
             let props = props.clone();
-            let ticket = TicketStatusInfo {
-                status: if ticket_status == "Closed" {
-                    "Open".to_string()
-                } else {
-                    "Closed".to_string()
-                },
+            let Some(status) = toggle_target.clone() else {
+                return;
             };
+            let ticket = TicketStatusInfo { status };
             //async block
             wasm_bindgen_futures::spawn_local(async move {
                 let res = update_status(ticket_id, &ticket).await;
@@ -103,17 +216,47 @@ pub fn ticket_menu(props: &Props) -> Html {
                 </button>
                 { if *dropdown { html! {
                 <div class="dropdown-content">
+                    <div class="watchers">
+                        { format!("{}: ", language.get("Watchers")) }
+                        { if watchers.is_empty() {
+                            language.get("None")
+                        } else {
+                            watchers.iter().map(|watcher| watcher.display_name.clone()).collect::<Vec<String>>().join(", ")
+                        } }
+                    </div>
+                    <div>
+                        <button class="btn" onclick={onclick_watch}>
+                        { if watching { language.get("Stop watching") } else { language.get("Watch") } }
+                        </button>
+                    </div>
+                    if role.can_edit() {
                     <div>
                         <button class="btn" onclick={onclick_edit}>
                         { language.get("Edit") }
                         </button>
+                    if toggle_target.is_some() {
                     <div>
                         <button class="btn" onclick={onclick_toggle_status}>
-                            //if ticket_status is open, show close ticket, else show open ticket
-                            { if props.ticket_status != "Closed" { "Close Ticket" } else { "Re-open Ticket" } }
+                            { if closed { "Re-open Ticket" } else { "Close Ticket" } }
                         </button>
                     </div>
+                    }
+                    <div>
+                        <button class="btn" onclick={onclick_merge}>
+                            { language.get("Merge into...") }
+                        </button>
+                    </div>
+                    if let Some(target) = &*merge_target {
+                    <form class="watchers" onsubmit={onsubmit_merge}>
+                        <input type="text" placeholder="#" value={target.clone()}
+                            oninput={oninput_merge} style="width: 80px; margin: 0px 8px 0px 0px;" />
+                        <button class="btn" type="submit">{ language.get("Merge") }</button>
+                        <div class="error">{ merge_error.to_string() }</div>
+                    </form>
+                    }
                     </div>
+                    }
+                    if role.is_admin() {
                     <div>
                     <DeleteItem
                         item_id={props.ticket_id.to_string()}
@@ -121,6 +264,7 @@ pub fn ticket_menu(props: &Props) -> Html {
                         callback={callback_deleted}
                     />
                     </div>
+                    }
                 </div>
                 } } else { html! {} } }
             </div>
@@ -3,20 +3,40 @@ mod note;
 mod note_input;
 mod note_list;
 mod event;
+mod links;
+mod sla;
+mod tags;
+mod tasks;
 
 use stylist::style;
 use stylist::yew::styled_component;
 
 use yew::prelude::*;
 use yew::suspense::use_future;
+use yew_router::prelude::*;
 
+use shared::models::custom_fields::CustomFieldType;
+use shared::models::live::{LiveEvent, PresenceTarget};
+use shared::models::reports::format_minutes;
+
+use crate::components::attachments::{AttachmentList, AttachmentOwner};
+use crate::components::editing_indicator::EditingIndicator;
+use crate::contexts::live::use_live_events;
 use crate::contexts::time::use_time;
+use crate::hooks::use_user_context;
+use crate::routes::AppRoute;
 use crate::services::tickets::*;
 use crate::services::users::get_display_names;
-use crate::types::TicketInfo;
+use crate::services::workflows::get_statuses;
+use crate::types::{TagInfo, TaskInfo, TicketInfo, TicketLinkInfo};
 use crate::utils::markdown_to_html;
+use links::TicketLinks;
 use menu::TicketMenu;
+use sla::TicketSla;
+use tags::TicketTags;
+use tasks::TicketTasks;
 pub use note_list::NoteList;
+pub(crate) use links::link_event_message;
 
 #[derive(Properties, Clone, PartialEq)]
 pub struct Props {
@@ -28,6 +48,13 @@ pub struct Props {
 pub fn ticket(props: &Props) -> Html {
     let ticket = use_state(|| TicketInfo::default());
     let time_ctx = use_time();
+    let user_ctx = use_user_context();
+    //Bumped when someone else changes the ticket or its notes, reloads the note list
+    let notes_version = use_state(|| 0_u32);
+    //Bumped when someone else changes the ticket, reloads the task list
+    let tasks_version = use_state(|| 0_u32);
+    //Bumped when someone else changes the ticket, reloads the links
+    let links_version = use_state(|| 0_u32);
     
     {
         let ticket = ticket.clone();
@@ -41,12 +68,52 @@ pub fn ticket(props: &Props) -> Html {
         })
     }
 
+    //Our own changes are already shown, only follow changes made by others
+    {
+        let ticket = ticket.clone();
+        let notes_version = notes_version.clone();
+        let tasks_version = tasks_version.clone();
+        let links_version = links_version.clone();
+        let ticket_id = props.ticket_id;
+        let user_id = user_ctx.user_id;
+        use_live_events(Callback::from(move |event| match event {
+            LiveEvent::TicketChanged { ticket_id: changed, user_id: by }
+                if changed == ticket_id && by != Some(user_id) =>
+            {
+                let ticket = ticket.clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    if let Ok(ticket_data) = get(ticket_id).await {
+                        ticket.set(ticket_data);
+                    }
+                });
+                //Ticket updates also add events to the note list
+                notes_version.set(*notes_version + 1);
+                tasks_version.set(*tasks_version + 1);
+                links_version.set(*links_version + 1);
+            }
+            LiveEvent::NotesChanged { ticket_id: changed, user_id: by }
+                if changed == ticket_id && by != Some(user_id) =>
+            {
+                notes_version.set(*notes_version + 1);
+            }
+            _ => (),
+        }));
+    }
+
     //Adding this for now just to pass to events, but it will also be needed for inline editing in the future
     let userlist = match { use_future(|| async { get_display_names().await.unwrap_or_default() }) } {
         Ok(users) => users.clone(),
         Err(_) => vec![],
     };
 
+    let statuses = match { use_future(|| async { get_statuses().await.unwrap_or_default() }) } {
+        Ok(statuses) => statuses.clone(),
+        Err(_) => vec![],
+    };
+    let closed = statuses
+        .iter()
+        .any(|status| status.is_closed && status.name == ticket.status);
+
     let style = style!(
         r#"
         margin: 0 auto;
@@ -80,6 +147,31 @@ pub fn ticket(props: &Props) -> Html {
             color: #838383;
             font-style: italic;
           }
+          .custom-field {
+            color: #838383;
+            font-style: italic;
+          }
+          .time-spent {
+            color: #838383;
+            font-style: italic;
+          }
+          .sla {
+            color: #838383;
+            font-style: italic;
+          }
+        .sla-status {
+            padding: 0px 4px;
+            border-radius: 4px;
+        }
+        .sla-at_risk {
+            background-color: rgba(255, 127, 31, 0.4);
+        }
+        .sla-breached {
+            background-color: rgba(255, 31, 31, 0.4);
+        }
+        .tags {
+            margin: 4px 0px;
+        }
         .description {
             word-wrap: break-word;
         }
@@ -96,10 +188,10 @@ pub fn ticket(props: &Props) -> Html {
             border: 1px solid;
             position: absolute;
         }
-        .status-Open {
+        .status-open {
             background-color:rgba(63, 223, 63, 0.5);
         }
-        .status-Closed {
+        .status-closed {
             background-color:rgba(255, 63, 63, 0.5);
         }
           "#,
@@ -114,6 +206,39 @@ pub fn ticket(props: &Props) -> Html {
         })
     };
 
+    let callback_tags = {
+        let ticket = ticket.clone();
+        let notes_version = notes_version.clone();
+        Callback::from(move |tags: Vec<TagInfo>| {
+            let mut new_ticket = (*ticket).clone();
+            new_ticket.tags = tags;
+            ticket.set(new_ticket);
+            //Shows the tag event in the note list
+            notes_version.set(*notes_version + 1);
+        })
+    };
+
+    let callback_tasks = {
+        let ticket = ticket.clone();
+        let notes_version = notes_version.clone();
+        Callback::from(move |tasks: Vec<TaskInfo>| {
+            let mut new_ticket = (*ticket).clone();
+            new_ticket.tasks_done = tasks.iter().filter(|task| task.done).count() as i64;
+            new_ticket.tasks_total = tasks.len() as i64;
+            ticket.set(new_ticket);
+            //Shows task events in the note list
+            notes_version.set(*notes_version + 1);
+        })
+    };
+
+    let callback_links = {
+        let notes_version = notes_version.clone();
+        Callback::from(move |_: Vec<TicketLinkInfo>| {
+            //Shows link events in the note list
+            notes_version.set(*notes_version + 1);
+        })
+    };
+
     //Default ticket id is 0, so we don't want to render anything until we have a valid ticket id
     //If we change this we need to fix our unwraps
     if ticket.ticket_id != 0 {
@@ -122,12 +247,24 @@ pub fn ticket(props: &Props) -> Html {
                 <div class="ticket-detail">
                     <div class="header">
                         <span class="ticket-id">{"(#"}{&ticket.ticket_id}{") "}</span>
+                        if let Some(reference) = &ticket.reference {
+                            <span class="ticket-id">{reference}{" "}</span>
+                        }
                         <span class="title">{&ticket.title}</span>
-                        <span class={format!("status-badge status-{}", ticket.status.clone())}>{&ticket.status}</span>
+                        <span class={if closed { "status-badge status-closed" } else { "status-badge status-open" }}>{&ticket.status}</span>
                         <span>
-                        <TicketMenu ticket_id={props.ticket_id} ticket_status={ticket.status.clone()} callback={callback_updated} />
+                        <TicketMenu ticket_id={props.ticket_id} ticket_status={ticket.status.clone()} statuses={statuses.clone()} callback={callback_updated} />
                         </span>
+                        <EditingIndicator target={PresenceTarget::Ticket(props.ticket_id)} />
                     </div>
+                    if let Some(merged_into) = ticket.merged_into {
+                        <div class="merged">
+                            { "Merged into " }
+                            <Link<AppRoute> to={AppRoute::Ticket { ticket_id: merged_into }}>
+                                { format!("#{}", merged_into) }
+                            </Link<AppRoute>>
+                        </div>
+                    }
                     <div class="assignee">
                         { "Assigned to: " }
                         { if ticket.assignee.is_some() {
@@ -152,13 +289,40 @@ pub fn ticket(props: &Props) -> Html {
                             html! { "None" }
                         } }
                     </div>
+                    if ticket.time_spent > 0 {
+                        <div class="time-spent">
+                            { "Time spent: " }
+                            { format_minutes(ticket.time_spent) }
+                        </div>
+                    }
+                    if let Some(sla) = &ticket.sla {
+                        <TicketSla sla={sla.clone()} />
+                    }
+                    { for ticket.custom_fields.iter().map(|field| html! {
+                        <div class="custom-field">
+                            { format!("{}: ", field.name) }
+                            { if field.field_type == CustomFieldType::User.as_str() {
+                                userlist
+                                    .iter()
+                                    .find(|user| user.user_id.to_string() == field.value)
+                                    .map(|user| user.display_name.clone())
+                                    .unwrap_or(field.value.clone())
+                            } else {
+                                field.value.clone()
+                            } }
+                        </div>
+                    })}
+                    <TicketTags ticket_id={props.ticket_id} tags={ticket.tags.clone()} callback={callback_tags} />
                     <div class="description">
                         { markdown_to_html(&ticket.description) }
                     </div>
+                    <TicketTasks ticket_id={props.ticket_id} version={*tasks_version} callback={callback_tasks} />
+                    <TicketLinks ticket_id={props.ticket_id} version={*links_version} callback={callback_links} />
+                    <AttachmentList owner={AttachmentOwner::Ticket(props.ticket_id)} />
                 </div>
                 <hr />
                 <div class="note-list">
-                    <NoteList ticket_id={props.ticket_id.clone()} userlist={userlist} />
+                    <NoteList ticket_id={props.ticket_id.clone()} userlist={userlist} version={*notes_version} />
                 </div>
             </div>
         }
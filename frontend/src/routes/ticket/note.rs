@@ -4,7 +4,7 @@ use yew::prelude::*;
 
 use crate::components::delete::{DeleteItem, ItemTypes};
 use crate::components::time_format::TimeFormat;
-use crate::hooks::use_language_context;
+use crate::hooks::{use_language_context, use_user_context};
 use crate::services::notes::update_note;
 use crate::types::{NoteCreateInfo, NoteInfo};
 use crate::utils::markdown_to_html;
@@ -21,6 +21,8 @@ pub struct Props {
 pub fn note(props: &Props) -> Html {
     let note = &props.note;
     let language = use_language_context();
+    let user_ctx = use_user_context();
+    let is_owner = note.owner.as_ref().map(|owner| owner.user_id) == Some(user_ctx.user_id);
     let edit_mode = use_state(|| false);
     let submitted = use_state(|| false);
     let update_info = use_state(|| note.clone());
@@ -43,6 +45,7 @@ pub fn note(props: &Props) -> Html {
                             owner: Some(note_owner),
                             text: update_info.text.clone(),
                             time: update_info.time,
+                            is_public: Some(update_info.is_public),
                         };
                         update_note(update_info.note_id, request).await
                     };
@@ -89,6 +92,16 @@ pub fn note(props: &Props) -> Html {
         })
     };
 
+    let onchange_public = {
+        let update_info = update_info.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut info = (*update_info).clone();
+            info.is_public = input.checked();
+            update_info.set(info);
+        })
+    };
+
     let onclick_edit = {
         let edit_mode = edit_mode.clone();
         Callback::from(move |_| {
@@ -97,22 +110,29 @@ pub fn note(props: &Props) -> Html {
     };
 
     html! {
-        <div class="note">
+        <div class={classes!("note", (!note.is_public).then_some("internal"))}>
             <div class="note-header">
                 <span class="note-owner">
                     { note.display_name() }
                     {" "}
                     <TimeFormat time={note.created_at} />
+                    if !note.is_public {
+                        <span class="internal-badge">{ language.get("Internal") }</span>
+                    }
                 </span>
                 <span>
+                    if is_owner || user_ctx.role().can_edit() {
                     <button class="btn" onclick={onclick_edit}>
                         {language.get("Edit")}
                     </button>
+                    }
+                    if is_owner || user_ctx.role().is_admin() {
                     <DeleteItem
                         item_id={note.note_id.to_string()}
                         item_type={ItemTypes::Note}
                         callback={props.callback.clone()}
                     />
+                    }
                 </span>
             </div>
             { if *edit_mode { //if edit mode is true, show the edit form
@@ -120,6 +140,12 @@ pub fn note(props: &Props) -> Html {
                     <div class="note-edit">
                         <form onsubmit={on_submit}>
                         <textarea placeholder="Text (Markdown)" rows=4 value={update_info.text.clone()} oninput={oninput_content} />
+                            if user_ctx.role().can_edit() {
+                                <label>
+                                    <input type="checkbox" checked={update_info.is_public} onchange={onchange_public} />
+                                    { language.get("Visible to the requester") }
+                                </label>
+                            }
                             <div>
                             <button class="btn" type="submit">
                                 { language.get("Submit") }
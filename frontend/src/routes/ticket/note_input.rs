@@ -3,8 +3,11 @@ use web_sys::HtmlInputElement;
 
 use yew::prelude::*;
 
+use crate::components::attachments::{selected_files, upload_error};
 use crate::hooks::{use_language_context, use_user_context};
+use crate::services::attachments::upload_note_attachment;
 use crate::services::notes::*;
+use crate::services::users::get_user_preferences;
 use crate::types::{NoteCreateInfo, NoteInfo};
 
 #[derive(Properties, Clone, PartialEq)]
@@ -20,6 +23,22 @@ pub fn note_input(props: &Props) -> Html {
     let language = use_language_context();
     let submitted = use_state(|| false);
     let error = use_state(|| String::new());
+    //Files are uploaded to the note once it is created
+    let file_input = use_node_ref();
+    //Shown until the author picks a visibility for the note
+    let public_default = use_state(|| true);
+
+    {
+        let public_default = public_default.clone();
+        use_effect_with((), move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Ok(prefs) = get_user_preferences().await {
+                    public_default.set(prefs.notes_public.unwrap_or(true));
+                }
+            });
+            || ()
+        })
+    }
 
     //When submitted set to true, send create request
     //On success, reset create_info, submitted, and error
@@ -28,25 +47,39 @@ pub fn note_input(props: &Props) -> Html {
         let error = error.clone();
         let ticket_id = props.ticket_id.clone();
         let callback = props.callback.clone();
+        let file_input = file_input.clone();
+        let user_ctx = user_ctx.clone();
         use_effect_with(submitted.clone(),move |submitted| {
             if **submitted {
                 let create_info = create_info.clone();
                 let error = error.clone();
                 let submitted = submitted.clone();
+                let input = file_input.cast::<HtmlInputElement>();
+                let files = input.as_ref().map(selected_files).unwrap_or_default();
                 wasm_bindgen_futures::spawn_local(async move {
                     let request = NoteCreateInfo {
                         ticket: ticket_id.clone(),
                         text: create_info.text.clone(),
                         owner: Some(user_ctx.user_id),
                         time: create_info.time.clone(),
+                        is_public: create_info.is_public,
                     };
                     let result = create(request).await;
                     match result {
                         Ok(note) => {
+                            let mut errors = vec![];
+                            for file in files {
+                                let filename = file.name();
+                                let result = upload_note_attachment(note.note_id, file).await;
+                                errors.extend(upload_error(&filename, result));
+                            }
+                            if let Some(input) = input {
+                                input.set_value("");
+                            }
                             create_info.set(NoteCreateInfo::default());
                             submitted.set(false);
                             callback.emit(note.clone());
-                            error.set(String::new());
+                            error.set(errors.join(", "));
                         }
                         Err(e) => {
                             error.set(e.to_string());
@@ -93,6 +126,16 @@ pub fn note_input(props: &Props) -> Html {
         })
     };
 
+    let onchange_public = {
+        let create_info = create_info.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut info = (*create_info).clone();
+            info.is_public = Some(input.checked());
+            create_info.set(info);
+        })
+    };
+
     let style = use_style! {
         r#"
         fieldset {
@@ -116,6 +159,19 @@ pub fn note_input(props: &Props) -> Html {
                     <input type="number" min="0" step="5" style="width: 60px;"
                         value={create_info.time.clone().to_string()} oninput={oninput_time} />
                 </div>
+                if user_ctx.role().can_edit() {
+                    <div>
+                        <label>
+                            <input type="checkbox" checked={create_info.is_public.unwrap_or(*public_default)}
+                                onchange={onchange_public} />
+                            { language.get("Visible to the requester") }
+                        </label>
+                    </div>
+                }
+                <div>
+                    <label>{language.get("Attachments")}</label>
+                    <input type="file" multiple=true ref={file_input} />
+                </div>
                 <button class="btn" type="submit" disabled={*submitted}>
                     { "Post Note" }
                 </button>
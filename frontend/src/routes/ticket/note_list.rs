@@ -18,6 +18,9 @@ use crate::types::{NoteInfo, events::TicketEvent};
 pub struct Props {
     pub ticket_id: i32,
     pub userlist: Vec<UserDisplay>,
+    /// Changing this reloads notes and events
+    #[prop_or_default]
+    pub version: u32,
 }
 
 //List of notes used by ticket detail page
@@ -32,7 +35,7 @@ pub fn note_list(props: &Props) -> Html {
     {
         let note_list = note_list.clone();
         let props = props.clone();
-        use_effect_with(props.ticket_id.clone(),move |_| {
+        use_effect_with((props.ticket_id.clone(), props.version),move |_| {
             let note_list = note_list.clone();
             wasm_bindgen_futures::spawn_local(async move {
                 let notes = get_notes(props.ticket_id).await.unwrap();
@@ -45,7 +48,7 @@ pub fn note_list(props: &Props) -> Html {
     {
         let event_list = event_list.clone();
         let props = props.clone();
-        use_effect_with(props.ticket_id.clone(),move |_| {
+        use_effect_with((props.ticket_id.clone(), props.version),move |_| {
             let event_list = event_list.clone();
             wasm_bindgen_futures::spawn_local(async move {
                 let events = get_events(props.ticket_id).await.unwrap();
@@ -151,6 +154,20 @@ pub fn note_list(props: &Props) -> Html {
             border-radius: 0.5rem;
             border: 1px solid ${border};
         }
+        .internal {
+            border: 1px dashed #d08a2c;
+        }
+        .internal .note-header {
+            background: repeating-linear-gradient(-45deg, transparent, transparent 8px, #d08a2c22 8px, #d08a2c22 16px), ${headerbg};
+        }
+        .internal-badge {
+            margin-left: 8px;
+            padding: 0px 6px;
+            border-radius: 8px;
+            font-size: 0.8rem;
+            color: #fff;
+            background: #d08a2c;
+        }
         .timeformat {
             font-size: 0.8rem;
             color: #838383;
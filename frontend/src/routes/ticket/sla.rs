@@ -0,0 +1,43 @@
+use yew::prelude::*;
+
+use crate::components::sla::sla_label;
+use crate::contexts::time::use_time;
+use crate::hooks::use_language_context;
+use crate::types::TicketSlaInfo;
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub sla: TicketSlaInfo,
+}
+
+/// SLA policy of a ticket with its deadlines, missed ones are marked
+#[function_component(TicketSla)]
+pub fn ticket_sla(props: &Props) -> Html {
+    let language = use_language_context();
+    let time_ctx = use_time();
+    let sla = &props.sla;
+
+    let deadline = |label: &str, due: Option<chrono::NaiveDateTime>, breached: bool| match due {
+        Some(due) => html! {
+            <div class="sla-deadline">
+                { format!("{}: ", language.get(label)) }
+                { time_ctx.convert_to_local(&due).format("%Y-%m-%d %H:%M") }
+                if breached {
+                    <span class="sla-breached">{ format!(" ({})", language.get("Breached")) }</span>
+                }
+            </div>
+        },
+        None => html! {},
+    };
+
+    html! {
+        <div class="sla">
+            { format!("SLA: {} ", sla.policy_name) }
+            <span class={format!("sla-status sla-{}", sla.sla_status)}>
+                { sla_label(&sla.sla_status, &language) }
+            </span>
+            { deadline("Response due", sla.response_due, sla.response_breached) }
+            { deadline("Resolution due", sla.resolution_due, sla.resolution_breached) }
+        </div>
+    }
+}
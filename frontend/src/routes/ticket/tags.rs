@@ -0,0 +1,137 @@
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::components::tag_chip::TagChip;
+use crate::hooks::{use_language_context, use_user_context};
+use crate::services::tags::{add_ticket_tag, create_tag, get_tags, remove_ticket_tag};
+use crate::types::{TagCreateInfo, TagInfo};
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub ticket_id: i32,
+    pub tags: Vec<TagInfo>,
+    /// Receives the tags of the ticket after a change
+    pub callback: Callback<Vec<TagInfo>>,
+}
+
+/// Tags of a ticket, agents can add and remove them.
+/// Typing a name that does not exist yet creates the tag.
+#[function_component(TicketTags)]
+pub fn ticket_tags(props: &Props) -> Html {
+    let language = use_language_context();
+    let user_ctx = use_user_context();
+    let all_tags = use_state(Vec::<TagInfo>::new);
+    let input = use_state(String::new);
+    let error = use_state(String::new);
+    let can_edit = user_ctx.role().can_edit();
+
+    {
+        let all_tags = all_tags.clone();
+        use_effect_with(can_edit, move |can_edit| {
+            if *can_edit {
+                wasm_bindgen_futures::spawn_local(async move {
+                    match get_tags().await {
+                        Ok(list) => all_tags.set(list),
+                        Err(e) => log::error!("Error loading tags: {}", e),
+                    }
+                });
+            }
+            || ()
+        })
+    }
+
+    let oninput = {
+        let input = input.clone();
+        Callback::from(move |e: InputEvent| {
+            let target: HtmlInputElement = e.target_unchecked_into();
+            input.set(target.value());
+        })
+    };
+
+    let onsubmit = {
+        let ticket_id = props.ticket_id;
+        let callback = props.callback.clone();
+        let all_tags = all_tags.clone();
+        let input = input.clone();
+        let error = error.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let name = input.trim().to_string();
+            if name.is_empty() {
+                return;
+            }
+            let callback = callback.clone();
+            let all_tags = all_tags.clone();
+            let input = input.clone();
+            let error = error.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let existing = all_tags.iter().find(|tag| tag.name == name).cloned();
+                let tag = match existing {
+                    Some(tag) => tag,
+                    None => {
+                        let info = TagCreateInfo { name, color: None };
+                        match create_tag(&info).await {
+                            Ok(response) if response.success => {
+                                let tag = response.data.unwrap_or_default();
+                                let mut list = (*all_tags).clone();
+                                list.push(tag.clone());
+                                all_tags.set(list);
+                                tag
+                            }
+                            Ok(response) => {
+                                return error.set(response.message.unwrap_or_default());
+                            }
+                            Err(e) => return error.set(e.to_string()),
+                        }
+                    }
+                };
+                match add_ticket_tag(ticket_id, tag.tag_id).await {
+                    Ok(response) if response.success => {
+                        error.set(String::new());
+                        input.set(String::new());
+                        callback.emit(response.data.unwrap_or_default());
+                    }
+                    Ok(response) => error.set(response.message.unwrap_or_default()),
+                    Err(e) => error.set(e.to_string()),
+                }
+            });
+        })
+    };
+
+    let onremove = {
+        let ticket_id = props.ticket_id;
+        let callback = props.callback.clone();
+        Callback::from(move |tag: TagInfo| {
+            let callback = callback.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match remove_ticket_tag(ticket_id, tag.tag_id).await {
+                    Ok(response) if response.success => {
+                        callback.emit(response.data.unwrap_or_default())
+                    }
+                    Ok(_) => (),
+                    Err(e) => log::error!("Error removing tag: {}", e),
+                }
+            });
+        })
+    };
+
+    html! {
+        <div class="tags">
+            { for props.tags.iter().map(|tag| html! {
+                <TagChip tag={tag.clone()} onremove={can_edit.then(|| onremove.clone())} />
+            })}
+            if can_edit {
+                <form style="display: inline;" {onsubmit}>
+                    <input type="text" list="tag-names" placeholder={language.get("Add tag")}
+                        value={(*input).clone()} {oninput} style="width: 120px; margin: 0px;" />
+                    <datalist id="tag-names">
+                        { for all_tags.iter()
+                            .filter(|tag| !props.tags.iter().any(|added| added.tag_id == tag.tag_id))
+                            .map(|tag| html! { <option value={tag.name.clone()} /> }) }
+                    </datalist>
+                </form>
+                <span class="error">{ error.to_string() }</span>
+            }
+        </div>
+    }
+}
@@ -0,0 +1,203 @@
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::hooks::{use_language_context, use_user_context};
+use crate::services::tasks::{create_task, delete_task, get_tasks, reorder_tasks, update_task};
+use crate::types::{Error, TaskCreateInfo, TaskInfo, TaskOrderInfo, TaskUpdateInfo};
+use shared::models::response::Response;
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct Props {
+    pub ticket_id: i32,
+    /// Bumped when someone else changes the ticket, reloads the tasks
+    pub version: u32,
+    /// Receives the tasks of the ticket after a change
+    pub callback: Callback<Vec<TaskInfo>>,
+}
+
+/// Checklist of a ticket, agents can add, check, reorder and remove tasks.
+/// Required tasks have to be done before the ticket can be closed.
+#[function_component(TicketTasks)]
+pub fn ticket_tasks(props: &Props) -> Html {
+    let language = use_language_context();
+    let user_ctx = use_user_context();
+    let tasks = use_state(Vec::<TaskInfo>::new);
+    let info = use_state(TaskCreateInfo::default);
+    let error = use_state(String::new);
+    let can_edit = user_ctx.role().can_edit();
+
+    {
+        let tasks = tasks.clone();
+        use_effect_with((props.ticket_id, props.version), move |(ticket_id, _)| {
+            let ticket_id = *ticket_id;
+            wasm_bindgen_futures::spawn_local(async move {
+                match get_tasks(ticket_id).await {
+                    Ok(list) => tasks.set(list),
+                    Err(e) => log::error!("Error loading tasks: {}", e),
+                }
+            });
+            || ()
+        })
+    }
+
+    //Every change responds with the whole list
+    let apply = {
+        let tasks = tasks.clone();
+        let error = error.clone();
+        let callback = props.callback.clone();
+        Callback::from(
+            move |result: Result<Response<Vec<TaskInfo>>, Error>| match result {
+                Ok(response) if response.success => {
+                    let list = response.data.unwrap_or_default();
+                    error.set(String::new());
+                    tasks.set(list.clone());
+                    callback.emit(list);
+                }
+                Ok(response) => error.set(response.message.unwrap_or_default()),
+                Err(e) => error.set(e.to_string()),
+            },
+        )
+    };
+
+    let oninput_text = {
+        let info = info.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut new_info = (*info).clone();
+            new_info.text = input.value();
+            info.set(new_info);
+        })
+    };
+
+    let onchange_required = {
+        let info = info.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut new_info = (*info).clone();
+            new_info.required = input.checked();
+            info.set(new_info);
+        })
+    };
+
+    let onsubmit = {
+        let ticket_id = props.ticket_id;
+        let info = info.clone();
+        let apply = apply.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            if info.text.trim().is_empty() {
+                return;
+            }
+            let info = info.clone();
+            let apply = apply.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let result = create_task(ticket_id, &info).await;
+                if matches!(&result, Ok(response) if response.success) {
+                    info.set(TaskCreateInfo::default());
+                }
+                apply.emit(result);
+            });
+        })
+    };
+
+    let onchange_done = |task: &TaskInfo| {
+        let ticket_id = props.ticket_id;
+        let task_id = task.task_id;
+        let apply = apply.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let info = TaskUpdateInfo {
+                done: Some(input.checked()),
+                ..Default::default()
+            };
+            let apply = apply.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                apply.emit(update_task(ticket_id, task_id, &info).await);
+            });
+        })
+    };
+
+    //Swaps the task with its neighbour, offset is -1 for up and 1 for down
+    let onclick_move = |index: usize, offset: isize| {
+        let ticket_id = props.ticket_id;
+        let tasks = tasks.clone();
+        let apply = apply.clone();
+        Callback::from(move |_: MouseEvent| {
+            let mut task_ids = tasks.iter().map(|task| task.task_id).collect::<Vec<_>>();
+            let Some(other) = index
+                .checked_add_signed(offset)
+                .filter(|i| *i < task_ids.len())
+            else {
+                return;
+            };
+            task_ids.swap(index, other);
+            let apply = apply.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                apply.emit(reorder_tasks(ticket_id, &TaskOrderInfo { task_ids }).await);
+            });
+        })
+    };
+
+    let onclick_delete = |task: &TaskInfo| {
+        let ticket_id = props.ticket_id;
+        let task_id = task.task_id;
+        let apply = apply.clone();
+        Callback::from(move |_: MouseEvent| {
+            let apply = apply.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                apply.emit(delete_task(ticket_id, task_id).await);
+            });
+        })
+    };
+
+    if tasks.is_empty() && !can_edit {
+        return html! {};
+    }
+
+    let done = tasks.iter().filter(|task| task.done).count();
+    let count = tasks.len();
+
+    html! {
+        <div class="tasks">
+            <div>
+                <b>{ language.get("Tasks") }</b>
+                if count > 0 {
+                    { format!(" {}/{}", done, count) }
+                }
+            </div>
+            { for tasks.iter().enumerate().map(|(index, task)| html! {
+                <div class="task">
+                    <label>
+                        <input type="checkbox" checked={task.done} disabled={!can_edit}
+                            onchange={onchange_done(task)} />
+                        if task.done {
+                            <s>{ &task.text }</s>
+                        } else {
+                            { &task.text }
+                        }
+                    </label>
+                    if task.required {
+                        <span class="task-required" title={language.get("Required to close the ticket")}>{ " *" }</span>
+                    }
+                    if can_edit {
+                        <button class="page-btn" disabled={index == 0} onclick={onclick_move(index, -1)}>{ "▲" }</button>
+                        <button class="page-btn" disabled={index + 1 == count} onclick={onclick_move(index, 1)}>{ "▼" }</button>
+                        <button class="page-btn" onclick={onclick_delete(task)}>{ "✘" }</button>
+                    }
+                </div>
+            })}
+            if can_edit {
+                <form {onsubmit}>
+                    <input type="text" placeholder={language.get("Add task")} value={info.text.clone()}
+                        oninput={oninput_text} style="width: 240px; margin: 0px;" />
+                    <label style="margin-left: 8px;">
+                        <input type="checkbox" checked={info.required} onchange={onchange_required} />
+                        { language.get("Required") }
+                    </label>
+                    <button class="btn" type="submit">{ language.get("Add") }</button>
+                </form>
+                <span class="error">{ error.to_string() }</span>
+            }
+        </div>
+    }
+}
@@ -1,5 +1,8 @@
 use chrono::Local;
 use chrono::TimeZone;
+use shared::models::custom_fields::CustomFieldType;
+use shared::models::live::PresenceTarget;
+use shared::models::users::UserDisplay;
 use shared::models::MAX_TITLE_LENGTH;
 use stylist::style;
 use stylist::yew::styled_component;
@@ -11,11 +14,17 @@ use yew::prelude::*;
 use yew::suspense::use_future;
 use yew_router::prelude::*;
 
+use crate::components::editing_indicator::EditingIndicator;
+use crate::contexts::live::use_presence;
 use crate::contexts::time::use_time;
 use crate::hooks::{use_language_context, use_user_context};
 use crate::routes::AppRoute;
+use crate::services::custom_fields::get_custom_fields;
+use crate::services::projects::get_projects;
 use crate::services::tickets::*;
 use crate::services::users::get_display_names;
+use crate::services::workflows::{get_priorities, get_statuses};
+use crate::types::CustomFieldInfo;
 use crate::types::TicketCreateInfo;
 use crate::types::TicketInfo;
 use crate::types::TicketUpdateInfo;
@@ -43,11 +52,46 @@ pub fn ticket_editor(props: &Props) -> Html {
     let navigator = use_navigator().unwrap();
     let retrieved_ticket = use_state(TicketInfo::default);
 
+    use_presence(props.ticket_id.map(PresenceTarget::Ticket));
+
     let userlist = match { use_future(|| async { get_display_names().await.unwrap_or_default() }) } {
         Ok(users) => users.clone(),
         Err(_) => vec![],
     };
 
+    let projects = match { use_future(|| async { get_projects().await.unwrap_or_default() }) } {
+        Ok(projects) => projects.clone(),
+        Err(_) => vec![],
+    };
+
+    let custom_fields = match { use_future(|| async { get_custom_fields().await.unwrap_or_default() }) } {
+        Ok(fields) => fields.clone(),
+        Err(_) => vec![],
+    };
+
+    let statuses = match { use_future(|| async { get_statuses().await.unwrap_or_default() }) } {
+        Ok(statuses) => statuses.clone(),
+        Err(_) => vec![],
+    };
+
+    let priorities = match { use_future(|| async { get_priorities().await.unwrap_or_default() }) } {
+        Ok(priorities) => priorities.clone(),
+        Err(_) => vec![],
+    };
+
+    //Existing tickets can only move to the statuses allowed from the saved one
+    let saved_status = statuses
+        .iter()
+        .find(|status| props.ticket_id.is_some() && status.name == retrieved_ticket.status)
+        .cloned();
+    let status_options = statuses
+        .iter()
+        .filter(|status| match &saved_status {
+            Some(saved) => saved.status_id == status.status_id || saved.allows(status),
+            None => true,
+        })
+        .cloned()
+        .collect::<Vec<_>>();
 
     //If props.ticket_id is some, get ticket info from server (retrieved_ticket)
     {
@@ -81,6 +125,12 @@ pub fn ticket_editor(props: &Props) -> Html {
                                     } else {
                                         None
                                     },
+                                project: ticket.project.map(|project| project.project_id),
+                                custom_fields: ticket
+                                    .custom_fields
+                                    .into_iter()
+                                    .map(|field| (field.field_id, field.value))
+                                    .collect(),
                             });
                         }
                         Err(e) => {
@@ -131,6 +181,10 @@ pub fn ticket_editor(props: &Props) -> Html {
                             }
                             ),
                             version: Some(retrieved_ticket.revision.clone()),
+                            project: Some(
+                                update_info.project.map(|project| project.to_string()).unwrap_or_default(),
+                            ),
+                            custom_fields: Some(update_info.custom_fields.clone()),
                         };
                         update(ticket_id, &request).await
                     } else {
@@ -199,6 +253,27 @@ pub fn ticket_editor(props: &Props) -> Html {
         })
     };
 
+    //Picking a project for a new ticket also picks its default assignee
+    let onselect_project: Callback<Event> = {
+        let update_info = update_info.clone();
+        let projects = projects.clone();
+        let is_new = props.ticket_id.is_none();
+        Callback::from(move |e: Event| {
+            let input: HtmlSelectElement = e.target_unchecked_into();
+            let mut info = (*update_info).clone();
+            info.project = input.value().parse().ok();
+            if is_new {
+                let project = projects
+                    .iter()
+                    .find(|project| Some(project.project_id) == info.project);
+                if let Some(default_assignee) = project.and_then(|project| project.default_assignee) {
+                    info.assignee = Some(default_assignee);
+                }
+            }
+            update_info.set(info);
+        })
+    };
+
     let onselect_priority = {
         let update_info = update_info.clone();
         Callback::from(move |e: Event| {
@@ -232,6 +307,15 @@ pub fn ticket_editor(props: &Props) -> Html {
         })
     };
 
+    let onchange_field = {
+        let update_info = update_info.clone();
+        Callback::from(move |(field_id, value): (Uuid, String)| {
+            let mut info = (*update_info).clone();
+            info.custom_fields.insert(field_id, value);
+            update_info.set(info);
+        })
+    };
+
     // let oninput_contact = {
     //     let update_info = update_info.clone();
     //     Callback::from(move |e: InputEvent| {
@@ -274,6 +358,9 @@ pub fn ticket_editor(props: &Props) -> Html {
                 <div class="error">
                     {error.to_string()}
                 </div>
+                if let Some(ticket_id) = props.ticket_id {
+                    <EditingIndicator target={PresenceTarget::Ticket(ticket_id)} />
+                }
                     <form {onsubmit}>
                         <fieldset class="editor-text">
                             <legend>{language.get("Title")}</legend>
@@ -287,6 +374,21 @@ pub fn ticket_editor(props: &Props) -> Html {
                                 value={update_info.description.clone()} oninput={oninput_description}>
                                 </textarea>
                         </fieldset>
+                        <fieldset class="editor-select">
+                            <legend>{language.get("Project")}</legend>
+                            <select onchange={onselect_project}>
+                                <option value="" selected={update_info.project.is_none()}>{language.get("No project")}</option>
+                                {
+                                    for projects.iter().map(|project| {
+                                        html! {
+                                        <option value={project.project_id.to_string()} selected={
+                                            update_info.project == Some(project.project_id)}>
+                                            {format!("{} ({})", project.name, project.key)}</option>
+                                        }
+                                    })
+                                }
+                            </select>
+                        </fieldset>
                         <fieldset class="editor-select">
                             <legend>{language.get("Assignee")}</legend>
                             <select onchange={onselect_assignee}>
@@ -306,18 +408,28 @@ pub fn ticket_editor(props: &Props) -> Html {
                             <legend>{language.get("Priority")}</legend>
                             <select onchange={onselect_priority}>
                                 <option value="" selected={update_info.priority == ""}></option>
-                                <option value="High" selected={update_info.priority=="High" }>{"High"}</option>
-                                <option value="Medium" selected={update_info.priority=="Medium" }>{"Medium"}</option>
-                                <option value="Low" selected={update_info.priority=="Low" }>{"Low"}</option>
+                                { for priorities.iter().map(|priority| html! {
+                                    <option value={priority.name.clone()} selected={update_info.priority == priority.name}>
+                                        {priority.name.clone()}</option>
+                                })}
+                                //Keeps a priority that is no longer configured
+                                if !update_info.priority.is_empty() && !priorities.iter().any(|priority| priority.name == update_info.priority) {
+                                    <option value={update_info.priority.clone()} selected=true>{update_info.priority.clone()}</option>
+                                }
                             </select>
                         </fieldset>
                         <fieldset class="editor-select">
                             <legend>{language.get("Status")}</legend>
                             <select onchange={onselect_status}>
-                                <option value="Closed" selected={update_info.status=="Closed" }>{"Closed"}</option>
-                                <option value="In Progress" selected={update_info.status=="In Progress" }>{"In Progress"}
-                                </option>
-                                <option value="Open" selected={update_info.status=="Open" }>{"Open"}</option>
+                                //New tickets get the default status unless another one is picked
+                                { for status_options.iter().map(|status| html! {
+                                    <option value={status.name.clone()} selected={
+                                        update_info.status == status.name || (update_info.status.is_empty() && status.is_default)}>
+                                        {status.name.clone()}</option>
+                                })}
+                                if !update_info.status.is_empty() && !statuses.iter().any(|status| status.name == update_info.status) {
+                                    <option value={update_info.status.clone()} selected=true>{update_info.status.clone()}</option>
+                                }
                             </select>
                         </fieldset>
                         <fieldset class="editor-select">
@@ -330,6 +442,15 @@ pub fn ticket_editor(props: &Props) -> Html {
                                 }
                             } oninput={oninput_due_date}/>
                         </fieldset>
+                        { for custom_fields.iter().map(|field| {
+                            let value = update_info.custom_fields.get(&field.field_id).cloned().unwrap_or_default();
+                            html! {
+                                <fieldset class="editor-select">
+                                    <legend>{ if field.required { format!("{} *", field.name) } else { field.name.clone() } }</legend>
+                                    { field_input(field, value, &userlist, onchange_field.clone()) }
+                                </fieldset>
+                            }
+                        })}
                         <button class="btn" type="submit">// disabled={ticket_update.loading}>
                             { language.get("Save") }
                         </button>
@@ -343,3 +464,51 @@ pub fn ticket_editor(props: &Props) -> Html {
         }
     }
 }
+
+/// Input matching the type of a custom field, emits the field id and new value
+fn field_input(
+    field: &CustomFieldInfo,
+    value: String,
+    userlist: &[UserDisplay],
+    onchange: Callback<(Uuid, String)>,
+) -> Html {
+    let field_id = field.field_id;
+    let oninput = onchange.reform(move |e: InputEvent| {
+        let input: HtmlInputElement = e.target_unchecked_into();
+        (field_id, input.value())
+    });
+    let onselect = onchange.reform(move |e: Event| {
+        let input: HtmlSelectElement = e.target_unchecked_into();
+        (field_id, input.value())
+    });
+
+    match field.field_type.parse::<CustomFieldType>() {
+        Ok(CustomFieldType::Number) => html! {
+            <input type="number" step="any" style="width: fit-content;" {value} {oninput} />
+        },
+        Ok(CustomFieldType::Date) => html! {
+            <input type="date" style="width: fit-content;" {value} {oninput} />
+        },
+        Ok(CustomFieldType::Select) => html! {
+            <select onchange={onselect}>
+                <option value="" selected={value.is_empty()}></option>
+                { for field.options.iter().map(|option| html! {
+                    <option value={option.clone()} selected={*option == value}>{ option }</option>
+                })}
+            </select>
+        },
+        Ok(CustomFieldType::User) => html! {
+            <select onchange={onselect}>
+                <option value="" selected={value.is_empty()}></option>
+                { for userlist.iter().map(|user| html! {
+                    <option value={user.user_id.to_string()} selected={user.user_id.to_string() == value}>
+                        { user.display_name.clone() }
+                    </option>
+                })}
+            </select>
+        },
+        _ => html! {
+            <input type="text" {value} {oninput} />
+        },
+    }
+}
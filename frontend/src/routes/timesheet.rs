@@ -0,0 +1,260 @@
+use shared::models::reports::{format_minutes, TimeGrouping, TimeReportQuery};
+use stylist::style;
+use stylist::yew::styled_component;
+use uuid::Uuid;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+use yew_router::prelude::*;
+
+use crate::contexts::theme::use_theme;
+use crate::contexts::time::use_time;
+use crate::hooks::{use_language_context, use_user_context};
+use crate::routes::AppRoute;
+use crate::services::reports::{get_time_report, time_report_csv_url};
+use crate::services::users::get_users;
+use crate::types::{TimeReportInfo, UserRepresentation};
+
+/// Time logged on notes with totals per user, ticket, contact or day, for billing and workload
+#[styled_component(Timesheet)]
+pub fn timesheet() -> Html {
+    let theme = use_theme();
+    let language = use_language_context();
+    let user_ctx = use_user_context();
+    let time_ctx = use_time();
+    let query = use_state(TimeReportQuery::default);
+    let report = use_state(TimeReportInfo::default);
+    let users = use_state(Vec::<UserRepresentation>::new);
+    let error = use_state(String::new);
+
+    {
+        let users = users.clone();
+        use_effect_with((), move |_| {
+            wasm_bindgen_futures::spawn_local(async move {
+                users.set(get_users().await.unwrap_or_default());
+            });
+            || ()
+        })
+    }
+
+    {
+        let report = report.clone();
+        let error = error.clone();
+        use_effect_with((*query).clone(), move |query| {
+            let query = query.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match get_time_report(&query).await {
+                    Ok(result) => {
+                        error.set(String::new());
+                        report.set(result);
+                    }
+                    Err(e) => error.set(e.to_string()),
+                }
+            });
+            || ()
+        })
+    }
+
+    if !user_ctx.role().can_view() {
+        return html! {};
+    }
+
+    //Empty inputs clear the filter
+    let onchange_from = {
+        let query = query.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut new_query = (*query).clone();
+            new_query.from = Some(input.value()).filter(|value| !value.is_empty());
+            query.set(new_query);
+        })
+    };
+
+    let onchange_to = {
+        let query = query.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let mut new_query = (*query).clone();
+            new_query.to = Some(input.value()).filter(|value| !value.is_empty());
+            query.set(new_query);
+        })
+    };
+
+    let onchange_user = {
+        let query = query.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlSelectElement = e.target_unchecked_into();
+            let mut new_query = (*query).clone();
+            new_query.user = Uuid::parse_str(&input.value()).ok();
+            query.set(new_query);
+        })
+    };
+
+    let onchange_group = {
+        let query = query.clone();
+        Callback::from(move |e: Event| {
+            let input: HtmlSelectElement = e.target_unchecked_into();
+            let mut new_query = (*query).clone();
+            new_query.group_by = Some(input.value());
+            query.set(new_query);
+        })
+    };
+
+    //Totals per ticket link to the ticket, per contact narrow the report down to the contact
+    let grouping = query
+        .group_by
+        .as_deref()
+        .and_then(|value| value.parse::<TimeGrouping>().ok())
+        .unwrap_or_default();
+    let total_label = |key: &str, label: &str| -> Html {
+        let label = if label.is_empty() {
+            language.get("None")
+        } else {
+            label.to_string()
+        };
+        match grouping {
+            TimeGrouping::Ticket => match key.parse::<i32>() {
+                Ok(ticket_id) => html! {
+                    <Link<AppRoute> to={AppRoute::Ticket { ticket_id }}>{ label }</Link<AppRoute>>
+                },
+                Err(_) => html! { label },
+            },
+            TimeGrouping::Contact => match Uuid::parse_str(key) {
+                Ok(contact) => {
+                    let query = query.clone();
+                    let onclick = Callback::from(move |_: MouseEvent| {
+                        let mut new_query = (*query).clone();
+                        new_query.contact = Some(contact);
+                        query.set(new_query);
+                    });
+                    html! { <a href="javascript:void(0)" {onclick}>{ label }</a> }
+                }
+                Err(_) => html! { label },
+            },
+            _ => html! { label },
+        }
+    };
+
+    let onclick_clear_contact = {
+        let query = query.clone();
+        Callback::from(move |_: MouseEvent| {
+            let mut new_query = (*query).clone();
+            new_query.contact = None;
+            query.set(new_query);
+        })
+    };
+
+    let style = style!(
+        r#"
+        .timesheet-filters label {
+            margin-right: 8px;
+        }
+        .timesheet table {
+            margin-top: 12px;
+            border-collapse: collapse;
+        }
+        .timesheet td, .timesheet th {
+            padding: 4px 8px;
+            border-bottom: 1px solid ${border};
+            text-align: left;
+        }
+        .minutes {
+            text-align: right;
+        }
+        "#,
+        border = theme.border.clone(),
+    )
+    .expect("Failed to parse style");
+
+    html! {
+        <div class={style} style="margin: 2px 16px;">
+            <div class="timesheet">
+                <h1>{ language.get("Timesheet") }</h1>
+                <div class="timesheet-filters">
+                    <label>
+                        { format!("{}: ", language.get("From")) }
+                        <input type="date" value={query.from.clone().unwrap_or_default()} onchange={onchange_from} />
+                    </label>
+                    <label>
+                        { format!("{}: ", language.get("To")) }
+                        <input type="date" value={query.to.clone().unwrap_or_default()} onchange={onchange_to} />
+                    </label>
+                    <label>
+                        { format!("{}: ", language.get("User")) }
+                        <select onchange={onchange_user}>
+                            <option value="" selected={query.user.is_none()}>{ "(All)" }</option>
+                            { for users.iter().map(|user| html! {
+                                <option value={user.user_id.to_string()} selected={query.user == Some(user.user_id)}>
+                                    { &user.display_name }
+                                </option>
+                            })}
+                        </select>
+                    </label>
+                    <label>
+                        { format!("{}: ", language.get("Group by")) }
+                        <select onchange={onchange_group}>
+                            { for TimeGrouping::iter().map(|option| html! {
+                                <option value={option.to_string()} selected={option == grouping}>
+                                    { language.get(option.label()) }
+                                </option>
+                            })}
+                        </select>
+                    </label>
+                    if query.contact.is_some() {
+                        <button class="btn" onclick={onclick_clear_contact}>{ language.get("All contacts") }</button>
+                    }
+                    <a class="btn" href={time_report_csv_url(&query)} download="time-report.csv">
+                        { language.get("Export CSV") }
+                    </a>
+                </div>
+                <div class="error">{ error.to_string() }</div>
+                <table>
+                    <thead>
+                        <tr>
+                            <th>{ language.get(grouping.label()) }</th>
+                            <th class="minutes">{ language.get("Time spent") }</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        { for report.totals.iter().map(|total| html! {
+                            <tr>
+                                <td>{ total_label(&total.key, &total.label) }</td>
+                                <td class="minutes">{ format_minutes(total.minutes) }</td>
+                            </tr>
+                        })}
+                        <tr>
+                            <th>{ language.get("Total") }</th>
+                            <th class="minutes">{ format_minutes(report.total_minutes) }</th>
+                        </tr>
+                    </tbody>
+                </table>
+                <h3>{ language.get("Entries") }</h3>
+                <table>
+                    <thead>
+                        <tr>
+                            <th>{ language.get("Date") }</th>
+                            <th>{ language.get("Ticket") }</th>
+                            <th>{ language.get("User") }</th>
+                            <th>{ language.get("Contact") }</th>
+                            <th class="minutes">{ language.get("Time spent") }</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        { for report.entries.iter().map(|entry| html! {
+                            <tr>
+                                <td>{ time_ctx.convert_to_local(&entry.created_at).format("%Y-%m-%d %H:%M").to_string() }</td>
+                                <td>
+                                    <Link<AppRoute> to={AppRoute::Ticket { ticket_id: entry.ticket_id }}>
+                                        { format!("#{} {}", entry.ticket_id, entry.title) }
+                                    </Link<AppRoute>>
+                                </td>
+                                <td>{ entry.user_name.clone().unwrap_or_default() }</td>
+                                <td>{ entry.contact_name.clone().unwrap_or_default() }</td>
+                                <td class="minutes">{ format_minutes(entry.minutes as i64) }</td>
+                            </tr>
+                        })}
+                    </tbody>
+                </table>
+            </div>
+        </div>
+    }
+}
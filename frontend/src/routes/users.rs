@@ -1,3 +1,6 @@
+use std::str::FromStr;
+
+use shared::models::users::Role;
 use yew::{prelude::*, suspense::use_future};
 use yew_router::prelude::{Link, Redirect};
 
@@ -19,13 +22,16 @@ pub fn users() -> Html {
         html! {
             <div>
                 <h1>{ "Users" }</h1>
+                if user_ctx.role().is_admin() {
                 <Link<AppRoute> to={AppRoute::Register} classes="nav-link">
                     { "Create new user" }
                 </Link<AppRoute>>
+                }
                 <table>
                     <thead>
                         <tr>
                             <th>{ "Username" }</th>
+                            <th>{ "Role" }</th>
                         </tr>
                     </thead>
                     <tbody>
@@ -36,6 +42,7 @@ pub fn users() -> Html {
                                     <Link<SettingsRoute> to={SettingsRoute::Account { user_id: user.user_id.clone() }} classes="nav-link">
                                         <td>{ &user.username }</td>
                                     </Link<SettingsRoute>>
+                                    <td>{ Role::from_str(&user.access).map(|role| role.label()).unwrap_or_default() }</td>
                                     </tr>
                                 }
                             })
@@ -0,0 +1,324 @@
+use stylist::style;
+use stylist::yew::styled_component;
+use uuid::Uuid;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::components::delete::{DeleteItem, ItemTypes};
+use crate::components::time_format::TimeFormat;
+use crate::contexts::theme::use_theme;
+use crate::hooks::{use_language_context, use_user_context};
+use crate::services::comments::{create_comment, get_comments, update_comment};
+use crate::types::{CommentCreateInfo, CommentInfo};
+use crate::utils::markdown_to_html;
+
+#[derive(Properties, Clone, PartialEq)]
+pub struct PanelProps {
+    pub document_id: Uuid,
+}
+
+/// Discussion shown below a wiki document
+#[styled_component(CommentPanel)]
+pub fn comment_panel(props: &PanelProps) -> Html {
+    let theme = use_theme();
+    let language = use_language_context();
+    let role = use_user_context().role();
+    let comments = use_state(Vec::<CommentInfo>::new);
+    //bumped whenever a comment is added, edited or removed to reload the list
+    let revision = use_state(|| 0u32);
+
+    {
+        let comments = comments.clone();
+        use_effect_with((props.document_id, *revision), move |(document_id, _)| {
+            let document_id = *document_id;
+            wasm_bindgen_futures::spawn_local(async move {
+                match get_comments(document_id).await {
+                    Ok(result) => comments.set(result),
+                    Err(e) => log::error!("Error loading comments: {}", e),
+                }
+            });
+            || ()
+        })
+    }
+
+    let on_changed = {
+        let revision = revision.clone();
+        Callback::from(move |_| revision.set(*revision + 1))
+    };
+
+    let style = style!(
+        r#"
+        margin-top: 2rem;
+        .comment {
+            margin: 0.5rem 0;
+            border-radius: 0.5rem;
+            border: 1px solid ${border};
+        }
+        .comment-header {
+            border-bottom: 1px solid ${border};
+            display: flex;
+            align-items: center;
+            justify-content: space-between;
+            background: ${headerbg};
+            padding: 0.2rem 0.75rem;
+            border-top-left-radius: inherit;
+            border-top-right-radius: inherit;
+        }
+        .comment-text {
+            padding: 0rem 0.75rem;
+        }
+        .comment-form {
+            padding: 0.25rem 0.5rem;
+        }
+        .comment-replies {
+            margin-left: 1.5rem;
+        }
+        .timeformat {
+            font-size: 0.8rem;
+            color: #838383;
+        }
+        textarea {
+            width: 100%;
+        }
+        "#,
+        headerbg = theme.secondary_background.clone(),
+        border = theme.border.clone(),
+    )
+    .expect("Failed to parse style");
+
+    html! {
+        <div class={style}>
+            <h3>{ language.get("Comments") }</h3>
+            { for comments.iter().filter(|comment| comment.parent_id.is_none()).map(|comment| html! {
+                <CommentThread
+                    comment={comment.clone()}
+                    comments={(*comments).clone()}
+                    on_changed={on_changed.clone()} />
+            })}
+            if role.can_edit() {
+                <CommentInput document_id={props.document_id} parent_id={None::<Uuid>}
+                    callback={on_changed.clone()} />
+            }
+        </div>
+    }
+}
+
+#[derive(Properties, Clone, PartialEq)]
+struct ThreadProps {
+    comment: CommentInfo,
+    //every comment on the document, used to find replies
+    comments: Vec<CommentInfo>,
+    on_changed: Callback<()>,
+}
+
+#[function_component(CommentThread)]
+fn comment_thread(props: &ThreadProps) -> Html {
+    let comment = &props.comment;
+    let language = use_language_context();
+    let user_ctx = use_user_context();
+    let replying = use_state(|| false);
+    let edit_mode = use_state(|| false);
+    let edit_text = use_state(|| comment.text.clone());
+    let error = use_state(String::new);
+
+    let is_author = comment.author.as_ref().map(|author| author.user_id) == Some(user_ctx.user_id);
+
+    let onclick_reply = {
+        let replying = replying.clone();
+        Callback::from(move |_| replying.set(!*replying))
+    };
+    let onclick_edit = {
+        let edit_mode = edit_mode.clone();
+        let edit_text = edit_text.clone();
+        let text = comment.text.clone();
+        Callback::from(move |_| {
+            edit_text.set(text.clone());
+            edit_mode.set(true);
+        })
+    };
+    let onclick_cancel = {
+        let edit_mode = edit_mode.clone();
+        Callback::from(move |e: MouseEvent| {
+            e.prevent_default();
+            edit_mode.set(false);
+        })
+    };
+    let oninput_edit = {
+        let edit_text = edit_text.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            edit_text.set(input.value());
+        })
+    };
+    let onsubmit_edit = {
+        let edit_text = edit_text.clone();
+        let edit_mode = edit_mode.clone();
+        let error = error.clone();
+        let comment = comment.clone();
+        let on_changed = props.on_changed.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            let request = CommentCreateInfo {
+                text: (*edit_text).clone(),
+                parent_id: comment.parent_id,
+            };
+            let edit_mode = edit_mode.clone();
+            let error = error.clone();
+            let on_changed = on_changed.clone();
+            let (document_id, comment_id) = (comment.document_id, comment.comment_id);
+            wasm_bindgen_futures::spawn_local(async move {
+                match update_comment(document_id, comment_id, request).await {
+                    Ok(result) if result.success => {
+                        error.set(String::new());
+                        edit_mode.set(false);
+                        on_changed.emit(());
+                    }
+                    Ok(result) => error.set(result.message.unwrap_or_default()),
+                    Err(e) => error.set(e.to_string()),
+                }
+            });
+        })
+    };
+    let on_replied = {
+        let replying = replying.clone();
+        let on_changed = props.on_changed.clone();
+        Callback::from(move |_| {
+            replying.set(false);
+            on_changed.emit(());
+        })
+    };
+    let on_deleted = {
+        let on_changed = props.on_changed.clone();
+        Callback::from(move |_: String| on_changed.emit(()))
+    };
+
+    html! {
+        <div>
+            <div class="comment">
+                <div class="comment-header">
+                    <span>
+                        { comment.display_name() }
+                        {" "}
+                        <TimeFormat time={comment.created_at} />
+                    </span>
+                    <span>
+                        if user_ctx.role().can_edit() {
+                        <button class="btn" onclick={onclick_reply}>
+                            { language.get("Reply") }
+                        </button>
+                        }
+                        if is_author || user_ctx.role().can_edit() {
+                        <button class="btn" onclick={onclick_edit}>
+                            { language.get("Edit") }
+                        </button>
+                        }
+                        if is_author || user_ctx.role().is_admin() {
+                        <DeleteItem
+                            item_id={comment.comment_id.to_string()}
+                            item_type={ItemTypes::Comment { document_id: comment.document_id }}
+                            callback={on_deleted} />
+                        }
+                    </span>
+                </div>
+                if *edit_mode {
+                    <form class="comment-form" onsubmit={onsubmit_edit}>
+                        <textarea rows=3 value={(*edit_text).clone()} oninput={oninput_edit} />
+                        <div>
+                            <button class="btn" type="submit">{ language.get("Save") }</button>
+                            <button class="btn" onclick={onclick_cancel}>{ language.get("Cancel") }</button>
+                            <span class="error">{ error.to_string() }</span>
+                        </div>
+                    </form>
+                } else {
+                    <div class="comment-text">
+                        { markdown_to_html(&comment.text) }
+                    </div>
+                }
+            </div>
+            <div class="comment-replies">
+                if *replying {
+                    <CommentInput document_id={comment.document_id} parent_id={Some(comment.comment_id)}
+                        callback={on_replied} />
+                }
+                { for props.comments.iter()
+                    .filter(|reply| reply.parent_id == Some(comment.comment_id))
+                    .map(|reply| html! {
+                        <CommentThread
+                            comment={reply.clone()}
+                            comments={props.comments.clone()}
+                            on_changed={props.on_changed.clone()} />
+                    })
+                }
+            </div>
+        </div>
+    }
+}
+
+#[derive(Properties, Clone, PartialEq)]
+struct InputProps {
+    document_id: Uuid,
+    parent_id: Option<Uuid>,
+    callback: Callback<()>,
+}
+
+#[function_component(CommentInput)]
+fn comment_input(props: &InputProps) -> Html {
+    let language = use_language_context();
+    let text = use_state(String::new);
+    let submitted = use_state(|| false);
+    let error = use_state(String::new);
+
+    let oninput = {
+        let text = text.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            text.set(input.value());
+        })
+    };
+    let onsubmit = {
+        let text = text.clone();
+        let submitted = submitted.clone();
+        let error = error.clone();
+        let props = props.clone();
+        Callback::from(move |e: SubmitEvent| {
+            e.prevent_default();
+            if text.trim().is_empty() {
+                return;
+            }
+            submitted.set(true);
+            let request = CommentCreateInfo {
+                text: (*text).clone(),
+                parent_id: props.parent_id,
+            };
+            let text = text.clone();
+            let submitted = submitted.clone();
+            let error = error.clone();
+            let props = props.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                match create_comment(props.document_id, request).await {
+                    Ok(result) if result.success => {
+                        text.set(String::new());
+                        error.set(String::new());
+                        props.callback.emit(());
+                    }
+                    Ok(result) => error.set(result.message.unwrap_or_default()),
+                    Err(e) => error.set(e.to_string()),
+                }
+                submitted.set(false);
+            });
+        })
+    };
+
+    html! {
+        <form class="comment-form" {onsubmit}>
+            <textarea placeholder={language.get("Comment (Markdown)")} rows=3
+                value={(*text).clone()} {oninput} />
+            <div>
+                <button class="btn" type="submit" disabled={*submitted}>
+                    { if props.parent_id.is_some() { language.get("Reply") } else { language.get("Post Comment") } }
+                </button>
+                <span class="error">{ error.to_string() }</span>
+            </div>
+        </form>
+    }
+}
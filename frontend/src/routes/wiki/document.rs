@@ -1,3 +1,4 @@
+use shared::models::live::{LiveEvent, PresenceTarget};
 use shared::models::MAX_TITLE_LENGTH;
 use stylist::style;
 use stylist::yew::styled_component;
@@ -7,8 +8,12 @@ use web_sys::HtmlInputElement;
 use yew::prelude::*;
 use yew_router::prelude::*;
 
+use crate::components::attachments::{AttachmentList, AttachmentOwner};
 use crate::components::delete::{DeleteItem, ItemTypes};
+use crate::components::editing_indicator::EditingIndicator;
+use crate::contexts::live::{use_live_events, use_presence};
 use crate::hooks::{use_language_context, use_user_context};
+use crate::routes::wiki::comments::CommentPanel;
 use crate::routes::wiki::revision_list::Revisions;
 use crate::routes::AppRoute;
 use crate::services::documents::{create_document, get_document, update_document};
@@ -24,6 +29,7 @@ pub struct Props {
 #[styled_component(WikiDocument)]
 pub fn wiki_document(props: &Props) -> Html {
     let user_ctx = use_user_context();
+    let role = user_ctx.role();
     let language = use_language_context();
     let navigator = use_navigator().unwrap();
     let update_info = use_state(DocumentUpdateInfo::default);
@@ -33,6 +39,39 @@ pub fn wiki_document(props: &Props) -> Html {
     let is_new = use_state(|| false);
     let edit_mode = use_state(|| false);
     let view_revisions = use_state(|| false);
+    //Bumped when someone else saves the document, reloads it unless we are editing
+    let version = use_state(|| 0_u32);
+
+    {
+        let version = version.clone();
+        let edit_mode = edit_mode.clone();
+        let document_id = props.document_id;
+        let user_id = user_ctx.user_id;
+        let needs_update = props.needs_update.clone();
+        use_live_events(Callback::from(move |event| {
+            if let LiveEvent::DocumentChanged {
+                document_id: changed,
+                user_id: by,
+            } = event
+            {
+                if by == Some(user_id) {
+                    return;
+                }
+                //Titles and nesting may have changed
+                needs_update.emit(true);
+                if Some(changed) == document_id && !*edit_mode {
+                    version.set(*version + 1);
+                }
+            }
+        }));
+    }
+
+    use_presence(
+        props
+            .document_id
+            .filter(|_| *edit_mode && !*is_new)
+            .map(PresenceTarget::Document),
+    );
 
     //Reruns on edit as a workaround for when editing is cancelled, since the html displays update_info
     //It might be better to have a different state that holds the original values
@@ -40,7 +79,7 @@ pub fn wiki_document(props: &Props) -> Html {
         let update_info = update_info.clone();
         let document_id = props.document_id.clone();
         let is_new = is_new.clone();
-        use_effect_with((props.document_id.clone(), edit_mode.clone()), move |_| {
+        use_effect_with((props.document_id.clone(), edit_mode.clone(), *version), move |_| {
             wasm_bindgen_futures::spawn_local(async move {
                 if let Some(id) = document_id {
                     if *is_new {
@@ -282,6 +321,7 @@ pub fn wiki_document(props: &Props) -> Html {
                     html! {
                         <div class="wiki-document">
                             <div class="wiki-buttons">
+                                if role.can_edit() {
                                 <button class="btn" onclick={onclick_create}
                                     title={language.get("Create a new nested document")}>
                                     {language.get("Create")}
@@ -289,12 +329,16 @@ pub fn wiki_document(props: &Props) -> Html {
                                 <button class="btn" onclick={on_click_edit}>
                                     {language.get("Edit")}
                                 </button>
+                                }
+                                if role.is_admin() {
                                 <DeleteItem item_id={document_id.to_string()} item_type={ItemTypes::Document}
                                     callback={callback_deleted} />
+                                }
                                 // <button class="btn" onclick={onclick_revisions}>
                                 //     {language.get("Revisions")}
                                 // </button>
                             </div>
+                            <EditingIndicator target={PresenceTarget::Document(document_id)} />
                             <h1 class="wiki_title">
                                 {update_info.title.clone()}
                             </h1>
@@ -308,7 +352,8 @@ pub fn wiki_document(props: &Props) -> Html {
                             } else {
                                 html! {}
                             }}
-
+                            <AttachmentList owner={AttachmentOwner::Document(document_id)} />
+                            <CommentPanel document_id={document_id} />
                         </div>
                     }
                 }
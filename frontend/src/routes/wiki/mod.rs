@@ -1,3 +1,4 @@
+mod comments;
 pub mod document;
 mod tree;
 mod revision_list;
@@ -0,0 +1,50 @@
+use uuid::Uuid;
+
+use super::requests::api_url;
+use super::{request_delete, request_get, request_upload};
+use crate::types::*;
+use shared::models::response::Response;
+
+pub async fn get_ticket_attachments(ticket_id: i32) -> Result<Vec<AttachmentInfo>, Error> {
+    request_get::<Vec<AttachmentInfo>>(format!("/tickets/{}/attachments", ticket_id)).await
+}
+
+pub async fn get_document_attachments(document_id: Uuid) -> Result<Vec<AttachmentInfo>, Error> {
+    request_get::<Vec<AttachmentInfo>>(format!("/documents/{}/attachments", document_id)).await
+}
+
+pub async fn upload_ticket_attachment(
+    ticket_id: i32,
+    file: web_sys::File,
+) -> Result<Response<AttachmentInfo>, Error> {
+    request_upload::<Response<AttachmentInfo>>(format!("/tickets/{}/attachments", ticket_id), file)
+        .await
+}
+
+pub async fn upload_note_attachment(
+    note_id: Uuid,
+    file: web_sys::File,
+) -> Result<Response<AttachmentInfo>, Error> {
+    request_upload::<Response<AttachmentInfo>>(format!("/notes/{}/attachments", note_id), file)
+        .await
+}
+
+pub async fn upload_document_attachment(
+    document_id: Uuid,
+    file: web_sys::File,
+) -> Result<Response<AttachmentInfo>, Error> {
+    request_upload::<Response<AttachmentInfo>>(
+        format!("/documents/{}/attachments", document_id),
+        file,
+    )
+    .await
+}
+
+pub async fn delete_attachment(attachment_id: Uuid) -> Result<SuccessResponse, Error> {
+    request_delete::<SuccessResponse>(format!("/attachments/{}", attachment_id)).await
+}
+
+/// Download link, the session cookie is sent along like for any other request
+pub fn attachment_url(attachment_id: Uuid) -> String {
+    api_url(&format!("/attachments/{}", attachment_id))
+}
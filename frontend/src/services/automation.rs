@@ -0,0 +1,40 @@
+use uuid::Uuid;
+
+use super::{request_delete, request_get, request_post, request_put};
+use crate::types::*;
+use shared::models::response::Response;
+
+/// Automation rules in the order they run
+pub async fn get_automation_rules() -> Result<Vec<AutomationRuleInfo>, Error> {
+    request_get::<Vec<AutomationRuleInfo>>("/automation_rules".to_string()).await
+}
+
+pub async fn create_automation_rule(
+    info: &AutomationRuleCreateInfo,
+) -> Result<Response<AutomationRuleInfo>, Error> {
+    request_post::<&AutomationRuleCreateInfo, Response<AutomationRuleInfo>>(
+        "/automation_rules".to_string(),
+        info,
+    )
+    .await
+}
+
+pub async fn update_automation_rule(
+    rule_id: Uuid,
+    info: &AutomationRuleCreateInfo,
+) -> Result<Response<AutomationRuleInfo>, Error> {
+    request_put::<&AutomationRuleCreateInfo, Response<AutomationRuleInfo>>(
+        format!("/automation_rules/{}", rule_id),
+        info,
+    )
+    .await
+}
+
+pub async fn delete_automation_rule(rule_id: Uuid) -> Result<SuccessResponse, Error> {
+    request_delete::<SuccessResponse>(format!("/automation_rules/{}", rule_id)).await
+}
+
+/// Execution log of a rule, latest first
+pub async fn get_automation_runs(rule_id: Uuid) -> Result<Vec<AutomationRunInfo>, Error> {
+    request_get::<Vec<AutomationRunInfo>>(format!("/automation_rules/{}/runs", rule_id)).await
+}
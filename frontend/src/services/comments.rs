@@ -0,0 +1,40 @@
+use uuid::Uuid;
+
+use super::{request_delete, request_get, request_post, request_put};
+use crate::types::*;
+use shared::models::response::Response;
+
+pub async fn get_comments(document_id: Uuid) -> Result<Vec<CommentInfo>, Error> {
+    request_get::<Vec<CommentInfo>>(format!("/documents/{}/comments", document_id)).await
+}
+
+pub async fn create_comment(
+    document_id: Uuid,
+    comment: CommentCreateInfo,
+) -> Result<Response<CommentInfo>, Error> {
+    request_post::<CommentCreateInfo, Response<CommentInfo>>(
+        format!("/documents/{}/comments", document_id),
+        comment,
+    )
+    .await
+}
+
+pub async fn update_comment(
+    document_id: Uuid,
+    comment_id: Uuid,
+    comment: CommentCreateInfo,
+) -> Result<Response<CommentInfo>, Error> {
+    request_put::<CommentCreateInfo, Response<CommentInfo>>(
+        format!("/documents/{}/comments/{}", document_id, comment_id),
+        comment,
+    )
+    .await
+}
+
+pub async fn delete_comment(document_id: Uuid, comment_id: Uuid) -> Result<SuccessResponse, Error> {
+    request_delete::<SuccessResponse>(format!(
+        "/documents/{}/comments/{}",
+        document_id, comment_id
+    ))
+    .await
+}
@@ -0,0 +1,34 @@
+use uuid::Uuid;
+
+use super::{request_delete, request_get, request_post, request_put};
+use crate::types::*;
+use shared::models::response::Response;
+
+pub async fn get_custom_fields() -> Result<Vec<CustomFieldInfo>, Error> {
+    request_get::<Vec<CustomFieldInfo>>("/custom_fields".to_string()).await
+}
+
+pub async fn create_custom_field(
+    info: &CustomFieldCreateInfo,
+) -> Result<Response<CustomFieldInfo>, Error> {
+    request_post::<&CustomFieldCreateInfo, Response<CustomFieldInfo>>(
+        "/custom_fields".to_string(),
+        info,
+    )
+    .await
+}
+
+pub async fn update_custom_field(
+    field_id: Uuid,
+    info: &CustomFieldCreateInfo,
+) -> Result<Response<CustomFieldInfo>, Error> {
+    request_put::<&CustomFieldCreateInfo, Response<CustomFieldInfo>>(
+        format!("/custom_fields/{}", field_id),
+        info,
+    )
+    .await
+}
+
+pub async fn delete_custom_field(field_id: Uuid) -> Result<SuccessResponse, Error> {
+    request_delete::<SuccessResponse>(format!("/custom_fields/{}", field_id)).await
+}
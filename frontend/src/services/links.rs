@@ -0,0 +1,32 @@
+use uuid::Uuid;
+
+use super::{request_delete, request_get, request_post};
+use crate::types::*;
+use shared::models::response::Response;
+
+pub async fn get_links(ticket_id: i32) -> Result<Vec<TicketLinkInfo>, Error> {
+    request_get::<Vec<TicketLinkInfo>>(format!("/tickets/{}/links", ticket_id)).await
+}
+
+/// Link changes respond with the links of the ticket after the change
+pub async fn create_link(
+    ticket_id: i32,
+    info: &TicketLinkCreateInfo,
+) -> Result<Response<Vec<TicketLinkInfo>>, Error> {
+    request_post::<&TicketLinkCreateInfo, Response<Vec<TicketLinkInfo>>>(
+        format!("/tickets/{}/links", ticket_id),
+        info,
+    )
+    .await
+}
+
+pub async fn delete_link(
+    ticket_id: i32,
+    link_id: Uuid,
+) -> Result<Response<Vec<TicketLinkInfo>>, Error> {
+    request_delete::<Response<Vec<TicketLinkInfo>>>(format!(
+        "/tickets/{}/links/{}",
+        ticket_id, link_id
+    ))
+    .await
+}
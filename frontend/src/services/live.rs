@@ -0,0 +1,12 @@
+use shared::models::live::{PresencePayload, PresenceTarget};
+
+use super::request_post;
+use crate::types::*;
+
+pub async fn send_presence(
+    target: PresenceTarget,
+    editing: bool,
+) -> Result<SuccessResponse, Error> {
+    let payload = PresencePayload { target, editing };
+    request_post::<PresencePayload, SuccessResponse>("/live/presence".to_string(), payload).await
+}
@@ -1,8 +1,27 @@
+pub mod attachments;
 pub mod auth;
+pub mod automation;
+pub mod comments;
+pub mod custom_fields;
 pub mod documents;
+pub mod links;
+pub mod live;
 pub mod notes;
+pub mod notifications;
+pub mod portal;
+pub mod projects;
+pub mod recurring;
+pub mod reports;
 pub mod requests;
+pub mod search;
+pub mod sla;
+pub mod tags;
+pub mod tasks;
 pub mod tickets;
 pub mod users;
+pub mod views;
+pub mod watchers;
+pub mod webhooks;
+pub mod workflows;
 
-pub use requests::{request_delete, request_get, request_post, request_put};
+pub use requests::{request_delete, request_get, request_post, request_put, request_upload};
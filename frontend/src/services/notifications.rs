@@ -0,0 +1,29 @@
+use shared::models::notifications::{NotificationQuery, UnreadCount};
+use uuid::Uuid;
+
+use super::{request_get, request_put};
+use crate::types::*;
+
+pub async fn get_notifications(query: &NotificationQuery) -> Result<Vec<NotificationInfo>, Error> {
+    let mut params = Vec::new();
+    if let Some(unread) = query.unread {
+        params.push(format!("unread={}", unread));
+    }
+    if let Some(limit) = query.limit {
+        params.push(format!("limit={}", limit));
+    }
+
+    request_get::<Vec<NotificationInfo>>(format!("/notifications?{}", params.join("&"))).await
+}
+
+pub async fn get_unread_count() -> Result<UnreadCount, Error> {
+    request_get::<UnreadCount>("/notifications/count".to_string()).await
+}
+
+pub async fn mark_read(notification_id: Uuid) -> Result<SuccessResponse, Error> {
+    request_put::<(), SuccessResponse>(format!("/notifications/{}/read", notification_id), ()).await
+}
+
+pub async fn mark_all_read() -> Result<SuccessResponse, Error> {
+    request_put::<(), SuccessResponse>("/notifications/read".to_string(), ()).await
+}
@@ -0,0 +1,62 @@
+use super::{request_get, request_post, request_put};
+use crate::types::*;
+use shared::models::response::Response;
+
+/// The signed in requester, fails with `Error::Unauthorized` otherwise
+pub async fn portal_me() -> Result<PortalContactInfo, Error> {
+    request_get::<PortalContactInfo>("/portal/me".to_string()).await
+}
+
+pub async fn portal_login(info: &PortalLoginInfo) -> Result<SuccessResponse, Error> {
+    request_post::<&PortalLoginInfo, SuccessResponse>("/portal/login".to_string(), info).await
+}
+
+/// E-mails a sign-in link, succeeds whether or not the address is known
+pub async fn send_magic_link(info: &MagicLinkInfo) -> Result<SuccessResponse, Error> {
+    request_post::<&MagicLinkInfo, SuccessResponse>("/portal/magic-link".to_string(), info).await
+}
+
+pub async fn verify_magic_link(info: &MagicLinkVerifyInfo) -> Result<SuccessResponse, Error> {
+    request_post::<&MagicLinkVerifyInfo, SuccessResponse>(
+        "/portal/magic-link/verify".to_string(),
+        info,
+    )
+    .await
+}
+
+pub async fn portal_logout() -> Result<SuccessResponse, Error> {
+    request_post::<(), SuccessResponse>("/portal/logout".to_string(), ()).await
+}
+
+pub async fn set_portal_password(info: &PortalPasswordInfo) -> Result<SuccessResponse, Error> {
+    request_put::<&PortalPasswordInfo, SuccessResponse>("/portal/password".to_string(), info).await
+}
+
+pub async fn portal_tickets() -> Result<Vec<PortalTicketInfo>, Error> {
+    request_get::<Vec<PortalTicketInfo>>("/portal/tickets".to_string()).await
+}
+
+pub async fn create_portal_ticket(
+    info: &PortalTicketCreateInfo,
+) -> Result<Response<PortalTicketInfo>, Error> {
+    request_post::<&PortalTicketCreateInfo, Response<PortalTicketInfo>>(
+        "/portal/tickets".to_string(),
+        info,
+    )
+    .await
+}
+
+pub async fn portal_ticket(ticket_id: i32) -> Result<PortalTicketDetailInfo, Error> {
+    request_get::<PortalTicketDetailInfo>(format!("/portal/tickets/{}", ticket_id)).await
+}
+
+pub async fn portal_reply(
+    ticket_id: i32,
+    info: &PortalReplyInfo,
+) -> Result<Response<PortalNoteInfo>, Error> {
+    request_post::<&PortalReplyInfo, Response<PortalNoteInfo>>(
+        format!("/portal/tickets/{}/notes", ticket_id),
+        info,
+    )
+    .await
+}
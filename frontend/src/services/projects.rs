@@ -0,0 +1,28 @@
+use uuid::Uuid;
+
+use super::{request_delete, request_get, request_post, request_put};
+use crate::types::*;
+use shared::models::response::Response;
+
+pub async fn get_projects() -> Result<Vec<ProjectInfo>, Error> {
+    request_get::<Vec<ProjectInfo>>("/projects".to_string()).await
+}
+
+pub async fn create_project(info: &ProjectCreateInfo) -> Result<Response<ProjectInfo>, Error> {
+    request_post::<&ProjectCreateInfo, Response<ProjectInfo>>("/projects".to_string(), info).await
+}
+
+pub async fn update_project(
+    project_id: Uuid,
+    info: &ProjectCreateInfo,
+) -> Result<Response<ProjectInfo>, Error> {
+    request_put::<&ProjectCreateInfo, Response<ProjectInfo>>(
+        format!("/projects/{}", project_id),
+        info,
+    )
+    .await
+}
+
+pub async fn delete_project(project_id: Uuid) -> Result<SuccessResponse, Error> {
+    request_delete::<SuccessResponse>(format!("/projects/{}", project_id)).await
+}
@@ -0,0 +1,43 @@
+use uuid::Uuid;
+
+use super::{request_delete, request_get, request_post, request_put};
+use crate::types::*;
+use shared::models::response::Response;
+
+/// Recurring tickets by name
+pub async fn get_recurring_tickets() -> Result<Vec<RecurringTicketInfo>, Error> {
+    request_get::<Vec<RecurringTicketInfo>>("/recurring_tickets".to_string()).await
+}
+
+pub async fn create_recurring_ticket(
+    info: &RecurringTicketCreateInfo,
+) -> Result<Response<RecurringTicketInfo>, Error> {
+    request_post::<&RecurringTicketCreateInfo, Response<RecurringTicketInfo>>(
+        "/recurring_tickets".to_string(),
+        info,
+    )
+    .await
+}
+
+pub async fn update_recurring_ticket(
+    recurring_id: Uuid,
+    info: &RecurringTicketCreateInfo,
+) -> Result<Response<RecurringTicketInfo>, Error> {
+    request_put::<&RecurringTicketCreateInfo, Response<RecurringTicketInfo>>(
+        format!("/recurring_tickets/{}", recurring_id),
+        info,
+    )
+    .await
+}
+
+pub async fn delete_recurring_ticket(recurring_id: Uuid) -> Result<SuccessResponse, Error> {
+    request_delete::<SuccessResponse>(format!("/recurring_tickets/{}", recurring_id)).await
+}
+
+/// Tickets created so far, latest first
+pub async fn get_recurring_ticket_runs(
+    recurring_id: Uuid,
+) -> Result<Vec<RecurringTicketRunInfo>, Error> {
+    request_get::<Vec<RecurringTicketRunInfo>>(format!("/recurring_tickets/{}/runs", recurring_id))
+        .await
+}
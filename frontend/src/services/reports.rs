@@ -0,0 +1,13 @@
+use shared::models::reports::TimeReportQuery;
+
+use super::{request_get, requests::api_url};
+use crate::types::*;
+
+pub async fn get_time_report(query: &TimeReportQuery) -> Result<TimeReportInfo, Error> {
+    request_get::<TimeReportInfo>(format!("/reports/time?{}", query.to_query_string())).await
+}
+
+/// Link to download the same report as CSV, the browser sends the session cookie
+pub fn time_report_csv_url(query: &TimeReportQuery) -> String {
+    api_url(&format!("/reports/time.csv?{}", query.to_query_string()))
+}
@@ -1,11 +1,8 @@
 use crate::types::Error;
 use serde::{de::DeserializeOwned, Serialize};
 
-pub async fn request<B, T>(method: reqwest::Method, url: String, body: B) -> Result<T, Error>
-where
-    T: DeserializeOwned + 'static + std::fmt::Debug,
-    B: Serialize + std::fmt::Debug,
-{
+/// Full URL of an API path, built from the server settings injected into the page
+pub fn api_url(path: &str) -> String {
     let hostname: String = js_sys::Reflect::get(&js_sys::global(), &"SERVER_FQDN".into())
         .unwrap()
         .as_string()
@@ -21,13 +18,21 @@ where
         .parse()
         .unwrap();
 
-    let url = format!(
+    format!(
         "{}://{}:{}/api{}",
         if disable_https { "http" } else { "https" },
         hostname,
         port,
-        url
-    );
+        path
+    )
+}
+
+pub async fn request<B, T>(method: reqwest::Method, url: String, body: B) -> Result<T, Error>
+where
+    T: DeserializeOwned + 'static + std::fmt::Debug,
+    B: Serialize + std::fmt::Debug,
+{
+    let url = api_url(&url);
 
     let allow_body = method == reqwest::Method::POST || method == reqwest::Method::PUT;
     let client: reqwest::Client = reqwest::ClientBuilder::new()
@@ -47,6 +52,42 @@ where
     log::info!("Sending request");
     let response = builder.send().await;
     log::info!("Got response");
+    handle_response(response).await
+}
+
+/// Sends a file as a multipart form, in a `file` field
+pub async fn request_upload<T>(url: String, file: web_sys::File) -> Result<T, Error>
+where
+    T: DeserializeOwned + 'static + std::fmt::Debug,
+{
+    let url = api_url(&url);
+
+    let buffer = wasm_bindgen_futures::JsFuture::from(file.array_buffer())
+        .await
+        .map_err(|_| Error::RequestError)?;
+    let contents = js_sys::Uint8Array::new(&buffer).to_vec();
+    let part = reqwest::multipart::Part::bytes(contents).file_name(file.name());
+    let form = reqwest::multipart::Form::new().part("file", part);
+
+    let client: reqwest::Client = reqwest::ClientBuilder::new()
+        .build()
+        .expect("failed to build client");
+
+    log::info!("Sending upload");
+    let response = client
+        .post(&url)
+        .fetch_credentials_include()
+        .multipart(form)
+        .send()
+        .await;
+    log::info!("Got response");
+    handle_response(response).await
+}
+
+async fn handle_response<T>(response: Result<reqwest::Response, reqwest::Error>) -> Result<T, Error>
+where
+    T: DeserializeOwned + 'static + std::fmt::Debug,
+{
     if let Ok(data) = response {
         if data.status().is_success() {
             let data: Result<T, _> = data.json::<T>().await;
@@ -63,6 +104,7 @@ where
                 401 => Err(Error::Unauthorized),
                 403 => Err(Error::Forbidden),
                 404 => Err(Error::NotFound),
+                413 => Err(Error::PayloadTooLarge),
                 422 => Err(Error::UnprocessableEntity),
                 500 => Err(Error::InternalServerError),
                 502 => Err(Error::BadGateway),
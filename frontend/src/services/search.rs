@@ -0,0 +1,13 @@
+use shared::models::search::{SearchQuery, SearchResult};
+
+use super::request_get;
+use crate::types::Error;
+
+pub async fn search(query: &SearchQuery) -> Result<Vec<SearchResult>, Error> {
+    let mut params = format!("q={}", js_sys::encode_uri_component(&query.q));
+    if let Some(limit) = query.limit {
+        params.push_str(&format!("&limit={}", limit));
+    }
+
+    request_get::<Vec<SearchResult>>(format!("/search?{}", params)).await
+}
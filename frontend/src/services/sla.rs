@@ -0,0 +1,32 @@
+use uuid::Uuid;
+
+use super::{request_delete, request_get, request_post, request_put};
+use crate::types::*;
+use shared::models::response::Response;
+
+/// SLA policies by name
+pub async fn get_sla_policies() -> Result<Vec<SlaPolicyInfo>, Error> {
+    request_get::<Vec<SlaPolicyInfo>>("/sla_policies".to_string()).await
+}
+
+pub async fn create_sla_policy(
+    info: &SlaPolicyCreateInfo,
+) -> Result<Response<SlaPolicyInfo>, Error> {
+    request_post::<&SlaPolicyCreateInfo, Response<SlaPolicyInfo>>("/sla_policies".to_string(), info)
+        .await
+}
+
+pub async fn update_sla_policy(
+    policy_id: Uuid,
+    info: &SlaPolicyCreateInfo,
+) -> Result<Response<SlaPolicyInfo>, Error> {
+    request_put::<&SlaPolicyCreateInfo, Response<SlaPolicyInfo>>(
+        format!("/sla_policies/{}", policy_id),
+        info,
+    )
+    .await
+}
+
+pub async fn delete_sla_policy(policy_id: Uuid) -> Result<SuccessResponse, Error> {
+    request_delete::<SuccessResponse>(format!("/sla_policies/{}", policy_id)).await
+}
@@ -0,0 +1,38 @@
+use uuid::Uuid;
+
+use super::{request_delete, request_get, request_post, request_put};
+use crate::types::*;
+use shared::models::response::Response;
+
+pub async fn get_tags() -> Result<Vec<TagInfo>, Error> {
+    request_get::<Vec<TagInfo>>("/tags".to_string()).await
+}
+
+pub async fn create_tag(info: &TagCreateInfo) -> Result<Response<TagInfo>, Error> {
+    request_post::<&TagCreateInfo, Response<TagInfo>>("/tags".to_string(), info).await
+}
+
+pub async fn update_tag(tag_id: Uuid, info: &TagCreateInfo) -> Result<Response<TagInfo>, Error> {
+    request_put::<&TagCreateInfo, Response<TagInfo>>(format!("/tags/{}", tag_id), info).await
+}
+
+pub async fn delete_tag(tag_id: Uuid) -> Result<SuccessResponse, Error> {
+    request_delete::<SuccessResponse>(format!("/tags/{}", tag_id)).await
+}
+
+/// Responds with the tags of the ticket after the change
+pub async fn add_ticket_tag(ticket_id: i32, tag_id: Uuid) -> Result<Response<Vec<TagInfo>>, Error> {
+    request_post::<TicketTagCreateInfo, Response<Vec<TagInfo>>>(
+        format!("/tickets/{}/tags", ticket_id),
+        TicketTagCreateInfo { tag_id },
+    )
+    .await
+}
+
+pub async fn remove_ticket_tag(
+    ticket_id: i32,
+    tag_id: Uuid,
+) -> Result<Response<Vec<TagInfo>>, Error> {
+    request_delete::<Response<Vec<TagInfo>>>(format!("/tickets/{}/tags/{}", ticket_id, tag_id))
+        .await
+}
@@ -0,0 +1,49 @@
+use uuid::Uuid;
+
+use super::{request_delete, request_get, request_post, request_put};
+use crate::types::*;
+use shared::models::response::Response;
+
+pub async fn get_tasks(ticket_id: i32) -> Result<Vec<TaskInfo>, Error> {
+    request_get::<Vec<TaskInfo>>(format!("/tickets/{}/tasks", ticket_id)).await
+}
+
+/// Task changes respond with the tasks of the ticket after the change
+pub async fn create_task(
+    ticket_id: i32,
+    info: &TaskCreateInfo,
+) -> Result<Response<Vec<TaskInfo>>, Error> {
+    request_post::<&TaskCreateInfo, Response<Vec<TaskInfo>>>(
+        format!("/tickets/{}/tasks", ticket_id),
+        info,
+    )
+    .await
+}
+
+pub async fn update_task(
+    ticket_id: i32,
+    task_id: Uuid,
+    info: &TaskUpdateInfo,
+) -> Result<Response<Vec<TaskInfo>>, Error> {
+    request_put::<&TaskUpdateInfo, Response<Vec<TaskInfo>>>(
+        format!("/tickets/{}/tasks/{}", ticket_id, task_id),
+        info,
+    )
+    .await
+}
+
+pub async fn reorder_tasks(
+    ticket_id: i32,
+    info: &TaskOrderInfo,
+) -> Result<Response<Vec<TaskInfo>>, Error> {
+    request_put::<&TaskOrderInfo, Response<Vec<TaskInfo>>>(
+        format!("/tickets/{}/tasks/order", ticket_id),
+        info,
+    )
+    .await
+}
+
+pub async fn delete_task(ticket_id: i32, task_id: Uuid) -> Result<Response<Vec<TaskInfo>>, Error> {
+    request_delete::<Response<Vec<TaskInfo>>>(format!("/tickets/{}/tasks/{}", ticket_id, task_id))
+        .await
+}
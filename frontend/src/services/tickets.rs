@@ -71,6 +71,54 @@ pub async fn get_filtered(
         }
         params.push_str(&format!("search={}", search));
     }
+    if let Some(watching) = query.watching {
+        if params.len() > 0 {
+            params.push_str("&");
+        }
+        params.push_str(&format!("watching={}", watching));
+    }
+    if let Some(project) = query.project {
+        if params.len() > 0 {
+            params.push_str("&");
+        }
+        params.push_str(&format!("project={}", project));
+    }
+    if let Some(tags) = &query.tags {
+        if params.len() > 0 {
+            params.push_str("&");
+        }
+        params.push_str(&format!("tags={}", js_sys::encode_uri_component(tags)));
+    }
+    if let Some(exclude_tags) = &query.exclude_tags {
+        if params.len() > 0 {
+            params.push_str("&");
+        }
+        params.push_str(&format!("exclude_tags={}", js_sys::encode_uri_component(exclude_tags)));
+    }
+    if let Some(field) = query.field {
+        if params.len() > 0 {
+            params.push_str("&");
+        }
+        params.push_str(&format!("field={}", field));
+    }
+    if let Some(field_value) = &query.field_value {
+        if params.len() > 0 {
+            params.push_str("&");
+        }
+        params.push_str(&format!("field_value={}", js_sys::encode_uri_component(field_value)));
+    }
+    if let Some(sla_status) = &query.sla_status {
+        if params.len() > 0 {
+            params.push_str("&");
+        }
+        params.push_str(&format!("sla_status={}", sla_status));
+    }
+    if let Some(breached) = query.breached {
+        if params.len() > 0 {
+            params.push_str("&");
+        }
+        params.push_str(&format!("breached={}", breached));
+    }
 
     let tickets: TicketListInfo = request_get::<TicketListInfo>(format!("/tickets?{}", params)).await?;
 
@@ -122,6 +170,16 @@ pub async fn update_status(ticket_id: i32, status: &TicketStatusInfo) -> Result<
     request_put::<&TicketStatusInfo, Response<TicketInfo>>(format!("/tickets/{}", ticket_id), status).await
 }
 
+/// Moves the history of the ticket into another one and closes it, responds with the other ticket
+pub async fn merge_ticket(ticket_id: i32, info: &TicketMergeInfo) -> Result<Response<TicketInfo>, Error> {
+    request_post::<&TicketMergeInfo, Response<TicketInfo>>(format!("/tickets/{}/merge", ticket_id), info).await
+}
+
+/// Applies the same change to all `ticket_ids`, responds with the changed ticket ids
+pub async fn bulk_update(info: &TicketBulkInfo) -> Result<Response<Vec<i32>>, Error> {
+    request_post::<&TicketBulkInfo, Response<Vec<i32>>>("/tickets/bulk".to_string(), info).await
+}
+
 pub async fn get_events(ticket_id: i32) -> Result<Vec<TicketEvent>, Error> {
     let mut events: Vec<TicketEvent> =
         request_get::<Vec<TicketEvent>>(format!("/tickets/{}/events", ticket_id)).await?;
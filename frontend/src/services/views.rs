@@ -0,0 +1,29 @@
+use uuid::Uuid;
+
+use super::{request_delete, request_get, request_post, request_put};
+use crate::types::*;
+use shared::models::response::Response;
+
+/// The user's own views followed by views shared by others
+pub async fn get_views() -> Result<Vec<SavedViewInfo>, Error> {
+    request_get::<Vec<SavedViewInfo>>("/views".to_string()).await
+}
+
+pub async fn create_view(info: &SavedViewCreateInfo) -> Result<Response<SavedViewInfo>, Error> {
+    request_post::<&SavedViewCreateInfo, Response<SavedViewInfo>>("/views".to_string(), info).await
+}
+
+pub async fn update_view(
+    view_id: Uuid,
+    info: &SavedViewCreateInfo,
+) -> Result<Response<SavedViewInfo>, Error> {
+    request_put::<&SavedViewCreateInfo, Response<SavedViewInfo>>(
+        format!("/views/{}", view_id),
+        info,
+    )
+    .await
+}
+
+pub async fn delete_view(view_id: Uuid) -> Result<SuccessResponse, Error> {
+    request_delete::<SuccessResponse>(format!("/views/{}", view_id)).await
+}
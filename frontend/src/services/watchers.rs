@@ -0,0 +1,25 @@
+use uuid::Uuid;
+
+use super::{request_delete, request_get, request_post};
+use crate::types::*;
+use shared::models::response::Response;
+
+pub async fn get_watchers(ticket_id: i32) -> Result<Vec<WatcherInfo>, Error> {
+    request_get::<Vec<WatcherInfo>>(format!("/tickets/{}/watchers", ticket_id)).await
+}
+
+/// Watch a ticket, `user_id` of None subscribes the current user
+pub async fn add_watcher(
+    ticket_id: i32,
+    user_id: Option<Uuid>,
+) -> Result<Response<Vec<WatcherInfo>>, Error> {
+    request_post::<WatcherCreateInfo, Response<Vec<WatcherInfo>>>(
+        format!("/tickets/{}/watchers", ticket_id),
+        WatcherCreateInfo { user_id },
+    )
+    .await
+}
+
+pub async fn remove_watcher(ticket_id: i32, user_id: Uuid) -> Result<SuccessResponse, Error> {
+    request_delete::<SuccessResponse>(format!("/tickets/{}/watchers/{}", ticket_id, user_id)).await
+}
@@ -0,0 +1,45 @@
+use uuid::Uuid;
+
+use super::{request_delete, request_get, request_post, request_put};
+use crate::types::*;
+use shared::models::response::Response;
+
+/// Webhooks by name
+pub async fn get_webhooks() -> Result<Vec<WebhookInfo>, Error> {
+    request_get::<Vec<WebhookInfo>>("/webhooks".to_string()).await
+}
+
+pub async fn create_webhook(info: &WebhookCreateInfo) -> Result<Response<WebhookInfo>, Error> {
+    request_post::<&WebhookCreateInfo, Response<WebhookInfo>>("/webhooks".to_string(), info).await
+}
+
+pub async fn update_webhook(
+    webhook_id: Uuid,
+    info: &WebhookCreateInfo,
+) -> Result<Response<WebhookInfo>, Error> {
+    request_put::<&WebhookCreateInfo, Response<WebhookInfo>>(
+        format!("/webhooks/{}", webhook_id),
+        info,
+    )
+    .await
+}
+
+pub async fn delete_webhook(webhook_id: Uuid) -> Result<SuccessResponse, Error> {
+    request_delete::<SuccessResponse>(format!("/webhooks/{}", webhook_id)).await
+}
+
+/// Delivery log of a webhook, latest first
+pub async fn get_webhook_deliveries(webhook_id: Uuid) -> Result<Vec<WebhookDeliveryInfo>, Error> {
+    request_get::<Vec<WebhookDeliveryInfo>>(format!("/webhooks/{}/deliveries", webhook_id)).await
+}
+
+/// Sends a delivery again, starting over with its retries
+pub async fn replay_webhook_delivery(
+    delivery_id: Uuid,
+) -> Result<Response<WebhookDeliveryInfo>, Error> {
+    request_post::<(), Response<WebhookDeliveryInfo>>(
+        format!("/webhook_deliveries/{}/replay", delivery_id),
+        (),
+    )
+    .await
+}
@@ -0,0 +1,51 @@
+use uuid::Uuid;
+
+use super::{request_delete, request_get, request_post, request_put};
+use crate::types::*;
+use shared::models::response::Response;
+
+/// Statuses in their configured order
+pub async fn get_statuses() -> Result<Vec<StatusInfo>, Error> {
+    request_get::<Vec<StatusInfo>>("/statuses".to_string()).await
+}
+
+pub async fn create_status(info: &StatusCreateInfo) -> Result<Response<StatusInfo>, Error> {
+    request_post::<&StatusCreateInfo, Response<StatusInfo>>("/statuses".to_string(), info).await
+}
+
+pub async fn update_status(
+    status_id: Uuid,
+    info: &StatusCreateInfo,
+) -> Result<Response<StatusInfo>, Error> {
+    request_put::<&StatusCreateInfo, Response<StatusInfo>>(format!("/statuses/{}", status_id), info)
+        .await
+}
+
+pub async fn delete_status(status_id: Uuid) -> Result<SuccessResponse, Error> {
+    request_delete::<SuccessResponse>(format!("/statuses/{}", status_id)).await
+}
+
+/// Priorities by rank, most urgent first
+pub async fn get_priorities() -> Result<Vec<PriorityInfo>, Error> {
+    request_get::<Vec<PriorityInfo>>("/priorities".to_string()).await
+}
+
+pub async fn create_priority(info: &PriorityCreateInfo) -> Result<Response<PriorityInfo>, Error> {
+    request_post::<&PriorityCreateInfo, Response<PriorityInfo>>("/priorities".to_string(), info)
+        .await
+}
+
+pub async fn update_priority(
+    priority_id: Uuid,
+    info: &PriorityCreateInfo,
+) -> Result<Response<PriorityInfo>, Error> {
+    request_put::<&PriorityCreateInfo, Response<PriorityInfo>>(
+        format!("/priorities/{}", priority_id),
+        info,
+    )
+    .await
+}
+
+pub async fn delete_priority(priority_id: Uuid) -> Result<SuccessResponse, Error> {
+    request_delete::<SuccessResponse>(format!("/priorities/{}", priority_id)).await
+}
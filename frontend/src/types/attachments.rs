@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AttachmentInfo {
+    pub attachment_id: Uuid,
+    pub ticket_id: Option<i32>,
+    /// Set for files added to a note, they are listed with the ticket as well
+    pub note_id: Option<Uuid>,
+    pub document_id: Option<Uuid>,
+    pub filename: String,
+    pub mime_type: String,
+    /// In bytes
+    pub size: i64,
+    pub uploaded_by: Option<Uuid>,
+    pub created_at: chrono::NaiveDateTime,
+}
@@ -1,4 +1,7 @@
+use std::str::FromStr;
+
 use serde::{Deserialize, Serialize};
+use shared::models::users::Role;
 use uuid::Uuid;
 
 #[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
@@ -35,6 +38,11 @@ impl MyUser {
     pub fn is_authenticated(&self) -> bool {
         self.user_id != uuid::Uuid::nil()
     }
+
+    //Unknown access levels get the most restricted role, the backend enforces the real one
+    pub fn role(&self) -> Role {
+        Role::from_str(&self.access).unwrap_or(Role::Requester)
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
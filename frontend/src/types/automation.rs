@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+use shared::models::automation::{AutomationAction, AutomationCondition, AutomationTrigger};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AutomationRuleInfo {
+    pub rule_id: Uuid,
+    pub name: String,
+    pub enabled: bool,
+    pub trigger: AutomationTrigger,
+    pub conditions: Vec<AutomationCondition>,
+    pub actions: Vec<AutomationAction>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct AutomationRuleCreateInfo {
+    pub name: String,
+    pub enabled: bool,
+    pub trigger: AutomationTrigger,
+    pub conditions: Vec<AutomationCondition>,
+    pub actions: Vec<AutomationAction>,
+}
+
+/// A rule running on a ticket, `ticket_id` is empty once the ticket was deleted
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AutomationRunInfo {
+    pub run_id: Uuid,
+    pub ticket_id: Option<i32>,
+    pub trigger: String,
+    /// Failed runs changed nothing, the message says why
+    pub success: bool,
+    pub message: String,
+    pub created_at: chrono::NaiveDateTime,
+}
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::UserRepresentation;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CommentInfo {
+    pub comment_id: Uuid,
+    pub document_id: Uuid,
+    pub parent_id: Option<Uuid>,
+    pub author: Option<UserRepresentation>,
+    pub text: String,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+impl CommentInfo {
+    pub fn display_name(&self) -> String {
+        match &self.author {
+            Some(author) => author.display_name.clone(),
+            None => "Unknown".to_string(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct CommentCreateInfo {
+    pub text: String,
+    pub parent_id: Option<Uuid>,
+}
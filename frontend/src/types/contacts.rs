@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ContactInfo {
+    pub contact_id: Uuid,
+    pub display_name: String,
+    pub email: String,
+    pub created_at: chrono::NaiveDateTime,
+}
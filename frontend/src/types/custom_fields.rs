@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct CustomFieldInfo {
+    pub field_id: Uuid,
+    pub name: String,
+    /// See `CustomFieldType`
+    pub field_type: String,
+    pub options: Vec<String>,
+    pub required: bool,
+    pub position: i32,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct CustomFieldCreateInfo {
+    pub name: String,
+    pub field_type: String,
+    pub options: Vec<String>,
+    pub required: bool,
+    pub position: i32,
+}
+
+/// Value of a custom field on a ticket
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct TicketFieldInfo {
+    pub field_id: Uuid,
+    pub name: String,
+    pub field_type: String,
+    pub value: String,
+}
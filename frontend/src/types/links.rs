@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+use shared::models::links::TicketLinkType;
+use uuid::Uuid;
+
+/// A link seen from the current ticket, with the other ticket
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TicketLinkInfo {
+    pub link_id: Uuid,
+    pub link_type: TicketLinkType,
+    pub ticket_id: i32,
+    pub title: String,
+    pub status: String,
+    pub is_closed: bool,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TicketLinkCreateInfo {
+    pub link_type: TicketLinkType,
+    pub ticket_id: i32,
+}
@@ -1,25 +1,83 @@
+mod attachments;
 mod auth;
+mod automation;
+mod comments;
+mod contacts;
+mod custom_fields;
 mod documents;
+mod links;
 mod notes;
+mod notifications;
+mod portal;
+mod projects;
+mod recurring;
+mod reports;
 mod response;
+mod sla;
+mod tags;
+mod tasks;
 mod tickets;
 mod users;
+mod views;
+mod watchers;
+mod webhooks;
+mod workflows;
 pub mod events;
 
 pub use tickets::{
-    TicketCreateInfo, TicketInfo, TicketInfoWrapper, TicketListInfo, TicketStatusInfo,
-    TicketUpdateInfo,
+    TicketBulkInfo, TicketCreateInfo, TicketInfo, TicketInfoWrapper, TicketListInfo,
+    TicketMergeInfo, TicketStatusInfo, TicketUpdateInfo,
 };
 
+pub use attachments::AttachmentInfo;
+
 pub use auth::{
     LoginInfo, MyUser, RegisterInfo, RegisterInfoWrapper, UserInfo, UserUpdateInfo,
     UserUpdateInfoWrapper,
 };
 
+pub use automation::{AutomationRuleCreateInfo, AutomationRuleInfo, AutomationRunInfo};
+
+pub use comments::{CommentCreateInfo, CommentInfo};
+
+pub use contacts::ContactInfo;
+
+pub use custom_fields::{CustomFieldCreateInfo, CustomFieldInfo, TicketFieldInfo};
+
+pub use links::{TicketLinkCreateInfo, TicketLinkInfo};
+
 pub use notes::{NoteCreateInfo, NoteInfo, NoteListInfo};
 
+pub use notifications::NotificationInfo;
+
+pub use portal::{
+    MagicLinkInfo, MagicLinkVerifyInfo, PortalContactInfo, PortalLoginInfo, PortalNoteInfo,
+    PortalPasswordInfo, PortalReplyInfo, PortalTicketCreateInfo, PortalTicketDetailInfo,
+    PortalTicketInfo,
+};
+
+pub use projects::{ProjectCreateInfo, ProjectInfo};
+
+pub use recurring::{RecurringTicketCreateInfo, RecurringTicketInfo, RecurringTicketRunInfo};
+
+pub use sla::{SlaPolicyCreateInfo, SlaPolicyInfo, TicketSlaInfo};
+
+pub use tags::{TagCreateInfo, TagInfo, TicketTagCreateInfo};
+
+pub use tasks::{TaskCreateInfo, TaskInfo, TaskOrderInfo, TaskUpdateInfo};
+
 pub use users::{UserPreferences, UserRepresentation};
 
+pub use views::{SavedViewCreateInfo, SavedViewInfo};
+
+pub use watchers::{WatcherCreateInfo, WatcherInfo};
+
+pub use webhooks::{WebhookCreateInfo, WebhookDeliveryInfo, WebhookInfo};
+
+pub use workflows::{PriorityCreateInfo, PriorityInfo, StatusCreateInfo, StatusInfo};
+
+pub use reports::{TimeEntryInfo, TimeReportInfo, TimeTotalInfo};
+
 pub use response::{Error, ErrorInfo, ErrorResponse, SuccessResponse};
 
 pub use documents::{DocumentCreateInfo, DocumentInfo, DocumentMetadata, DocumentUpdateInfo, DocumentRevision};
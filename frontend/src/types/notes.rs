@@ -1,15 +1,20 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use super::UserRepresentation;
+use super::{ContactInfo, UserRepresentation};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct NoteInfo {
     pub note_id: Uuid,
     pub ticket: i32,
     pub owner: Option<UserRepresentation>,
+    //set for replies that came in by e-mail
+    #[serde(default)]
+    pub contact: Option<ContactInfo>,
     pub text: String,
     pub time: i32,
+    //internal notes are hidden from requesters
+    pub is_public: bool,
     pub created_at: chrono::NaiveDateTime,
     pub updated_at: chrono::NaiveDateTime,
 }
@@ -19,6 +24,8 @@ impl NoteInfo {
         //backend will return 0's for owner if it is not set
         if self.owner.is_some() && self.owner.clone().unwrap().user_id != Uuid::nil() {
             self.owner.clone().unwrap().display_name
+        } else if let Some(contact) = &self.contact {
+            format!("{} (e-mail)", contact.display_name)
         } else {
             "Unknown".to_string()
         }
@@ -31,6 +38,9 @@ pub struct NoteCreateInfo {
     pub owner: Option<Uuid>,
     pub text: String,
     pub time: i32,
+    //left out to use the author's default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_public: Option<bool>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
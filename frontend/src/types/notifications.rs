@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct NotificationInfo {
+    pub notification_id: Uuid,
+    pub ticket_id: i32,
+    pub ticket_title: String,
+    pub event_type: String,
+    pub event_data: String,
+    pub actor: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+    pub read_at: Option<chrono::NaiveDateTime>,
+}
@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// The requester signed in to the portal
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PortalContactInfo {
+    pub contact_id: Uuid,
+    pub display_name: String,
+    pub email: String,
+    pub has_password: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PortalTicketInfo {
+    pub ticket_id: i32,
+    pub title: String,
+    pub description: String,
+    pub status: String,
+    pub is_closed: bool,
+    pub merged_into: Option<i32>,
+    /// Tickets merged into someone else's can not be opened
+    pub merged_into_own: bool,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PortalNoteInfo {
+    pub note_id: Uuid,
+    pub author: String,
+    /// Written by the requester rather than by support
+    pub from_requester: bool,
+    pub text: String,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PortalTicketDetailInfo {
+    pub ticket: PortalTicketInfo,
+    pub notes: Vec<PortalNoteInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct PortalLoginInfo {
+    pub email: String,
+    pub password: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct PortalTicketCreateInfo {
+    pub title: String,
+    pub description: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MagicLinkInfo {
+    pub email: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MagicLinkVerifyInfo {
+    pub token: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PortalPasswordInfo {
+    pub password: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PortalReplyInfo {
+    pub text: String,
+}
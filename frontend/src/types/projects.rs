@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct ProjectInfo {
+    pub project_id: Uuid,
+    pub name: String,
+    pub key: String,
+    pub default_assignee: Option<Uuid>,
+    pub next_number: i32,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct ProjectCreateInfo {
+    pub name: String,
+    pub key: String,
+    pub default_assignee: Option<Uuid>,
+}
@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+use shared::models::recurring::Recurrence;
+use uuid::Uuid;
+
+use super::TaskCreateInfo;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RecurringTicketInfo {
+    pub recurring_id: Uuid,
+    pub name: String,
+    pub title: String,
+    pub description: String,
+    pub assignee: Option<Uuid>,
+    pub priority: Option<String>,
+    pub tasks: Vec<TaskCreateInfo>,
+    pub recurrence: Recurrence,
+    pub start_at: chrono::NaiveDateTime,
+    /// Seconds east of UTC, days of the schedule are counted in this time zone
+    pub utc_offset: i32,
+    /// Empty while disabled
+    pub next_run: Option<chrono::NaiveDateTime>,
+    pub enabled: bool,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct RecurringTicketCreateInfo {
+    pub name: String,
+    pub title: String,
+    pub description: String,
+    pub assignee: Option<Uuid>,
+    /// Empty for the default priority
+    pub priority: Option<String>,
+    pub tasks: Vec<TaskCreateInfo>,
+    pub recurrence: Recurrence,
+    pub start_at: chrono::NaiveDateTime,
+    pub utc_offset: i32,
+    pub enabled: bool,
+}
+
+/// A ticket created by a recurring ticket, `ticket_id` is empty once the ticket was deleted
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RecurringTicketRunInfo {
+    pub run_id: Uuid,
+    pub scheduled_for: chrono::NaiveDateTime,
+    pub ticket_id: Option<i32>,
+    pub created_at: chrono::NaiveDateTime,
+}
@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TimeEntryInfo {
+    pub note_id: Uuid,
+    pub ticket_id: i32,
+    pub title: String,
+    pub user_id: Option<Uuid>,
+    pub user_name: Option<String>,
+    pub contact_id: Option<Uuid>,
+    pub contact_name: Option<String>,
+    pub minutes: i32,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TimeTotalInfo {
+    pub key: String,
+    pub label: String,
+    pub minutes: i64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct TimeReportInfo {
+    pub entries: Vec<TimeEntryInfo>,
+    pub totals: Vec<TimeTotalInfo>,
+    pub total_minutes: i64,
+}
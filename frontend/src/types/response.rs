@@ -32,6 +32,9 @@ pub enum Error {
     //404
     #[error("Not Found")]
     NotFound,
+    //413 (eg an upload over the size limit)
+    #[error("File is too large")]
+    PayloadTooLarge,
     //422
     #[error("Unprocessable Entity")]
     UnprocessableEntity,
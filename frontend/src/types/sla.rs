@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct SlaPolicyInfo {
+    pub policy_id: Uuid,
+    pub name: String,
+    /// Criteria left empty match any ticket
+    pub priority: Option<String>,
+    pub project: Option<Uuid>,
+    pub contact: Option<Uuid>,
+    pub response_minutes: Option<i32>,
+    pub resolution_minutes: Option<i32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct SlaPolicyCreateInfo {
+    pub name: String,
+    pub priority: Option<String>,
+    pub project: Option<Uuid>,
+    pub contact: Option<Uuid>,
+    pub response_minutes: Option<i32>,
+    pub resolution_minutes: Option<i32>,
+}
+
+/// SLA of a ticket, see `SlaStatus` for `sla_status`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct TicketSlaInfo {
+    pub policy_id: Uuid,
+    pub policy_name: String,
+    pub response_due: Option<chrono::NaiveDateTime>,
+    pub resolution_due: Option<chrono::NaiveDateTime>,
+    pub first_response_at: Option<chrono::NaiveDateTime>,
+    pub resolved_at: Option<chrono::NaiveDateTime>,
+    pub paused: bool,
+    pub response_breached: bool,
+    pub resolution_breached: bool,
+    pub sla_status: String,
+}
@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct TagInfo {
+    pub tag_id: Uuid,
+    pub name: String,
+    /// CSS color like #808080
+    pub color: String,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct TagCreateInfo {
+    pub name: String,
+    pub color: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TicketTagCreateInfo {
+    pub tag_id: Uuid,
+}
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TaskInfo {
+    pub task_id: Uuid,
+    pub ticket_id: i32,
+    pub text: String,
+    pub position: i32,
+    pub done: bool,
+    /// Required tasks have to be done before the ticket can be closed
+    pub required: bool,
+    pub completed_at: Option<chrono::NaiveDateTime>,
+    pub completed_by: Option<Uuid>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct TaskCreateInfo {
+    pub text: String,
+    pub required: bool,
+}
+
+/// Fields left out are kept
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct TaskUpdateInfo {
+    pub text: Option<String>,
+    pub done: Option<bool>,
+    pub required: Option<bool>,
+}
+
+/// Every task of the ticket in the new order
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TaskOrderInfo {
+    pub task_ids: Vec<Uuid>,
+}
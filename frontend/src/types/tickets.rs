@@ -1,4 +1,6 @@
-use super::UserRepresentation;
+use std::collections::HashMap;
+
+use super::{ProjectInfo, TagInfo, TicketFieldInfo, TicketSlaInfo, UserRepresentation};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -26,6 +28,28 @@ pub struct TicketInfo {
     pub updated_by: Option<Uuid>,
     pub revision: chrono::NaiveDateTime,
     pub revision_by: Option<Uuid>,
+    pub project: Option<ProjectInfo>,
+    pub project_number: Option<i32>,
+    /// Project-scoped number like NET-42
+    pub reference: Option<String>,
+    /// The ticket this one was merged into
+    #[serde(default)]
+    pub merged_into: Option<i32>,
+    #[serde(default)]
+    pub tags: Vec<TagInfo>,
+    #[serde(default)]
+    pub custom_fields: Vec<TicketFieldInfo>,
+    /// Only when an SLA policy applies
+    #[serde(default)]
+    pub sla: Option<TicketSlaInfo>,
+    /// Minutes logged on the notes
+    #[serde(default)]
+    pub time_spent: i64,
+    /// Checked tasks of the task list
+    #[serde(default)]
+    pub tasks_done: i64,
+    #[serde(default)]
+    pub tasks_total: i64,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
@@ -50,6 +74,9 @@ pub struct TicketCreateInfo {
     pub priority: String,
     pub status: String,
     pub due_date: Option<chrono::NaiveDateTime>,
+    pub project: Option<Uuid>,
+    /// Values by custom field id
+    pub custom_fields: HashMap<Uuid, String>,
 }
 
 impl TicketCreateInfo {
@@ -60,7 +87,8 @@ impl TicketCreateInfo {
             assignee: None,
             contact: None,
             priority: "".to_string(),
-            status: "Open".to_string(),
+            //The server picks the default status
+            status: "".to_string(),
             due_date: None,
             ..Default::default()
         }
@@ -93,6 +121,14 @@ pub struct TicketUpdateInfo {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
     pub version: Option<chrono::NaiveDateTime>,
+    /// "" removes the ticket from its project
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub project: Option<String>,
+    /// "" clears a value
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub custom_fields: Option<HashMap<Uuid, String>>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
@@ -100,6 +136,25 @@ pub struct TicketStatusInfo {
     pub status: String,
 }
 
+/// Merges a ticket into `target`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TicketMergeInfo {
+    pub target: i32,
+}
+
+/// The same change for many tickets, fields left as None stay as they are
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct TicketBulkInfo {
+    pub ticket_ids: Vec<i32>,
+    pub status: Option<String>,
+    pub priority: Option<String>,
+    /// "" unassigns the tickets
+    pub assignee: Option<String>,
+    pub add_tag: Option<Uuid>,
+    pub remove_tag: Option<Uuid>,
+    pub delete: bool,
+}
+
 
 //impl TicketStatus {
 //     pub fn to_string(&self) -> String {
@@ -33,4 +33,10 @@ pub struct UserPreferences {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
     pub timezone: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub email_notifications: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub notes_public: Option<bool>,
 }
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+use shared::models::{tickets::TicketFilterPayload, users::UserDisplay};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SavedViewInfo {
+    pub view_id: Uuid,
+    pub name: String,
+    pub filter: TicketFilterPayload,
+    /// Shown to every agent, only the owner can change it
+    pub shared: bool,
+    pub owner: UserDisplay,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SavedViewCreateInfo {
+    pub name: String,
+    pub filter: TicketFilterPayload,
+    pub shared: bool,
+}
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct WatcherInfo {
+    pub user_id: Uuid,
+    pub username: String,
+    pub display_name: String,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct WatcherCreateInfo {
+    pub user_id: Option<Uuid>,
+}
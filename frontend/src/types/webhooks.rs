@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+use shared::models::webhooks::{DeliveryStatus, WebhookEvent};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct WebhookInfo {
+    pub webhook_id: Uuid,
+    pub name: String,
+    pub url: String,
+    pub secret: String,
+    pub events: Vec<WebhookEvent>,
+    pub enabled: bool,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct WebhookCreateInfo {
+    pub name: String,
+    pub url: String,
+    /// Empty to generate one, or to keep the current one
+    pub secret: String,
+    pub events: Vec<WebhookEvent>,
+    pub enabled: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct WebhookDeliveryInfo {
+    pub delivery_id: Uuid,
+    pub event: String,
+    /// The signed body as sent
+    pub payload: String,
+    pub status: DeliveryStatus,
+    pub attempts: i32,
+    pub next_attempt_at: Option<chrono::NaiveDateTime>,
+    pub response_status: Option<i32>,
+    pub error: Option<String>,
+    pub created_at: chrono::NaiveDateTime,
+    pub delivered_at: Option<chrono::NaiveDateTime>,
+}
@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct StatusInfo {
+    pub status_id: Uuid,
+    pub name: String,
+    pub is_closed: bool,
+    pub is_default: bool,
+    pub position: i32,
+    /// SLA clocks stop while tickets are in this status
+    #[serde(default)]
+    pub pauses_sla: bool,
+    /// Statuses a ticket may change to, empty allows any
+    pub transitions: Vec<Uuid>,
+}
+
+impl StatusInfo {
+    pub fn allows(&self, to: &StatusInfo) -> bool {
+        self.transitions.is_empty() || self.transitions.contains(&to.status_id)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct StatusCreateInfo {
+    pub name: String,
+    pub is_closed: bool,
+    pub is_default: bool,
+    pub position: i32,
+    pub pauses_sla: bool,
+    pub transitions: Vec<Uuid>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct PriorityInfo {
+    pub priority_id: Uuid,
+    pub name: String,
+    /// Lower ranks are more urgent
+    pub rank: i32,
+    pub is_default: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct PriorityCreateInfo {
+    pub name: String,
+    pub rank: i32,
+    pub is_default: bool,
+}
@@ -0,0 +1,20 @@
+use web_sys::Node;
+use yew::{virtual_dom::VNode, Html};
+
+/// Renders a search snippet, only the `<mark>` tags added by the backend survive sanitizing
+pub fn highlight_to_html(snippet: &str) -> Html {
+    let html_text = ammonia::Builder::empty()
+        .add_tags(std::iter::once("mark"))
+        .clean(snippet)
+        .to_string();
+
+    let span = web_sys::window()
+        .unwrap()
+        .document()
+        .unwrap()
+        .create_element("span")
+        .unwrap();
+    span.set_inner_html(&html_text);
+    let node = Node::from(span);
+    VNode::VRef(node)
+}
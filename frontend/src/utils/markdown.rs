@@ -1,14 +1,42 @@
-use pulldown_cmark::{html::push_html, Options, Parser};
+use pulldown_cmark::{html::push_html, CowStr, Event, Options, Parser, Tag};
+use shared::models::attachments::ATTACHMENT_URL_SCHEME;
 use web_sys::Node;
 use yew::{virtual_dom::VNode, Html};
 
+use crate::services::attachments::attachment_url;
+
+/// Points `attachment:<id>` links and images at the download url of the attachment
+fn resolve_attachment(dest: CowStr) -> CowStr {
+    match dest
+        .strip_prefix(ATTACHMENT_URL_SCHEME)
+        .and_then(|id| uuid::Uuid::parse_str(id).ok())
+    {
+        Some(attachment_id) => attachment_url(attachment_id).into(),
+        None => dest,
+    }
+}
+
+fn resolve_tag(tag: Tag) -> Tag {
+    match tag {
+        Tag::Link(link_type, dest, title) => Tag::Link(link_type, resolve_attachment(dest), title),
+        Tag::Image(link_type, dest, title) => {
+            Tag::Image(link_type, resolve_attachment(dest), title)
+        }
+        tag => tag,
+    }
+}
+
 pub fn markdown_to_html(body: &str) -> Html {
     let mut options = Options::empty();
     options.insert(Options::ENABLE_TABLES);
     options.insert(Options::ENABLE_FOOTNOTES);
     options.insert(Options::ENABLE_TASKLISTS);
     options.insert(Options::ENABLE_STRIKETHROUGH);
-    let parser = Parser::new_ext(body, options);
+    let parser = Parser::new_ext(body, options).map(|event| match event {
+        Event::Start(tag) => Event::Start(resolve_tag(tag)),
+        Event::End(tag) => Event::End(resolve_tag(tag)),
+        event => event,
+    });
 
     let mut html_text = String::new();
     push_html(&mut html_text, parser);
@@ -1,3 +1,5 @@
+mod highlight;
 mod markdown;
 
-pub use markdown::markdown_to_html;
\ No newline at end of file
+pub use highlight::highlight_to_html;
+pub use markdown::markdown_to_html;
@@ -0,0 +1,45 @@
+/// Largest file that can be uploaded, in bytes
+pub const MAX_ATTACHMENT_SIZE: usize = 10 * 1024 * 1024;
+
+/// Types that can be uploaded, guessed from the file name.
+/// Anything a browser would run when opened, like HTML or SVG, is left out.
+pub const ALLOWED_MIME_TYPES: &[&str] = &[
+    "image/png",
+    "image/jpeg",
+    "image/gif",
+    "image/webp",
+    "application/pdf",
+    "text/plain",
+    "text/csv",
+    "application/json",
+    "application/zip",
+    "application/gzip",
+    "message/rfc822",
+    "application/msword",
+    "application/vnd.ms-excel",
+    "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+];
+
+pub fn is_allowed_mime_type(mime_type: &str) -> bool {
+    ALLOWED_MIME_TYPES.contains(&mime_type)
+}
+
+/// Images are shown inline, everything else is downloaded
+pub fn is_image(mime_type: &str) -> bool {
+    mime_type.starts_with("image/") && is_allowed_mime_type(mime_type)
+}
+
+/// Markdown links to `attachment:<id>` point to the download of the attachment,
+/// `![screenshot](attachment:<id>)` shows an image inline
+pub const ATTACHMENT_URL_SCHEME: &str = "attachment:";
+
+/// Size in KB or MB for lists
+pub fn format_size(size: i64) -> String {
+    if size < 1024 * 1024 {
+        format!("{} KB", (size + 1023) / 1024)
+    } else {
+        format!("{:.1} MB", size as f64 / (1024.0 * 1024.0))
+    }
+}
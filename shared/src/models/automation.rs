@@ -0,0 +1,185 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::tickets::TicketEventType;
+
+/// Event types of ticket updates that can trigger a rule, events recorded elsewhere
+/// (tasks, links, SLA breaches) do not trigger rules
+pub const TRIGGER_EVENT_TYPES: [TicketEventType; 5] = [
+    TicketEventType::Assigned,
+    TicketEventType::StatusUpdated,
+    TicketEventType::PriorityUpdated,
+    TicketEventType::TitleUpdated,
+    TicketEventType::DueDateUpdated,
+];
+
+/// Longest idle time a rule can wait for, ten years
+pub const MAX_IDLE_HOURS: u32 = 87_600;
+
+/// What makes a rule run, changes made by rules never trigger other rules
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AutomationTrigger {
+    #[default]
+    TicketCreated,
+    /// A ticket update recorded an event of this type, see `TRIGGER_EVENT_TYPES`
+    TicketEvent { event_type: String },
+    /// The ticket was not updated for this many hours, checked periodically.
+    /// Runs once until the ticket is updated again, closed tickets are never idle.
+    Idle { hours: u32 },
+}
+
+impl AutomationTrigger {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AutomationTrigger::TicketCreated => "ticket_created",
+            AutomationTrigger::TicketEvent { .. } => "ticket_event",
+            AutomationTrigger::Idle { .. } => "idle",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConditionField {
+    Title,
+    Description,
+    Status,
+    Priority,
+    /// User id, empty for unassigned tickets
+    Assignee,
+    /// Project id, empty for tickets without a project
+    Project,
+}
+
+impl ConditionField {
+    pub fn iter() -> impl Iterator<Item = ConditionField> {
+        [
+            ConditionField::Title,
+            ConditionField::Description,
+            ConditionField::Status,
+            ConditionField::Priority,
+            ConditionField::Assignee,
+            ConditionField::Project,
+        ]
+        .into_iter()
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConditionField::Title => "title",
+            ConditionField::Description => "description",
+            ConditionField::Status => "status",
+            ConditionField::Priority => "priority",
+            ConditionField::Assignee => "assignee",
+            ConditionField::Project => "project",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ConditionField::Title => "Title",
+            ConditionField::Description => "Description",
+            ConditionField::Status => "Status",
+            ConditionField::Priority => "Priority",
+            ConditionField::Assignee => "Assignee",
+            ConditionField::Project => "Project",
+        }
+    }
+}
+
+/// Text is compared ignoring case
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConditionOperator {
+    Is,
+    IsNot,
+    Contains,
+    NotContains,
+}
+
+impl ConditionOperator {
+    pub fn iter() -> impl Iterator<Item = ConditionOperator> {
+        [
+            ConditionOperator::Is,
+            ConditionOperator::IsNot,
+            ConditionOperator::Contains,
+            ConditionOperator::NotContains,
+        ]
+        .into_iter()
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConditionOperator::Is => "is",
+            ConditionOperator::IsNot => "is_not",
+            ConditionOperator::Contains => "contains",
+            ConditionOperator::NotContains => "not_contains",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ConditionOperator::Is => "is",
+            ConditionOperator::IsNot => "is not",
+            ConditionOperator::Contains => "contains",
+            ConditionOperator::NotContains => "does not contain",
+        }
+    }
+
+    pub fn matches(&self, actual: &str, expected: &str) -> bool {
+        let actual = actual.to_lowercase();
+        let expected = expected.to_lowercase();
+        match self {
+            ConditionOperator::Is => actual == expected,
+            ConditionOperator::IsNot => actual != expected,
+            ConditionOperator::Contains => actual.contains(&expected),
+            ConditionOperator::NotContains => !actual.contains(&expected),
+        }
+    }
+}
+
+/// A rule only runs when all of its conditions match
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct AutomationCondition {
+    pub field: ConditionField,
+    pub operator: ConditionOperator,
+    pub value: String,
+}
+
+/// Actions run in order, a failing action undoes the ones before it
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum AutomationAction {
+    SetStatus {
+        status: String,
+    },
+    SetPriority {
+        priority: String,
+    },
+    /// Empty to unassign
+    Assign {
+        user_id: Option<Uuid>,
+    },
+    AddNote {
+        text: String,
+        is_public: bool,
+    },
+    /// Notification for a user, or the assignee when empty
+    Notify {
+        user_id: Option<Uuid>,
+        message: String,
+    },
+}
+
+impl AutomationAction {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AutomationAction::SetStatus { .. } => "set_status",
+            AutomationAction::SetPriority { .. } => "set_priority",
+            AutomationAction::Assign { .. } => "assign",
+            AutomationAction::AddNote { .. } => "add_note",
+            AutomationAction::Notify { .. } => "notify",
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// `sort_by` value that sorts tickets by a custom field, followed by the field id
+pub const FIELD_SORT_PREFIX: &str = "field:";
+
+/// Kind of value a custom field holds, stored as text in `custom_fields.field_type`
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CustomFieldType {
+    #[default]
+    Text,
+    Number,
+    /// Stored as YYYY-MM-DD
+    Date,
+    /// One of the options of the field
+    Select,
+    /// Stored as the user id
+    User,
+}
+
+impl CustomFieldType {
+    pub fn iter() -> impl Iterator<Item = CustomFieldType> {
+        [
+            CustomFieldType::Text,
+            CustomFieldType::Number,
+            CustomFieldType::Date,
+            CustomFieldType::Select,
+            CustomFieldType::User,
+        ]
+        .into_iter()
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CustomFieldType::Text => "text",
+            CustomFieldType::Number => "number",
+            CustomFieldType::Date => "date",
+            CustomFieldType::Select => "select",
+            CustomFieldType::User => "user",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CustomFieldType::Text => "Text",
+            CustomFieldType::Number => "Number",
+            CustomFieldType::Date => "Date",
+            CustomFieldType::Select => "Select",
+            CustomFieldType::User => "User",
+        }
+    }
+}
+
+impl fmt::Display for CustomFieldType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for CustomFieldType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(CustomFieldType::Text),
+            "number" => Ok(CustomFieldType::Number),
+            "date" => Ok(CustomFieldType::Date),
+            "select" => Ok(CustomFieldType::Select),
+            "user" => Ok(CustomFieldType::User),
+            _ => Err(format!("Unknown field type: {}", s)),
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// How a ticket relates to a linked ticket, read as "this ticket <type> the other one".
+/// Links are stored in one direction, see `stored`, the other types are their inverses.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TicketLinkType {
+    Duplicates,
+    DuplicatedBy,
+    Blocks,
+    BlockedBy,
+    RelatesTo,
+    ParentOf,
+    ChildOf,
+}
+
+impl TicketLinkType {
+    pub fn iter() -> impl Iterator<Item = TicketLinkType> {
+        [
+            TicketLinkType::Duplicates,
+            TicketLinkType::DuplicatedBy,
+            TicketLinkType::Blocks,
+            TicketLinkType::BlockedBy,
+            TicketLinkType::RelatesTo,
+            TicketLinkType::ParentOf,
+            TicketLinkType::ChildOf,
+        ]
+        .into_iter()
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TicketLinkType::Duplicates => "duplicates",
+            TicketLinkType::DuplicatedBy => "duplicated_by",
+            TicketLinkType::Blocks => "blocks",
+            TicketLinkType::BlockedBy => "blocked_by",
+            TicketLinkType::RelatesTo => "relates_to",
+            TicketLinkType::ParentOf => "parent_of",
+            TicketLinkType::ChildOf => "child_of",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TicketLinkType::Duplicates => "Duplicates",
+            TicketLinkType::DuplicatedBy => "Duplicated by",
+            TicketLinkType::Blocks => "Blocks",
+            TicketLinkType::BlockedBy => "Blocked by",
+            TicketLinkType::RelatesTo => "Relates to",
+            TicketLinkType::ParentOf => "Parent of",
+            TicketLinkType::ChildOf => "Child of",
+        }
+    }
+
+    /// The same link seen from the other ticket
+    pub fn inverse(&self) -> TicketLinkType {
+        match self {
+            TicketLinkType::Duplicates => TicketLinkType::DuplicatedBy,
+            TicketLinkType::DuplicatedBy => TicketLinkType::Duplicates,
+            TicketLinkType::Blocks => TicketLinkType::BlockedBy,
+            TicketLinkType::BlockedBy => TicketLinkType::Blocks,
+            TicketLinkType::RelatesTo => TicketLinkType::RelatesTo,
+            TicketLinkType::ParentOf => TicketLinkType::ChildOf,
+            TicketLinkType::ChildOf => TicketLinkType::ParentOf,
+        }
+    }
+
+    /// Whether links of this type are stored as is, otherwise they are stored as the inverse
+    /// with source and target swapped
+    pub fn stored(&self) -> bool {
+        matches!(
+            self,
+            TicketLinkType::Duplicates
+                | TicketLinkType::Blocks
+                | TicketLinkType::RelatesTo
+                | TicketLinkType::ParentOf
+        )
+    }
+}
+
+impl fmt::Display for TicketLinkType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for TicketLinkType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        TicketLinkType::iter()
+            .find(|link_type| link_type.as_str() == s)
+            .ok_or_else(|| format!("Unknown link type: {}", s))
+    }
+}
+
+/// Event data of `Linked` and `Unlinked` events, the link type from the ticket of the event
+/// and the other ticket id, like `blocked_by:42`
+pub fn link_event_data(link_type: TicketLinkType, other: i32) -> String {
+    format!("{}:{}", link_type, other)
+}
+
+/// Splits `Linked` and `Unlinked` event data into the link type and the other ticket id
+pub fn parse_link_event_data(data: &str) -> Option<(TicketLinkType, i32)> {
+    let (link_type, other) = data.split_once(':')?;
+    Some((link_type.parse().ok()?, other.parse().ok()?))
+}
@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// What a user has open in an editor
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "kind", content = "id", rename_all = "snake_case")]
+pub enum PresenceTarget {
+    Ticket(i32),
+    Document(Uuid),
+}
+
+/// Pushed to clients over `/live`, `user_id` is whoever made the change
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LiveEvent {
+    /// A ticket was created, updated or deleted
+    TicketChanged {
+        ticket_id: i32,
+        user_id: Option<Uuid>,
+    },
+    /// A note on the ticket was added, edited or removed
+    NotesChanged {
+        ticket_id: i32,
+        user_id: Option<Uuid>,
+    },
+    DocumentChanged {
+        document_id: Uuid,
+        user_id: Option<Uuid>,
+    },
+    /// Sent when someone opens or leaves an editor, and repeated while it stays open
+    Presence {
+        target: PresenceTarget,
+        user_id: Uuid,
+        display_name: String,
+        editing: bool,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PresencePayload {
+    pub target: PresenceTarget,
+    pub editing: bool,
+}
@@ -1,6 +1,17 @@
+pub mod attachments;
+pub mod automation;
+pub mod custom_fields;
 pub mod documents;
+pub mod links;
+pub mod live;
+pub mod notifications;
+pub mod recurring;
+pub mod reports;
 pub mod response;
+pub mod search;
+pub mod sla;
 pub mod tickets;
 pub mod users;
+pub mod webhooks;
 
 pub const MAX_TITLE_LENGTH: usize = 128;
\ No newline at end of file
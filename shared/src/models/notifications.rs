@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+/// Event type used for notifications about new notes,
+/// everything else uses the `TicketEventType` names
+pub const NOTE_ADDED: &str = "note_added";
+
+/// Event type used for notifications sent by automation rules, the data is the message
+pub const AUTOMATION_NOTICE: &str = "automation_notice";
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct NotificationQuery {
+    /// Only unread notifications
+    pub unread: Option<bool>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct UnreadCount {
+    pub unread: i64,
+}
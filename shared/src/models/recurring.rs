@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+
+/// Weekday names by number, `Recurrence::Weekly` counts from Monday
+pub const WEEKDAYS: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+
+/// Longest interval of `Recurrence::Daily`, in days
+pub const MAX_DAILY_INTERVAL: u32 = 366;
+
+/// Longest interval of `Recurrence::Weekly`, in weeks
+pub const MAX_WEEKLY_INTERVAL: u32 = 104;
+
+/// Schedule of a recurring ticket, runs happen at the time of day of its start
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(tag = "frequency", rename_all = "snake_case")]
+pub enum Recurrence {
+    /// Every `interval` days
+    Daily { interval: u32 },
+    /// On the given weekdays (0 is Monday) of every `interval` weeks
+    Weekly { interval: u32, weekdays: Vec<u32> },
+    /// On a day of every month, months that are too short use their last day
+    Monthly { day: u32 },
+    /// On the first weekday of every month
+    FirstBusinessDay,
+}
+
+impl Default for Recurrence {
+    fn default() -> Self {
+        Recurrence::Daily { interval: 1 }
+    }
+}
+
+impl Recurrence {
+    pub fn frequency(&self) -> &'static str {
+        match self {
+            Recurrence::Daily { .. } => "daily",
+            Recurrence::Weekly { .. } => "weekly",
+            Recurrence::Monthly { .. } => "monthly",
+            Recurrence::FirstBusinessDay => "first_business_day",
+        }
+    }
+
+    /// Reason the schedule can never run, if any
+    pub fn check(&self) -> Option<String> {
+        match self {
+            Recurrence::Daily { interval } | Recurrence::Weekly { interval, .. }
+                if *interval == 0 =>
+            {
+                Some("The interval must be at least one".to_string())
+            }
+            Recurrence::Daily { interval } if *interval > MAX_DAILY_INTERVAL => Some(format!(
+                "The interval can be at most {} days",
+                MAX_DAILY_INTERVAL
+            )),
+            Recurrence::Weekly { interval, .. } if *interval > MAX_WEEKLY_INTERVAL => Some(
+                format!("The interval can be at most {} weeks", MAX_WEEKLY_INTERVAL),
+            ),
+            Recurrence::Weekly { weekdays, .. } if weekdays.is_empty() => {
+                Some("Pick at least one weekday".to_string())
+            }
+            Recurrence::Weekly { weekdays, .. } if weekdays.iter().any(|day| *day > 6) => {
+                Some("Unknown weekday".to_string())
+            }
+            Recurrence::Monthly { day } if !(1..=31).contains(day) => {
+                Some("The day of the month must be between 1 and 31".to_string())
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Query of the time report, every filter is optional.
+/// Dates are `YYYY-MM-DD` in UTC and both ends are included.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct TimeReportQuery {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub user: Option<Uuid>,
+    pub ticket: Option<i32>,
+    /// Contact of the ticket the time was logged on
+    pub contact: Option<Uuid>,
+    /// See `TimeGrouping`, defaults to users
+    pub group_by: Option<String>,
+}
+
+impl TimeReportQuery {
+    /// Query string for the report endpoints, without the leading `?`
+    pub fn to_query_string(&self) -> String {
+        let mut params = vec![];
+        if let Some(from) = &self.from {
+            params.push(format!("from={}", from));
+        }
+        if let Some(to) = &self.to {
+            params.push(format!("to={}", to));
+        }
+        if let Some(user) = self.user {
+            params.push(format!("user={}", user));
+        }
+        if let Some(ticket) = self.ticket {
+            params.push(format!("ticket={}", ticket));
+        }
+        if let Some(contact) = self.contact {
+            params.push(format!("contact={}", contact));
+        }
+        if let Some(group_by) = &self.group_by {
+            params.push(format!("group_by={}", group_by));
+        }
+        params.join("&")
+    }
+}
+
+/// How the totals of a time report are summed up
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeGrouping {
+    #[default]
+    User,
+    Ticket,
+    Contact,
+    Date,
+}
+
+impl TimeGrouping {
+    pub fn iter() -> impl Iterator<Item = TimeGrouping> {
+        [
+            TimeGrouping::User,
+            TimeGrouping::Ticket,
+            TimeGrouping::Contact,
+            TimeGrouping::Date,
+        ]
+        .into_iter()
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TimeGrouping::User => "user",
+            TimeGrouping::Ticket => "ticket",
+            TimeGrouping::Contact => "contact",
+            TimeGrouping::Date => "date",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TimeGrouping::User => "User",
+            TimeGrouping::Ticket => "Ticket",
+            TimeGrouping::Contact => "Contact",
+            TimeGrouping::Date => "Date",
+        }
+    }
+}
+
+impl fmt::Display for TimeGrouping {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for TimeGrouping {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "user" => Ok(TimeGrouping::User),
+            "ticket" => Ok(TimeGrouping::Ticket),
+            "contact" => Ok(TimeGrouping::Contact),
+            "date" => Ok(TimeGrouping::Date),
+            _ => Err(format!("Unknown grouping: {}", s)),
+        }
+    }
+}
+
+/// Minutes as hours and minutes like 2:05
+pub fn format_minutes(minutes: i64) -> String {
+    format!("{}:{:02}", minutes / 60, minutes % 60)
+}
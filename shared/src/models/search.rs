@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchEntity {
+    Ticket,
+    Note,
+    Document,
+}
+
+/// A single hit from `/search`, ordered by rank
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SearchResult {
+    pub entity_type: SearchEntity,
+    /// Set for tickets and notes (the ticket the note belongs to)
+    pub ticket_id: Option<i32>,
+    pub document_id: Option<Uuid>,
+    pub note_id: Option<Uuid>,
+    pub title: String,
+    /// Excerpt with matches wrapped in `<mark>`, the rest is unescaped user content
+    pub snippet: String,
+    pub rank: f32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct SearchQuery {
+    pub q: String,
+    pub limit: Option<i64>,
+}
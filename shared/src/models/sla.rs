@@ -0,0 +1,80 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// Tickets whose next deadline is closer than this are at risk
+pub const AT_RISK_MINUTES: i64 = 60;
+
+/// Event data of `SlaBreached` events
+pub const RESPONSE_TARGET: &str = "response";
+pub const RESOLUTION_TARGET: &str = "resolution";
+
+/// Where a ticket stands against its SLA policy, also a ticket list filter
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SlaStatus {
+    OnTrack,
+    /// A deadline is less than `AT_RISK_MINUTES` away
+    AtRisk,
+    /// On hold in a status that pauses the SLA
+    Paused,
+    /// A target was missed, this sticks after the ticket is closed
+    Breached,
+    /// Closed without missing a target
+    Met,
+}
+
+impl SlaStatus {
+    pub fn iter() -> impl Iterator<Item = SlaStatus> {
+        [
+            SlaStatus::OnTrack,
+            SlaStatus::AtRisk,
+            SlaStatus::Paused,
+            SlaStatus::Breached,
+            SlaStatus::Met,
+        ]
+        .into_iter()
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SlaStatus::OnTrack => "on_track",
+            SlaStatus::AtRisk => "at_risk",
+            SlaStatus::Paused => "paused",
+            SlaStatus::Breached => "breached",
+            SlaStatus::Met => "met",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SlaStatus::OnTrack => "On track",
+            SlaStatus::AtRisk => "At risk",
+            SlaStatus::Paused => "Paused",
+            SlaStatus::Breached => "Breached",
+            SlaStatus::Met => "Met",
+        }
+    }
+}
+
+impl fmt::Display for SlaStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SlaStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "on_track" => Ok(SlaStatus::OnTrack),
+            "at_risk" => Ok(SlaStatus::AtRisk),
+            "paused" => Ok(SlaStatus::Paused),
+            "breached" => Ok(SlaStatus::Breached),
+            "met" => Ok(SlaStatus::Met),
+            _ => Err(format!("Unknown SLA status: {}", s)),
+        }
+    }
+}
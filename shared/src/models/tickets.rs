@@ -1,6 +1,12 @@
+use std::fmt;
+use std::str::FromStr;
+
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+/// Most tickets one bulk operation can change
+pub const MAX_BULK_TICKETS: usize = 200;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub enum TicketEventType {
     Assigned,
@@ -8,21 +14,85 @@ pub enum TicketEventType {
     PriorityUpdated,
     TitleUpdated,
     DueDateUpdated,
+    /// A tag was added or removed, see `tagged_event_data`
+    Tagged,
+    /// An SLA target was missed, the data is `RESPONSE_TARGET` or `RESOLUTION_TARGET`
+    SlaBreached,
+    /// A task was checked, the data is its text
+    TaskCompleted,
+    /// A checked task was unchecked, the data is its text
+    TaskReopened,
+    /// A link to another ticket was added, see `links::link_event_data`
+    Linked,
+    /// A link to another ticket was removed, the data is like for `Linked`
+    Unlinked,
+    /// The ticket was closed while it duplicates another, the data is the original ticket id
+    ClosedAsDuplicate,
+    /// Another ticket was merged into this one, the data is its id
+    Merged,
+    /// The ticket was merged into another one and closed, the data is the other ticket id
+    MergedInto,
+}
+
+//impl Display for storing in databaes (this_style)
+impl fmt::Display for TicketEventType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            TicketEventType::Assigned => "assigned",
+            TicketEventType::StatusUpdated => "status_updated",
+            TicketEventType::PriorityUpdated => "priority_updated",
+            TicketEventType::TitleUpdated => "title_updated",
+            TicketEventType::DueDateUpdated => "due_date_updated",
+            TicketEventType::Tagged => "tagged",
+            TicketEventType::SlaBreached => "sla_breached",
+            TicketEventType::TaskCompleted => "task_completed",
+            TicketEventType::TaskReopened => "task_reopened",
+            TicketEventType::Linked => "linked",
+            TicketEventType::Unlinked => "unlinked",
+            TicketEventType::ClosedAsDuplicate => "closed_as_duplicate",
+            TicketEventType::Merged => "merged",
+            TicketEventType::MergedInto => "merged_into",
+        };
+        f.write_str(s)
+    }
 }
 
-//impl to string for storing in databaes (this_style)
-impl ToString for TicketEventType {
-    fn to_string(&self) -> String {
-        match self {
-            TicketEventType::Assigned => "assigned".to_string(),
-            TicketEventType::StatusUpdated => "status_updated".to_string(),
-            TicketEventType::PriorityUpdated => "priority_updated".to_string(),
-            TicketEventType::TitleUpdated => "title_updated".to_string(),
-            TicketEventType::DueDateUpdated => "due_date_updated".to_string(),
+impl FromStr for TicketEventType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "assigned" => Ok(TicketEventType::Assigned),
+            "status_updated" => Ok(TicketEventType::StatusUpdated),
+            "priority_updated" => Ok(TicketEventType::PriorityUpdated),
+            "title_updated" => Ok(TicketEventType::TitleUpdated),
+            "due_date_updated" => Ok(TicketEventType::DueDateUpdated),
+            "tagged" => Ok(TicketEventType::Tagged),
+            "sla_breached" => Ok(TicketEventType::SlaBreached),
+            "task_completed" => Ok(TicketEventType::TaskCompleted),
+            "task_reopened" => Ok(TicketEventType::TaskReopened),
+            "linked" => Ok(TicketEventType::Linked),
+            "unlinked" => Ok(TicketEventType::Unlinked),
+            "closed_as_duplicate" => Ok(TicketEventType::ClosedAsDuplicate),
+            "merged" => Ok(TicketEventType::Merged),
+            "merged_into" => Ok(TicketEventType::MergedInto),
+            _ => Err(format!("Unknown ticket event type: {}", s)),
         }
     }
 }
 
+/// Event data of `Tagged` events, the tag name prefixed with + when added or - when removed
+pub fn tagged_event_data(tag: &str, added: bool) -> String {
+    format!("{}{}", if added { '+' } else { '-' }, tag)
+}
+
+/// Splits `Tagged` event data into whether the tag was added and its name
+pub fn parse_tagged_event_data(data: &str) -> (bool, &str) {
+    match data.strip_prefix('-') {
+        Some(tag) => (false, tag),
+        None => (true, data.strip_prefix('+').unwrap_or(data)),
+    }
+}
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct TicketFilterPayload {
@@ -33,4 +103,21 @@ pub struct TicketFilterPayload {
     pub sort_by: Option<String>,
     pub sort_order: Option<String>,
     pub search: Option<String>,
-}
\ No newline at end of file
+    /// Only tickets the caller is watching
+    pub watching: Option<bool>,
+    /// Nil for tickets without a project
+    pub project: Option<Uuid>,
+    /// Comma-separated tag names, tickets need all of them
+    pub tags: Option<String>,
+    /// Comma-separated tag names, tickets with any of them are left out
+    pub exclude_tags: Option<String>,
+    /// Custom field to filter by together with `field_value`
+    pub field: Option<Uuid>,
+    /// Text fields match if they contain it, other fields need the exact value.
+    /// Empty for tickets without a value.
+    pub field_value: Option<String>,
+    /// See `SlaStatus`, tickets without an SLA policy never match
+    pub sla_status: Option<String>,
+    /// Tickets that missed (true) or have not missed (false) an SLA target
+    pub breached: Option<bool>,
+}
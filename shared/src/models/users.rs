@@ -1,3 +1,6 @@
+use std::fmt;
+use std::str::FromStr;
+
 use serde::{Serialize, Deserialize};
 use uuid::Uuid;
 
@@ -6,4 +9,79 @@ use uuid::Uuid;
 pub struct UserDisplay {
     pub user_id: Uuid,
     pub display_name: String,
-}
\ No newline at end of file
+}
+
+/// Access level of a user, stored as text in `users.access`
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Admin,
+    #[default]
+    Agent,
+    ReadOnly,
+    Requester,
+}
+
+impl Role {
+    pub fn iter() -> impl Iterator<Item = Role> {
+        [Role::Admin, Role::Agent, Role::ReadOnly, Role::Requester].into_iter()
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Admin => "admin",
+            Role::Agent => "agent",
+            Role::ReadOnly => "readonly",
+            Role::Requester => "requester",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Role::Admin => "Administrator",
+            Role::Agent => "Agent",
+            Role::ReadOnly => "Read-only",
+            Role::Requester => "Requester",
+        }
+    }
+
+    /// Manage users and delete tickets, documents and contacts
+    pub fn is_admin(&self) -> bool {
+        matches!(self, Role::Admin)
+    }
+
+    /// Modify tickets, documents and contacts
+    pub fn can_edit(&self) -> bool {
+        matches!(self, Role::Admin | Role::Agent)
+    }
+
+    /// Read everything, not just tickets the user opened
+    pub fn can_view(&self) -> bool {
+        matches!(self, Role::Admin | Role::Agent | Role::ReadOnly)
+    }
+
+    /// Open tickets and post notes on them
+    pub fn can_create_tickets(&self) -> bool {
+        matches!(self, Role::Admin | Role::Agent | Role::Requester)
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Role {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "admin" => Ok(Role::Admin),
+            "agent" => Ok(Role::Agent),
+            "readonly" => Ok(Role::ReadOnly),
+            "requester" => Ok(Role::Requester),
+            _ => Err(format!("Unknown access level: {}", s)),
+        }
+    }
+}
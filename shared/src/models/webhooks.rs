@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+
+/// Changes a webhook can subscribe to, named like `ticket.updated` in payloads
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookEvent {
+    #[serde(rename = "ticket.created")]
+    TicketCreated,
+    /// Also sent when automation rules or bulk updates change a ticket
+    #[serde(rename = "ticket.updated")]
+    TicketUpdated,
+    #[serde(rename = "ticket.deleted")]
+    TicketDeleted,
+    #[serde(rename = "note.created")]
+    NoteCreated,
+    #[serde(rename = "note.updated")]
+    NoteUpdated,
+    #[serde(rename = "note.deleted")]
+    NoteDeleted,
+    #[serde(rename = "document.created")]
+    DocumentCreated,
+    #[serde(rename = "document.updated")]
+    DocumentUpdated,
+    #[serde(rename = "document.deleted")]
+    DocumentDeleted,
+}
+
+impl WebhookEvent {
+    pub fn iter() -> impl Iterator<Item = WebhookEvent> {
+        [
+            WebhookEvent::TicketCreated,
+            WebhookEvent::TicketUpdated,
+            WebhookEvent::TicketDeleted,
+            WebhookEvent::NoteCreated,
+            WebhookEvent::NoteUpdated,
+            WebhookEvent::NoteDeleted,
+            WebhookEvent::DocumentCreated,
+            WebhookEvent::DocumentUpdated,
+            WebhookEvent::DocumentDeleted,
+        ]
+        .into_iter()
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::TicketCreated => "ticket.created",
+            WebhookEvent::TicketUpdated => "ticket.updated",
+            WebhookEvent::TicketDeleted => "ticket.deleted",
+            WebhookEvent::NoteCreated => "note.created",
+            WebhookEvent::NoteUpdated => "note.updated",
+            WebhookEvent::NoteDeleted => "note.deleted",
+            WebhookEvent::DocumentCreated => "document.created",
+            WebhookEvent::DocumentUpdated => "document.updated",
+            WebhookEvent::DocumentDeleted => "document.deleted",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            WebhookEvent::TicketCreated => "Ticket created",
+            WebhookEvent::TicketUpdated => "Ticket updated",
+            WebhookEvent::TicketDeleted => "Ticket deleted",
+            WebhookEvent::NoteCreated => "Note created",
+            WebhookEvent::NoteUpdated => "Note updated",
+            WebhookEvent::NoteDeleted => "Note deleted",
+            WebhookEvent::DocumentCreated => "Document created",
+            WebhookEvent::DocumentUpdated => "Document updated",
+            WebhookEvent::DocumentDeleted => "Document deleted",
+        }
+    }
+}
+
+/// Where a delivery is in the queue
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    /// Waiting for its first attempt or a retry
+    Pending,
+    Delivered,
+    /// Gave up after the last retry, can be replayed
+    Failed,
+}
+
+impl DeliveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeliveryStatus::Pending => "pending",
+            DeliveryStatus::Delivered => "delivered",
+            DeliveryStatus::Failed => "failed",
+        }
+    }
+}